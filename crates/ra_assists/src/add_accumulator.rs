@@ -0,0 +1,154 @@
+use hir::{CallableDef, Ty, db::HirDatabase, source_binder};
+use ra_syntax::{
+    AstNode, SyntaxNode, TextUnit,
+    ast::{self, ArgListOwner, NameOwner},
+};
+
+use crate::{AssistCtx, Assist, AssistId, utils::for_each_call};
+
+pub(crate) fn add_accumulator(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.node_at_offset::<ast::FnDef>()?;
+    let body = fn_def.body()?;
+    if body.syntax().range().contains_inclusive(ctx.frange.range.start()) {
+        return None;
+    }
+    let ret_type = fn_def.ret_type()?.type_ref()?.syntax().text().to_string();
+    let param_list = fn_def.param_list()?;
+    let has_acc =
+        param_list.params().filter_map(|it| it.pat()).any(|it| it.syntax().text() == "acc");
+    if has_acc {
+        return None;
+    }
+
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, body.syntax())?;
+    let infer = function.infer(ctx.db);
+    let source_map = function.body_source_map(ctx.db);
+    let mut recursive_calls = Vec::new();
+    for node in body.syntax().descendants() {
+        let arg_list = if let Some(call) = ast::CallExpr::cast(node) {
+            let callee = call.expr().and_then(|it| source_map.node_expr(it));
+            match callee.map(|it| &infer[it]) {
+                Some(Ty::FnDef { def: CallableDef::Function(it), .. }) if *it == function => {}
+                _ => continue,
+            }
+            call.arg_list()
+        } else if let Some(call) = ast::MethodCallExpr::cast(node) {
+            let expr = ast::Expr::cast(call.syntax()).and_then(|it| source_map.node_expr(it));
+            if expr.and_then(|it| infer.method_resolution(it)) != Some(function) {
+                continue;
+            }
+            call.arg_list()
+        } else {
+            continue;
+        };
+        recursive_calls.push(arg_list?);
+    }
+    if recursive_calls.is_empty() {
+        return None;
+    }
+    // Calls from elsewhere are left for the user to update.
+    let mut calls = 0;
+    let all_calls = for_each_call(ctx.db, function, ctx.frange.file_id, |_| {
+        calls += 1;
+        Some(())
+    });
+    let label = match all_calls {
+        Some(()) if calls == recursive_calls.len() => "add accumulator parameter",
+        _ => "add accumulator parameter (other call sites are not updated)",
+    };
+
+    let has_params = param_list.params().next().is_some() || param_list.self_param().is_some();
+    let (param_offset, param) = closing_offset(param_list.syntax(), has_params, "acc: ")?;
+    ctx.add_action(AssistId("add_accumulator"), label, |edit| {
+        edit.target(fn_def.name().map_or(fn_def.syntax().range(), |it| it.syntax().range()));
+        edit.insert(param_offset, format!("{}{}", param, ret_type));
+        for arg_list in recursive_calls {
+            let has_args = arg_list.args().next().is_some();
+            if let Some((offset, arg)) = closing_offset(arg_list.syntax(), has_args, "acc") {
+                edit.insert(offset, arg);
+            }
+        }
+        edit.set_cursor(param_offset + TextUnit::of_str(&param) - TextUnit::of_str(": "));
+    });
+
+    ctx.build()
+}
+
+/// Where to append `item` to a parenthesized list, and the text to insert.
+fn closing_offset(list: &SyntaxNode, has_items: bool, item: &str) -> Option<(TextUnit, String)> {
+    let offset = list.last_child()?.range().start();
+    let text = if has_items { format!(", {}", item) } else { item.to_string() };
+    Some((offset, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use hir::mock::MockDatabase;
+    use ra_db::FileRange;
+    use ra_syntax::TextRange;
+    use test_utils::extract_offset;
+
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_accumulator_to_factorial() {
+        check_assist(
+            add_accumulator,
+            "
+fn fact<|>(n: u64) -> u64 {
+    if n == 0 { 1 } else { n * fact(n - 1) }
+}",
+            "
+fn fact(n: u64, acc<|>: u64) -> u64 {
+    if n == 0 { 1 } else { n * fact(n - 1, acc) }
+}",
+        );
+    }
+
+    #[test]
+    fn add_accumulator_to_method() {
+        check_assist(
+            add_accumulator,
+            "
+struct S;
+impl S {
+    fn count<|>(&self) -> u32 { self.count() }
+}",
+            "
+struct S;
+impl S {
+    fn count(&self, acc<|>: u32) -> u32 { self.count(acc) }
+}",
+        );
+    }
+
+    #[test]
+    fn add_accumulator_flags_other_call_sites() {
+        let (offset, before) = extract_offset(
+            "
+fn sum<|>(n: u32) -> u32 { if n == 0 { 0 } else { n + sum(n - 1) } }
+fn main() { sum(3); }",
+        );
+        let (db, _source_root, file_id) = MockDatabase::with_single_file(&before);
+        let frange = FileRange { file_id, range: TextRange::offset_len(offset, 0.into()) };
+        let labels =
+            crate::assists(&db, frange).into_iter().map(|it| it.0.label).collect::<Vec<_>>();
+        assert!(labels
+            .iter()
+            .any(|it| it == "add accumulator parameter (other call sites are not updated)"));
+    }
+
+    #[test]
+    fn add_accumulator_not_applicable() {
+        check_assist_not_applicable(add_accumulator, "fn f<|>(n: u32) -> u32 { n }");
+        check_assist_not_applicable(add_accumulator, "fn f<|>(n: u32) { f(n) }");
+        check_assist_not_applicable(add_accumulator, "fn f(n: u32) -> u32 { f(<|>n) }");
+    }
+
+    #[test]
+    fn add_accumulator_target() {
+        check_assist_target(add_accumulator, "fn f<|>() -> u32 { f() }", "f");
+    }
+}
@@ -0,0 +1,119 @@
+use hir::{db::HirDatabase, source_binder};
+use ra_syntax::{AstNode, SyntaxNode, ast, SyntaxKind::FN_DEF};
+
+use crate::{
+    AssistCtx, Assist, AssistId,
+    utils::{type_annotation, unannotated_pat},
+};
+
+pub(crate) fn add_all_type_annotations(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.node_at_offset::<ast::FnDef>()?;
+    let body = fn_def.body()?;
+    // Only offered on the signature, so that it doesn't get in the way in
+    // the body.
+    if body.syntax().range().contains(ctx.frange.range.start()) {
+        return None;
+    }
+    let function = source_binder::function_from_source(ctx.db, ctx.frange.file_id, fn_def)?;
+
+    let annotations = body
+        .syntax()
+        .descendants()
+        .filter(|it| !is_in_nested_fn(it, fn_def.syntax()))
+        .filter_map(unannotated_pat)
+        .filter_map(|pat| type_annotation(ctx.db, function, pat))
+        .collect::<Vec<_>>();
+    if annotations.is_empty() {
+        return None;
+    }
+
+    ctx.add_action(AssistId("add_all_type_annotations"), "add all type annotations", |edit| {
+        edit.target(fn_def.syntax().range());
+        for (offset, annotation) in annotations {
+            edit.insert(offset, annotation);
+        }
+    });
+
+    ctx.build()
+}
+
+fn is_in_nested_fn(node: &SyntaxNode, fn_def: &SyntaxNode) -> bool {
+    node.ancestors().take_while(|it| *it != fn_def).any(|it| it.kind() == FN_DEF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn annotates_all_locals() {
+        check_assist(
+            add_all_type_annotations,
+            "
+struct Point { x: f64, y: f64 }
+fn ma<|>in() {
+    let p = Point { x: 1.0, y: 2.0 };
+    let x: f64 = p.x;
+    let mut sum = p.x + p.y;
+    let (a, b) = (&p, 1u8);
+    let unknown = foo();
+    let n = 92;
+}",
+            "
+struct Point { x: f64, y: f64 }
+fn ma<|>in() {
+    let p: Point = Point { x: 1.0, y: 2.0 };
+    let x: f64 = p.x;
+    let mut sum: f64 = p.x + p.y;
+    let (a, b): (&Point, u8) = (&p, 1u8);
+    let unknown = foo();
+    let n: i32 = 92;
+}",
+        );
+    }
+
+    #[test]
+    fn annotates_closure_params() {
+        check_assist(
+            add_all_type_annotations,
+            "
+struct S;
+fn <|>main(s: S) {
+    let double = |x: u32| x * 2;
+    let id = |x| x;
+    id(&s);
+    fn inner() { let y = 1u64; }
+}",
+            "
+struct S;
+fn <|>main(s: S) {
+    let double = |x: u32| x * 2;
+    let id = |x: &S| x;
+    id(&s);
+    fn inner() { let y = 1u64; }
+}",
+        );
+    }
+
+    #[test]
+    fn add_all_type_annotations_not_applicable() {
+        check_assist_not_applicable(
+            add_all_type_annotations,
+            "fn main() { let x: u32 = 1; let <|>y = x; }",
+        );
+        check_assist_not_applicable(
+            add_all_type_annotations,
+            "fn main<|>() { let x: u32 = 1; let f = || x; }",
+        );
+    }
+
+    #[test]
+    fn add_all_type_annotations_target() {
+        check_assist_target(
+            add_all_type_annotations,
+            "fn f<|>() { let x = 1u8; }",
+            "fn f() { let x = 1u8; }",
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use hir::{db::HirDatabase, source_binder};
+use ra_db::FileId;
+use ra_syntax::{
+    AstNode, SyntaxNode, TextUnit,
+    ast::{self, NameOwner},
+    SyntaxKind::*,
+};
+
+use crate::{
+    AssistCtx, Assist, AssistId,
+    utils::{crate_modules, item_indent},
+};
+
+pub(crate) fn add_allow(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let name = ctx.node_at_offset::<ast::Name>()?;
+    let (lint, node) = match name.syntax().parent()?.kind() {
+        BIND_PAT => unused_variable(&ctx, ast::BindPat::cast(name.syntax().parent()?)?)?,
+        _ => dead_code(&ctx, name)?,
+    };
+
+    let allow = node
+        .children()
+        .filter_map(ast::Attr::cast)
+        .filter_map(|it| it.as_call())
+        .find(|(name, _)| name == "allow")
+        .map(|(_, tt)| tt);
+    let (offset, text) = match allow {
+        Some(tt) => {
+            if tt.syntax().children().any(|it| it.kind() == IDENT && it.text() == lint) {
+                return None;
+            }
+            (tt.syntax().range().end() - TextUnit::of_char(')'), format!(", {}", lint))
+        }
+        None => {
+            let start = node
+                .children()
+                .find(|it| it.kind() != COMMENT && it.kind() != WHITESPACE)?
+                .range()
+                .start();
+            (start, format!("#[allow({})]\n{}", lint, item_indent(node)))
+        }
+    };
+
+    ctx.add_action(AssistId("add_allow"), format!("allow `{}`", lint), |edit| {
+        edit.target(node.range());
+        edit.insert(offset, text);
+        edit.set_cursor(node.range().start());
+    });
+
+    ctx.build()
+}
+
+/// A binding which is never read, with the statement, match arm or function
+/// the `#[allow]` goes on.
+fn unused_variable<'a>(
+    ctx: &AssistCtx<impl HirDatabase>,
+    pat: &'a ast::BindPat,
+) -> Option<(&'static str, &'a SyntaxNode)> {
+    if pat.name()?.text().starts_with('_') {
+        return None;
+    }
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, pat.syntax())?;
+    if !function.scopes(ctx.db).find_all_refs(pat).is_empty() {
+        return None;
+    }
+    let node = pat
+        .syntax()
+        .ancestors()
+        .find(|it| it.kind() == LET_STMT || it.kind() == MATCH_ARM || it.kind() == FN_DEF)?;
+    Some(("unused_variables", node))
+}
+
+/// A private item of a module which its crate never mentions.
+fn dead_code<'a>(
+    ctx: &AssistCtx<impl HirDatabase>,
+    name: &'a ast::Name,
+) -> Option<(&'static str, &'a SyntaxNode)> {
+    let item = name.syntax().parent()?;
+    match item.kind() {
+        FN_DEF | STRUCT_DEF | ENUM_DEF | CONST_DEF | STATIC_DEF | TYPE_ALIAS_DEF => (),
+        _ => return None,
+    }
+    let in_module = match item.parent()?.kind() {
+        SOURCE_FILE => true,
+        ITEM_LIST => item.parent()?.parent()?.kind() == MODULE,
+        _ => false,
+    };
+    if !in_module || item.children().any(|it| it.kind() == VISIBILITY) {
+        return None;
+    }
+    // Entry points and tests are used by the compiler.
+    let is_test = item
+        .children()
+        .filter_map(ast::Attr::cast)
+        .any(|it| it.as_atom().map_or(false, |it| it == "test"));
+    if is_test || name.text() == "main" {
+        return None;
+    }
+    for file_id in crate_files(ctx.db, ctx.frange.file_id) {
+        let file = ctx.db.parse(file_id);
+        let is_used = file
+            .syntax()
+            .descendants()
+            .filter_map(ast::NameRef::cast)
+            .any(|it| it.text() == name.text());
+        if is_used {
+            return None;
+        }
+    }
+    Some(("dead_code", item))
+}
+
+/// The files of the crate `file_id` belongs to, or just `file_id` if it isn't
+/// part of a crate.
+fn crate_files(db: &impl HirDatabase, file_id: FileId) -> Vec<FileId> {
+    let modules = source_binder::module_from_file_id(db, file_id)
+        .and_then(|it| it.krate(db))
+        .and_then(|it| crate_modules(db, it));
+    let modules = match modules {
+        Some(it) => it,
+        None => return vec![file_id],
+    };
+    let mut res = Vec::new();
+    for module in modules {
+        let file = module.definition_source(db).0.original_file(db);
+        if !res.contains(&file) {
+            res.push(file);
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use hir::mock::MockDatabase;
+    use ra_db::FileRange;
+    use ra_syntax::TextRange;
+
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_allow_unused_variables() {
+        check_assist(
+            add_allow,
+            "
+fn f() {
+    let x<|> = 92;
+}",
+            "
+fn f() {
+    <|>#[allow(unused_variables)]
+    let x = 92;
+}",
+        );
+        check_assist(
+            add_allow,
+            "
+/// Docs.
+fn f(x<|>: u32) {}",
+            "
+<|>/// Docs.
+#[allow(unused_variables)]
+fn f(x: u32) {}",
+        );
+    }
+
+    #[test]
+    fn add_allow_merges_into_existing_allow() {
+        check_assist(
+            add_allow,
+            "
+mod m {
+    #[allow(unused_imports)]
+    fn helper<|>() {}
+}",
+            "
+mod m {
+    <|>#[allow(unused_imports, dead_code)]
+    fn helper() {}
+}",
+        );
+    }
+
+    #[test]
+    fn add_allow_not_applicable() {
+        check_assist_not_applicable(add_allow, "fn f() { let x<|> = 92; x; }");
+        check_assist_not_applicable(add_allow, "fn f() { let _x<|> = 92; }");
+        check_assist_not_applicable(
+            add_allow,
+            "fn f() { #[allow(unused_variables)] let x<|> = 92; }",
+        );
+        check_assist_not_applicable(add_allow, "pub fn helper<|>() {}");
+        check_assist_not_applicable(add_allow, "fn helper<|>() {} fn main() { helper() }");
+    }
+
+    #[test]
+    fn add_allow_dead_code_checks_the_whole_crate() {
+        let (db, position) = MockDatabase::with_position(
+            "
+//- /lib.rs
+mod foo;
+fn helper<|>() {}
+//- /foo.rs
+fn f() { super::helper() }
+",
+        );
+        let frange = FileRange {
+            file_id: position.file_id,
+            range: TextRange::offset_len(position.offset, 0.into()),
+        };
+        assert!(AssistCtx::with_ctx(&db, frange, true, add_allow).is_none());
+    }
+
+    #[test]
+    fn add_allow_target() {
+        check_assist_target(add_allow, "fn f() { let x<|> = 92; }", "let x = 92;");
+    }
+}
@@ -0,0 +1,66 @@
+use hir::{db::HirDatabase, source_binder};
+use ra_syntax::{AstNode, ast};
+
+use crate::{
+    AssistCtx, Assist, AssistId,
+    utils::{type_annotation, unannotated_pat},
+};
+
+pub(crate) fn add_explicit_type(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    // A closure parameter is more specific than the `let` it may be in.
+    let pat = match ctx.node_at_offset::<ast::Param>().and_then(|it| unannotated_pat(it.syntax())) {
+        Some(it) => it,
+        None => unannotated_pat(ctx.node_at_offset::<ast::LetStmt>()?.syntax())?,
+    };
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, pat.syntax())?;
+    let (offset, annotation) = type_annotation(ctx.db, function, pat)?;
+
+    ctx.add_action(AssistId("add_explicit_type"), "add explicit type", |edit| {
+        edit.target(pat.syntax().range());
+        edit.insert(offset, annotation);
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_explicit_type_to_let() {
+        check_assist(
+            add_explicit_type,
+            "struct S; fn f() { let <|>s = S; }",
+            "struct S; fn f() { let <|>s: S = S; }",
+        );
+        check_assist(
+            add_explicit_type,
+            "fn f() { let (a, b) = (1u8, <|>true); }",
+            "fn f() { let (a, b): (u8, bool) = (1u8, <|>true); }",
+        );
+    }
+
+    #[test]
+    fn add_explicit_type_to_closure_param() {
+        check_assist(
+            add_explicit_type,
+            "struct S; fn f(s: S) { let id = |<|>x| x; id(&s); }",
+            "struct S; fn f(s: S) { let id = |<|>x: &S| x; id(&s); }",
+        );
+    }
+
+    #[test]
+    fn add_explicit_type_not_applicable() {
+        check_assist_not_applicable(add_explicit_type, "fn f() { let <|>x: u32 = 1; }");
+        check_assist_not_applicable(add_explicit_type, "fn f() { let <|>x = foo(); }");
+        check_assist_not_applicable(add_explicit_type, "fn f() { let <|>g = || 1u8; }");
+    }
+
+    #[test]
+    fn add_explicit_type_target() {
+        check_assist_target(add_explicit_type, "fn f() { let <|>x = 1u8; }", "x");
+    }
+}
@@ -1,33 +1,20 @@
-use join_to_string::join;
 use hir::db::HirDatabase;
 use ra_syntax::{
-    ast::{self, AstNode, AstToken, NameOwner, TypeParamsOwner},
+    ast::{self, AstNode},
     TextUnit,
 };
 
-use crate::{AssistCtx, Assist, AssistId};
+use crate::{AssistCtx, Assist, AssistId, utils::impl_header};
 
 pub(crate) fn add_impl(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
     let nominal = ctx.node_at_offset::<ast::NominalDef>()?;
-    let name = nominal.name()?;
+    let header = impl_header(nominal)?;
     ctx.add_action(AssistId("add_impl"), "add impl", |edit| {
         edit.target(nominal.syntax().range());
-        let type_params = nominal.type_param_list();
         let start_offset = nominal.syntax().range().end();
         let mut buf = String::new();
-        buf.push_str("\n\nimpl");
-        if let Some(type_params) = type_params {
-            type_params.syntax().text().push_to(&mut buf);
-        }
-        buf.push_str(" ");
-        buf.push_str(name.text().as_str());
-        if let Some(type_params) = type_params {
-            let lifetime_params =
-                type_params.lifetime_params().filter_map(|it| it.lifetime()).map(|it| it.text());
-            let type_params =
-                type_params.type_params().filter_map(|it| it.name()).map(|it| it.text());
-            join(lifetime_params.chain(type_params)).surround_with("<", ">").to_buf(&mut buf);
-        }
+        buf.push_str("\n\n");
+        buf.push_str(&header);
         buf.push_str(" {\n");
         edit.set_cursor(start_offset + TextUnit::of_str(&buf));
         buf.push_str("\n}");
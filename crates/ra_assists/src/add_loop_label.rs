@@ -0,0 +1,139 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    SyntaxNode, TextRange, TextUnit,
+    SyntaxKind::{
+        LOOP_EXPR, WHILE_EXPR, FOR_EXPR, LABEL, LIFETIME, BLOCK, BREAK_EXPR, CONTINUE_EXPR,
+        FN_DEF, LAMBDA_EXPR,
+    },
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn add_loop_label(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let loop_expr = ctx
+        .leaf_at_offset()
+        .filter_map(|leaf| {
+            leaf.ancestors().find(|it| [LOOP_EXPR, WHILE_EXPR, FOR_EXPR].contains(&it.kind()))
+        })
+        .min_by_key(|it| it.range().len())?;
+    if loop_expr.children().any(|it| it.kind() == LABEL) {
+        return None;
+    }
+    let body = loop_expr.children().find(|it| it.kind() == BLOCK)?;
+
+    // Only offer the assist on the loop header, not in the body.
+    let head = TextRange::from_to(loop_expr.range().start(), body.range().start());
+    if !head.contains_inclusive(ctx.frange.range.start()) {
+        return None;
+    }
+
+    let label = fresh_label(loop_expr);
+    let insert = format!("{}: ", label);
+    let start = loop_expr.range().start();
+    let cursor = match first_unlabeled_jump(body) {
+        Some(keyword_end) => keyword_end + TextUnit::of_str(&insert),
+        None => start + TextUnit::of_str(&label),
+    };
+    ctx.add_action(AssistId("add_loop_label"), "add loop label", |edit| {
+        edit.target(head);
+        edit.insert(start, insert);
+        edit.set_cursor(cursor);
+    });
+
+    ctx.build()
+}
+
+/// Picks a label which isn't used by any loop in the same function.
+fn fresh_label(loop_expr: &SyntaxNode) -> String {
+    let scope = loop_expr
+        .ancestors()
+        .find(|it| it.kind() == FN_DEF)
+        .unwrap_or_else(|| loop_expr.ancestors().last().unwrap());
+    let used: Vec<_> = scope
+        .descendants()
+        .filter(|it| it.kind() == LIFETIME)
+        .filter_map(SyntaxNode::leaf_text)
+        .collect();
+    let mut label = "'outer".to_string();
+    let mut idx = 1;
+    while used.iter().any(|it| it.as_str() == label) {
+        idx += 1;
+        label = format!("'outer{}", idx);
+    }
+    label
+}
+
+/// Finds the first `break` or `continue` without a label in the loop body, and
+/// returns the end of its keyword, where the new label could be typed.
+fn first_unlabeled_jump(body: &SyntaxNode) -> Option<TextUnit> {
+    let jump = body
+        .descendants()
+        .filter(|it| it.kind() == BREAK_EXPR || it.kind() == CONTINUE_EXPR)
+        .filter(|it| !it.children().any(|child| child.kind() == LIFETIME))
+        // Closures and nested functions can't jump out of the loop.
+        .find(|it| {
+            it.ancestors()
+                .take_while(|ancestor| *ancestor != body)
+                .all(|ancestor| ancestor.kind() != LAMBDA_EXPR && ancestor.kind() != FN_DEF)
+        })?;
+    Some(jump.first_child()?.range().end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_loop_label_to_loop() {
+        check_assist(
+            add_loop_label,
+            "fn f() { lo<|>op { foo(); } }",
+            "fn f() { 'outer<|>: loop { foo(); } }",
+        );
+    }
+
+    #[test]
+    fn add_loop_label_places_cursor_after_break() {
+        check_assist(
+            add_loop_label,
+            "fn f() { <|>for x in xs { while cond { if x { break; } } } }",
+            "fn f() { 'outer: for x in xs { while cond { if x { break<|>; } } } }",
+        );
+        check_assist(
+            add_loop_label,
+            "fn f() { <|>while a { let g = || loop { break }; continue } }",
+            "fn f() { 'outer: while a { let g = || loop { break }; continue<|> } }",
+        );
+    }
+
+    #[test]
+    fn add_loop_label_avoids_existing_labels() {
+        check_assist(
+            add_loop_label,
+            "fn f() { <|>loop { 'outer: loop { break 'outer; } } }",
+            "fn f() { 'outer2<|>: loop { 'outer: loop { break 'outer; } } }",
+        );
+        check_assist(
+            add_loop_label,
+            "fn f() { 'outer: loop { <|>loop { 'outer2: for x in xs { continue 'outer; } } } }",
+            "fn f() { 'outer: loop { 'outer3<|>: loop { 'outer2: for x in xs { continue 'outer; } } } }",
+        );
+    }
+
+    #[test]
+    fn add_loop_label_not_applicable() {
+        check_assist_not_applicable(add_loop_label, "fn f() { 'a: lo<|>op {} }");
+        check_assist_not_applicable(
+            add_loop_label,
+            "fn f() { loop { 'inner: <|>loop { break 'inner; } } }",
+        );
+        check_assist_not_applicable(add_loop_label, "fn f() { loop { fo<|>o(); } }");
+        check_assist_not_applicable(add_loop_label, "fn f() { <|>{ foo(); } }");
+    }
+
+    #[test]
+    fn add_loop_label_target() {
+        check_assist_target(add_loop_label, "fn f() { while <|>a { b } }", "while a ");
+    }
+}
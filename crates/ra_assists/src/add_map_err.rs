@@ -0,0 +1,191 @@
+use hir::{Function, Ty, TypeRef, db::HirDatabase, source_binder};
+use ra_syntax::{
+    AstNode, TextUnit, ast,
+    SyntaxKind::{FN_DEF, LAMBDA_EXPR},
+};
+
+use crate::{
+    AssistCtx, Assist, AssistId,
+    utils::{crate_modules, enum_name},
+};
+
+/// Adds `.map_err(|e| )` before a `?` whose error type differs from the one
+/// the function returns, unless `?` converts the error with a `From` impl.
+pub(crate) fn add_map_err(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let try_expr = ctx.node_at_offset::<ast::TryExpr>()?;
+    let expr = try_expr.expr()?;
+    // `?` inside a closure returns from the closure.
+    if try_expr
+        .syntax()
+        .ancestors()
+        .take_while(|it| it.kind() != FN_DEF)
+        .any(|it| it.kind() == LAMBDA_EXPR)
+    {
+        return None;
+    }
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, try_expr.syntax())?;
+    let infer = function.infer(ctx.db);
+    let source_map = function.body_source_map(ctx.db);
+    let expr_error = result_error_type(ctx.db, &infer[source_map.node_expr(expr)?])?;
+    let ret_type = function.ty(ctx.db).callable_sig(ctx.db)?.ret().clone();
+    let fn_error = result_error_type(ctx.db, &ret_type)?;
+    if expr_error == fn_error || *expr_error == Ty::Unknown || *fn_error == Ty::Unknown {
+        return None;
+    }
+    if has_from_impl(ctx.db, function, expr_error, fn_error)? {
+        return None;
+    }
+
+    let offset = expr.syntax().range().end();
+    ctx.add_action(AssistId("add_map_err"), "add map_err", |edit| {
+        edit.target(try_expr.syntax().range());
+        edit.insert(offset, ".map_err(|e| )");
+        edit.set_cursor(offset + TextUnit::of_str(".map_err(|e| "));
+    });
+
+    ctx.build()
+}
+
+/// The `E` of a `Result<T, E>`.
+fn result_error_type<'a>(db: &impl HirDatabase, ty: &'a Ty) -> Option<&'a Ty> {
+    match ty {
+        Ty::Adt { substs, .. } if enum_name(db, ty)? == "Result" => substs.get(1),
+        _ => None,
+    }
+}
+
+/// Whether there is an `impl From<from> for to`. It can only be in the crate
+/// of `function` or in one of its dependencies.
+fn has_from_impl(db: &impl HirDatabase, function: Function, from: &Ty, to: &Ty) -> Option<bool> {
+    let mut crates = vec![function.module(db).krate(db)?];
+    let mut idx = 0;
+    while idx < crates.len() {
+        for dep in crates[idx].dependencies(db) {
+            if !crates.contains(&dep.krate) {
+                crates.push(dep.krate);
+            }
+        }
+        idx += 1;
+    }
+    let modules = crates.into_iter().filter_map(|it| crate_modules(db, it)).flatten();
+    for impl_block in modules.flat_map(|it| it.impl_blocks(db)) {
+        let trait_name = impl_block.target_trait(db).and_then(|it| it.name(db));
+        if trait_name.map_or(true, |it| it.to_string() != "From") || impl_block.target_ty(db) != *to
+        {
+            continue;
+        }
+        let source = impl_block.source(db).1;
+        let arg = source
+            .target_trait()
+            .and_then(|it| match it.kind() {
+                ast::TypeRefKind::PathType(it) => it.path(),
+                _ => None,
+            })
+            .and_then(|it| it.segment())
+            .and_then(|it| it.type_arg_list())
+            .and_then(|it| it.type_args().next())
+            .and_then(|it| it.type_ref());
+        let arg = match arg {
+            Some(it) => TypeRef::from_ast(it),
+            None => continue,
+        };
+        if Ty::from_hir(db, &impl_block.resolver(db), &arg) == *from {
+            return Some(true);
+        }
+    }
+    Some(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use hir::{crate_graph, mock::MockDatabase};
+    use ra_db::FileRange;
+    use ra_syntax::TextRange;
+
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    const RESULT: &str = "
+enum Result<T, E> { Ok(T), Err(E) }
+struct IoError;
+struct ParseError;
+fn read() -> Result<u32, IoError> { loop {} }";
+
+    #[test]
+    fn add_map_err_on_mismatched_error() {
+        check_assist(
+            add_map_err,
+            &format!("{}\nfn f() -> Result<u32, ParseError> {{ let x = read()<|>?; }}", RESULT),
+            &format!(
+                "{}\nfn f() -> Result<u32, ParseError> {{ let x = read().map_err(|e| <|>)?; }}",
+                RESULT
+            ),
+        );
+    }
+
+    #[test]
+    fn add_map_err_not_applicable() {
+        // The error types match.
+        check_assist_not_applicable(
+            add_map_err,
+            &format!("{}\nfn f() -> Result<(), IoError> {{ let x = read()<|>?; }}", RESULT),
+        );
+        // The function doesn't return a `Result`.
+        check_assist_not_applicable(
+            add_map_err,
+            &format!("{}\nfn f() {{ let x = read()<|>?; }}", RESULT),
+        );
+        // `?` converts the error with `From`.
+        check_assist_not_applicable(
+            add_map_err,
+            &format!(
+                "{}\ntrait From<T> {{ fn from(t: T) -> Self; }}
+impl From<IoError> for ParseError {{ fn from(e: IoError) -> ParseError {{ ParseError }} }}
+fn f() -> Result<u32, ParseError> {{ let x = read()<|>?; }}",
+                RESULT
+            ),
+        );
+        // The `?` returns from the closure.
+        check_assist_not_applicable(
+            add_map_err,
+            &format!("{}\nfn f() -> Result<(), ParseError> {{ let g = || read()<|>?; }}", RESULT),
+        );
+    }
+
+    #[test]
+    fn add_map_err_not_applicable_with_from_impl_of_dependency() {
+        let (mut db, position) = MockDatabase::with_position(
+            "
+//- /main.rs
+use errors::{Result, IoError, AnyError};
+fn read() -> Result<u32, IoError> { loop {} }
+fn f() -> Result<u32, AnyError> { let x = read()<|>?; }
+//- /lib.rs
+pub enum Result<T, E> { Ok(T), Err(E) }
+pub struct IoError;
+pub struct AnyError;
+pub trait From<T> { fn from(t: T) -> Self; }
+impl From<IoError> for AnyError { fn from(e: IoError) -> AnyError { AnyError } }
+",
+        );
+        db.set_crate_graph_from_fixture(crate_graph! {
+            "main": ("/main.rs", ["errors"]),
+            "errors": ("/lib.rs", []),
+        });
+        let frange = FileRange {
+            file_id: position.file_id,
+            range: TextRange::offset_len(position.offset, 0.into()),
+        };
+        assert!(AssistCtx::with_ctx(&db, frange, true, add_map_err).is_none());
+    }
+
+    #[test]
+    fn add_map_err_target() {
+        check_assist_target(
+            add_map_err,
+            &format!("{}\nfn f() -> Result<(), ParseError> {{ g(read()<|>?); }}", RESULT),
+            "read()?",
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode},
+    algo::non_trivia_sibling,
+    Direction, SyntaxNode, TextRange, TextUnit,
+    SyntaxKind::{
+        L_PAREN, R_PAREN, L_CURLY, R_CURLY, L_BRACK, R_BRACK, EXCL, IDENT, TOKEN_TREE, COMMA,
+        MACRO_CALL, EXPR_STMT, WHITESPACE,
+    },
+};
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn add_dbg(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let mut expr = if ctx.frange.range.is_empty() {
+        ctx.leaf_at_offset()
+            .filter(|leaf| !leaf.kind().is_trivia())
+            .filter_map(enclosing_expr)
+            .min_by_key(|it| it.syntax().range().len())?
+    } else {
+        enclosing_expr(ctx.covering_node())?
+    };
+    match expr.kind() {
+        ast::ExprKind::ReturnExpr(_)
+        | ast::ExprKind::BreakExpr(_)
+        | ast::ExprKind::ContinueExpr(_) => return None,
+        _ => (),
+    }
+    // Wrap `foo(x)` rather than `foo` if the cursor is on the callee.
+    if let Some(call) = expr.syntax().parent().and_then(ast::CallExpr::cast) {
+        if call.expr().map(ast::Expr::syntax) == Some(expr.syntax()) {
+            expr = ast::Expr::cast(call.syntax())?;
+        }
+    }
+
+    let range = expr.syntax().range();
+    let cursor = ctx.frange.range.start() + TextUnit::of_str("dbg!(");
+    ctx.add_action(AssistId("add_dbg"), "wrap in dbg!()", |edit| {
+        edit.target(range);
+        edit.replace(range, format!("dbg!({})", expr.syntax().text()));
+        edit.set_cursor(cursor);
+    });
+
+    ctx.build()
+}
+
+/// Macro arguments are not parsed, so we can't wrap anything inside them.
+fn enclosing_expr(node: &SyntaxNode) -> Option<&ast::Expr> {
+    node.ancestors().take_while(|it| it.kind() != MACRO_CALL).find_map(ast::Expr::cast)
+}
+
+pub(crate) fn remove_dbg(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let macro_call = ctx.node_at_offset::<ast::MacroCall>()?;
+
+    if !is_valid_macrocall(macro_call, "dbg")? {
+        return None;
+    }
+
+    // In `dbg!(dbg!(x))`, only unwrap the innermost call around the cursor.
+    let offset = ctx.frange.range.start();
+    let call = dbg_calls(macro_call)
+        .into_iter()
+        .filter(|it| it.range.contains_inclusive(offset))
+        .min_by_key(|it| it.range.len())?;
+
+    // If the cursor is inside the macro call, we'll try to maintain the cursor
+    // position by subtracting the length of dbg!( from the start of the file
+    // range, otherwise we'll default to using the start of the macro call
+    let content_start = call.args.range().start() + TextUnit::of_char('(');
+    let mut content_end = call.args.range().end() - TextUnit::of_char(')');
+    // `dbg!(a, b)` returns the tuple `(a, b)`; a trailing comma is dropped.
+    let mut commas = call.args.children().filter(|it| it.kind() == COMMA).collect::<Vec<_>>();
+    if let Some(&comma) = commas.last() {
+        if non_trivia_sibling(comma, Direction::Next) == call.args.last_child() {
+            content_end = comma.range().start();
+            commas.pop();
+        }
+    }
+    let is_tuple = !commas.is_empty();
+    let cursor_pos = if offset > content_start {
+        let paren = if is_tuple { TextUnit::of_char('(') } else { 0.into() };
+        call.range.start() + paren + (offset.min(content_end) - content_start)
+    } else {
+        call.range.start()
+    };
+
+    let macro_content = call.args.text().slice(content_start..content_end).to_string();
+    let macro_content = if is_tuple { format!("({})", macro_content) } else { macro_content };
+
+    // `dbg!()` without an argument is removed altogether, together with its
+    // statement.
+    let mut range = call.range;
+    if macro_content.trim().is_empty() && range == macro_call.syntax().range() {
+        if let Some(stmt) = macro_call.syntax().parent().filter(|it| it.kind() == EXPR_STMT) {
+            range = stmt.range();
+            if let Some(ws) = stmt.next_sibling().filter(|it| it.kind() == WHITESPACE) {
+                range = TextRange::from_to(range.start(), ws.range().end());
+            }
+        }
+    }
+    let replacement = if macro_content.trim().is_empty() { String::new() } else { macro_content };
+
+    ctx.add_action(AssistId("remove_dbg"), "remove dbg!()", |edit| {
+        edit.target(call.range);
+        edit.replace(range, replacement);
+        edit.set_cursor(if range == call.range { cursor_pos } else { range.start() });
+    });
+
+    ctx.build()
+}
+
+/// A `dbg!` call, either the macro call itself, or a nested one in its
+/// arguments.
+struct DbgCall<'a> {
+    range: TextRange,
+    args: &'a SyntaxNode,
+}
+
+fn dbg_calls(macro_call: &ast::MacroCall) -> Vec<DbgCall<'_>> {
+    let args = match macro_call.token_tree() {
+        Some(it) => it.syntax(),
+        None => return Vec::new(),
+    };
+    let mut res = vec![DbgCall { range: macro_call.syntax().range(), args }];
+    // Nested calls are just tokens: `IDENT EXCL TOKEN_TREE`.
+    res.extend(
+        args.descendants()
+            .filter(|it| it.kind() == IDENT && it.leaf_text().map_or(false, |text| text == "dbg"))
+            .filter_map(|name| {
+                let excl = non_trivia_sibling(name, Direction::Next)?;
+                let args = non_trivia_sibling(excl, Direction::Next)?;
+                if excl.kind() != EXCL || args.kind() != TOKEN_TREE || !is_delimited(args) {
+                    return None;
+                }
+                Some(DbgCall {
+                    range: TextRange::from_to(name.range().start(), args.range().end()),
+                    args,
+                })
+            }),
+    );
+    res
+}
+
+/// Verifies that the given macro_call actually matches the given name
+/// and contains proper ending tokens
+fn is_valid_macrocall(macro_call: &ast::MacroCall, macro_name: &str) -> Option<bool> {
+    let path = macro_call.path()?;
+    let name_ref = path.segment()?.name_ref()?;
+
+    // Make sure it is actually a dbg-macro call, dbg followed by !
+    let excl = path.syntax().next_sibling()?;
+
+    if name_ref.text() != macro_name || excl.kind() != EXCL {
+        return None;
+    }
+
+    Some(is_delimited(macro_call.token_tree()?.syntax()))
+}
+
+fn is_delimited(token_tree: &SyntaxNode) -> bool {
+    let (first_child, last_child) = match (token_tree.first_child(), token_tree.last_child()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return false,
+    };
+    match (first_child.kind(), last_child.kind()) {
+        (L_PAREN, R_PAREN) | (L_BRACK, R_BRACK) | (L_CURLY, R_CURLY) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_range, check_assist_not_applicable};
+    use crate::helpers::check_assist_target;
+
+    #[test]
+    fn test_remove_dbg() {
+        check_assist(remove_dbg, "<|>dbg!(1 + 1)", "<|>1 + 1");
+
+        check_assist(remove_dbg, "dbg!<|>((1 + 1))", "<|>(1 + 1)");
+
+        check_assist(remove_dbg, "dbg!(1 <|>+ 1)", "1 <|>+ 1");
+
+        check_assist(remove_dbg, "let _ = <|>dbg!(1 + 1)", "let _ = <|>1 + 1");
+
+        check_assist(
+            remove_dbg,
+            "
+fn foo(n: usize) {
+    if let Some(_) = dbg!(n.<|>checked_sub(4)) {
+        // ...
+    }
+}
+",
+            "
+fn foo(n: usize) {
+    if let Some(_) = n.<|>checked_sub(4) {
+        // ...
+    }
+}
+",
+        );
+    }
+    #[test]
+    fn test_remove_dbg_with_several_args() {
+        check_assist(remove_dbg, "fn f() { foo(<|>dbg!(a, b)); }", "fn f() { foo(<|>(a, b)); }");
+        check_assist(remove_dbg, "fn f() { foo(dbg!(a, <|>b,)); }", "fn f() { foo((a, <|>b)); }");
+        check_assist(remove_dbg, "let _ = dbg!((a, b), <|>c)", "let _ = ((a, b), <|>c)");
+    }
+
+    #[test]
+    fn test_remove_dbg_with_trailing_comma() {
+        check_assist(remove_dbg, "fn f() { foo(<|>dbg!(x,)); }", "fn f() { foo(<|>x); }");
+        check_assist(remove_dbg, "fn f() { foo(dbg!(x<|>, )); }", "fn f() { foo(x<|>); }");
+    }
+
+    #[test]
+    fn test_remove_dbg_with_brackets_and_braces() {
+        check_assist(remove_dbg, "dbg![<|>1 + 1]", "<|>1 + 1");
+        check_assist(remove_dbg, "dbg!{<|>1 + 1}", "<|>1 + 1");
+    }
+
+    #[test]
+    fn test_remove_nested_dbg() {
+        check_assist(remove_dbg, "dbg!(dbg!(x<|>))", "dbg!(x<|>)");
+        check_assist(remove_dbg, "dbg!(<|>dbg!(x))", "dbg!(<|>x)");
+        check_assist(remove_dbg, "<|>dbg!(dbg!(x))", "<|>dbg!(x)");
+        check_assist(remove_dbg, "dbg!((1, dbg !(<|>x)))", "dbg!((1, <|>x))");
+    }
+
+    #[test]
+    fn test_remove_empty_dbg() {
+        check_assist(
+            remove_dbg,
+            "fn f() { foo(); <|>dbg!(); bar(); }",
+            "fn f() { foo(); <|>bar(); }",
+        );
+        check_assist(remove_dbg, "fn f() { foo(dbg!(<|>)); }", "fn f() { foo(<|>); }");
+    }
+
+    #[test]
+    fn test_remove_dbg_not_applicable() {
+        check_assist_not_applicable(remove_dbg, "<|>vec![1, 2, 3]");
+        check_assist_not_applicable(remove_dbg, "<|>dbg(5, 6, 7)");
+        check_assist_not_applicable(remove_dbg, "<|>dbg!(5, 6, 7");
+    }
+
+    #[test]
+    fn remove_dbg_target() {
+        check_assist_target(
+            remove_dbg,
+            "
+fn foo(n: usize) {
+    if let Some(_) = dbg!(n.<|>checked_sub(4)) {
+        // ...
+    }
+}
+",
+            "dbg!(n.checked_sub(4))",
+        );
+    }
+
+    #[test]
+    fn test_add_dbg() {
+        check_assist(
+            add_dbg,
+            "fn f() { let x = fo<|>o(1); }",
+            "fn f() { let x = dbg!(fo<|>o(1)); }",
+        );
+        check_assist(add_dbg, "fn f() { foo(<|>x, 1); }", "fn f() { foo(dbg!(<|>x), 1); }");
+        check_assist(add_dbg, "fn f() { s.fo<|>o().bar() }", "fn f() { dbg!(s.fo<|>o()).bar() }");
+    }
+
+    #[test]
+    fn test_add_dbg_to_selection() {
+        check_assist_range(
+            add_dbg,
+            "fn f() { foo(<|>a + b<|>, c); }",
+            "fn f() { foo(dbg!(<|>a + b), c); }",
+        );
+    }
+
+    #[test]
+    fn test_add_dbg_not_applicable() {
+        check_assist_not_applicable(add_dbg, "fn f() { <|> }");
+        check_assist_not_applicable(add_dbg, "fn f() { dbg!(<|>x); }");
+        check_assist_not_applicable(add_dbg, "fn f() { loop { <|>break; } }");
+    }
+
+    #[test]
+    fn add_dbg_target() {
+        check_assist_target(add_dbg, "fn f() { let x = 1 + fo<|>o(2); }", "foo(2)");
+    }
+}
@@ -0,0 +1,167 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    AstNode, TextUnit,
+    ast::{self, AttrsOwner, ModuleItemOwner, NameOwner},
+    SyntaxKind::SOURCE_FILE,
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn add_test_module(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    // Only offered between the items of the file.
+    let scope = ctx
+        .covering_node()
+        .ancestors()
+        .find(|it| it.kind() == SOURCE_FILE || ast::ModuleItem::cast(it).is_some())?;
+    let file = ast::SourceFile::cast(scope)?;
+
+    match file.items().filter_map(|it| ast::Module::cast(it.syntax())).find(|it| is_test_module(it))
+    {
+        Some(module) => {
+            let item_list = module.item_list()?;
+            let indent = leading_indent(module.syntax()).unwrap_or("").to_string();
+            let fn_name = fresh_test_name(item_list);
+            let offset = match item_list.items().last() {
+                Some(last) => last.syntax().range().end(),
+                None => item_list.syntax().range().start() + TextUnit::of_char('{'),
+            };
+            ctx.add_action(AssistId("add_test_function"), "add test function", |edit| {
+                edit.target(module.syntax().range());
+                let prefix = if item_list.items().next().is_some() { "\n\n" } else { "\n" };
+                let mut buf = format!(
+                    "{}{indent}    #[test]\n{indent}    fn {}() {{\n{indent}        ",
+                    prefix,
+                    fn_name,
+                    indent = indent,
+                );
+                edit.set_cursor(offset + TextUnit::of_str(&buf));
+                buf.push_str(&format!("\n{indent}    }}", indent = indent));
+                if item_list.items().next().is_none() {
+                    buf.push_str(&format!("\n{}", indent));
+                }
+                edit.insert(offset, buf);
+            });
+        }
+        None => {
+            let offset = file.syntax().range().end();
+            ctx.add_action(AssistId("add_test_module"), "add test module", |edit| {
+                edit.target(file.syntax().range());
+                let ends_with_newline = file.syntax().text().to_string().ends_with('\n');
+                let mut buf = String::from(if ends_with_newline { "\n" } else { "\n\n" });
+                buf.push_str("#[cfg(test)]\nmod tests {\n    use super::*;\n\n");
+                buf.push_str("    #[test]\n    fn it_works() {\n        ");
+                edit.set_cursor(offset + TextUnit::of_str(&buf));
+                buf.push_str("\n    }\n}\n");
+                edit.insert(offset, buf);
+            });
+        }
+    }
+
+    ctx.build()
+}
+
+/// Checks for `#[cfg(test)] mod tests`.
+fn is_test_module(module: &ast::Module) -> bool {
+    let is_cfg_test = module
+        .attrs()
+        .filter_map(|it| it.as_call())
+        .any(|(name, args)| name == "cfg" && args.syntax().text() == "(test)");
+    is_cfg_test && module.name().map_or(false, |it| it.text() == "tests")
+}
+
+/// Picks `it_works`, or `it_works_2` and so on if the name is taken.
+fn fresh_test_name(item_list: &ast::ItemList) -> String {
+    let names = item_list
+        .items()
+        .filter_map(|it| ast::FnDef::cast(it.syntax()))
+        .filter_map(|it| it.name())
+        .map(|it| it.text().to_string())
+        .collect::<Vec<_>>();
+    let mut name = "it_works".to_string();
+    let mut idx = 1;
+    while names.contains(&name) {
+        idx += 1;
+        name = format!("it_works_{}", idx);
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    #[test]
+    fn add_test_module_at_end_of_file() {
+        check_assist(
+            add_test_module,
+            "fn foo() {}\n<|>\nfn bar() {}\n",
+            "fn foo() {}\n
+fn bar() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        <|>
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn add_test_function_to_existing_module() {
+        check_assist(
+            add_test_module,
+            "
+fn foo() {}
+<|>
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {}
+}",
+            "
+fn foo() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {}
+
+    #[test]
+    fn it_works_2() {
+        <|>
+    }
+}",
+        );
+        check_assist(
+            add_test_module,
+            "<|>
+#[cfg(test)]
+mod tests {}",
+            "
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn it_works() {
+        <|>
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_test_module_not_applicable_in_items() {
+        check_assist_not_applicable(add_test_module, "fn foo() { <|> }");
+        check_assist_not_applicable(add_test_module, "struct <|>Foo;");
+    }
+}
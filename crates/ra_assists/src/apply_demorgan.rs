@@ -0,0 +1,133 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    AstNode, SyntaxNode,
+    ast::{self, BinOp, PrefixOp},
+};
+
+use crate::{
+    AssistCtx, Assist, AssistId,
+    utils::{negate_with_prec, Prec},
+};
+
+pub(crate) fn apply_demorgan(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let prefix_expr = ctx.node_at_offset::<ast::PrefixExpr>()?;
+    if prefix_expr.op()? != PrefixOp::Not {
+        return None;
+    }
+    let paren_expr = match prefix_expr.expr()?.kind() {
+        ast::ExprKind::ParenExpr(it) => it,
+        _ => return None,
+    };
+    let bin_expr = match paren_expr.expr()?.kind() {
+        ast::ExprKind::BinExpr(it) => it,
+        _ => return None,
+    };
+    match bin_expr.op()? {
+        BinOp::BooleanAnd | BinOp::BooleanOr => (),
+        _ => return None,
+    }
+
+    ctx.add_action(AssistId("apply_demorgan"), "apply De Morgan's law", |edit| {
+        let (mut replacement, prec) = negate_with_prec(bin_expr.into());
+        if needs_parens_in_parent(prefix_expr.syntax(), prec) {
+            replacement = format!("({})", replacement);
+        }
+        edit.target(prefix_expr.syntax().range());
+        edit.replace(prefix_expr.syntax().range(), replacement);
+        edit.set_cursor(prefix_expr.syntax().range().start());
+    });
+
+    ctx.build()
+}
+
+/// Checks whether an expression with the given top-level operator would need
+/// parentheses if it replaced `node`.
+fn needs_parens_in_parent(node: &SyntaxNode, prec: Prec) -> bool {
+    if prec == Prec::Atom {
+        return false;
+    }
+    let parent = match node.parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    if let Some(bin) = ast::BinExpr::cast(parent) {
+        let parent_prec = match bin.op() {
+            Some(BinOp::BooleanOr) => Prec::Or,
+            Some(BinOp::BooleanAnd) => Prec::And,
+            _ => return true,
+        };
+        return parent_prec != prec;
+    }
+    ast::PrefixExpr::cast(parent).is_some()
+        || ast::MethodCallExpr::cast(parent).is_some()
+        || ast::FieldExpr::cast(parent).is_some()
+        || ast::CastExpr::cast(parent).is_some()
+        || ast::RefExpr::cast(parent).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn demorgan_turns_and_into_or() {
+        check_assist(
+            apply_demorgan,
+            "fn f() { if <|>!(a && b) {} }",
+            "fn f() { if <|>!a || !b {} }",
+        )
+    }
+
+    #[test]
+    fn demorgan_turns_or_into_and() {
+        check_assist(
+            apply_demorgan,
+            "fn f() { if !(a ||<|> b) {} }",
+            "fn f() { if <|>!a && !b {} }",
+        )
+    }
+
+    #[test]
+    fn demorgan_simplifies_operands() {
+        check_assist(
+            apply_demorgan,
+            "fn f() { if <|>!(!a && x == y && z < 1) {} }",
+            "fn f() { if <|>a || x != y || z >= 1 {} }",
+        )
+    }
+
+    #[test]
+    fn demorgan_nested_conjunction_disjunction() {
+        check_assist(
+            apply_demorgan,
+            "fn f() { if <|>!(a && (b || !c)) {} }",
+            "fn f() { if <|>!a || (!b && c) {} }",
+        );
+        check_assist(
+            apply_demorgan,
+            "fn f() { if <|>!(a || b && c.is_ok()) {} }",
+            "fn f() { if <|>!a && (!b || !c.is_ok()) {} }",
+        );
+    }
+
+    #[test]
+    fn demorgan_parenthesizes_inside_conjunction() {
+        check_assist(
+            apply_demorgan,
+            "fn f() { if x && <|>!(a && b) {} }",
+            "fn f() { if x && <|>(!a || !b) {} }",
+        )
+    }
+
+    #[test]
+    fn demorgan_not_applicable_without_negation() {
+        check_assist_not_applicable(apply_demorgan, "fn f() { if a &&<|> b {} }");
+        check_assist_not_applicable(apply_demorgan, "fn f() { if <|>!(a + b) {} }");
+    }
+
+    #[test]
+    fn demorgan_target() {
+        check_assist_target(apply_demorgan, "fn f() { if x && <|>!(a && b) {} }", "!(a && b)")
+    }
+}
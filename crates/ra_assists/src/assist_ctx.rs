@@ -1,13 +1,14 @@
 use hir::db::HirDatabase;
 use ra_text_edit::TextEditBuilder;
-use ra_db::FileRange;
+use ra_db::{FileId, FileRange, SourceRootId};
+use relative_path::RelativePathBuf;
 use ra_syntax::{
     SourceFile, TextRange, AstNode, TextUnit, SyntaxNode,
     algo::{find_leaf_at_offset, find_node_at_offset, find_covering_node, LeafAtOffset},
 };
 use ra_fmt::{leading_indent, reindent};
 
-use crate::{AssistLabel, AssistAction, AssistId};
+use crate::{AssistLabel, AssistAction, AssistId, FileSystemEdit};
 
 #[derive(Clone, Debug)]
 pub(crate) enum Assist {
@@ -121,6 +122,7 @@ pub(crate) struct AssistBuilder {
     edit: TextEditBuilder,
     cursor_position: Option<TextUnit>,
     target: Option<TextRange>,
+    file_system_edits: Vec<FileSystemEdit>,
 }
 
 impl AssistBuilder {
@@ -140,7 +142,6 @@ impl AssistBuilder {
         self.replace(node.range(), replace_with)
     }
 
-    #[allow(unused)]
     pub(crate) fn delete(&mut self, range: TextRange) {
         self.edit.delete(range)
     }
@@ -157,11 +158,26 @@ impl AssistBuilder {
         self.target = Some(target)
     }
 
+    pub(crate) fn create_file(
+        &mut self,
+        source_root: SourceRootId,
+        path: RelativePathBuf,
+        contents: impl Into<String>,
+    ) {
+        let contents = contents.into();
+        self.file_system_edits.push(FileSystemEdit::CreateFile { source_root, path, contents })
+    }
+
+    pub(crate) fn delete_file(&mut self, file_id: FileId) {
+        self.file_system_edits.push(FileSystemEdit::DeleteFile { file_id })
+    }
+
     fn build(self) -> AssistAction {
         AssistAction {
             edit: self.edit.finish(),
             cursor_position: self.cursor_position,
             target: self.target,
+            file_system_edits: self.file_system_edits,
         }
     }
 }
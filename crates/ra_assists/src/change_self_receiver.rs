@@ -0,0 +1,332 @@
+use hir::{Function, ImplItem, Mutability, ScopesWithSourceMap, Ty, db::HirDatabase, source_binder};
+use ra_db::FileId;
+use ra_syntax::{
+    AstNode, TextRange,
+    ast::{self, ArgListOwner, SelfParamFlavor, TypeAscriptionOwner, VisibilityOwner},
+    SyntaxKind::LIFETIME,
+};
+
+use crate::{
+    AssistCtx, Assist, AssistId,
+    utils::{crate_functions, receiver_text},
+};
+
+pub(crate) fn change_self_receiver(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let self_param = ctx.node_at_offset::<ast::SelfParam>()?;
+    // Leave `self: Box<Self>` and the like alone.
+    if self_param.ascribed_type().is_some() {
+        return None;
+    }
+    let method =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, self_param.syntax())?;
+    // The receivers of trait methods are decided by the trait.
+    if method.impl_block(ctx.db)?.target_trait(ctx.db).is_some() {
+        return None;
+    }
+
+    let current = self_param.flavor();
+    let lifetime = self_param.syntax().children().find(|it| it.kind() == LIFETIME);
+    let lifetime = lifetime.map(|it| it.text().to_string());
+    let range = self_param.syntax().range();
+    let flavors = [SelfParamFlavor::Owned, SelfParamFlavor::Ref, SelfParamFlavor::MutRef];
+    let flavors = flavors.iter().cloned().filter(|&it| it != current).collect::<Vec<_>>();
+
+    for &flavor in flavors.iter() {
+        let receiver = self_param_text(flavor, lifetime.as_ref());
+        ctx.add_action(
+            AssistId("change_self_receiver"),
+            format!("change receiver to `{}`", receiver),
+            |edit| {
+                edit.target(range);
+                edit.replace(range, receiver.clone());
+                edit.set_cursor(range.start());
+            },
+        );
+    }
+
+    let is_public =
+        method.source(ctx.db).1.visibility().map_or(false, |it| it.syntax().text() == "pub");
+    if is_public {
+        return ctx.build();
+    }
+    for &flavor in flavors.iter() {
+        let call_edits = match ufcs_call_edits(ctx.db, method, ctx.frange.file_id, current, flavor)
+        {
+            Some(it) => it,
+            None => continue,
+        };
+        if call_edits.is_empty() {
+            continue;
+        }
+        let receiver = self_param_text(flavor, lifetime.as_ref());
+        ctx.add_action(
+            AssistId("change_self_receiver_and_callers"),
+            format!("change receiver to `{}` and update callers", receiver),
+            |edit| {
+                edit.target(range);
+                edit.replace(range, receiver.clone());
+                for (range, arg) in call_edits {
+                    edit.replace(range, arg);
+                }
+                edit.set_cursor(range.start());
+            },
+        );
+    }
+
+    ctx.build()
+}
+
+fn self_param_text(flavor: SelfParamFlavor, lifetime: Option<&String>) -> String {
+    let lifetime = lifetime.map(|it| format!("{} ", it)).unwrap_or_default();
+    match flavor {
+        SelfParamFlavor::Owned => "self".to_string(),
+        SelfParamFlavor::Ref => format!("&{}self", lifetime),
+        SelfParamFlavor::MutRef => format!("&{}mut self", lifetime),
+    }
+}
+
+/// Finds the calls like `Type::method(&receiver)` in the crate and adjusts
+/// their receivers for the new `self` parameter. Method call syntax takes
+/// care of itself, as long as autoref can produce the new receiver. Returns
+/// `None` if some call can't be adjusted, in particular if it is not in
+/// `file_id`.
+fn ufcs_call_edits(
+    db: &impl HirDatabase,
+    method: Function,
+    file_id: FileId,
+    from: SelfParamFlavor,
+    to: SelfParamFlavor,
+) -> Option<Vec<(TextRange, String)>> {
+    let krate = method.module(db).krate(db)?;
+    let mut res = Vec::new();
+    for function in crate_functions(db, krate)? {
+        let (file, fn_def) = function.source(db);
+        let infer = function.infer(db);
+        let source_map = function.body_source_map(db);
+        let scopes = function.scopes(db);
+        for call in fn_def.syntax().descendants().filter_map(ast::MethodCallExpr::cast) {
+            let expr = match source_map.node_expr(call.into()) {
+                Some(it) => it,
+                None => continue,
+            };
+            if infer.method_resolution(expr) != Some(method) {
+                continue;
+            }
+            let receiver = call.expr()?;
+            let is_mut_ref = match &infer[source_map.node_expr(receiver)?] {
+                Ty::Ref(_, Mutability::Mut) => true,
+                Ty::Ref(_, Mutability::Shared) => false,
+                _ => is_mutable_local(&scopes, receiver)?,
+            };
+            match to {
+                SelfParamFlavor::Ref => (),
+                SelfParamFlavor::MutRef if is_mut_ref => (),
+                // `receiver.method()` would move the receiver, or borrow it
+                // mutably where it can't be.
+                _ => return None,
+            }
+        }
+        for call in fn_def.syntax().descendants().filter_map(ast::CallExpr::cast) {
+            let callee = match call.expr().and_then(|it| source_map.node_expr(it)) {
+                Some(it) => it,
+                None => continue,
+            };
+            if infer.assoc_resolutions_for_expr(callee) != Some(ImplItem::Method(method)) {
+                continue;
+            }
+            if file.original_file(db) != file_id {
+                return None;
+            }
+            let receiver = call.arg_list()?.args().next()?;
+            res.push((receiver.syntax().range(), adjusted_receiver(receiver, from, to)?));
+        }
+    }
+    Some(res)
+}
+
+/// Whether `expr` is a local variable declared `mut`. Returns `None` for
+/// anything else than a path.
+fn is_mutable_local(scopes: &ScopesWithSourceMap, expr: &ast::Expr) -> Option<bool> {
+    let path = match expr.kind() {
+        ast::ExprKind::PathExpr(it) => it.path()?,
+        _ => return Some(false),
+    };
+    if path.qualifier().is_some() {
+        return Some(false);
+    }
+    let name_ref = path.segment()?.name_ref()?;
+    let entry = match scopes.resolve_local_name(name_ref) {
+        Some(it) => it,
+        None => return Some(false),
+    };
+    let file = ast::SourceFile::cast(expr.syntax().ancestors().last()?)?;
+    let pat = ast::BindPat::cast(entry.ptr().to_node(file));
+    Some(pat.map_or(false, |it| it.is_mutable()))
+}
+
+fn adjusted_receiver(
+    receiver: &ast::Expr,
+    from: SelfParamFlavor,
+    to: SelfParamFlavor,
+) -> Option<String> {
+    let value = match receiver.kind() {
+        ast::ExprKind::RefExpr(it) => it.expr()?,
+        // We can't turn a reference held in a variable into another one.
+        _ if from != SelfParamFlavor::Owned => return None,
+        _ => receiver,
+    };
+    let value_text = receiver_text(value);
+    let res = match to {
+        SelfParamFlavor::Owned => value.syntax().text().to_string(),
+        SelfParamFlavor::Ref => format!("&{}", value_text),
+        SelfParamFlavor::MutRef => format!("&mut {}", value_text),
+    };
+    Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_nth_action, check_assist_not_applicable};
+
+    #[test]
+    fn change_ref_self_to_other_receivers() {
+        check_assist(
+            change_self_receiver,
+            "
+struct S;
+impl S {
+    fn foo(&se<|>lf) {}
+}",
+            "
+struct S;
+impl S {
+    fn foo(<|>self) {}
+}",
+        );
+        check_assist_nth_action(
+            change_self_receiver,
+            "
+struct S;
+impl S {
+    fn foo(&se<|>lf) {}
+}",
+            "
+struct S;
+impl S {
+    fn foo(<|>&mut self) {}
+}",
+            1,
+        );
+    }
+
+    #[test]
+    fn change_receiver_keeps_lifetime() {
+        check_assist_nth_action(
+            change_self_receiver,
+            "
+struct S;
+impl S {
+    fn foo<'a>(<|>self) {}
+}",
+            "
+struct S;
+impl S {
+    fn foo<'a>(<|>&self) {}
+}",
+            0,
+        );
+        check_assist_nth_action(
+            change_self_receiver,
+            "
+struct S;
+impl S {
+    fn foo<'a>(&'a <|>self) -> &'a S { self }
+}",
+            "
+struct S;
+impl S {
+    fn foo<'a>(<|>&'a mut self) -> &'a S { self }
+}",
+            1,
+        );
+    }
+
+    #[test]
+    fn change_receiver_and_update_callers() {
+        check_assist_nth_action(
+            change_self_receiver,
+            "
+struct S;
+impl S {
+    fn foo(&<|>self) {}
+}
+fn main() {
+    let mut s = S;
+    S::foo(&s);
+    s.foo();
+}",
+            "
+struct S;
+impl S {
+    fn foo(<|>&mut self) {}
+}
+fn main() {
+    let mut s = S;
+    S::foo(&mut s);
+    s.foo();
+}",
+            2,
+        );
+    }
+
+    #[test]
+    fn change_receiver_skips_moving_method_calls() {
+        // `s.foo()` would move `s` with `self`, so only `&self` updates callers.
+        check_assist_nth_action(
+            change_self_receiver,
+            "
+struct S;
+impl S {
+    fn foo(&mut <|>self) {}
+}
+fn main() {
+    let mut s = S;
+    S::foo(&mut s);
+    s.foo();
+}",
+            "
+struct S;
+impl S {
+    fn foo(<|>&self) {}
+}
+fn main() {
+    let mut s = S;
+    S::foo(&s);
+    s.foo();
+}",
+            2,
+        );
+    }
+
+    #[test]
+    fn change_self_receiver_not_applicable() {
+        check_assist_not_applicable(
+            change_self_receiver,
+            "
+trait T { fn foo(&self); }
+struct S;
+impl T for S {
+    fn foo(&<|>self) {}
+}",
+        );
+        check_assist_not_applicable(
+            change_self_receiver,
+            "
+struct S;
+impl S {
+    fn foo(self<|>: Box<Self>) {}
+}",
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use hir::{db::HirDatabase, source_binder};
+use ra_syntax::{
+    AstNode, SyntaxNode, TextRange,
+    ast::{self, NameOwner, TypeAscriptionOwner, VisibilityOwner},
+    algo::find_covering_node,
+    SyntaxKind::IDENT,
+};
+
+use crate::{AssistCtx, Assist, AssistId, utils::for_each_call};
+
+/// Macros which only borrow their arguments.
+const FORMAT_MACROS: &[&str] =
+    &["format", "print", "println", "eprint", "eprintln", "write", "writeln", "panic"];
+
+pub(crate) fn change_string_param(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let param = ctx.node_at_offset::<ast::Param>()?;
+    let type_ref = param.ascribed_type()?;
+    if type_ref.syntax().text() != "String" {
+        return None;
+    }
+    let pat = match param.pat()?.kind() {
+        ast::PatKind::BindPat(it) => it,
+        _ => return None,
+    };
+    // A `mut` string is most likely modified.
+    if pat.is_mutable() {
+        return None;
+    }
+    let name = pat.name()?.text().clone();
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, param.syntax())?;
+    let fn_def = function.source(ctx.db).1;
+    let body_edits = body_edits(&ctx, function, pat)?;
+    // Macros are not expanded, so the name can only be looked for.
+    let used_in_other_macros =
+        fn_def.syntax().descendants().filter_map(ast::MacroCall::cast).any(|it| {
+            let is_format = it
+                .path()
+                .and_then(|it| it.segment())
+                .and_then(|it| it.name_ref())
+                .map_or(false, |it| FORMAT_MACROS.contains(&it.text().as_str()));
+            let mentions_name = it.token_tree().map_or(false, |tt| {
+                tt.syntax().descendants().any(|it| it.kind() == IDENT && it.text() == name.as_str())
+            });
+            !is_format && mentions_name
+        });
+    if used_in_other_macros {
+        return None;
+    }
+
+    let idx = ast::ParamList::cast(param.syntax().parent()?)?
+        .params()
+        .position(|it| it.syntax() == param.syntax())?;
+    // Public functions may be called from other crates, so we can't know all
+    // the call sites.
+    let call_edits = if fn_def.visibility().is_some() {
+        None
+    } else {
+        let mut res = Vec::new();
+        for_each_call(ctx.db, function, ctx.frange.file_id, |args| {
+            res.push(borrow_arg(args.get(idx)?));
+            Some(())
+        })
+        .map(|()| res)
+    };
+    let label = match call_edits {
+        Some(_) => "change to `&str`",
+        None => "change to `&str` (call sites are not updated)",
+    };
+
+    let range = type_ref.syntax().range();
+    ctx.add_action(AssistId("change_string_param"), label, |edit| {
+        edit.target(param.syntax().range());
+        edit.replace(range, "&str");
+        for (range, text) in body_edits.into_iter().chain(call_edits.into_iter().flatten()) {
+            edit.replace(range, text);
+        }
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+/// The edits which keep the body working with a `&str`, or `None` if the body
+/// needs to own the string, like to move it elsewhere.
+fn body_edits(
+    ctx: &AssistCtx<impl HirDatabase>,
+    function: hir::Function,
+    pat: &ast::BindPat,
+) -> Option<Vec<(TextRange, String)>> {
+    let name = pat.name()?.text().to_string();
+    let infer = function.infer(ctx.db);
+    let source_map = function.body_source_map(ctx.db);
+    let file = pat.syntax().ancestors().last()?;
+    let mut res = Vec::new();
+    for reference in function.scopes(ctx.db).find_all_refs(pat) {
+        let path_expr =
+            find_covering_node(file, reference.range).ancestors().find_map(ast::PathExpr::cast)?;
+        let parent = path_expr.syntax().parent()?;
+        if let Some(ref_expr) = ast::RefExpr::cast(parent) {
+            if ref_expr.is_mut() {
+                return None;
+            }
+            res.push((ref_expr.syntax().range(), name.clone()));
+        } else if let Some(call) = ast::MethodCallExpr::cast(parent) {
+            if call.expr().map(|it| it.syntax()) != Some(path_expr.syntax()) {
+                return None;
+            }
+            let method = call.name_ref()?.text().clone();
+            match method.as_str() {
+                "as_str" => res.push((call.syntax().range(), name.clone())),
+                // These would now produce a `&str`, or need the `String`.
+                "clone" | "into" => return None,
+                _ => {
+                    let expr = source_map.node_expr(ast::Expr::cast(call.syntax())?)?;
+                    // Methods we can't resolve come from `str` through `Deref`,
+                    // and borrow it.
+                    if let Some(method) = infer.method_resolution(expr) {
+                        if !borrows_self(ctx.db, method) {
+                            return None;
+                        }
+                    }
+                }
+            }
+        } else if !is_comparison(parent) {
+            return None;
+        }
+    }
+    Some(res)
+}
+
+fn borrows_self(db: &impl HirDatabase, method: hir::Function) -> bool {
+    let fn_def = method.source(db).1;
+    match fn_def.param_list().and_then(|it| it.self_param()) {
+        Some(it) => it.flavor() == ast::SelfParamFlavor::Ref,
+        None => false,
+    }
+}
+
+fn is_comparison(node: &SyntaxNode) -> bool {
+    match ast::BinExpr::cast(node).and_then(|it| it.op()) {
+        Some(ast::BinOp::EqualityTest)
+        | Some(ast::BinOp::NegatedEqualityTest)
+        | Some(ast::BinOp::LesserTest)
+        | Some(ast::BinOp::GreaterTest)
+        | Some(ast::BinOp::LesserEqualTest)
+        | Some(ast::BinOp::GreaterEqualTest) => true,
+        _ => false,
+    }
+}
+
+/// Borrows a `String` argument, which derefs to the `&str`.
+fn borrow_arg(arg: &ast::Expr) -> (TextRange, String) {
+    let text = match arg.kind() {
+        ast::ExprKind::PathExpr(_)
+        | ast::ExprKind::FieldExpr(_)
+        | ast::ExprKind::CallExpr(_)
+        | ast::ExprKind::MethodCallExpr(_)
+        | ast::ExprKind::ParenExpr(_)
+        | ast::ExprKind::IndexExpr(_) => format!("&{}", arg.syntax().text()),
+        _ => format!("&({})", arg.syntax().text()),
+    };
+    (arg.syntax().range(), text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn change_string_param_that_is_only_read() {
+        check_assist(
+            change_string_param,
+            "
+struct String;
+impl String {
+    fn len(&self) -> usize { 0 }
+    fn as_str(&self) -> &str { \"\" }
+}
+fn greet(name: <|>String, times: u32) {
+    if name.len() > 0 && name != \"\" {
+        println!(\"hello {}\", name);
+        take(name.as_str(), &name);
+    }
+}
+fn main() {
+    let s = String;
+    greet(s, 1);
+    greet(String, 2);
+}",
+            "
+struct String;
+impl String {
+    fn len(&self) -> usize { 0 }
+    fn as_str(&self) -> &str { \"\" }
+}
+fn greet(name: <|>&str, times: u32) {
+    if name.len() > 0 && name != \"\" {
+        println!(\"hello {}\", name);
+        take(name, name);
+    }
+}
+fn main() {
+    let s = String;
+    greet(&s, 1);
+    greet(&String, 2);
+}",
+        );
+    }
+
+    #[test]
+    fn change_string_param_of_public_function() {
+        check_assist(
+            change_string_param,
+            "pub fn f(s: String<|>) -> bool { s == \"\" }",
+            "pub fn f(s: <|>&str) -> bool { s == \"\" }",
+        );
+    }
+
+    #[test]
+    fn change_string_param_not_applicable() {
+        // The string is moved.
+        check_assist_not_applicable(change_string_param, "fn f(s: <|>String) { g(s); }");
+        check_assist_not_applicable(change_string_param, "fn f(s: <|>String) -> String { s }");
+        check_assist_not_applicable(change_string_param, "fn f(s: <|>String) { let v = vec![s]; }");
+        check_assist_not_applicable(
+            change_string_param,
+            "
+struct String;
+impl String { fn into_bytes(self) {} }
+fn f(s: <|>String) { s.into_bytes(); }",
+        );
+        check_assist_not_applicable(change_string_param, "fn f(mut s: <|>String) {}");
+        check_assist_not_applicable(change_string_param, "fn f(s: <|>&str) {}");
+    }
+
+    #[test]
+    fn change_string_param_target() {
+        check_assist_target(change_string_param, "fn f(s: <|>String) {}", "s: String");
+    }
+}
@@ -0,0 +1,147 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{AstNode, TextRange, ast, SyntaxKind::COMMENT};
+
+use crate::{AssistCtx, Assist, AssistId, utils::shift_indent};
+
+pub(crate) fn collapse_nested_if(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let if_expr = ctx.node_at_offset::<ast::IfExpr>()?;
+    let then_block = if_expr.then_branch()?;
+    if if_expr.else_branch().is_some() {
+        return None;
+    }
+    // Only offer the assist on the `if` keyword and the condition, not in the
+    // branches.
+    let head =
+        TextRange::from_to(if_expr.syntax().range().start(), then_block.syntax().range().start());
+    if !head.contains_inclusive(ctx.frange.range.start()) {
+        return None;
+    }
+
+    // The inner `if` must be all there is to the outer one.
+    let mut exprs = then_block
+        .statements()
+        .map(|stmt| match stmt.kind() {
+            ast::StmtKind::ExprStmt(it) => it.expr(),
+            ast::StmtKind::LetStmt(_) => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    exprs.extend(then_block.expr());
+    let inner_if = match exprs.as_slice() {
+        [expr] => match expr.kind() {
+            ast::ExprKind::IfExpr(it) => it,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    if inner_if.else_branch().is_some()
+        || then_block.syntax().children().any(|it| it.kind() == COMMENT)
+    {
+        return None;
+    }
+    let outer_cond = condition(if_expr)?;
+    let inner_cond = condition(inner_if)?;
+    let inner_block = inner_if.then_branch()?.syntax().text().to_string();
+    let body = match (leading_indent(inner_if.syntax()), leading_indent(if_expr.syntax())) {
+        (Some(from), Some(to)) => shift_indent(&inner_block, from, to),
+        _ => inner_block,
+    };
+
+    let range =
+        TextRange::from_to(outer_cond.syntax().range().start(), if_expr.syntax().range().end());
+    ctx.add_action(AssistId("collapse_nested_if"), "collapse nested if", |edit| {
+        edit.target(head);
+        edit.replace(range, format!("{} && {} {}", operand(outer_cond), operand(inner_cond), body));
+        edit.set_cursor(if_expr.syntax().range().start());
+    });
+
+    ctx.build()
+}
+
+/// The condition of an `if`, unless it is an `if let`.
+fn condition(if_expr: &ast::IfExpr) -> Option<&ast::Expr> {
+    let cond = if_expr.condition()?;
+    if cond.pat().is_some() {
+        return None;
+    }
+    cond.expr()
+}
+
+/// The text of `expr` as an operand of `&&`.
+fn operand(expr: &ast::Expr) -> String {
+    match expr.kind() {
+        ast::ExprKind::BinExpr(it) if it.op() == Some(ast::BinOp::BooleanOr) => {
+            format!("({})", expr.syntax().text())
+        }
+        _ => expr.syntax().text().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn collapse_nested_if_combines_conditions() {
+        check_assist(
+            collapse_nested_if,
+            "
+fn f() {
+    i<|>f a {
+        if b.is_empty() {
+            foo();
+            bar();
+        }
+    }
+}",
+            "
+fn f() {
+    <|>if a && b.is_empty() {
+        foo();
+        bar();
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn collapse_nested_if_parenthesizes_or() {
+        check_assist(
+            collapse_nested_if,
+            "fn f() { <|>if a || b { if c && d { foo() } } }",
+            "fn f() { <|>if (a || b) && c && d { foo() } }",
+        );
+        check_assist(
+            collapse_nested_if,
+            "fn f() { <|>if a { if c || d { foo() } } }",
+            "fn f() { <|>if a && (c || d) { foo() } }",
+        );
+    }
+
+    #[test]
+    fn collapse_nested_if_not_applicable() {
+        check_assist_not_applicable(
+            collapse_nested_if,
+            "fn f() { <|>if a { if b { foo() } else { bar() } } }",
+        );
+        check_assist_not_applicable(
+            collapse_nested_if,
+            "fn f() { <|>if a { if b { foo() } } else { bar() } }",
+        );
+        check_assist_not_applicable(
+            collapse_nested_if,
+            "fn f() { <|>if a { foo(); if b { bar() } } }",
+        );
+        check_assist_not_applicable(
+            collapse_nested_if,
+            "fn f() { <|>if let Some(x) = a { if b { foo() } } }",
+        );
+        check_assist_not_applicable(collapse_nested_if, "fn f() { if a { if b { <|>foo() } } }");
+    }
+
+    #[test]
+    fn collapse_nested_if_target() {
+        check_assist_target(collapse_nested_if, "fn f() { <|>if a { if b { foo() } } }", "if a ");
+    }
+}
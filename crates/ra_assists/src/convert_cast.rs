@@ -0,0 +1,301 @@
+use hir::{HirDisplay, db::HirDatabase, source_binder};
+use ra_syntax::{
+    AstNode, SyntaxNode, TextUnit,
+    ast::{self, ModuleItemOwner},
+    SyntaxKind::LAMBDA_EXPR,
+};
+
+use crate::{AssistCtx, Assist, AssistId, utils::item_indent};
+
+pub(crate) fn convert_cast(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let cast = ctx.node_at_offset::<ast::CastExpr>()?;
+    let expr = cast.expr()?;
+    let target = cast.type_ref()?.syntax().text().to_string();
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, cast.syntax())?;
+    let infer = function.infer(ctx.db);
+    let source_map = function.body_source_map(ctx.db);
+    let from = infer[source_map.node_expr(expr)?].display(ctx.db).to_string();
+    let to =
+        infer[source_map.node_expr(ast::Expr::cast(cast.syntax())?)?].display(ctx.db).to_string();
+    let conversion = conversion(&Primitive::parse(&from)?, &Primitive::parse(&to)?)?;
+
+    // The argument list brings its own parentheses.
+    let expr = match expr.kind() {
+        ast::ExprKind::ParenExpr(it) => it.expr()?,
+        _ => expr,
+    };
+    let range = cast.syntax().range();
+    match conversion {
+        Conversion::From => {
+            let call = format!("{}::from({})", target, expr.syntax().text());
+            ctx.add_action(AssistId("convert_cast_to_from"), "convert cast to `From`", |edit| {
+                edit.target(range);
+                edit.replace(range, call);
+                edit.set_cursor(range.start());
+            });
+        }
+        Conversion::TryFrom => {
+            let call = format!("{}::try_from({})", target, expr.syntax().text());
+            let import = try_from_import(&ctx, cast.syntax());
+            // The import comes before the cast and shifts it.
+            let cursor = range.start()
+                + import.as_ref().map_or(TextUnit::from(0), |(_, text)| TextUnit::of_str(text));
+            ctx.add_action(
+                AssistId("convert_cast_to_try_from"),
+                "convert cast to `TryFrom`",
+                |edit| {
+                    edit.target(range);
+                    edit.replace(range, call.clone());
+                    if let Some((offset, text)) = import.clone() {
+                        edit.insert(offset, text);
+                    }
+                    edit.set_cursor(cursor);
+                },
+            );
+            if returns_result(cast.syntax()) {
+                ctx.add_action(
+                    AssistId("convert_cast_to_try_from_with_try"),
+                    "convert cast to `TryFrom` with `?`",
+                    |edit| {
+                        edit.target(range);
+                        edit.replace(range, format!("{}?", call));
+                        if let Some((offset, text)) = import {
+                            edit.insert(offset, text);
+                        }
+                        edit.set_cursor(cursor);
+                    },
+                );
+            }
+        }
+    }
+
+    ctx.build()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Conversion {
+    From,
+    TryFrom,
+}
+
+/// A numeric primitive type. The size of `usize` and `isize` is `None`, as it
+/// depends on the platform.
+#[derive(Debug, PartialEq, Eq)]
+enum Primitive {
+    Int { signed: bool, bits: Option<u32> },
+    Float { bits: u32 },
+}
+
+impl Primitive {
+    fn parse(name: &str) -> Option<Primitive> {
+        let res = match name {
+            "usize" => Primitive::Int { signed: false, bits: None },
+            "isize" => Primitive::Int { signed: true, bits: None },
+            "f32" => Primitive::Float { bits: 32 },
+            "f64" => Primitive::Float { bits: 64 },
+            _ => {
+                let signed = match name.chars().next()? {
+                    'u' => false,
+                    'i' => true,
+                    _ => return None,
+                };
+                Primitive::Int { signed, bits: Some(name[1..].parse().ok()?) }
+            }
+        };
+        Some(res)
+    }
+}
+
+/// How the standard library converts between `from` and `to`: `From` for
+/// lossless conversions, `TryFrom` between integers otherwise.
+fn conversion(from: &Primitive, to: &Primitive) -> Option<Conversion> {
+    let is_lossless = match (from, to) {
+        _ if from == to => true,
+        (
+            Primitive::Int { signed: from_signed, bits: Some(from_bits) },
+            Primitive::Int { signed: to_signed, bits: Some(to_bits) },
+        ) => match (from_signed, to_signed) {
+            (false, true) => to_bits > from_bits,
+            (true, false) => false,
+            _ => to_bits >= from_bits,
+        },
+        // The pointer size is at least 16 bits.
+        (Primitive::Int { signed: false, bits: Some(bits) }, Primitive::Int { bits: None, .. }) => {
+            *bits <= 8 || (*bits == 16 && *to == Primitive::Int { signed: false, bits: None })
+        }
+        (
+            Primitive::Int { signed: true, bits: Some(bits) },
+            Primitive::Int { signed, bits: None },
+        ) => *signed && *bits <= 16,
+        (Primitive::Int { bits: Some(from_bits), .. }, Primitive::Float { bits: to_bits }) => {
+            from_bits * 2 <= *to_bits
+        }
+        (Primitive::Float { bits: from_bits }, Primitive::Float { bits: to_bits }) => {
+            from_bits <= to_bits
+        }
+        _ => false,
+    };
+    match (from, to) {
+        _ if is_lossless => Some(Conversion::From),
+        (Primitive::Int { .. }, Primitive::Int { .. }) => Some(Conversion::TryFrom),
+        _ => None,
+    }
+}
+
+/// `TryFrom` is not in the 2018 prelude. Returns where to import it, unless it
+/// already is in scope.
+fn try_from_import(
+    ctx: &AssistCtx<impl HirDatabase>,
+    node: &SyntaxNode,
+) -> Option<(TextUnit, String)> {
+    let resolver = source_binder::resolver_for_node(ctx.db, ctx.frange.file_id, node);
+    if resolver.all_names(ctx.db).keys().any(|it| it.to_string() == "TryFrom") {
+        return None;
+    }
+    let items = match node.ancestors().find_map(ast::Module::cast) {
+        Some(module) => module.item_list()?.items().collect::<Vec<_>>(),
+        None => node.ancestors().find_map(ast::SourceFile::cast)?.items().collect(),
+    };
+    let last_use = items.iter().filter(|it| ast::UseItem::cast(it.syntax()).is_some()).last();
+    let res = match last_use {
+        Some(it) => (
+            it.syntax().range().end(),
+            format!("\n{}use std::convert::TryFrom;", item_indent(it.syntax())),
+        ),
+        None => {
+            let first = items.first()?.syntax();
+            (first.range().start(), format!("use std::convert::TryFrom;\n\n{}", item_indent(first)))
+        }
+    };
+    Some(res)
+}
+
+/// Whether `?` can be used at `node`, judging by the function returning a
+/// `Result`.
+fn returns_result(node: &SyntaxNode) -> bool {
+    let fn_def =
+        match node.ancestors().take_while(|it| it.kind() != LAMBDA_EXPR).find_map(ast::FnDef::cast)
+        {
+            Some(it) => it,
+            None => return false,
+        };
+    let ret_type = fn_def.ret_type().and_then(|it| it.type_ref()).map(|it| it.kind());
+    match ret_type {
+        Some(ast::TypeRefKind::PathType(it)) => it
+            .path()
+            .and_then(|it| it.segment())
+            .and_then(|it| it.name_ref())
+            .map_or(false, |it| it.text() == "Result"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_nth_action, check_assist_not_applicable, check_assist_target,
+    };
+
+    #[test]
+    fn convert_narrowing_cast_to_try_from() {
+        check_assist(
+            convert_cast,
+            "
+use std::fmt;
+
+fn f(x: u32) {
+    let y = x as<|> u8;
+}",
+            "
+use std::fmt;
+use std::convert::TryFrom;
+
+fn f(x: u32) {
+    let y = <|>u8::try_from(x);
+}",
+        );
+        check_assist(
+            convert_cast,
+            "
+use std::convert::TryFrom;
+fn f(x: i64) {
+    let y = (x + 1) as<|> u64;
+}",
+            "
+use std::convert::TryFrom;
+fn f(x: i64) {
+    let y = <|>u64::try_from(x + 1);
+}",
+        );
+    }
+
+    #[test]
+    fn convert_cast_to_try_from_with_try() {
+        check_assist_nth_action(
+            convert_cast,
+            "
+enum Error {}
+fn f(x: usize) -> Result<u16, Error> {
+    Ok(x as<|> u16)
+}",
+            "
+use std::convert::TryFrom;
+
+enum Error {}
+fn f(x: usize) -> Result<u16, Error> {
+    Ok(<|>u16::try_from(x)?)
+}",
+            1,
+        );
+    }
+
+    #[test]
+    fn convert_widening_cast_to_from() {
+        check_assist(
+            convert_cast,
+            "fn f(x: u8) { let y = x <|>as u32; }",
+            "fn f(x: u8) { let y = <|>u32::from(x); }",
+        );
+        check_assist(
+            convert_cast,
+            "fn f(x: u16) { let y = x <|>as i32; }",
+            "fn f(x: u16) { let y = <|>i32::from(x); }",
+        );
+        check_assist(
+            convert_cast,
+            "fn f(x: i16) { let y = x <|>as f32; }",
+            "fn f(x: i16) { let y = <|>f32::from(x); }",
+        );
+        check_assist(
+            convert_cast,
+            "fn f(x: u16) { let y = x <|>as usize; }",
+            "fn f(x: u16) { let y = <|>usize::from(x); }",
+        );
+    }
+
+    #[test]
+    fn conversions() {
+        let parse = |it| Primitive::parse(it).unwrap();
+        assert_eq!(conversion(&parse("i8"), &parse("u8")), Some(Conversion::TryFrom));
+        assert_eq!(conversion(&parse("u32"), &parse("i32")), Some(Conversion::TryFrom));
+        assert_eq!(conversion(&parse("u16"), &parse("isize")), Some(Conversion::TryFrom));
+        assert_eq!(conversion(&parse("i16"), &parse("isize")), Some(Conversion::From));
+        assert_eq!(conversion(&parse("usize"), &parse("u64")), Some(Conversion::TryFrom));
+        assert_eq!(conversion(&parse("f32"), &parse("f64")), Some(Conversion::From));
+        assert_eq!(conversion(&parse("i32"), &parse("f32")), None);
+        assert_eq!(conversion(&parse("f64"), &parse("u8")), None);
+    }
+
+    #[test]
+    fn convert_cast_not_applicable() {
+        check_assist_not_applicable(convert_cast, "fn f(x: f64) { let y = x <|>as u8; }");
+        check_assist_not_applicable(convert_cast, "fn f(x: &u8) { let y = x <|>as *const u8; }");
+    }
+
+    #[test]
+    fn convert_cast_target() {
+        check_assist_target(convert_cast, "fn f(x: u8) { g(x <|>as u32); }", "x as u32");
+    }
+}
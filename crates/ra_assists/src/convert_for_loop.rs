@@ -0,0 +1,607 @@
+use hir::{HirDisplay, Mutability, Ty, db::HirDatabase, source_binder};
+use ra_syntax::{
+    AstNode, SyntaxNode, TextRange,
+    ast::{self, ArgListOwner, LoopBodyOwner, NameOwner, TypeAscriptionOwner},
+    algo::non_trivia_sibling,
+    Direction,
+    SyntaxKind::{BREAK_EXPR, CONTINUE_EXPR, LABEL, MACRO_CALL, RETURN_EXPR, TRY_EXPR},
+};
+
+use crate::{AssistCtx, Assist, AssistId, utils::receiver_text};
+
+pub(crate) fn convert_for_loop_to_iterator_chain(
+    mut ctx: AssistCtx<impl HirDatabase>,
+) -> Option<Assist> {
+    let for_expr = ctx.node_at_offset::<ast::ForExpr>()?;
+    let pat = for_expr.pat()?;
+    let iterable = for_expr.iterable()?;
+    let body = for_expr.loop_body()?;
+    if for_expr.syntax().children().any(|it| it.kind() == LABEL) {
+        return None;
+    }
+
+    let mut exprs = body
+        .statements()
+        .map(|stmt| match stmt.kind() {
+            ast::StmtKind::ExprStmt(it) => it.expr(),
+            ast::StmtKind::LetStmt(_) => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    exprs.extend(body.expr());
+    let (filter, action) = match exprs.as_slice() {
+        [action] => (None, *action),
+        [guard, action] => (Some(filter_condition(guard)?), *action),
+        _ => return None,
+    };
+    if has_control_flow(action.syntax()) {
+        return None;
+    }
+    let action = LoopAction::from_expr(action)?;
+
+    let pat_text = pat.syntax().text().to_string();
+    let mut chain = iterator_text(iterable);
+    if let Some(condition) = filter {
+        // `|&x|` takes the item out of the reference `filter` passes in.
+        if !is_plain_binding(pat) || !items_are_copy(&ctx, iterable) {
+            return None;
+        }
+        chain.push_str(&format!(".filter(|&{}| {})", pat_text, negated(condition)));
+    }
+    if let Some(map) = map_fn(&pat_text, action.value) {
+        chain.push_str(&format!(".map({})", map));
+    }
+
+    // The loop itself, with its semicolon, if any.
+    let stmt = match for_expr.syntax().parent().and_then(ast::ExprStmt::cast) {
+        Some(it) => it.syntax(),
+        None => for_expr.syntax(),
+    };
+    let acc_decl = non_trivia_sibling(stmt, Direction::Prev)
+        .and_then(ast::LetStmt::cast)
+        .filter(|it| declares_accumulator(it, &action));
+    let (start, replacement) = match (action.kind, acc_decl) {
+        (LoopActionKind::Push, Some(decl)) => {
+            let ty = match decl.ascribed_type() {
+                Some(it) => it.syntax().text().to_string(),
+                None => "Vec<_>".to_string(),
+            };
+            let replacement = format!("let {}: {} = {}.collect();", action.acc, ty, chain);
+            (decl.syntax().range().start(), replacement)
+        }
+        (LoopActionKind::Push, None) => {
+            (stmt.range().start(), format!("{}.extend({});", action.acc, chain))
+        }
+        (LoopActionKind::Sum, Some(decl)) => {
+            let ty = match decl.ascribed_type() {
+                Some(it) => it.syntax().text().to_string(),
+                None => inferred_type(&ctx, decl)?,
+            };
+            let replacement = format!("let {}: {} = {}.sum();", action.acc, ty, chain);
+            (decl.syntax().range().start(), replacement)
+        }
+        (LoopActionKind::Sum, None) => return None,
+    };
+
+    let range = TextRange::from_to(start, stmt.range().end());
+    ctx.add_action(
+        AssistId("convert_for_loop_to_iterator_chain"),
+        "convert to iterator chain",
+        |edit| {
+            edit.target(for_expr.syntax().range());
+            edit.replace(range, replacement);
+            edit.set_cursor(range.start());
+        },
+    );
+
+    ctx.build()
+}
+
+pub(crate) fn convert_for_each_to_for_loop(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = ctx.node_at_offset::<ast::MethodCallExpr>()?;
+    if call.name_ref()?.text() != "for_each" {
+        return None;
+    }
+    // The value of the call, `()`, must not be used.
+    let stmt = call.syntax().parent().and_then(ast::ExprStmt::cast)?;
+    let receiver = call.expr()?;
+    let mut args = call.arg_list()?.args();
+    let lambda = match args.next()?.kind() {
+        ast::ExprKind::LambdaExpr(it) => it,
+        _ => return None,
+    };
+    if args.next().is_some() {
+        return None;
+    }
+    let mut params = lambda.param_list()?.params();
+    let pat = params.next()?.pat()?;
+    if params.next().is_some() {
+        return None;
+    }
+    // Macro calls aren't expressions, but can be the body of a closure too.
+    let body = match lambda.body() {
+        Some(it) => it.syntax(),
+        None => lambda.syntax().children().find(|it| it.kind() == MACRO_CALL)?,
+    };
+    // A `return` in the closure would return from the function instead.
+    if body.descendants().any(|it| it.kind() == RETURN_EXPR || it.kind() == TRY_EXPR) {
+        return None;
+    }
+    let body_text = match ast::BlockExpr::cast(body) {
+        Some(it) => it.block()?.syntax().text().to_string(),
+        None => format!("{{ {}; }}", body.text()),
+    };
+    let replacement =
+        format!("for {} in {} {}", pat.syntax().text(), receiver.syntax().text(), body_text);
+
+    let range = stmt.syntax().range();
+    ctx.add_action(AssistId("convert_for_each_to_for_loop"), "convert to for loop", |edit| {
+        edit.target(call.syntax().range());
+        edit.replace(range, replacement);
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LoopActionKind {
+    /// `acc.push(value)`
+    Push,
+    /// `acc += value`
+    Sum,
+}
+
+/// The single thing a convertible loop does with each item.
+struct LoopAction<'a> {
+    kind: LoopActionKind,
+    acc: String,
+    value: &'a ast::Expr,
+}
+
+impl<'a> LoopAction<'a> {
+    fn from_expr(expr: &'a ast::Expr) -> Option<LoopAction<'a>> {
+        let (kind, acc, value) = match expr.kind() {
+            ast::ExprKind::MethodCallExpr(call) => {
+                if call.name_ref()?.text() != "push" {
+                    return None;
+                }
+                let mut args = call.arg_list()?.args();
+                let value = args.next()?;
+                if args.next().is_some() {
+                    return None;
+                }
+                (LoopActionKind::Push, call.expr()?, value)
+            }
+            ast::ExprKind::BinExpr(bin) => {
+                if bin.op()? != ast::BinOp::AddAssign {
+                    return None;
+                }
+                (LoopActionKind::Sum, bin.lhs()?, bin.rhs()?)
+            }
+            _ => return None,
+        };
+        let acc = match acc.kind() {
+            ast::ExprKind::PathExpr(it) => it.path()?,
+            _ => return None,
+        };
+        if acc.qualifier().is_some() {
+            return None;
+        }
+        let acc = acc.segment()?.name_ref()?.text().to_string();
+        Some(LoopAction { kind, acc, value })
+    }
+}
+
+/// Matches `if condition { continue; }`, returning the condition.
+fn filter_condition(guard: &ast::Expr) -> Option<&ast::Expr> {
+    let if_expr = match guard.kind() {
+        ast::ExprKind::IfExpr(it) => it,
+        _ => return None,
+    };
+    if if_expr.else_branch().is_some() {
+        return None;
+    }
+    let condition = if_expr.condition()?;
+    if condition.pat().is_some() {
+        return None;
+    }
+    let then_branch = if_expr.then_branch()?;
+    let mut exprs = then_branch
+        .statements()
+        .map(|stmt| match stmt.kind() {
+            ast::StmtKind::ExprStmt(it) => it.expr(),
+            ast::StmtKind::LetStmt(_) => None,
+        })
+        .chain(then_branch.expr().map(Some));
+    match exprs.next()?? {
+        it if it.syntax().kind() == CONTINUE_EXPR => {}
+        _ => return None,
+    }
+    if exprs.next().is_some() || then_branch.syntax().descendants().any(|it| it.kind() == LABEL) {
+        return None;
+    }
+    let condition = condition.expr()?;
+    if has_control_flow(condition.syntax()) {
+        return None;
+    }
+    Some(condition)
+}
+
+fn has_control_flow(node: &SyntaxNode) -> bool {
+    node.descendants().any(|it| match it.kind() {
+        RETURN_EXPR | BREAK_EXPR | CONTINUE_EXPR | TRY_EXPR => true,
+        _ => false,
+    })
+}
+
+fn is_plain_binding(pat: &ast::Pat) -> bool {
+    match pat.kind() {
+        ast::PatKind::BindPat(it) => !it.is_mutable() && !it.is_ref() && it.pat().is_none(),
+        _ => false,
+    }
+}
+
+/// Whether the items of `iterable` are known to be `Copy`.
+fn items_are_copy(ctx: &AssistCtx<impl HirDatabase>, iterable: &ast::Expr) -> bool {
+    match iterable.kind() {
+        ast::ExprKind::RefExpr(it) => return !it.is_mut(),
+        ast::ExprKind::RangeExpr(_) => return true,
+        ast::ExprKind::MethodCallExpr(it) => {
+            return it.name_ref().map_or(false, |it| it.text() == "iter");
+        }
+        _ => (),
+    }
+    let item = match expr_type(ctx, iterable) {
+        Some(Ty::Ref(_, Mutability::Shared)) => return true,
+        Some(Ty::Array(it)) => Ty::clone(&it),
+        // Collections like `Vec<T>`, but not maps.
+        Some(Ty::Adt { substs, .. }) if substs.len() == 1 => substs[0].clone(),
+        _ => return false,
+    };
+    item != Ty::Unknown && item.is_copy()
+}
+
+fn expr_type(ctx: &AssistCtx<impl HirDatabase>, expr: &ast::Expr) -> Option<Ty> {
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, expr.syntax())?;
+    let expr = function.body_source_map(ctx.db).node_expr(expr)?;
+    Some(function.infer(ctx.db)[expr].clone())
+}
+
+/// The iterator for the `iterable` of a `for` loop. Calls are assumed to
+/// return iterators already, like `v.iter()`, and `&v` becomes `v.iter()`.
+fn iterator_text(iterable: &ast::Expr) -> String {
+    match iterable.kind() {
+        ast::ExprKind::MethodCallExpr(_) | ast::ExprKind::CallExpr(_) => {
+            iterable.syntax().text().to_string()
+        }
+        ast::ExprKind::RangeExpr(_) => format!("({})", iterable.syntax().text()),
+        ast::ExprKind::RefExpr(it) => {
+            let method = if it.is_mut() { "iter_mut" } else { "iter" };
+            match it.expr() {
+                Some(value) => format!("{}.{}()", receiver_text(value), method),
+                None => format!("({}).into_iter()", iterable.syntax().text()),
+            }
+        }
+        _ => format!("{}.into_iter()", receiver_text(iterable)),
+    }
+}
+
+/// The argument of `map` turning the item `pat` into `value`, if any is
+/// needed.
+fn map_fn(pat: &str, value: &ast::Expr) -> Option<String> {
+    let value_text = value.syntax().text().to_string();
+    if value_text == pat {
+        return None;
+    }
+    if let ast::ExprKind::CallExpr(call) = value.kind() {
+        let callee = call.expr()?;
+        let mut args = call.arg_list()?.args();
+        let is_item = args.next().map_or(false, |it| it.syntax().text() == pat);
+        if let ast::ExprKind::PathExpr(_) = callee.kind() {
+            if is_item && args.next().is_none() {
+                return Some(callee.syntax().text().to_string());
+            }
+        }
+    }
+    Some(format!("|{}| {}", pat, value_text))
+}
+
+fn negated(condition: &ast::Expr) -> String {
+    match condition.kind() {
+        ast::ExprKind::PrefixExpr(it) if it.op() == Some(ast::PrefixOp::Not) => match it.expr() {
+            Some(inner) => inner.syntax().text().to_string(),
+            None => format!("!({})", condition.syntax().text()),
+        },
+        ast::ExprKind::BinExpr(it) => {
+            let op = match it.op() {
+                Some(ast::BinOp::EqualityTest) => "!=",
+                Some(ast::BinOp::NegatedEqualityTest) => "==",
+                _ => return format!("!({})", condition.syntax().text()),
+            };
+            match it.sub_exprs() {
+                (Some(lhs), Some(rhs)) => {
+                    format!("{} {} {}", lhs.syntax().text(), op, rhs.syntax().text())
+                }
+                _ => format!("!({})", condition.syntax().text()),
+            }
+        }
+        ast::ExprKind::PathExpr(_)
+        | ast::ExprKind::CallExpr(_)
+        | ast::ExprKind::MethodCallExpr(_)
+        | ast::ExprKind::FieldExpr(_)
+        | ast::ExprKind::ParenExpr(_) => format!("!{}", condition.syntax().text()),
+        _ => format!("!({})", condition.syntax().text()),
+    }
+}
+
+/// Checks that `decl` is `let mut acc = Vec::new();` for a push, or
+/// `let mut acc = 0;` for a sum.
+fn declares_accumulator(decl: &ast::LetStmt, action: &LoopAction) -> bool {
+    let bind_pat = match decl.pat().map(ast::Pat::kind) {
+        Some(ast::PatKind::BindPat(it)) => it,
+        _ => return false,
+    };
+    let is_acc = bind_pat.is_mutable()
+        && !bind_pat.is_ref()
+        && bind_pat.name().map_or(false, |it| *it.text() == action.acc);
+    let init = match decl.initializer() {
+        Some(it) => it.syntax(),
+        None => match decl.syntax().children().find(|it| it.kind() == MACRO_CALL) {
+            Some(it) => it,
+            None => return false,
+        },
+    };
+    let init = init.text().to_string();
+    let is_empty = match action.kind {
+        LoopActionKind::Push => init == "Vec::new()" || init == "vec![]",
+        LoopActionKind::Sum => {
+            let digits = init.trim_end_matches(|c: char| c.is_alphanumeric() && c != '0');
+            init.starts_with('0') && digits.chars().all(|c| c == '0' || c == '.' || c == '_')
+        }
+    };
+    is_acc && is_empty
+}
+
+fn inferred_type(ctx: &AssistCtx<impl HirDatabase>, decl: &ast::LetStmt) -> Option<String> {
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, decl.syntax())?;
+    let pat = function.body_source_map(ctx.db).node_pat(decl.pat()?)?;
+    let ty = function.infer(ctx.db)[pat].display(ctx.db).to_string();
+    // Integer variables and unknown types can't be written down.
+    if ty.contains('{') {
+        return None;
+    }
+    Some(ty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    #[test]
+    fn convert_push_loop_to_map_collect() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main(it: Vec<u32>) {
+    let mut acc = Vec::new();
+    for<|> x in it {
+        acc.push(f(x));
+    }
+}",
+            "
+fn main(it: Vec<u32>) {
+    <|>let acc: Vec<_> = it.into_iter().map(f).collect();
+}",
+        );
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main(it: Vec<u32>) {
+    let mut acc: Vec<u64> = vec![];
+    for x in it.iter()<|> {
+        acc.push(x.len() + 1)
+    }
+}",
+            "
+fn main(it: Vec<u32>) {
+    <|>let acc: Vec<u64> = it.iter().map(|x| x.len() + 1).collect();
+}",
+        );
+    }
+
+    #[test]
+    fn convert_loop_over_reference() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main(v: Vec<u32>, acc: &mut Vec<u32>) {
+    for<|> x in &v {
+        acc.push(x + 1);
+    }
+}",
+            "
+fn main(v: Vec<u32>, acc: &mut Vec<u32>) {
+    <|>acc.extend(v.iter().map(|x| x + 1));
+}",
+        );
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main(v: &mut Vec<u32>, acc: &mut Vec<u32>) {
+    for<|> x in &mut *v {
+        acc.push(f(x));
+    }
+}",
+            "
+fn main(v: &mut Vec<u32>, acc: &mut Vec<u32>) {
+    <|>acc.extend((*v).iter_mut().map(f));
+}",
+        );
+    }
+
+    #[test]
+    fn convert_push_loop_without_declaration_to_extend() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main(acc: &mut Vec<u32>) {
+    for<|> x in 0..10 {
+        if x % 2 == 0 {
+            continue;
+        }
+        acc.push(x);
+    }
+}",
+            "
+fn main(acc: &mut Vec<u32>) {
+    <|>acc.extend((0..10).filter(|&x| x % 2 != 0));
+}",
+        );
+    }
+
+    #[test]
+    fn convert_sum_loop() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main(it: &[u32]) {
+    let mut total: u32 = 0;
+    for<|> x in it.iter() {
+        if !is_valid(x) {
+            continue;
+        }
+        total += x * 2;
+    }
+}",
+            "
+fn main(it: &[u32]) {
+    <|>let total: u32 = it.iter().filter(|&x| is_valid(x)).map(|x| x * 2).sum();
+}",
+        );
+    }
+
+    #[test]
+    fn convert_for_loop_not_applicable() {
+        check_assist_not_applicable(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main(it: Vec<u32>) {
+    let mut acc = Vec::new();
+    for<|> x in it {
+        if x == 0 {
+            return;
+        }
+        acc.push(x);
+    }
+}",
+        );
+        check_assist_not_applicable(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main(it: Vec<u32>) {
+    let mut acc = Vec::new();
+    for<|> x in it {
+        let y = x + 1;
+        acc.push(y);
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn convert_filter_loop_over_copy_items() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            "
+struct Vec<T>(T);
+fn main(it: Vec<u32>, acc: &mut Vec<u32>) {
+    for<|> x in it {
+        if x == 0 {
+            continue;
+        }
+        acc.push(x);
+    }
+}",
+            "
+struct Vec<T>(T);
+fn main(it: Vec<u32>, acc: &mut Vec<u32>) {
+    <|>acc.extend(it.into_iter().filter(|&x| x != 0));
+}",
+        );
+    }
+
+    #[test]
+    fn convert_filter_loop_not_applicable_without_copy_items() {
+        // `|&s|` can't move a `String` out of the reference.
+        check_assist_not_applicable(
+            convert_for_loop_to_iterator_chain,
+            "
+struct Vec<T>(T);
+struct String;
+fn main(strings: Vec<String>, acc: &mut Vec<String>) {
+    for<|> s in strings {
+        if s.is_empty() {
+            continue;
+        }
+        acc.push(s);
+    }
+}",
+        );
+        check_assist_not_applicable(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main(pairs: &[(u32, u32)], acc: &mut Vec<u32>) {
+    for<|> (a, b) in pairs.iter() {
+        if a == b {
+            continue;
+        }
+        acc.push(a + b);
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn convert_for_each_to_for_loop_simple() {
+        check_assist(
+            convert_for_each_to_for_loop,
+            "
+fn main(it: Vec<u32>) {
+    it.iter().for_<|>each(|x| println!(\"{}\", x));
+}",
+            "
+fn main(it: Vec<u32>) {
+    <|>for x in it.iter() { println!(\"{}\", x); }
+}",
+        );
+        check_assist(
+            convert_for_each_to_for_loop,
+            "
+fn main(it: Vec<u32>) {
+    it.into_iter().for_each<|>(|x| {
+        foo(x);
+    });
+}",
+            "
+fn main(it: Vec<u32>) {
+    <|>for x in it.into_iter() {
+        foo(x);
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn convert_for_each_not_applicable_with_return() {
+        check_assist_not_applicable(
+            convert_for_each_to_for_loop,
+            "
+fn main(it: Vec<u32>) {
+    it.iter().for_<|>each(|x| { if *x == 0 { return; } foo(x); });
+}",
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    AstNode,
+    ast,
+    SyntaxKind::{COMMA, L_PAREN, R_PAREN},
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn convert_if_chain_to_match(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let mut if_expr = ctx.node_at_offset::<ast::IfExpr>()?;
+    // Start from the first `if` of the chain, even if the cursor is on an `else if`.
+    while let Some(parent) = if_expr.syntax().parent().and_then(ast::IfExpr::cast) {
+        if_expr = parent;
+    }
+    let chain = if_expr;
+
+    let mut scrutinee = None;
+    let mut arms = Vec::new();
+    let else_block = loop {
+        let (expr, pat) = compared_pattern(if_expr.condition()?)?;
+        match &scrutinee {
+            None => scrutinee = Some(expr),
+            Some(it) if *it == expr => {}
+            Some(_) => return None,
+        }
+        arms.push((pat, if_expr.then_branch()?));
+        match if_expr.else_branch() {
+            Some(ast::ElseBranchFlavor::IfExpr(it)) => if_expr = it,
+            Some(ast::ElseBranchFlavor::Block(it)) => break Some(it),
+            None => break None,
+        }
+    };
+    if arms.len() < 2 {
+        return None;
+    }
+
+    ctx.add_action(AssistId("convert_if_chain_to_match"), "convert if chain to match", |edit| {
+        let mut buf = format!("match {} {{\n", scrutinee.unwrap());
+        for (pat, block) in arms {
+            buf.push_str(&format!("    {} => {}\n", pat, block.syntax().text()));
+        }
+        match else_block {
+            Some(block) => buf.push_str(&format!("    _ => {}\n", block.syntax().text())),
+            None => buf.push_str("    _ => {}\n"),
+        }
+        buf.push_str("}");
+        edit.target(chain.syntax().range());
+        edit.replace_node_and_indent(chain.syntax(), buf);
+        edit.set_cursor(chain.syntax().range().start());
+    });
+
+    ctx.build()
+}
+
+/// Splits `expr == pat` and `matches!(expr, pat)` into the compared expression
+/// and the pattern it is compared against.
+fn compared_pattern(cond: &ast::Condition) -> Option<(String, String)> {
+    if cond.pat().is_some() {
+        return None;
+    }
+    if let Some(expr) = cond.expr() {
+        let bin_expr = match expr.kind() {
+            ast::ExprKind::BinExpr(it) if it.op() == Some(ast::BinOp::EqualityTest) => it,
+            _ => return None,
+        };
+        let rhs = bin_expr.rhs()?;
+        if !is_pattern(rhs) {
+            return None;
+        }
+        return Some((
+            bin_expr.lhs()?.syntax().text().to_string(),
+            rhs.syntax().text().to_string(),
+        ));
+    }
+
+    let macro_call = cond.syntax().children().find_map(ast::MacroCall::cast)?;
+    let segment = macro_call.path()?.segment()?;
+    if segment.name_ref()?.text() != "matches" {
+        return None;
+    }
+    let tt = macro_call.token_tree()?;
+    let (open, close) = (tt.syntax().first_child()?, tt.syntax().last_child()?);
+    if open.kind() != L_PAREN || close.kind() != R_PAREN {
+        return None;
+    }
+    let comma = tt.syntax().children().find(|it| it.kind() == COMMA)?;
+    let text = tt.syntax().text();
+    let expr = text.slice(open.range().end()..comma.range().start()).to_string();
+    let pat = text.slice(comma.range().end()..close.range().start()).to_string();
+    let pat = pat.trim().trim_end_matches(',').trim_end();
+    if expr.trim().is_empty() || pat.is_empty() {
+        return None;
+    }
+    Some((expr.trim().to_string(), pat.to_string()))
+}
+
+/// Whether `expr` means the same as a pattern. Lowercase names are most likely
+/// locals, which would be bound by the pattern instead of compared.
+fn is_pattern(expr: &ast::Expr) -> bool {
+    match expr.kind() {
+        ast::ExprKind::Literal(_) => true,
+        ast::ExprKind::PrefixExpr(it) => {
+            it.op() == Some(ast::PrefixOp::Neg)
+                && it.expr().map_or(false, |it| ast::Literal::cast(it.syntax()).is_some())
+        }
+        ast::ExprKind::PathExpr(it) => it.path().map_or(false, |path| {
+            path.qualifier().is_some()
+                || path
+                    .segment()
+                    .and_then(|it| it.name_ref())
+                    .map_or(false, |it| it.text().starts_with(char::is_uppercase))
+        }),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_three_branch_if_chain_to_match() {
+        check_assist(
+            convert_if_chain_to_match,
+            "
+fn f(x: u32) {
+    <|>if x == 1 {
+        one();
+    } else if x == 2 {
+        two();
+    } else {
+        many();
+    }
+}",
+            "
+fn f(x: u32) {
+    <|>match x {
+        1 => {
+            one();
+        }
+        2 => {
+            two();
+        }
+        _ => {
+            many();
+        }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn convert_if_chain_with_matches_and_no_else() {
+        check_assist(
+            convert_if_chain_to_match,
+            "fn f() { if matches!(e, Some(1) | None) { a() } else <|>if e == Foo::BAR { b() } }",
+            "fn f() { <|>match e {
+    Some(1) | None => { a() }
+    Foo::BAR => { b() }
+    _ => {}
+} }",
+        );
+    }
+
+    #[test]
+    fn convert_if_chain_to_match_target() {
+        check_assist_target(
+            convert_if_chain_to_match,
+            "fn f() { let y = if x == 1 { 1 } else if<|> x == 2 { 4 } else { 0 }; }",
+            "if x == 1 { 1 } else if x == 2 { 4 } else { 0 }",
+        );
+    }
+
+    #[test]
+    fn convert_if_chain_to_match_not_applicable() {
+        check_assist_not_applicable(
+            convert_if_chain_to_match,
+            "fn f() { <|>if x == 1 { } else if y == 2 { } }",
+        );
+        check_assist_not_applicable(
+            convert_if_chain_to_match,
+            "fn f() { <|>if x == 1 { } else if x < 2 { } }",
+        );
+        check_assist_not_applicable(
+            convert_if_chain_to_match,
+            "fn f() { <|>if x == y { } else if x == 2 { } }",
+        );
+        check_assist_not_applicable(convert_if_chain_to_match, "fn f() { <|>if x == 1 { } }");
+    }
+}
@@ -0,0 +1,189 @@
+use hir::{db::HirDatabase, source_binder};
+use ra_syntax::{
+    AstNode, SyntaxNode,
+    ast::{self, NameOwner, TypeAscriptionOwner},
+    SyntaxKind::*,
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+/// Macros which never return.
+const DIVERGING_MACROS: &[&str] = &["panic", "unreachable", "unimplemented", "todo"];
+
+pub(crate) fn convert_let_else(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let let_stmt = ctx.node_at_offset::<ast::LetStmt>()?;
+    if let_stmt.ascribed_type().is_some() {
+        return None;
+    }
+    let let_pat = match let_stmt.pat()?.kind() {
+        ast::PatKind::BindPat(it) if !it.is_mutable() && !it.is_ref() => it,
+        _ => return None,
+    };
+    let if_expr = match let_stmt.initializer()?.kind() {
+        ast::ExprKind::IfExpr(it) => it,
+        _ => return None,
+    };
+    let cond = if_expr.condition()?;
+    let pat = cond.pat()?;
+    let expr = cond.expr()?;
+    // `let PAT = EXPR else` can't tell where a `}` of `EXPR` belongs.
+    if expr.syntax().text().to_string().ends_with('}') {
+        return None;
+    }
+    let else_block = match if_expr.else_branch()? {
+        ast::ElseBranchFlavor::Block(it) => it,
+        ast::ElseBranchFlavor::IfExpr(_) => return None,
+    };
+    if !diverges(else_block) {
+        return None;
+    }
+    // The `if let` must only pick one of the bindings of its pattern.
+    let then_block = if_expr.then_branch()?;
+    if then_block.statements().next().is_some() {
+        return None;
+    }
+    let value = match then_block.expr()?.kind() {
+        ast::ExprKind::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    if value.qualifier().is_some() {
+        return None;
+    }
+    let value = value.segment()?.name_ref()?.text().clone();
+    let binds_value = pat
+        .syntax()
+        .descendants()
+        .filter_map(ast::BindPat::cast)
+        .filter_map(|it| it.name())
+        .any(|it| *it.text() == value);
+    if !binds_value {
+        return None;
+    }
+
+    let mut renames = Vec::new();
+    if *let_pat.name()?.text() != value {
+        let function =
+            source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, let_stmt.syntax())?;
+        for reference in function.scopes(ctx.db).find_all_refs(let_pat) {
+            renames.push(reference.range);
+        }
+    }
+    let replacement = format!(
+        "let {} = {} else {};",
+        pat.syntax().text(),
+        expr.syntax().text(),
+        else_block.syntax().text()
+    );
+
+    let range = let_stmt.syntax().range();
+    ctx.add_action(AssistId("convert_let_else"), "convert to let-else", |edit| {
+        edit.target(range);
+        edit.replace(range, replacement);
+        for range in renames {
+            edit.replace(range, value.to_string());
+        }
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+/// Whether the block ends in a `return`, `break`, `continue` or `panic!()`.
+fn diverges(block: &ast::Block) -> bool {
+    let last = block
+        .syntax()
+        .children()
+        .filter(|it| match it.kind() {
+            WHITESPACE | COMMENT | SEMI | L_CURLY | R_CURLY => false,
+            _ => true,
+        })
+        .last();
+    let last = match last {
+        Some(it) if it.kind() == EXPR_STMT => it.first_child(),
+        it => it,
+    };
+    match last.map(|it| (it.kind(), it)) {
+        Some((RETURN_EXPR, _)) | Some((BREAK_EXPR, _)) | Some((CONTINUE_EXPR, _)) => true,
+        Some((MACRO_CALL, it)) => is_diverging_macro(it),
+        _ => false,
+    }
+}
+
+fn is_diverging_macro(node: &SyntaxNode) -> bool {
+    ast::MacroCall::cast(node)
+        .and_then(|it| it.path())
+        .and_then(|it| it.segment())
+        .and_then(|it| it.name_ref())
+        .map_or(false, |it| DIVERGING_MACROS.contains(&it.text().as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_let_else_with_return() {
+        check_assist(
+            convert_let_else,
+            "
+fn f(opt: Option<u32>) -> u32 {
+    let x = if let Some(v) = opt<|> { v } else { return 0; };
+    x + 1
+}",
+            "
+fn f(opt: Option<u32>) -> u32 {
+    <|>let Some(v) = opt else { return 0; };
+    v + 1
+}",
+        );
+    }
+
+    #[test]
+    fn convert_let_else_with_panic() {
+        check_assist(
+            convert_let_else,
+            "
+fn f(opt: Option<u32>) {
+    let <|>v = if let Some(v) = opt {
+        v
+    } else {
+        panic!(\"no value\")
+    };
+}",
+            "
+fn f(opt: Option<u32>) {
+    <|>let Some(v) = opt else {
+        panic!(\"no value\")
+    };
+}",
+        );
+    }
+
+    #[test]
+    fn convert_let_else_not_applicable() {
+        // The `else` branch doesn't diverge.
+        check_assist_not_applicable(
+            convert_let_else,
+            "fn f(opt: Option<u32>) { let x = if let Some(v) = opt<|> { v } else { 0 }; }",
+        );
+        // The `if let` computes something else.
+        check_assist_not_applicable(
+            convert_let_else,
+            "fn f(opt: Option<u32>) { let x = if let Some(v) = opt<|> { v + 1 } else { return; }; }",
+        );
+        check_assist_not_applicable(
+            convert_let_else,
+            "fn f(opt: Option<u32>) { let x = if opt<|>.is_some() { 1 } else { return; }; }",
+        );
+    }
+
+    #[test]
+    fn convert_let_else_target() {
+        check_assist_target(
+            convert_let_else,
+            "fn f(opt: Option<u32>) { let x = if let Some(v) = opt<|> { v } else { return; }; }",
+            "let x = if let Some(v) = opt { v } else { return; };",
+        );
+    }
+}
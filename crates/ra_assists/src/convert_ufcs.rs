@@ -0,0 +1,357 @@
+use hir::{
+    Function, ImplItem, ModuleDef, Mutability, Path, Resolution, Ty, TypeRef, db::HirDatabase,
+    source_binder,
+};
+use ra_syntax::{
+    AstNode, TreeArc,
+    ast::{self, ArgListOwner, NameOwner},
+};
+
+use crate::{AssistCtx, Assist, AssistId, utils::receiver_text};
+
+pub(crate) fn convert_to_ufcs(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let method_call = ctx.node_at_offset::<ast::MethodCallExpr>()?;
+    let receiver = method_call.expr()?;
+    let name_ref = method_call.name_ref()?;
+    let arg_list = method_call.arg_list()?;
+    // Only offer the assist on the `receiver.method` part.
+    if ctx.frange.range.start() > arg_list.syntax().range().start() {
+        return None;
+    }
+
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, method_call.syntax())?;
+    let infer = function.infer(ctx.db);
+    let source_map = function.body_source_map(ctx.db);
+    let method =
+        infer.method_resolution(source_map.node_expr(ast::Expr::cast(method_call.syntax())?)?)?;
+    let receiver_ty = &infer[source_map.node_expr(receiver)?];
+
+    // Name the trait if there is one: there might be several traits with a
+    // method of that name, which `Type::method` can't pick from.
+    let impl_block = method.impl_block(ctx.db)?;
+    let qualifier = match impl_block.target_trait(ctx.db) {
+        Some(tr) => tr.source(ctx.db).1.name()?.text().to_string(),
+        None => match impl_block.target_type(ctx.db) {
+            TypeRef::Path(path) => path.segments.last()?.name.to_string(),
+            _ => return None,
+        },
+    };
+
+    let receiver_text = receiver.syntax().text().to_string();
+    let receiver_arg = match (self_param_flavor(ctx.db, method)?, receiver_ty) {
+        (ast::SelfParamFlavor::Owned, _)
+        | (ast::SelfParamFlavor::Ref, Ty::Ref(..))
+        | (ast::SelfParamFlavor::MutRef, Ty::Ref(_, Mutability::Mut)) => receiver_text,
+        (ast::SelfParamFlavor::Ref, _) => format!("&{}", receiver_text),
+        (ast::SelfParamFlavor::MutRef, _) => format!("&mut {}", receiver_text),
+    };
+    let mut args = vec![receiver_arg];
+    args.extend(arg_list.args().map(|it| it.syntax().text().to_string()));
+    let type_args = method_call.type_arg_list().map(|it| it.syntax().text().to_string());
+    let replacement = format!(
+        "{}::{}{}({})",
+        qualifier,
+        name_ref.text(),
+        type_args.unwrap_or_default(),
+        args.join(", ")
+    );
+
+    let range = method_call.syntax().range();
+    ctx.add_action(AssistId("convert_to_ufcs"), "convert to fully-qualified call", |edit| {
+        edit.target(range);
+        edit.replace(range, replacement);
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+pub(crate) fn convert_to_method_call(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = ctx.node_at_offset::<ast::CallExpr>()?;
+    let callee = call.expr()?;
+    let path = match callee.kind() {
+        ast::ExprKind::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    let arg_list = call.arg_list()?;
+    if ctx.frange.range.start() > arg_list.syntax().range().start() {
+        return None;
+    }
+    let segment = path.segment()?;
+    let name = segment.name_ref()?;
+    let qualifier = path.qualifier()?;
+    let mut args = arg_list.args();
+    let first_arg = args.next()?;
+
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, call.syntax())?;
+    if !is_method(&ctx, function, callee, qualifier, &name.text().to_string())? {
+        return None;
+    }
+
+    // Method call syntax takes care of the autoref.
+    let receiver = match first_arg.kind() {
+        ast::ExprKind::RefExpr(it) => it.expr()?,
+        _ => first_arg,
+    };
+    let type_args = segment.type_arg_list().map(|it| it.syntax().text().to_string());
+    let rest = args.map(|it| it.syntax().text().to_string()).collect::<Vec<_>>();
+    let replacement = format!(
+        "{}.{}{}({})",
+        receiver_text(receiver),
+        name.text(),
+        type_args.unwrap_or_default(),
+        rest.join(", ")
+    );
+
+    let range = call.syntax().range();
+    ctx.add_action(AssistId("convert_to_method_call"), "convert to method call", |edit| {
+        edit.target(range);
+        edit.replace(range, replacement);
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+/// Checks whether `Qualifier::name` refers to a function with a `self`
+/// parameter.
+fn is_method(
+    ctx: &AssistCtx<impl HirDatabase>,
+    function: Function,
+    callee: &ast::Expr,
+    qualifier: &ast::Path,
+    name: &str,
+) -> Option<bool> {
+    let infer = function.infer(ctx.db);
+    let source_map = function.body_source_map(ctx.db);
+    if let Some(ImplItem::Method(method)) =
+        infer.assoc_resolutions_for_expr(source_map.node_expr(callee)?)
+    {
+        return Some(method.signature(ctx.db).has_self_param());
+    }
+
+    // Methods of traits are not resolved yet, so look for one in the trait
+    // itself.
+    let resolver = source_binder::resolver_for_node(ctx.db, ctx.frange.file_id, callee.syntax());
+    let tr = match resolver.resolve_path(ctx.db, &Path::from_ast(qualifier)?).take_types()? {
+        Resolution::Def(ModuleDef::Trait(it)) => it,
+        _ => return None,
+    };
+    let trait_def = tr.source(ctx.db).1;
+    let method = trait_fn(&trait_def, name)?;
+    Some(method.param_list()?.self_param().is_some())
+}
+
+fn trait_fn(trait_def: &TreeArc<ast::TraitDef>, name: &str) -> Option<TreeArc<ast::FnDef>> {
+    trait_def
+        .item_list()?
+        .impl_items()
+        .filter_map(|item| match item.kind() {
+            ast::ImplItemKind::FnDef(it) => Some(it),
+            _ => None,
+        })
+        .find(|it| it.name().map(|it| it.text() == name).unwrap_or(false))
+        .map(ast::FnDef::to_owned)
+}
+
+fn self_param_flavor(db: &impl HirDatabase, function: Function) -> Option<ast::SelfParamFlavor> {
+    let fn_def = function.source(db).1;
+    let self_param = fn_def.param_list()?.self_param()?;
+    Some(self_param.flavor())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_inherent_method_to_ufcs() {
+        check_assist(
+            convert_to_ufcs,
+            "
+struct Foo;
+impl Foo {
+    fn by_ref(&self, x: u32) {}
+    fn by_mut(&mut self) {}
+    fn by_value(self) {}
+}
+fn main() {
+    let foo = Foo;
+    foo.by_<|>ref(1);
+}
+",
+            "
+struct Foo;
+impl Foo {
+    fn by_ref(&self, x: u32) {}
+    fn by_mut(&mut self) {}
+    fn by_value(self) {}
+}
+fn main() {
+    let foo = Foo;
+    <|>Foo::by_ref(&foo, 1);
+}
+",
+        );
+        check_assist(
+            convert_to_ufcs,
+            "
+struct Foo;
+impl Foo { fn by_mut(&mut self) {} fn by_value(self) {} }
+fn main() {
+    let mut foo = Foo;
+    foo.by_mut<|>();
+    foo.by_value();
+}
+",
+            "
+struct Foo;
+impl Foo { fn by_mut(&mut self) {} fn by_value(self) {} }
+fn main() {
+    let mut foo = Foo;
+    <|>Foo::by_mut(&mut foo);
+    foo.by_value();
+}
+",
+        );
+        check_assist(
+            convert_to_ufcs,
+            "
+struct Foo;
+impl Foo { fn by_value(self) {} }
+fn main() { Foo.<|>by_value() }
+",
+            "
+struct Foo;
+impl Foo { fn by_value(self) {} }
+fn main() { <|>Foo::by_value(Foo) }
+",
+        );
+    }
+
+    #[test]
+    fn convert_trait_method_to_ufcs() {
+        check_assist(
+            convert_to_ufcs,
+            "
+trait Frobnicate { fn frob<T>(&self, t: T); }
+trait Other { fn frob(&self); }
+struct Foo;
+impl Frobnicate for Foo { fn frob<T>(&self, t: T) {} }
+impl Other for Foo { fn frob(&self) {} }
+fn main(foo: &Foo) {
+    foo.<|>frob::<u32>(92)
+}
+",
+            "
+trait Frobnicate { fn frob<T>(&self, t: T); }
+trait Other { fn frob(&self); }
+struct Foo;
+impl Frobnicate for Foo { fn frob<T>(&self, t: T) {} }
+impl Other for Foo { fn frob(&self) {} }
+fn main(foo: &Foo) {
+    <|>Frobnicate::frob::<u32>(foo, 92)
+}
+",
+        );
+    }
+
+    #[test]
+    fn convert_to_ufcs_not_applicable() {
+        check_assist_not_applicable(
+            convert_to_ufcs,
+            "
+fn main(foo: Foo) { foo.unresolved<|>() }
+",
+        );
+        check_assist_not_applicable(
+            convert_to_ufcs,
+            "
+struct Foo;
+impl Foo { fn by_value(self, x: u32) {} }
+fn main() { Foo.by_value(<|>1) }
+",
+        );
+    }
+
+    #[test]
+    fn convert_ufcs_to_method_call() {
+        check_assist(
+            convert_to_method_call,
+            "
+struct Foo;
+impl Foo { fn by_ref(&self, x: u32, y: u32) {} }
+fn main() {
+    let foo = Foo;
+    Foo::by_<|>ref(&foo, 1, 2);
+}
+",
+            "
+struct Foo;
+impl Foo { fn by_ref(&self, x: u32, y: u32) {} }
+fn main() {
+    let foo = Foo;
+    <|>foo.by_ref(1, 2);
+}
+",
+        );
+        check_assist(
+            convert_to_method_call,
+            "
+trait Frobnicate { fn frob(self); }
+fn main(x: u32, y: u32) {
+    Frobnicate::frob<|>(x + y);
+}
+",
+            "
+trait Frobnicate { fn frob(self); }
+fn main(x: u32, y: u32) {
+    <|>(x + y).frob();
+}
+",
+        );
+    }
+
+    #[test]
+    fn convert_to_method_call_not_applicable() {
+        check_assist_not_applicable(
+            convert_to_method_call,
+            "
+struct Foo;
+impl Foo { fn new() -> Foo { Foo } }
+fn main() { Foo::ne<|>w(); }
+",
+        );
+        check_assist_not_applicable(
+            convert_to_method_call,
+            "
+struct Foo;
+impl Foo { fn by_ref(&self) {} }
+fn main(foo: Foo) { by_<|>ref(&foo); }
+",
+        );
+        check_assist_not_applicable(
+            convert_to_method_call,
+            "
+trait Frobnicate { fn frob(x: u32); }
+fn main() { Frobnicate::fr<|>ob(92); }
+",
+        );
+    }
+
+    #[test]
+    fn convert_to_ufcs_target() {
+        check_assist_target(
+            convert_to_ufcs,
+            "
+struct Foo;
+impl Foo { fn foo(&self) {} }
+fn main() { let x = Foo.fo<|>o(); }
+",
+            "Foo.foo()",
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use hir::{Ty, source_binder, db::HirDatabase};
+use ra_syntax::{
+    AstNode, SyntaxNode, TextUnit, Direction,
+    ast::{self, TypeAscriptionOwner},
+    SyntaxKind::{
+        REF_EXPR, LET_STMT, ARG_LIST, SEMI, INT_NUMBER, IDENT, COLONCOLON, CONST_DEF, STATIC_DEF,
+    },
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn convert_vec_to_array(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let macro_call = ctx.node_at_offset::<ast::MacroCall>()?;
+    if macro_call.path()?.syntax().text() != "vec" {
+        return None;
+    }
+    let token_tree = macro_call.token_tree()?.syntax();
+    // `vec![x; n]` only works as an array if `n` is known at compile time.
+    if let Some(semi) = token_tree.children().find(|it| it.kind() == SEMI) {
+        if !is_const_len(semi) {
+            return None;
+        }
+    }
+    if !array_is_expected(&ctx, macro_call.syntax())? {
+        return None;
+    }
+
+    let range = token_tree.range();
+    let delimiter = TextUnit::of_char('[');
+    let args = token_tree.text().slice(range.start() + delimiter..range.end() - delimiter);
+    ctx.add_action(AssistId("convert_vec_to_array"), "convert to array", |edit| {
+        edit.target(macro_call.syntax().range());
+        edit.replace(macro_call.syntax().range(), format!("[{}]", args));
+        edit.set_cursor(macro_call.syntax().range().start());
+    });
+
+    ctx.build()
+}
+
+pub(crate) fn convert_array_to_vec(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let array = ctx.node_at_offset::<ast::ArrayExpr>()?;
+    // Allocations are not allowed in constants.
+    if array.syntax().ancestors().any(|it| it.kind() == CONST_DEF || it.kind() == STATIC_DEF) {
+        return None;
+    }
+
+    ctx.add_action(AssistId("convert_array_to_vec"), "convert to `vec!`", |edit| {
+        edit.target(array.syntax().range());
+        edit.replace(array.syntax().range(), format!("vec!{}", array.syntax().text()));
+        edit.set_cursor(array.syntax().range().start());
+    });
+
+    ctx.build()
+}
+
+/// Whether the length after `semi` is an integer literal or a path to a
+/// constant, going by the naming convention.
+fn is_const_len(semi: &SyntaxNode) -> bool {
+    let tokens = semi
+        .siblings(Direction::Next)
+        .skip(1)
+        .filter(|it| !it.kind().is_trivia())
+        .collect::<Vec<_>>();
+    // The last token is the closing delimiter.
+    let len = match tokens.split_last() {
+        Some((_, len)) => len,
+        None => return false,
+    };
+    match len {
+        [lit] if lit.kind() == INT_NUMBER => true,
+        [.., last] => {
+            len.iter().all(|it| it.kind() == IDENT || it.kind() == COLONCOLON)
+                && last.leaf_text().map_or(false, |name| {
+                    name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+                })
+        }
+        [] => false,
+    }
+}
+
+/// Best-effort check whether an array can be used where `expr` is, judging
+/// by the type which is expected there.
+fn array_is_expected(ctx: &AssistCtx<impl HirDatabase>, expr: &SyntaxNode) -> Option<bool> {
+    let parent = expr.parent()?;
+    let res = match parent.kind() {
+        // `&vec![..]` is usually coerced to a slice.
+        REF_EXPR => true,
+        LET_STMT => match ast::LetStmt::cast(parent)?.ascribed_type() {
+            Some(ty) => is_array_like(ty),
+            None => false,
+        },
+        ARG_LIST => {
+            let call = parent.parent().and_then(ast::CallExpr::cast)?;
+            // Macro calls are not expressions in the syntax tree, so count
+            // them separately.
+            let idx = parent
+                .children()
+                .filter(|it| ast::Expr::cast(it).is_some() || ast::MacroCall::cast(it).is_some())
+                .position(|it| it == expr)?;
+            let function =
+                source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, call.syntax())?;
+            let infer_result = function.infer(ctx.db);
+            let source_map = function.body_source_map(ctx.db);
+            let callee = infer_result[source_map.node_expr(call.expr()?)?].clone();
+            match callee.callable_sig(ctx.db)?.params().get(idx) {
+                Some(Ty::Array(_)) => true,
+                _ => false,
+            }
+        }
+        _ => false,
+    };
+    Some(res)
+}
+
+fn is_array_like(ty: &ast::TypeRef) -> bool {
+    match ty.kind() {
+        ast::TypeRefKind::ArrayType(_) | ast::TypeRefKind::SliceType(_) => true,
+        ast::TypeRefKind::ReferenceType(it) => it.type_ref().map_or(false, is_array_like),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_simple_vec_to_array() {
+        check_assist(
+            convert_vec_to_array,
+            "fn f() { let xs: &[u32] = &v<|>ec![1, 2, 3]; }",
+            "fn f() { let xs: &[u32] = &<|>[1, 2, 3]; }",
+        );
+        check_assist(
+            convert_vec_to_array,
+            "fn f() { let xs: [u32; 3] = vec!(1, 2, <|>3); }",
+            "fn f() { let xs: [u32; 3] = <|>[1, 2, 3]; }",
+        );
+        check_assist(
+            convert_vec_to_array,
+            "fn foo(xs: [u32; 2]) {} fn f() { foo(<|>vec![0; 2]); }",
+            "fn foo(xs: [u32; 2]) {} fn f() { foo(<|>[0; 2]); }",
+        );
+    }
+
+    #[test]
+    fn convert_repeat_vec_to_array_needs_const_len() {
+        check_assist(
+            convert_vec_to_array,
+            "const N: usize = 4; fn f() { let xs = &vec![0;<|> N]; }",
+            "const N: usize = 4; fn f() { let xs = &<|>[0; N]; }",
+        );
+        check_assist_not_applicable(
+            convert_vec_to_array,
+            "fn f(n: usize) { let xs = &vec![0;<|> n]; }",
+        );
+    }
+
+    #[test]
+    fn convert_vec_to_array_not_applicable() {
+        check_assist_not_applicable(convert_vec_to_array, "fn f() { let xs = vec![1, <|>2]; }");
+        check_assist_not_applicable(
+            convert_vec_to_array,
+            "fn foo(xs: Vec<u32>) {} fn f() { foo(<|>vec![1, 2]); }",
+        );
+        check_assist_not_applicable(convert_vec_to_array, "fn f() { let xs = &foo![1, <|>2]; }");
+    }
+
+    #[test]
+    fn convert_simple_array_to_vec() {
+        check_assist(
+            convert_array_to_vec,
+            "fn f() { let xs = [1, <|>2, 3]; }",
+            "fn f() { let xs = <|>vec![1, 2, 3]; }",
+        );
+        check_assist(
+            convert_array_to_vec,
+            "fn f() { let xs = [<|>0; 16]; }",
+            "fn f() { let xs = <|>vec![0; 16]; }",
+        );
+    }
+
+    #[test]
+    fn convert_array_to_vec_not_applicable_in_const() {
+        check_assist_not_applicable(convert_array_to_vec, "const XS: [u32; 2] = [1, <|>2];");
+    }
+
+    #[test]
+    fn convert_array_to_vec_target() {
+        check_assist_target(convert_array_to_vec, "fn f() { g([1, <|>2]); }", "[1, 2]");
+    }
+}
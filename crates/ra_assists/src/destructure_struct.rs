@@ -0,0 +1,170 @@
+use hir::{AdtDef, ModuleDef, Resolution, Ty, db::HirDatabase, source_binder};
+use ra_syntax::{
+    AstNode,
+    algo::find_covering_node,
+    ast,
+    SyntaxKind::{NAME_REF, FIELD_EXPR, METHOD_CALL_EXPR},
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn destructure_struct_binding(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let let_stmt = ctx.node_at_offset::<ast::LetStmt>()?;
+    let bind_pat = match let_stmt.pat()?.kind() {
+        ast::PatKind::BindPat(it) if it.pat().is_none() && !it.is_mutable() && !it.is_ref() => it,
+        _ => return None,
+    };
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, let_stmt.syntax())?;
+    let pat = function.body_source_map(ctx.db).node_pat(let_stmt.pat()?)?;
+    let strukt = match &function.infer(ctx.db)[pat] {
+        Ty::Adt { def_id: AdtDef::Struct(it), .. } => *it,
+        _ => return None,
+    };
+    let struct_name = strukt.name(ctx.db)?;
+    // The pattern names the struct, so it has to be in scope.
+    let resolver = source_binder::resolver_for_node(ctx.db, ctx.frange.file_id, let_stmt.syntax());
+    let struct_res = Resolution::Def(ModuleDef::Struct(strukt));
+    if resolver.resolve_name(ctx.db, &struct_name).take_types() != Some(struct_res) {
+        return None;
+    }
+    // Tuple structs have numbers for field names.
+    let fields =
+        strukt.fields(ctx.db).iter().map(|it| it.name(ctx.db).to_string()).collect::<Vec<_>>();
+    if fields.iter().any(|it| it.starts_with(|c: char| c.is_ascii_digit())) {
+        return None;
+    }
+
+    let file = let_stmt.syntax().ancestors().last()?;
+    let mut accesses = Vec::new();
+    for reference in function.scopes(ctx.db).find_all_refs(bind_pat) {
+        let name_ref = find_covering_node(file, reference.range);
+        let path_expr = name_ref.ancestors().find_map(ast::PathExpr::cast)?;
+        // Any other use, like passing the struct along, needs it whole.
+        let field_expr = path_expr.syntax().parent().and_then(ast::FieldExpr::cast)?;
+        let field = field_expr.name_ref()?.text().to_string();
+        if !fields.contains(&field) {
+            return None;
+        }
+        accesses.push((field_expr.syntax().range(), field));
+    }
+    if accesses.is_empty() {
+        return None;
+    }
+    let used_fields = fields
+        .iter()
+        .filter(|it| accesses.iter().any(|(_, field)| field == *it))
+        .collect::<Vec<_>>();
+    // The new bindings must not shadow anything used after the `let`.
+    let fn_def = let_stmt.syntax().ancestors().find_map(ast::FnDef::cast)?;
+    let shadows = fn_def.syntax().descendants().any(|it| {
+        it.kind() == NAME_REF
+            && it.range().start() >= let_stmt.syntax().range().end()
+            && it
+                .parent()
+                .map_or(true, |it| it.kind() != FIELD_EXPR && it.kind() != METHOD_CALL_EXPR)
+            && used_fields.iter().any(|field| it.text() == field.as_str())
+    });
+    if shadows {
+        return None;
+    }
+
+    let rest = if used_fields.len() < fields.len() { ", .." } else { "" };
+    let fields_pat = used_fields.iter().map(|it| it.as_str()).collect::<Vec<_>>().join(", ");
+    let pat_range = bind_pat.syntax().range();
+    ctx.add_action(AssistId("destructure_struct_binding"), "destructure into fields", |edit| {
+        edit.target(pat_range);
+        edit.replace(pat_range, format!("{} {{ {}{} }}", struct_name, fields_pat, rest));
+        for (range, field) in accesses {
+            edit.replace(range, field);
+        }
+        edit.set_cursor(pat_range.start());
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn destructure_struct_with_two_fields() {
+        check_assist(
+            destructure_struct_binding,
+            "
+struct Point { x: f64, y: f64 }
+fn make() -> Point { Point { x: 0.0, y: 0.0 } }
+fn f() {
+    let <|>p = make();
+    let len = p.x * p.x + p.y * p.y;
+    g(p.y);
+}",
+            "
+struct Point { x: f64, y: f64 }
+fn make() -> Point { Point { x: 0.0, y: 0.0 } }
+fn f() {
+    let <|>Point { x, y } = make();
+    let len = x * x + y * y;
+    g(y);
+}",
+        );
+    }
+
+    #[test]
+    fn destructure_struct_skips_unused_fields() {
+        check_assist(
+            destructure_struct_binding,
+            "
+struct S { a: u32, b: u32, c: u32 }
+fn f(s: S) {
+    let t<|> = s;
+    t.b.count_ones();
+}",
+            "
+struct S { a: u32, b: u32, c: u32 }
+fn f(s: S) {
+    let <|>S { b, .. } = s;
+    b.count_ones();
+}",
+        );
+    }
+
+    #[test]
+    fn destructure_struct_not_applicable() {
+        // Used whole.
+        check_assist_not_applicable(
+            destructure_struct_binding,
+            "struct S { a: u32 } fn f(s: S) { let t<|> = s; g(t.a); h(&t); }",
+        );
+        // Method call on the struct.
+        check_assist_not_applicable(
+            destructure_struct_binding,
+            "struct S { a: u32 } impl S { fn m(&self) {} } fn f(s: S) { let t<|> = s; t.m(); }",
+        );
+        // `a` would be shadowed.
+        check_assist_not_applicable(
+            destructure_struct_binding,
+            "struct S { a: u32 } fn f(s: S, a: u32) { let t<|> = s; g(t.a, a); }",
+        );
+        // Tuple structs.
+        check_assist_not_applicable(
+            destructure_struct_binding,
+            "struct S(u32); fn f(s: S) { let t<|> = s; g(t.0); }",
+        );
+        check_assist_not_applicable(
+            destructure_struct_binding,
+            "struct S { a: u32 } fn f(s: S) { let mut t<|> = s; t.a = 1; }",
+        );
+    }
+
+    #[test]
+    fn destructure_struct_target() {
+        check_assist_target(
+            destructure_struct_binding,
+            "struct S { a: u32 } fn f(s: S) { let t<|> = s; g(t.a); }",
+            "t",
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    SyntaxNode, TextRange, TextUnit,
+    algo::non_trivia_sibling,
+    Direction,
+    SyntaxKind::{TYPE_PARAM, WHERE_PRED, WHERE_CLAUSE, LIFETIME, COLON, COMMA, SEMI},
+};
+
+use crate::{
+    AssistCtx, Assist, AssistId,
+    utils::{param_name_node, bounds_after, where_clause_with_whitespace},
+};
+
+pub(crate) fn add_bound(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let bounded = bounded_at_offset(&ctx)?;
+    let (offset, insert, cursor) = match bounded.bounds() {
+        Some(it) => {
+            (it.range.end(), " + Trait".to_string(), it.range.end() + TextUnit::of_str(" + "))
+        }
+        None => bounded.first_bound_insert("Trait"),
+    };
+
+    ctx.add_action(AssistId("add_bound"), "add trait bound", |edit| {
+        edit.target(bounded.node.range());
+        edit.insert(offset, insert);
+        edit.set_cursor(cursor);
+    });
+
+    ctx.build()
+}
+
+pub(crate) fn add_maybe_sized(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let bounded = bounded_at_offset(&ctx)?;
+    if bounded.is_lifetime() {
+        return None;
+    }
+    let (offset, insert, cursor) = match bounded.bounds() {
+        Some(it) => {
+            if it.items.iter().any(|&item| bounded.text(item) == "?Sized") {
+                return None;
+            }
+            // `?Sized` goes first, as rustfmt and the standard library have it.
+            (it.range.start(), "?Sized + ".to_string(), it.range.start())
+        }
+        None => bounded.first_bound_insert("?Sized"),
+    };
+
+    ctx.add_action(AssistId("add_maybe_sized"), "add `?Sized`", |edit| {
+        edit.target(bounded.node.range());
+        edit.insert(offset, insert);
+        edit.set_cursor(cursor);
+    });
+
+    ctx.build()
+}
+
+pub(crate) fn remove_bound(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let bounded = bounded_at_offset(&ctx)?;
+    let bounds = bounded.bounds()?;
+    let offset = ctx.frange.range.start();
+    let idx = bounds.items.iter().position(|it| it.contains_inclusive(offset))?;
+    let target = bounds.items[idx];
+
+    let (delete, replace_with) = if bounds.items.len() > 1 {
+        let range = match bounds.items.get(idx + 1) {
+            Some(next) => TextRange::from_to(target.start(), next.start()),
+            None => TextRange::from_to(bounds.items[idx - 1].end(), target.end()),
+        };
+        (range, "")
+    } else if bounded.node.kind() == TYPE_PARAM {
+        (TextRange::from_to(bounded.name.range().end(), bounds.range.end()), "")
+    } else {
+        remove_where_predicate(bounded.node)?
+    };
+
+    ctx.add_action(AssistId("remove_bound"), "remove bound", |edit| {
+        edit.target(target);
+        edit.replace(delete, replace_with);
+        edit.set_cursor(delete.start());
+    });
+
+    ctx.build()
+}
+
+/// A type parameter or a where predicate, together with its bounds.
+struct Bounded<'a> {
+    node: &'a SyntaxNode,
+    /// The parameter name, or the bounded type of a where predicate.
+    name: &'a SyntaxNode,
+    colon: Option<&'a SyntaxNode>,
+}
+
+/// The bounds of a parameter, as in `Clone + Debug`.
+struct Bounds {
+    range: TextRange,
+    /// The individual `+`-separated bounds.
+    items: Vec<TextRange>,
+}
+
+impl<'a> Bounded<'a> {
+    fn bounds(&self) -> Option<Bounds> {
+        let range = bounds_after(self.colon?)?;
+        let items = split_bounds(&self.text(range), range.start());
+        Some(Bounds { range, items })
+    }
+
+    fn text(&self, range: TextRange) -> String {
+        self.node.text().slice(range).to_string()
+    }
+
+    fn is_lifetime(&self) -> bool {
+        self.name.kind() == LIFETIME
+    }
+
+    /// Where to insert `bound` when there are no bounds yet, together with the
+    /// cursor position pointing at the inserted bound.
+    fn first_bound_insert(&self, bound: &str) -> (TextUnit, String, TextUnit) {
+        match self.colon {
+            Some(colon) => {
+                let offset = colon.range().end();
+                (offset, format!(" {}", bound), offset + TextUnit::of_char(' '))
+            }
+            None => {
+                let offset = self.name.range().end();
+                (offset, format!(": {}", bound), offset + TextUnit::of_str(": "))
+            }
+        }
+    }
+}
+
+fn bounded_at_offset<'a>(ctx: &AssistCtx<'a, impl HirDatabase>) -> Option<Bounded<'a>> {
+    let node = ctx.leaf_at_offset().find_map(|leaf| {
+        leaf.ancestors().find(|it| it.kind() == TYPE_PARAM || it.kind() == WHERE_PRED)
+    })?;
+    let name = match node.kind() {
+        TYPE_PARAM => param_name_node(node)?,
+        _ => node.first_child()?,
+    };
+    let colon = node.children().find(|it| it.kind() == COLON);
+    Some(Bounded { node, name, colon })
+}
+
+/// Splits `text` on the top level `+`, returning the trimmed ranges of the
+/// bounds shifted by `start`.
+fn split_bounds(text: &str, start: TextUnit) -> Vec<TextRange> {
+    let mut res = Vec::new();
+    let mut push = |from: usize, to: usize| {
+        let item = &text[from..to];
+        let from = from + (item.len() - item.trim_start().len());
+        let to = from + item.trim().len();
+        res.push(TextRange::from_to(
+            start + TextUnit::from_usize(from),
+            start + TextUnit::from_usize(to),
+        ));
+    };
+    let mut depth = 0usize;
+    let mut item_start = 0;
+    let mut prev = None;
+    for (idx, c) in text.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            // The arrow of `Fn() -> T` does not close anything.
+            '>' if prev == Some('-') => (),
+            '>' | ')' | ']' => depth = depth.saturating_sub(1),
+            '+' if depth == 0 => {
+                push(item_start, idx);
+                item_start = idx + 1;
+            }
+            _ => (),
+        }
+        prev = Some(c);
+    }
+    push(item_start, text.len());
+    res
+}
+
+/// The range to delete to remove a where predicate along with its comma, and
+/// the text to replace it with.
+fn remove_where_predicate(predicate: &SyntaxNode) -> Option<(TextRange, &'static str)> {
+    let where_clause = predicate.parent().filter(|it| it.kind() == WHERE_CLAUSE)?;
+    let trailing_comma =
+        non_trivia_sibling(predicate, Direction::Next).filter(|it| it.kind() == COMMA);
+    let next = trailing_comma.and_then(|it| non_trivia_sibling(it, Direction::Next));
+    if let Some(next) = next.filter(|it| it.kind() == WHERE_PRED) {
+        return Some((TextRange::from_to(predicate.range().start(), next.range().start()), ""));
+    }
+    let prev_comma = non_trivia_sibling(predicate, Direction::Prev).filter(|it| it.kind() == COMMA);
+    if let Some(prev_comma) = prev_comma {
+        let range = match trailing_comma {
+            Some(comma) => TextRange::from_to(prev_comma.range().end(), comma.range().end()),
+            None => TextRange::from_to(prev_comma.range().start(), predicate.range().end()),
+        };
+        return Some((range, ""));
+    }
+    // The only predicate, remove the whole where clause.
+    let owner = where_clause.parent()?;
+    let body_follows = owner.last_child().map_or(false, |it| it.kind() != SEMI);
+    Some((where_clause_with_whitespace(where_clause), if body_follows { " " } else { "" }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_bound_to_param() {
+        check_assist(add_bound, "fn foo<T<|>>(t: T) {}", "fn foo<T: <|>Trait>(t: T) {}");
+        check_assist(
+            add_bound,
+            "fn foo<T<|>: Clone = u32>(t: T) {}",
+            "fn foo<T: Clone + <|>Trait = u32>(t: T) {}",
+        );
+        check_assist(
+            add_bound,
+            "fn foo<T>(t: T) where T<|>: Clone {}",
+            "fn foo<T>(t: T) where T: Clone + <|>Trait {}",
+        );
+    }
+
+    #[test]
+    fn add_maybe_sized_to_param() {
+        check_assist(add_maybe_sized, "fn foo<T<|>>(t: &T) {}", "fn foo<T: <|>?Sized>(t: &T) {}");
+        check_assist(
+            add_maybe_sized,
+            "fn foo<T<|>: Clone>(t: &T) {}",
+            "fn foo<T: <|>?Sized + Clone>(t: &T) {}",
+        );
+        check_assist(
+            add_maybe_sized,
+            "fn foo<T>(t: &T) where <|>T: Clone + Debug {}",
+            "fn foo<T>(t: &T) where T: <|>?Sized + Clone + Debug {}",
+        );
+    }
+
+    #[test]
+    fn add_maybe_sized_not_applicable() {
+        check_assist_not_applicable(add_maybe_sized, "fn foo<T<|>: Clone + ?Sized>(t: &T) {}");
+        check_assist_not_applicable(add_maybe_sized, "fn foo<'a<|>>(t: &'a u32) {}");
+        check_assist_not_applicable(add_maybe_sized, "fn foo<T>(t: &T) { <|> }");
+    }
+
+    #[test]
+    fn remove_bound_from_param() {
+        check_assist(
+            remove_bound,
+            "fn foo<T: Clone + Deb<|>ug>(t: T) {}",
+            "fn foo<T: Clone<|>>(t: T) {}",
+        );
+        check_assist(
+            remove_bound,
+            "fn foo<T: Cl<|>one + Iterator<Item = u32>>(t: T) {}",
+            "fn foo<T: <|>Iterator<Item = u32>>(t: T) {}",
+        );
+        check_assist(
+            remove_bound,
+            "fn foo<T: Cl<|>one = u32>(t: T) {}",
+            "fn foo<T<|> = u32>(t: T) {}",
+        );
+    }
+
+    #[test]
+    fn remove_bound_from_where_clause() {
+        check_assist(
+            remove_bound,
+            "fn foo<T, U>() where T: Fn() -> u32 + Cl<|>one, U: Copy {}",
+            "fn foo<T, U>() where T: Fn() -> u32<|>, U: Copy {}",
+        );
+        check_assist(
+            remove_bound,
+            "fn foo<T, U>() where T: Cl<|>one, U: Copy {}",
+            "fn foo<T, U>() where <|>U: Copy {}",
+        );
+        check_assist(
+            remove_bound,
+            "
+fn foo<T, U>()
+where
+    U: Copy,
+    T: Cl<|>one,
+{
+}",
+            "
+fn foo<T, U>()
+where
+    U: Copy,<|>
+{
+}",
+        );
+        check_assist(remove_bound, "fn foo<T>() where T: Cl<|>one {}", "fn foo<T>()<|> {}");
+    }
+
+    #[test]
+    fn remove_bound_target() {
+        check_assist_target(remove_bound, "fn foo<T: Clone + Deb<|>ug>() {}", "Debug");
+    }
+}
@@ -0,0 +1,320 @@
+use hir::{Path, PerNs, Resolution, ModuleDef, source_binder, db::HirDatabase};
+use ra_syntax::{
+    AstNode, TextRange,
+    ast,
+    SyntaxKind::{STAR, WHITESPACE},
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+/// How many names have to be imported from a module before we suggest to
+/// import everything with a glob instead.
+const COLLAPSE_THRESHOLD: usize = 3;
+
+pub(crate) fn expand_glob(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let use_item = ctx.node_at_offset::<ast::UseItem>()?;
+    let use_tree = use_item.use_tree()?;
+    if !use_tree.has_star() {
+        return None;
+    }
+    let star = use_tree.syntax().children().find(|it| it.kind() == STAR)?;
+    let module = match glob_target(&ctx, use_item, use_tree.path()?)? {
+        ModuleDef::Module(it) => it,
+        _ => return None,
+    };
+    let scope = module.scope(ctx.db);
+
+    let source_file = use_item.syntax().ancestors().last()?;
+    let mut names = source_file
+        .descendants()
+        .filter_map(ast::NameRef::cast)
+        .filter(|name_ref| is_first_segment_outside_imports(name_ref))
+        .filter(|name_ref| {
+            let path = Path::from_name_ref(name_ref);
+            let entry = match scope.get(&path.segments[0].name) {
+                Some(it) => it,
+                None => return false,
+            };
+            let resolver =
+                source_binder::resolver_for_node(ctx.db, ctx.frange.file_id, name_ref.syntax());
+            let resolution = resolver.resolve_path(ctx.db, &path);
+            comes_from_glob(resolution, entry.def)
+        })
+        .map(|name_ref| name_ref.text().to_string())
+        .collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+
+    if names.is_empty() {
+        ctx.add_action(AssistId("remove_glob_import"), "remove unused glob import", |edit| {
+            let mut range = use_item.syntax().range();
+            if let Some(ws) = use_item.syntax().next_sibling().filter(|it| it.kind() == WHITESPACE)
+            {
+                range = TextRange::from_to(range.start(), ws.range().end());
+            }
+            edit.target(use_item.syntax().range());
+            edit.delete(range);
+            edit.set_cursor(range.start());
+        });
+    } else {
+        let replacement =
+            if names.len() == 1 { names[0].clone() } else { format!("{{{}}}", names.join(", ")) };
+        ctx.add_action(AssistId("expand_glob"), "expand glob import", |edit| {
+            edit.target(use_item.syntax().range());
+            edit.replace(star.range(), replacement);
+            edit.set_cursor(use_tree.syntax().range().start());
+        });
+    }
+
+    ctx.build()
+}
+
+pub(crate) fn collapse_to_glob(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let use_item = ctx.node_at_offset::<ast::UseItem>()?;
+    let use_tree = use_item.use_tree()?;
+    use_tree.path()?;
+    let use_tree_list = use_tree.use_tree_list()?;
+    let mut count = 0;
+    for tree in use_tree_list.use_trees() {
+        if !is_plain_name(tree) {
+            return None;
+        }
+        count += 1;
+    }
+    if count < COLLAPSE_THRESHOLD {
+        return None;
+    }
+
+    ctx.add_action(AssistId("collapse_to_glob"), "collapse to glob", |edit| {
+        edit.target(use_item.syntax().range());
+        edit.replace(use_tree_list.syntax().range(), "*");
+        edit.set_cursor(use_tree.syntax().range().start());
+    });
+
+    ctx.build()
+}
+
+/// Resolves the path in front of `::*`.
+fn glob_target(
+    ctx: &AssistCtx<impl HirDatabase>,
+    use_item: &ast::UseItem,
+    path: &ast::Path,
+) -> Option<ModuleDef> {
+    let path = Path::from_ast(path)?;
+    let resolver = source_binder::resolver_for_node(ctx.db, ctx.frange.file_id, use_item.syntax());
+    match resolver.resolve_path(ctx.db, &path).take_types()? {
+        Resolution::Def(def) => Some(def),
+        _ => None,
+    }
+}
+
+/// Only the first segment of a path can refer to a glob-imported name, and
+/// paths in other imports are resolved relative to the crate root.
+fn is_first_segment_outside_imports(name_ref: &ast::NameRef) -> bool {
+    let segment = name_ref.syntax().parent().and_then(ast::PathSegment::cast);
+    let path = match segment.and_then(|it| it.syntax().parent()).and_then(ast::Path::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    path.qualifier().is_none()
+        && !path.syntax().ancestors().any(|it| ast::UseItem::cast(it).is_some())
+}
+
+/// Checks that a name resolves to the item the glob imports, and not to
+/// something shadowing it, in at least one namespace.
+fn comes_from_glob(resolution: PerNs<Resolution>, imported: PerNs<ModuleDef>) -> bool {
+    let types = match (resolution.clone().take_types(), imported.take_types()) {
+        (Some(Resolution::Def(def)), Some(imported)) => def == imported,
+        _ => false,
+    };
+    let values = match (resolution.take_values(), imported.take_values()) {
+        (Some(Resolution::Def(def)), Some(imported)) => def == imported,
+        _ => false,
+    };
+    types || values
+}
+
+fn is_plain_name(tree: &ast::UseTree) -> bool {
+    if tree.has_star() || tree.use_tree_list().is_some() || tree.alias().is_some() {
+        return false;
+    }
+    match tree.path() {
+        Some(path) => {
+            path.qualifier().is_none()
+                && path.segment().and_then(ast::PathSegment::name_ref).is_some()
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn expand_glob_to_used_names() {
+        check_assist(
+            expand_glob,
+            "
+mod foo {
+    pub fn bar() {}
+    pub fn baz() {}
+    pub struct Qux;
+    pub struct Unused;
+}
+use foo::*<|>;
+
+fn main() {
+    let q: Qux = Qux;
+    baz();
+    bar();
+    baz();
+}
+",
+            "
+mod foo {
+    pub fn bar() {}
+    pub fn baz() {}
+    pub struct Qux;
+    pub struct Unused;
+}
+use <|>foo::{Qux, bar, baz};
+
+fn main() {
+    let q: Qux = Qux;
+    baz();
+    bar();
+    baz();
+}
+",
+        );
+    }
+
+    #[test]
+    fn expand_glob_ignores_shadowed_names() {
+        check_assist(
+            expand_glob,
+            "
+mod foo {
+    pub fn bar() {}
+    pub fn baz() {}
+    pub fn qux() {}
+}
+<|>use foo::*;
+
+fn baz() {}
+
+fn main() {
+    let bar = 92;
+    bar;
+    baz();
+    foo::bar();
+    qux();
+}
+",
+            "
+mod foo {
+    pub fn bar() {}
+    pub fn baz() {}
+    pub fn qux() {}
+}
+use <|>foo::qux;
+
+fn baz() {}
+
+fn main() {
+    let bar = 92;
+    bar;
+    baz();
+    foo::bar();
+    qux();
+}
+",
+        );
+    }
+
+    #[test]
+    fn expand_glob_single_name() {
+        check_assist(
+            expand_glob,
+            "
+mod foo {
+    pub fn bar() {}
+    pub fn baz() {}
+}
+use foo::<|>*;
+
+fn main() { bar() }
+",
+            "
+mod foo {
+    pub fn bar() {}
+    pub fn baz() {}
+}
+use <|>foo::bar;
+
+fn main() { bar() }
+",
+        );
+    }
+
+    #[test]
+    fn expand_unused_glob_removes_import() {
+        check_assist(
+            expand_glob,
+            "
+mod foo {
+    pub fn bar() {}
+}
+use foo::<|>*;
+fn main() {}
+",
+            "
+mod foo {
+    pub fn bar() {}
+}
+<|>fn main() {}
+",
+        );
+    }
+
+    #[test]
+    fn expand_glob_not_applicable() {
+        check_assist_not_applicable(
+            expand_glob,
+            "
+mod foo { pub fn bar() {} }
+use foo::<|>bar;
+",
+        );
+        check_assist_not_applicable(expand_glob, "use unresolved::<|>*;");
+    }
+
+    #[test]
+    fn expand_glob_target() {
+        check_assist_target(
+            expand_glob,
+            "
+mod foo { pub fn bar() {} }
+use foo::<|>*;
+fn main() { bar() }
+",
+            "use foo::*;",
+        );
+    }
+
+    #[test]
+    fn collapse_many_names_to_glob() {
+        check_assist(collapse_to_glob, "use foo::{a, <|>b, c};", "use <|>foo::*;");
+    }
+
+    #[test]
+    fn collapse_to_glob_not_applicable() {
+        check_assist_not_applicable(collapse_to_glob, "use foo::{a, <|>b};");
+        check_assist_not_applicable(collapse_to_glob, "use foo::{a, <|>b as c, d};");
+        check_assist_not_applicable(collapse_to_glob, "use foo::{self, <|>b, c};");
+        check_assist_not_applicable(collapse_to_glob, "use foo::{a, <|>b::c, d};");
+        check_assist_not_applicable(collapse_to_glob, "use foo::{a, <|>b::{c, e}, d};");
+    }
+}
@@ -0,0 +1,188 @@
+use hir::{HirDisplay, Ty, db::HirDatabase, source_binder};
+use ra_syntax::{
+    AstNode, TextUnit,
+    ast::{self, NameOwner},
+    SyntaxKind::{BLOCK, SELF_KW},
+};
+
+use crate::{
+    AssistCtx, Assist, AssistId,
+    utils::{fresh_name, item_indent},
+};
+
+pub(crate) fn extract_closure_to_fn(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let lambda = ctx.node_at_offset::<ast::LambdaExpr>()?;
+    let body = lambda.body()?;
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, lambda.syntax())?;
+    if captures_environment(&ctx, function, lambda) {
+        return None;
+    }
+    let infer = function.infer(ctx.db);
+    let source_map = function.body_source_map(ctx.db);
+    let sig = match &infer[source_map.node_expr(ast::Expr::cast(lambda.syntax())?)?] {
+        Ty::Closure { sig, .. } => sig.clone(),
+        _ => return None,
+    };
+    let pats = lambda.param_list()?.params().map(|it| it.pat()).collect::<Option<Vec<_>>>()?;
+    let mut params = Vec::new();
+    for (pat, ty) in pats.iter().zip(sig.params()) {
+        params.push(format!("{}: {}", pat.syntax().text(), type_text(ctx.db, ty)?));
+    }
+    let ret = match sig.ret() {
+        Ty::Tuple(it) if it.is_empty() => String::new(),
+        ty => format!(" -> {}", type_text(ctx.db, ty)?),
+    };
+    let body = match body.kind() {
+        ast::ExprKind::BlockExpr(_) => body.syntax().text().to_string(),
+        _ => format!("{{ {} }}", body.syntax().text()),
+    };
+
+    // The function goes right before the statement with the closure.
+    let anchor = lambda
+        .syntax()
+        .ancestors()
+        .take_while(|it| *it != function.source(ctx.db).1.syntax())
+        .find(|it| it.parent().map(|it| it.kind()) == Some(BLOCK))?;
+    let name = fresh_name(anchor.ancestors().last()?, &fn_name(lambda));
+    let fn_def =
+        format!("fn {}({}){} {}\n{}", name, params.join(", "), ret, body, item_indent(anchor));
+    let fn_start = anchor.range().start();
+
+    ctx.add_action(AssistId("extract_closure_to_fn"), "extract closure to local fn", |edit| {
+        edit.target(lambda.syntax().range());
+        edit.insert(fn_start, fn_def);
+        edit.replace(lambda.syntax().range(), name.clone());
+        edit.set_cursor(fn_start + TextUnit::of_str("fn "));
+    });
+
+    ctx.build()
+}
+
+/// Whether the closure uses a local variable or `self` of the function it is
+/// in, which a `fn` can't.
+fn captures_environment(
+    ctx: &AssistCtx<impl HirDatabase>,
+    function: hir::Function,
+    lambda: &ast::LambdaExpr,
+) -> bool {
+    let scopes = function.scopes(ctx.db);
+    let range = lambda.syntax().range();
+    lambda.syntax().descendants().any(|node| {
+        if node.kind() == SELF_KW {
+            return true;
+        }
+        let entry = ast::NameRef::cast(node).and_then(|it| scopes.resolve_local_name(it));
+        entry.map_or(false, |it| !it.ptr().range().is_subrange(&range))
+    })
+}
+
+/// The type as it can be written down, with unconstrained numbers defaulted as
+/// the compiler does.
+fn type_text(db: &impl HirDatabase, ty: &Ty) -> Option<String> {
+    let text = ty.display(db).to_string();
+    match text.as_str() {
+        "{integer}" => Some("i32".to_string()),
+        "{float}" => Some("f64".to_string()),
+        _ if text.contains('{') => None,
+        _ => Some(text),
+    }
+}
+
+/// Names the function after the variable the closure is assigned to, or the
+/// call it is passed to.
+fn fn_name(lambda: &ast::LambdaExpr) -> String {
+    let parent = lambda.syntax().parent();
+    if let Some(let_stmt) = parent.and_then(ast::LetStmt::cast) {
+        if let Some(ast::PatKind::BindPat(it)) = let_stmt.pat().map(|it| it.kind()) {
+            if let Some(name) = it.name() {
+                return format!("{}_fn", name.text());
+            }
+        }
+    }
+    let call = parent.and_then(|it| it.parent());
+    let callee = call.and_then(|call| {
+        if let Some(call) = ast::MethodCallExpr::cast(call) {
+            return call.name_ref().map(|it| it.text().to_string());
+        }
+        match ast::CallExpr::cast(call)?.expr()?.kind() {
+            ast::ExprKind::PathExpr(it) => {
+                Some(it.path()?.segment()?.name_ref()?.text().to_string())
+            }
+            _ => None,
+        }
+    });
+    match callee {
+        Some(callee) => format!("{}_fn", callee),
+        None => "closure_fn".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn extract_closure_passed_to_map() {
+        check_assist(
+            extract_closure_to_fn,
+            "
+fn f(xs: Iter) {
+    let ys = xs.map(|x: u32<|>| x + 1);
+}",
+            "
+fn f(xs: Iter) {
+    fn <|>map_fn(x: u32) -> u32 { x + 1 }
+    let ys = xs.map(map_fn);
+}",
+        );
+    }
+
+    #[test]
+    fn extract_closure_assigned_to_variable() {
+        check_assist(
+            extract_closure_to_fn,
+            "
+fn f() {
+    let add = |<|>a: u32, b: u32| {
+        a + b
+    };
+}",
+            "
+fn f() {
+    fn <|>add_fn(a: u32, b: u32) -> u32 {
+        a + b
+    }
+    let add = add_fn;
+}",
+        );
+    }
+
+    #[test]
+    fn extract_closure_to_fn_not_applicable() {
+        // The closure captures `y`.
+        check_assist_not_applicable(
+            extract_closure_to_fn,
+            "fn f(xs: Iter, y: u32) { xs.map(|x: u32<|>| x + y); }",
+        );
+        check_assist_not_applicable(
+            extract_closure_to_fn,
+            "struct S; impl S { fn f(&self, xs: Iter) { xs.map(|x: u32<|>| self); } }",
+        );
+        // The type of `x` is not known.
+        check_assist_not_applicable(
+            extract_closure_to_fn,
+            "fn f(xs: Iter) { xs.map(|x<|>| x + 1); }",
+        );
+    }
+
+    #[test]
+    fn extract_closure_to_fn_target() {
+        check_assist_target(
+            extract_closure_to_fn,
+            "fn f(xs: Iter) { xs.map(|x: u32<|>| x + 1); }",
+            "|x: u32| x + 1",
+        );
+    }
+}
@@ -0,0 +1,282 @@
+use hir::{HirDisplay, db::HirDatabase, source_binder};
+use ra_syntax::{
+    AstNode, SyntaxNode, TextUnit,
+    ast::{self, LiteralFlavor},
+    SyntaxKind::{CONST_DEF, STATIC_DEF, MODULE},
+};
+
+use crate::{
+    AssistCtx, Assist, AssistId,
+    utils::{is_module_item, fresh_name, item_indent},
+};
+
+pub(crate) fn extract_constant(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let literal = ctx.node_at_offset::<ast::Literal>()?;
+    if is_const_initializer(literal.syntax()) {
+        return None;
+    }
+    let anchor = literal.syntax().ancestors().find(|it| is_module_item(it))?;
+    let scope = anchor.parent()?;
+
+    let name = fresh_name(scope.ancestors().last()?, "CONSTANT");
+    let ty = const_type(&ctx, literal)?;
+    let value = literal.syntax().text().to_string();
+    let const_def = format!("const {}: {} = {};\n\n{}", name, ty, value, item_indent(anchor));
+    let const_start = anchor.range().start();
+    let cursor = const_start + TextUnit::of_str("const ");
+
+    ctx.add_action(AssistId("extract_constant"), "extract constant", |edit| {
+        edit.target(literal.syntax().range());
+        edit.insert(const_start, const_def.clone());
+        edit.replace(literal.syntax().range(), name.clone());
+        edit.set_cursor(cursor);
+    });
+
+    let occurrences = scope
+        .descendants()
+        .filter_map(ast::Literal::cast)
+        .filter(|it| {
+            it.syntax().ancestors().take_while(|it| *it != scope).all(|it| it.kind() != MODULE)
+        })
+        .filter(|it| !is_const_initializer(it.syntax()))
+        .filter(|it| it.syntax().text() == value.as_str())
+        // The same `1` can be a `u8` in one place and a `usize` in another.
+        .filter(|it| const_type(&ctx, it).as_ref() == Some(&ty))
+        .collect::<Vec<_>>();
+    if occurrences.len() > 1 {
+        // Replacements before the constant shift it around.
+        let mut cursor = cursor;
+        for occurrence in occurrences.iter() {
+            let range = occurrence.syntax().range();
+            if range.end() <= const_start {
+                cursor = cursor + TextUnit::of_str(&name) - range.len();
+            }
+        }
+        ctx.add_action(
+            AssistId("extract_constant_all"),
+            "extract constant for all occurrences",
+            |edit| {
+                edit.target(literal.syntax().range());
+                edit.insert(const_start, const_def);
+                for occurrence in occurrences {
+                    edit.replace(occurrence.syntax().range(), name.clone());
+                }
+                edit.set_cursor(cursor);
+            },
+        );
+    }
+
+    ctx.build()
+}
+
+/// Literals which already are the value of a constant stay where they are.
+fn is_const_initializer(literal: &SyntaxNode) -> bool {
+    literal.parent().map_or(false, |it| it.kind() == CONST_DEF || it.kind() == STATIC_DEF)
+}
+
+/// The type of the constant, as inferred for the literal or, for literals
+/// outside of functions and unconstrained numbers, as the compiler defaults it.
+fn const_type(ctx: &AssistCtx<impl HirDatabase>, literal: &ast::Literal) -> Option<String> {
+    let inferred =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, literal.syntax())
+            .and_then(|function| {
+                let expr = ast::Expr::cast(literal.syntax())?;
+                let expr = function.body_source_map(ctx.db).node_expr(expr)?;
+                Some(function.infer(ctx.db)[expr].display(ctx.db).to_string())
+            });
+    match inferred {
+        // `{integer}`, `{float}` and `{unknown}` can't be written down.
+        Some(ty) if !ty.contains('{') => return Some(ty),
+        _ => (),
+    }
+    let ty = match literal.literal_expr()?.flavor() {
+        LiteralFlavor::IntNumber { suffix } => {
+            suffix.map_or("i32".to_string(), |it| it.to_string())
+        }
+        LiteralFlavor::FloatNumber { suffix } => {
+            suffix.map_or("f64".to_string(), |it| it.to_string())
+        }
+        LiteralFlavor::String => "&str".to_string(),
+        LiteralFlavor::ByteString => "&[u8]".to_string(),
+        LiteralFlavor::Char => "char".to_string(),
+        LiteralFlavor::Byte => "u8".to_string(),
+        LiteralFlavor::Bool => "bool".to_string(),
+    };
+    Some(ty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_nth_action, check_assist_not_applicable, check_assist_target,
+    };
+
+    #[test]
+    fn extract_magic_number_used_twice() {
+        let before = "
+fn area(r: f64) -> f64 {
+    r * r * 3.14<|>159
+}
+
+fn circumference(r: f64) -> f64 {
+    2.0 * r * 3.14159
+}
+";
+        check_assist(
+            extract_constant,
+            before,
+            "
+const <|>CONSTANT: f64 = 3.14159;
+
+fn area(r: f64) -> f64 {
+    r * r * CONSTANT
+}
+
+fn circumference(r: f64) -> f64 {
+    2.0 * r * 3.14159
+}
+",
+        );
+        check_assist_nth_action(
+            extract_constant,
+            before,
+            "
+const <|>CONSTANT: f64 = 3.14159;
+
+fn area(r: f64) -> f64 {
+    r * r * CONSTANT
+}
+
+fn circumference(r: f64) -> f64 {
+    2.0 * r * CONSTANT
+}
+",
+            1,
+        );
+    }
+
+    #[test]
+    fn extract_constant_uses_inferred_type() {
+        check_assist(
+            extract_constant,
+            "
+fn foo(x: u8) {}
+
+fn main() {
+    foo(4<|>2);
+}
+",
+            "
+fn foo(x: u8) {}
+
+const <|>CONSTANT: u8 = 42;
+
+fn main() {
+    foo(CONSTANT);
+}
+",
+        );
+    }
+
+    #[test]
+    fn extract_constant_shifts_cursor_for_earlier_occurrences() {
+        check_assist_nth_action(
+            extract_constant,
+            "
+fn limit() -> usize { 1 + 100 }
+
+mod inner {
+    fn f() -> usize { 100 }
+}
+
+fn main() {
+    let a: usize = 10<|>0;
+}
+",
+            "
+fn limit() -> usize { 1 + CONSTANT }
+
+mod inner {
+    fn f() -> usize { 100 }
+}
+
+const <|>CONSTANT: usize = 100;
+
+fn main() {
+    let a: usize = CONSTANT;
+}
+",
+            1,
+        );
+    }
+
+    #[test]
+    fn extract_constant_for_occurrences_of_the_same_type() {
+        check_assist_nth_action(
+            extract_constant,
+            "
+fn f(x: u8, y: usize) {}
+
+fn main() {
+    f(1<|>, 1);
+    f(1, 2);
+}
+",
+            "
+fn f(x: u8, y: usize) {}
+
+const <|>CONSTANT: u8 = 1;
+
+fn main() {
+    f(CONSTANT, 1);
+    f(CONSTANT, 2);
+}
+",
+            1,
+        );
+    }
+
+    #[test]
+    fn extract_string_constant() {
+        check_assist(
+            extract_constant,
+            "fn main() { let _ = \"ab<|>c\"; }",
+            "const <|>CONSTANT: &str = \"abc\";\n\nfn main() { let _ = CONSTANT; }",
+        );
+    }
+
+    #[test]
+    fn extract_constant_picks_fresh_name() {
+        check_assist(
+            extract_constant,
+            "
+const CONSTANT: char = 'a';
+
+fn main() {
+    let c = 'b<|>';
+}
+",
+            "
+const CONSTANT: char = 'a';
+
+const <|>CONSTANT2: char = 'b';
+
+fn main() {
+    let c = CONSTANT2;
+}
+",
+        );
+    }
+
+    #[test]
+    fn extract_constant_not_applicable_to_const_value() {
+        check_assist_not_applicable(extract_constant, "const X: u32 = 9<|>2;");
+        check_assist_not_applicable(extract_constant, "fn f() { let x<|> = 1; }");
+    }
+
+    #[test]
+    fn extract_constant_target() {
+        check_assist_target(extract_constant, "fn f() { g(1, 2<|>3); }", "23");
+    }
+}
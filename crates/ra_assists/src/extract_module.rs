@@ -0,0 +1,263 @@
+use hir::{ModuleSource, db::HirDatabase, source_binder};
+use ra_db::{Edition, FileId};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    AstNode, Direction, TextRange, TextUnit,
+    ast::{self, ModuleItemOwner, NameOwner},
+    algo::non_trivia_sibling,
+    SyntaxKind::SEMI,
+};
+use relative_path::RelativePathBuf;
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn extract_module(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let module = ctx.node_at_offset::<ast::Module>()?;
+    let item_list = module.item_list()?;
+    // Only offered on the header, not in the items of the module.
+    if ctx.frange.range.start() > item_list.syntax().range().start() {
+        return None;
+    }
+    let name = module.name()?.text().to_string();
+    let file_id = ctx.frange.file_id;
+    let file_module = source_binder::module_from_file_id(ctx.db, file_id)?;
+
+    // In the 2015 edition, only `mod.rs` files can declare modules in files.
+    let has_file_submodules = item_list
+        .items()
+        .filter_map(|it| ast::Module::cast(it.syntax()))
+        .any(|it| it.item_list().is_none());
+    let is_2015 =
+        file_module.krate(ctx.db).map_or(false, |it| it.edition(ctx.db) == Edition::Edition2015);
+    let dir = module_dir(ctx.db, file_id, file_module.parent(ctx.db).is_none(), module);
+    let path = if has_file_submodules && is_2015 {
+        dir.join(format!("{}/mod.rs", name))
+    } else {
+        dir.join(format!("{}.rs", name))
+    };
+    let source_root = ctx.db.file_source_root(file_id);
+    if ctx.db.source_root(source_root).files.contains_key(&path) {
+        return None;
+    }
+
+    let indent = format!("{}    ", leading_indent(module.syntax()).unwrap_or(""));
+    let range = item_list.syntax().range();
+    let body = item_list
+        .syntax()
+        .text()
+        .slice(range.start() + TextUnit::of_char('{')..range.end() - TextUnit::of_char('}'))
+        .to_string();
+    let lines = body
+        .lines()
+        .map(|line| if line.starts_with(&indent) { &line[indent.len()..] } else { line.trim() })
+        .collect::<Vec<_>>();
+    let mut contents = lines.join("\n").trim().to_string();
+    if !contents.is_empty() {
+        contents.push('\n');
+    }
+
+    let start = non_trivia_sibling(item_list.syntax(), Direction::Prev)
+        .map_or(range.start(), |it| it.range().end());
+    ctx.add_action(AssistId("extract_module"), "extract module to file", |edit| {
+        edit.target(module.syntax().range());
+        edit.replace(TextRange::from_to(start, range.end()), ";");
+        edit.create_file(source_root, path, contents);
+        edit.set_cursor(start);
+    });
+
+    ctx.build()
+}
+
+pub(crate) fn inline_module(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let module = ctx.node_at_offset::<ast::Module>()?;
+    let semi = module.syntax().children().find(|it| it.kind() == SEMI)?;
+    let hir_module = source_binder::module_from_declaration(ctx.db, ctx.frange.file_id, module)?;
+    let (module_file, source) = hir_module.definition_source(ctx.db);
+    match source {
+        ModuleSource::SourceFile(_) => {}
+        ModuleSource::Module(_) => return None,
+    }
+    let module_file = module_file.original_file(ctx.db);
+    if module_file == ctx.frange.file_id {
+        return None;
+    }
+
+    let indent = leading_indent(module.syntax()).unwrap_or("").to_string();
+    let text = ctx.db.file_text(module_file);
+    let lines = text
+        .trim()
+        .lines()
+        .map(|line| if line.is_empty() { String::new() } else { format!("{}    {}", indent, line) })
+        .collect::<Vec<_>>();
+    let body = if lines.is_empty() {
+        " {}".to_string()
+    } else {
+        format!(" {{\n{}\n{}}}", lines.join("\n"), indent)
+    };
+
+    let start = non_trivia_sibling(semi, Direction::Prev)
+        .map_or(semi.range().start(), |it| it.range().end());
+    ctx.add_action(AssistId("inline_module"), "inline module from file", |edit| {
+        edit.target(module.syntax().range());
+        edit.replace(TextRange::from_to(start, semi.range().end()), body);
+        edit.delete_file(module_file);
+        edit.set_cursor(start);
+    });
+
+    ctx.build()
+}
+
+/// The directory of the files of the submodules of the inline `module`. Unlike
+/// the module tree, which doesn't handle submodules of inline modules yet, this
+/// includes the names of the inline parents of `module`, like rustc does.
+fn module_dir(
+    db: &impl HirDatabase,
+    file_id: FileId,
+    is_root: bool,
+    module: &ast::Module,
+) -> RelativePathBuf {
+    let path = db.file_relative_path(file_id);
+    let mut dir = path.parent().map(|it| it.to_relative_path_buf()).unwrap_or_default();
+    let stem = path.file_stem().unwrap_or("");
+    if !is_root && stem != "mod" {
+        dir = dir.join(stem);
+    }
+    let parents = module
+        .syntax()
+        .ancestors()
+        .skip(1)
+        .filter_map(ast::Module::cast)
+        .filter_map(|it| it.name())
+        .map(|it| it.text().to_string())
+        .collect::<Vec<_>>();
+    for name in parents.iter().rev() {
+        dir = dir.join(name);
+    }
+    dir
+}
+
+#[cfg(test)]
+mod tests {
+    use hir::mock::MockDatabase;
+    use ra_db::{FileRange, SourceDatabase};
+    use test_utils::assert_eq_text;
+
+    use super::*;
+    use crate::{FileSystemEdit, helpers::check_assist_not_applicable};
+
+    fn check_extract_module(fixture: &str, after: &str, path: &str, contents: &str) {
+        let (db, position) = MockDatabase::with_position(fixture);
+        let frange = FileRange {
+            file_id: position.file_id,
+            range: TextRange::offset_len(position.offset, 0.into()),
+        };
+        let action = match AssistCtx::with_ctx(&db, frange, true, extract_module) {
+            Some(Assist::Resolved(mut labels_actions)) => labels_actions.remove(0).1,
+            _ => panic!("code action is not applicable"),
+        };
+        let before = db.file_text(position.file_id);
+        assert_eq_text!(after, &action.edit.apply(&before));
+        match &action.file_system_edits[..] {
+            [FileSystemEdit::CreateFile { path: actual_path, contents: actual_contents, .. }] => {
+                assert_eq!(path, actual_path.as_str());
+                assert_eq_text!(contents, actual_contents);
+            }
+            edits => panic!("unexpected file system edits: {:?}", edits),
+        }
+    }
+
+    #[test]
+    fn extract_module_to_file() {
+        check_extract_module(
+            "
+//- /main.rs
+mod foo<|> {
+    use super::*;
+
+    fn bar() {
+        baz();
+    }
+}
+fn baz() {}
+",
+            "mod foo;
+fn baz() {}
+",
+            "foo.rs",
+            "use super::*;
+
+fn bar() {
+    baz();
+}
+",
+        );
+    }
+
+    #[test]
+    fn extract_nested_module_to_file() {
+        check_extract_module(
+            "
+//- /main.rs
+mod a;
+//- /a.rs
+pub mod b {
+    #[cfg(test)]
+    mod c<|> {}
+}
+",
+            "pub mod b {
+    #[cfg(test)]
+    mod c;
+}
+",
+            "a/b/c.rs",
+            "",
+        );
+    }
+
+    #[test]
+    fn inline_module_from_file() {
+        let (db, position) = MockDatabase::with_position(
+            "
+//- /main.rs
+mod m {}
+mod foo<|>;
+//- /foo.rs
+fn bar() {}
+
+fn baz() {}
+",
+        );
+        let frange = FileRange {
+            file_id: position.file_id,
+            range: TextRange::offset_len(position.offset, 0.into()),
+        };
+        let action = match AssistCtx::with_ctx(&db, frange, true, inline_module) {
+            Some(Assist::Resolved(mut labels_actions)) => labels_actions.remove(0).1,
+            _ => panic!("code action is not applicable"),
+        };
+        let before = db.file_text(position.file_id);
+        assert_eq_text!(
+            "mod m {}
+mod foo {
+    fn bar() {}
+
+    fn baz() {}
+}
+",
+            &action.edit.apply(&before)
+        );
+        match &action.file_system_edits[..] {
+            [FileSystemEdit::DeleteFile { file_id }] => {
+                assert_eq!(*file_id, db.file_id_of("/foo.rs"))
+            }
+            edits => panic!("unexpected file system edits: {:?}", edits),
+        }
+    }
+
+    #[test]
+    fn extract_module_not_applicable() {
+        check_assist_not_applicable(extract_module, "mod foo<|>;");
+        check_assist_not_applicable(extract_module, "mod foo { fn bar<|>() {} }");
+    }
+}
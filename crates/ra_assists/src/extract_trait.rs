@@ -0,0 +1,222 @@
+use hir::db::HirDatabase;
+use join_to_string::join;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    AstNode, SyntaxNode, TextRange, TextUnit,
+    ast::{self, NameOwner, TypeParamsOwner, VisibilityOwner},
+    SyntaxKind::{LIFETIME, WHITESPACE},
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+const TRAIT_NAME: &str = "NewTrait";
+
+pub(crate) fn extract_trait(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let impl_block = ctx.covering_node().ancestors().find_map(ast::ImplBlock::cast)?;
+    if impl_block.target_trait().is_some() {
+        return None;
+    }
+    let target_type = impl_block.target_type()?;
+    let item_list = impl_block.item_list()?;
+    let selection = ctx.frange.range;
+    // The selected methods, or all public ones.
+    let methods = item_list
+        .impl_items()
+        .filter_map(|it| match it.kind() {
+            ast::ImplItemKind::FnDef(it) => Some(it),
+            _ => None,
+        })
+        .filter(|it| {
+            if selection.is_empty() {
+                it.visibility().is_some()
+            } else {
+                it.syntax().range().intersection(&selection).map_or(false, |it| !it.is_empty())
+            }
+        })
+        .collect::<Vec<_>>();
+    if methods.is_empty() {
+        return None;
+    }
+    let moves_all_items = item_list.impl_items().count() == methods.len();
+
+    let indent = leading_indent(impl_block.syntax()).unwrap_or("");
+    let params = impl_block.type_param_list();
+    let trait_args = params.map(|params| {
+        let lifetimes = params
+            .lifetime_params()
+            .filter_map(|it| it.syntax().children().find(|it| it.kind() == LIFETIME))
+            .map(|it| it.text().to_string());
+        let types = params.type_params().filter_map(|it| it.name()).map(|it| it.text().to_string());
+        join(lifetimes.chain(types)).separator(", ").surround_with("<", ">").to_string()
+    });
+    let trait_ref = format!("{}{}", TRAIT_NAME, trait_args.unwrap_or_default());
+    let signatures = methods.iter().filter_map(|it| {
+        let body = it.body()?;
+        let start = it
+            .visibility()
+            .map_or_else(|| item_start(it.syntax()), |it| non_whitespace_after(it.syntax()));
+        let signature = TextRange::from_to(start, body.syntax().range().start());
+        Some(format!("{}    {};", indent, it.syntax().text().slice(signature).to_string().trim()))
+    });
+    let trait_def = format!(
+        "trait {}{} {{\n{}\n{}}}\n\n{}",
+        TRAIT_NAME,
+        params.map(|it| it.syntax().text().to_string()).unwrap_or_default(),
+        join(signatures).separator("\n").to_string(),
+        indent,
+        indent
+    );
+    let impl_start = impl_block.syntax().range().start();
+
+    ctx.add_action(AssistId("extract_trait"), "extract trait", |edit| {
+        edit.target(impl_block.syntax().range());
+        edit.insert(impl_start, trait_def);
+        if moves_all_items {
+            edit.insert(target_type.syntax().range().start(), format!("{} for ", trait_ref));
+            for method in methods.iter() {
+                if let Some(visibility) = method.visibility() {
+                    let end = non_whitespace_after(visibility.syntax());
+                    edit.delete(TextRange::from_to(visibility.syntax().range().start(), end));
+                }
+            }
+        } else {
+            let mut impl_items = Vec::new();
+            for method in methods.iter() {
+                let range = method.syntax().range();
+                let start = match method.syntax().prev_sibling() {
+                    Some(ws) if ws.kind() == WHITESPACE => ws.range().start(),
+                    _ => range.start(),
+                };
+                edit.delete(TextRange::from_to(start, range.end()));
+                let text = match method.visibility() {
+                    Some(visibility) => {
+                        let rest = TextRange::from_to(
+                            non_whitespace_after(visibility.syntax()),
+                            range.end(),
+                        );
+                        method.syntax().text().slice(rest).to_string()
+                    }
+                    None => method.syntax().text().to_string(),
+                };
+                impl_items.push(format!("{}    {}", indent, text));
+            }
+            let where_clause = impl_block
+                .where_clause()
+                .map(|it| format!(" {}", it.syntax().text()))
+                .unwrap_or_default();
+            let trait_impl = format!(
+                "\n\n{}impl{} {} for {}{} {{\n{}\n{}}}",
+                indent,
+                params.map(|it| it.syntax().text().to_string()).unwrap_or_default(),
+                trait_ref,
+                target_type.syntax().text(),
+                where_clause,
+                impl_items.join("\n\n"),
+                indent
+            );
+            edit.insert(impl_block.syntax().range().end(), trait_impl);
+        }
+        edit.set_cursor(impl_start + TextUnit::of_str("trait "));
+    });
+
+    ctx.build()
+}
+
+/// Where the item starts after its doc comments and attributes.
+fn item_start(node: &SyntaxNode) -> TextUnit {
+    node.children()
+        .find(|it| !it.kind().is_trivia() && ast::Attr::cast(it).is_none())
+        .map_or(node.range().start(), |it| it.range().start())
+}
+
+fn non_whitespace_after(node: &SyntaxNode) -> TextUnit {
+    match node.next_sibling() {
+        Some(ws) if ws.kind() == WHITESPACE => ws.range().end(),
+        _ => node.range().end(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_not_applicable, check_assist_range, check_assist_target,
+    };
+
+    #[test]
+    fn extract_trait_from_two_method_impl() {
+        check_assist(
+            extract_trait,
+            "
+struct S;
+
+impl<|> S {
+    /// Makes noise.
+    pub fn bark(&self, times: u32) -> String {
+        String::new()
+    }
+
+    pub fn sleep(&mut self) {}
+}",
+            "
+struct S;
+
+trait <|>NewTrait {
+    fn bark(&self, times: u32) -> String;
+    fn sleep(&mut self);
+}
+
+impl NewTrait for S {
+    /// Makes noise.
+    fn bark(&self, times: u32) -> String {
+        String::new()
+    }
+
+    fn sleep(&mut self) {}
+}",
+        );
+    }
+
+    #[test]
+    fn extract_trait_from_selected_generic_methods() {
+        check_assist_range(
+            extract_trait,
+            "
+impl<'a, T: Clone> S<'a, T> {
+    fn new() -> Self { S }
+    <|>pub fn get(&self) -> &'a T { self.0 }<|>
+}",
+            "
+trait <|>NewTrait<'a, T: Clone> {
+    fn get(&self) -> &'a T;
+}
+
+impl<'a, T: Clone> S<'a, T> {
+    fn new() -> Self { S }
+}
+
+impl<'a, T: Clone> NewTrait<'a, T> for S<'a, T> {
+    fn get(&self) -> &'a T { self.0 }
+}",
+        );
+    }
+
+    #[test]
+    fn extract_trait_not_applicable() {
+        // No public methods.
+        check_assist_not_applicable(extract_trait, "impl<|> S { fn f(&self) {} }");
+        check_assist_not_applicable(
+            extract_trait,
+            "impl<|> Clone for S { pub fn clone(&self) {} }",
+        );
+    }
+
+    #[test]
+    fn extract_trait_target() {
+        check_assist_target(
+            extract_trait,
+            "impl<|> S { pub fn f() {} }",
+            "impl S { pub fn f() {} }",
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    AstNode, SyntaxNode, SmolStr, TextUnit,
+    ast::{self, NameOwner},
+    SyntaxKind::{MODULE, WHITESPACE, COMMENT, LIFETIME},
+};
+
+use crate::{
+    AssistCtx, Assist, AssistId,
+    utils::{fresh_name, is_module_item, item_indent},
+};
+
+pub(crate) fn extract_type_alias(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let type_ref = if ctx.frange.range.is_empty() {
+        // Extract the whole type, not just the innermost component.
+        ctx.leaf_at_offset()
+            .filter_map(outermost_type)
+            .max_by_key(|it| it.syntax().range().len())?
+    } else {
+        ctx.covering_node().ancestors().find_map(ast::TypeRef::cast)?
+    };
+    if !is_complex(type_ref) {
+        return None;
+    }
+    let anchor = type_ref.syntax().ancestors().find(|it| is_module_item(it))?;
+    let scope = anchor.parent()?;
+
+    let name = fresh_name(scope.ancestors().last()?, "Alias");
+    let params = used_generic_params(type_ref);
+    // The same text both declares the alias and refers to it.
+    let alias = if params.is_empty() { name } else { format!("{}<{}>", name, params.join(", ")) };
+    let alias_def =
+        format!("type {} = {};\n\n{}", alias, type_ref.syntax().text(), item_indent(anchor));
+    let alias_start = anchor.range().start();
+    let cursor = alias_start + TextUnit::of_str("type ");
+
+    ctx.add_action(AssistId("extract_type_alias"), "extract type alias", |edit| {
+        edit.target(type_ref.syntax().range());
+        edit.insert(alias_start, alias_def.clone());
+        edit.replace(type_ref.syntax().range(), alias.clone());
+        edit.set_cursor(cursor);
+    });
+
+    // Other occurrences might use different generic parameters with the same
+    // names, so we only replace all of them for concrete types.
+    if params.is_empty() {
+        let occurrences = scope
+            .descendants()
+            .filter_map(ast::TypeRef::cast)
+            .filter(|it| {
+                it.syntax().ancestors().take_while(|it| *it != scope).all(|it| it.kind() != MODULE)
+            })
+            .filter(|it| tokens(it.syntax()).eq(tokens(type_ref.syntax())))
+            .collect::<Vec<_>>();
+        if occurrences.len() > 1 {
+            // Replacements before the alias shift it around.
+            let mut cursor = cursor;
+            for occurrence in occurrences.iter() {
+                let range = occurrence.syntax().range();
+                if range.end() <= alias_start {
+                    cursor = cursor + TextUnit::of_str(&alias) - range.len();
+                }
+            }
+            ctx.add_action(
+                AssistId("extract_type_alias_all"),
+                "extract type alias for all occurrences",
+                |edit| {
+                    edit.target(type_ref.syntax().range());
+                    edit.insert(alias_start, alias_def);
+                    for occurrence in occurrences {
+                        edit.replace(occurrence.syntax().range(), alias.clone());
+                    }
+                    edit.set_cursor(cursor);
+                },
+            );
+        }
+    }
+
+    ctx.build()
+}
+
+/// Finds the largest type containing `leaf`, without leaving the expression
+/// or item the type appears in.
+fn outermost_type(leaf: &SyntaxNode) -> Option<&ast::TypeRef> {
+    leaf.ancestors()
+        .take_while(|it| ast::Expr::cast(it).is_none() && !is_module_item(it))
+        .filter_map(ast::TypeRef::cast)
+        .last()
+}
+
+/// There's no point in aliasing plain names, like `u32` or `String`.
+fn is_complex(type_ref: &ast::TypeRef) -> bool {
+    match type_ref.kind() {
+        ast::TypeRefKind::PathType(path_type) => {
+            path_type.syntax().descendants().any(|it| ast::TypeArgList::cast(it).is_some())
+        }
+        ast::TypeRefKind::NeverType(_) | ast::TypeRefKind::PlaceholderType(_) => false,
+        ast::TypeRefKind::TupleType(it) => {
+            it.syntax().children().any(|it| ast::TypeRef::cast(it).is_some())
+        }
+        _ => true,
+    }
+}
+
+/// Returns the generic parameters of the surrounding items which appear in
+/// `type_ref`, lifetimes first, in the order they are declared.
+fn used_generic_params(type_ref: &ast::TypeRef) -> Vec<String> {
+    let mut lifetimes = Vec::new();
+    let mut types = Vec::new();
+    let param_lists = type_ref
+        .syntax()
+        .ancestors()
+        .filter_map(|it| it.children().find_map(ast::TypeParamList::cast))
+        .collect::<Vec<_>>();
+    // Outer items declare their parameters first.
+    for param_list in param_lists.into_iter().rev() {
+        for lifetime_param in param_list.lifetime_params() {
+            let lifetime = match lifetime_param.lifetime() {
+                Some(it) => it.syntax().text().to_string(),
+                None => continue,
+            };
+            let is_used = type_ref
+                .syntax()
+                .descendants()
+                .any(|it| it.kind() == LIFETIME && it.text() == lifetime.as_str());
+            if is_used {
+                lifetimes.push(lifetime);
+            }
+        }
+        for type_param in param_list.type_params() {
+            let name = match type_param.name() {
+                Some(it) => it.text().clone(),
+                None => continue,
+            };
+            let is_used =
+                type_ref.syntax().descendants().filter_map(ast::PathType::cast).any(|it| {
+                    it.path()
+                        .map(|path| {
+                            path.qualifier().is_none() && path.syntax().text() == name.as_str()
+                        })
+                        .unwrap_or(false)
+                });
+            if is_used {
+                types.push(name.to_string());
+            }
+        }
+    }
+    lifetimes.extend(types);
+    lifetimes
+}
+
+fn tokens(node: &SyntaxNode) -> impl Iterator<Item = &SmolStr> {
+    node.descendants()
+        .filter(|it| it.is_leaf() && it.kind() != WHITESPACE && it.kind() != COMMENT)
+        .filter_map(SyntaxNode::leaf_text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_nth_action, check_assist_not_applicable, check_assist_target,
+    };
+
+    #[test]
+    fn extract_type_alias_from_param() {
+        check_assist(
+            extract_type_alias,
+            "
+use std::collections::HashMap;
+
+fn foo(map: HashMap<String, Vec<(u32, <|>String)>>) {}
+",
+            "
+use std::collections::HashMap;
+
+type <|>Alias = HashMap<String, Vec<(u32, String)>>;
+
+fn foo(map: Alias) {}
+",
+        );
+    }
+
+    #[test]
+    fn extract_type_alias_with_generic_params() {
+        check_assist(
+            extract_type_alias,
+            "
+struct S<'a, T> { x: &'a T }
+impl<'a, T, U> S<'a, T> {
+    fn foo<V>(&self) -> Result<(&'a T, <|>V), U> {}
+}
+",
+            "
+struct S<'a, T> { x: &'a T }
+type <|>Alias<'a, T, U, V> = Result<(&'a T, V), U>;
+
+impl<'a, T, U> S<'a, T> {
+    fn foo<V>(&self) -> Alias<'a, T, U, V> {}
+}
+",
+        );
+    }
+
+    #[test]
+    fn extract_type_alias_in_nested_module() {
+        check_assist(
+            extract_type_alias,
+            "
+mod m {
+    fn foo() -> Option<Vec<u32>> {
+        let x: Vec<<|>u32> = Vec::new();
+    }
+}
+",
+            "
+mod m {
+    type <|>Alias = Vec<u32>;
+
+    fn foo() -> Option<Vec<u32>> {
+        let x: Alias = Vec::new();
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn extract_type_alias_for_all_occurrences() {
+        check_assist_nth_action(
+            extract_type_alias,
+            "
+struct Alias;
+fn foo(x: Vec<Option<u32>>) -> Vec<Option< u32 >> {}
+fn bar(x: Vec<Option<u32>>, y: Vec<u32>) {}
+mod m { fn baz(x: Vec<Option<u32>>) {} }
+fn baz(x: Vec<Option<<|>u32>>) {}
+",
+            "
+struct Alias;
+fn foo(x: Alias2) -> Alias2 {}
+fn bar(x: Alias2, y: Vec<u32>) {}
+mod m { fn baz(x: Vec<Option<u32>>) {} }
+type <|>Alias2 = Vec<Option<u32>>;
+
+fn baz(x: Alias2) {}
+",
+            1,
+        );
+    }
+
+    #[test]
+    fn extract_type_alias_replaces_single_occurrence_by_default() {
+        check_assist(
+            extract_type_alias,
+            "
+fn foo(x: (u32, u32)) -> (u32, <|>u32) {}
+",
+            "
+type <|>Alias = (u32, u32);
+
+fn foo(x: (u32, u32)) -> Alias {}
+",
+        );
+    }
+
+    #[test]
+    fn extract_type_alias_not_applicable() {
+        check_assist_not_applicable(extract_type_alias, "fn foo(x: u<|>32) {}");
+        check_assist_not_applicable(extract_type_alias, "fn foo(x: std::string::Str<|>ing) {}");
+        check_assist_not_applicable(extract_type_alias, "fn foo() { let x = 1<|>; }");
+        check_assist_not_applicable(extract_type_alias, "fn foo() -> (<|>) {}");
+    }
+
+    #[test]
+    fn extract_type_alias_target() {
+        check_assist_target(extract_type_alias, "fn foo(x: Vec<Vec<<|>u32>>) {}", "Vec<Vec<u32>>");
+    }
+}
@@ -1,10 +1,8 @@
 use std::fmt::Write;
 
-use hir::{
-    AdtDef, Ty, FieldSource, source_binder,
-    db::HirDatabase,
-};
-use ra_syntax::ast::{self, AstNode};
+use hir::{AdtDef, Enum, EnumVariant, Name, Ty, FieldSource, source_binder, db::HirDatabase};
+use ra_fmt::leading_indent;
+use ra_syntax::ast::{self, AstNode, NameOwner};
 
 use crate::{AssistCtx, Assist, AssistId};
 
@@ -20,20 +18,7 @@ pub(crate) fn fill_match_arms(mut ctx: AssistCtx<impl HirDatabase>) -> Option<As
     }
 
     let expr = match_expr.expr()?;
-    let function =
-        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, expr.syntax())?;
-    let infer_result = function.infer(ctx.db);
-    let source_map = function.body_source_map(ctx.db);
-    let node_expr = source_map.node_expr(expr)?;
-    let match_expr_ty = infer_result[node_expr].clone();
-    let enum_def = match match_expr_ty {
-        Ty::Adt { def_id: AdtDef::Enum(e), .. } => e,
-        Ty::Ref(adt, _) => match *adt {
-            Ty::Adt { def_id: AdtDef::Enum(e), .. } => e,
-            _ => return None,
-        },
-        _ => return None,
-    };
+    let enum_def = scrutinee_enum(&ctx, expr)?;
     let enum_name = enum_def.name(ctx.db)?;
     let db = ctx.db;
 
@@ -41,32 +26,11 @@ pub(crate) fn fill_match_arms(mut ctx: AssistCtx<impl HirDatabase>) -> Option<As
         let mut buf = format!("match {} {{\n", expr.syntax().text().to_string());
         let variants = enum_def.variants(db);
         for variant in variants {
-            let name = match variant.name(db) {
+            let pat = match variant_pat(db, &enum_name, variant) {
                 Some(it) => it,
                 None => continue,
             };
-            write!(&mut buf, "    {}::{}", enum_name, name.to_string()).unwrap();
-
-            let pat = variant
-                .fields(db)
-                .into_iter()
-                .map(|field| {
-                    let name = field.name(db).to_string();
-                    let (_, source) = field.source(db);
-                    match source {
-                        FieldSource::Named(_) => name,
-                        FieldSource::Pos(_) => "_".to_string(),
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            match pat.first().map(|s| s.as_str()) {
-                Some("_") => write!(&mut buf, "({})", pat.join(", ")).unwrap(),
-                Some(_) => write!(&mut buf, "{{{}}}", pat.join(", ")).unwrap(),
-                None => (),
-            };
-
-            buf.push_str(" => (),\n");
+            write!(&mut buf, "    {} => (),\n", pat).unwrap();
         }
         buf.push_str("}");
         edit.target(match_expr.syntax().range());
@@ -77,11 +41,141 @@ pub(crate) fn fill_match_arms(mut ctx: AssistCtx<impl HirDatabase>) -> Option<As
     ctx.build()
 }
 
+/// Replaces the `_` arm of a `match` on an enum by arms for each of the
+/// variants it stands for, all evaluating to what the `_` arm did.
+pub(crate) fn expand_wildcard_arm(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let match_expr = ctx.node_at_offset::<ast::MatchExpr>()?;
+    let expr = match_expr.expr()?;
+    let arms = match_expr.match_arm_list()?.arms().collect::<Vec<_>>();
+    let wildcard_arm = *arms.iter().find(|arm| {
+        let pats = arm.pats().map(|it| it.kind()).collect::<Vec<_>>();
+        match pats.as_slice() {
+            [ast::PatKind::PlaceholderPat(_)] => arm.guard().is_none(),
+            _ => false,
+        }
+    })?;
+    // Offered on the scrutinee and on the `_` arm.
+    let offset = ctx.frange.range.start();
+    let on_arm = wildcard_arm.syntax().range().contains_inclusive(offset);
+    if !on_arm && !expr.syntax().range().contains_inclusive(offset) {
+        return None;
+    }
+    let body = wildcard_arm.expr()?.syntax().text().to_string();
+
+    let enum_def = scrutinee_enum(&ctx, expr)?;
+    let enum_name = enum_def.name(ctx.db)?;
+    let covered = arms
+        .iter()
+        .filter(|arm| arm.guard().is_none())
+        .flat_map(|arm| arm.pats())
+        .filter_map(covered_variant)
+        .collect::<Vec<_>>();
+    let pats = enum_def
+        .variants(ctx.db)
+        .into_iter()
+        .filter(|variant| {
+            variant.name(ctx.db).map_or(false, |name| !covered.contains(&name.to_string()))
+        })
+        .filter_map(|variant| variant_pat(ctx.db, &enum_name, variant))
+        .collect::<Vec<_>>();
+    if pats.is_empty() {
+        return None;
+    }
+
+    let indent = leading_indent(wildcard_arm.syntax()).unwrap_or("");
+    let separator = if body.starts_with('{') { "" } else { "," };
+    let replacement = pats
+        .iter()
+        .map(|pat| format!("{} => {}", pat, body))
+        .collect::<Vec<_>>()
+        .join(&format!("{}\n{}", separator, indent));
+    let range = wildcard_arm.syntax().range();
+    ctx.add_action(AssistId("expand_wildcard_arm"), "replace `_` with missing variants", |edit| {
+        edit.target(range);
+        edit.replace(range, replacement);
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+fn scrutinee_enum(ctx: &AssistCtx<impl HirDatabase>, expr: &ast::Expr) -> Option<Enum> {
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, expr.syntax())?;
+    let infer_result = function.infer(ctx.db);
+    let source_map = function.body_source_map(ctx.db);
+    let node_expr = source_map.node_expr(expr)?;
+    let match_expr_ty = infer_result[node_expr].clone();
+    match match_expr_ty {
+        Ty::Adt { def_id: AdtDef::Enum(e), .. } => Some(e),
+        Ty::Ref(adt, _) => match *adt {
+            Ty::Adt { def_id: AdtDef::Enum(e), .. } => Some(e),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The pattern matching any value of `variant`, like `E::V(_, _)`.
+fn variant_pat(db: &impl HirDatabase, enum_name: &Name, variant: EnumVariant) -> Option<String> {
+    let mut buf = format!("{}::{}", enum_name, variant.name(db)?);
+    let pat = variant
+        .fields(db)
+        .into_iter()
+        .map(|field| {
+            let name = field.name(db).to_string();
+            let (_, source) = field.source(db);
+            match source {
+                FieldSource::Named(_) => name,
+                FieldSource::Pos(_) => "_".to_string(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    match pat.first().map(|s| s.as_str()) {
+        Some("_") => write!(&mut buf, "({})", pat.join(", ")).unwrap(),
+        Some(_) => write!(&mut buf, "{{{}}}", pat.join(", ")).unwrap(),
+        None => (),
+    };
+    Some(buf)
+}
+
+/// The name of the variant all values of which `pat` matches. Patterns which
+/// only match some of them, like `E::V(0)`, don't cover the variant.
+fn covered_variant(pat: &ast::Pat) -> Option<String> {
+    let path = match pat.kind() {
+        ast::PatKind::PathPat(it) => it.path()?,
+        ast::PatKind::TupleStructPat(it) if it.args().all(is_irrefutable) => it.path()?,
+        ast::PatKind::StructPat(it) => {
+            let fields = it.field_pat_list()?;
+            if !fields.field_pats().filter_map(|it| it.pat()).all(is_irrefutable) {
+                return None;
+            }
+            it.path()?
+        }
+        // Imported variants, like `None`.
+        ast::PatKind::BindPat(it) if it.pat().is_none() => {
+            return Some(it.name()?.text().to_string())
+        }
+        _ => return None,
+    };
+    Some(path.segment()?.name_ref()?.text().to_string())
+}
+
+fn is_irrefutable(pat: &ast::Pat) -> bool {
+    match pat.kind() {
+        ast::PatKind::PlaceholderPat(_) => true,
+        ast::PatKind::BindPat(it) => it.pat().map_or(true, is_irrefutable),
+        ast::PatKind::TuplePat(it) => it.args().all(is_irrefutable),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::helpers::{check_assist, check_assist_target};
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
 
-    use super::fill_match_arms;
+    use super::{fill_match_arms, expand_wildcard_arm};
 
     #[test]
     fn fill_match_arms_empty_body() {
@@ -236,4 +330,86 @@ mod tests {
             "match E::X {}",
         );
     }
+
+    #[test]
+    fn expand_wildcard_arm_of_three_variants() {
+        check_assist(
+            expand_wildcard_arm,
+            r#"
+            enum E { X, Y(u32), Z { a: u32 } }
+
+            fn main(e: E) {
+                match e {
+                    E::Y(0) => 1,
+                    E::Z { a: _ } => 2,
+                    <|>_ => 3,
+                }
+            }
+            "#,
+            r#"
+            enum E { X, Y(u32), Z { a: u32 } }
+
+            fn main(e: E) {
+                match e {
+                    E::Y(0) => 1,
+                    E::Z { a: _ } => 2,
+                    <|>E::X => 3,
+                    E::Y(_) => 3,
+                }
+            }
+            "#,
+        );
+        check_assist(
+            expand_wildcard_arm,
+            r#"
+            enum E { X, Y, Z }
+
+            fn main(e: E) {
+                match e<|> {
+                    E::Y => (),
+                    _ => {}
+                }
+            }
+            "#,
+            r#"
+            enum E { X, Y, Z }
+
+            fn main(e: E) {
+                match e {
+                    E::Y => (),
+                    <|>E::X => {}
+                    E::Z => {}
+                }
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn expand_wildcard_arm_not_applicable() {
+        // Every variant is already covered.
+        check_assist_not_applicable(
+            expand_wildcard_arm,
+            "enum E { X, Y } fn main(e: E) { match e { E::X => (), E::Y => (), <|>_ => () } }",
+        );
+        // The `_` arm is guarded.
+        check_assist_not_applicable(
+            expand_wildcard_arm,
+            "enum E { X, Y } fn main(e: E) { match e { E::X => (), <|>_ if true => () } }",
+        );
+        // Not on the scrutinee or the `_` arm.
+        check_assist_not_applicable(
+            expand_wildcard_arm,
+            "enum E { X, Y } fn main(e: E) { match e { E::X => (<|>), _ => () } }",
+        );
+    }
+
+    #[test]
+    fn expand_wildcard_arm_target() {
+        check_assist_target(
+            expand_wildcard_arm,
+            "enum E { X, Y } fn main(e: E) { match e { E::X => (), <|>_ => g() } }",
+            "_ => g()",
+        );
+    }
 }
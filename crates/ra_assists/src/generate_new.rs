@@ -0,0 +1,205 @@
+use hir::{ImplItem, db::HirDatabase, source_binder};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    AstNode, TextRange, TextUnit,
+    ast::{self, NameOwner, TypeAscriptionOwner},
+};
+
+use crate::{AssistCtx, Assist, AssistId, utils::impl_header};
+
+pub(crate) fn generate_new(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let struct_def = ctx.node_at_offset::<ast::StructDef>()?;
+    let name = struct_def.name()?.text().to_string();
+    let (params, construction) = match struct_def.flavor() {
+        ast::StructFlavor::Named(list) => {
+            let fields = list
+                .fields()
+                .map(|it| {
+                    Some((it.name()?.text().to_string(), it.ascribed_type()?.syntax().text()))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            let params = fields.iter().map(|(name, ty)| format!("{}: {}", name, ty));
+            let names = fields.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+            (params.collect::<Vec<_>>(), format!("Self {{ {} }}", names.join(", ")))
+        }
+        ast::StructFlavor::Tuple(list) => {
+            let types = list
+                .fields()
+                .map(|it| Some(it.type_ref()?.syntax().text()))
+                .collect::<Option<Vec<_>>>()?;
+            let params = types.iter().enumerate().map(|(idx, ty)| format!("field{}: {}", idx, ty));
+            let names = (0..types.len()).map(|idx| format!("field{}", idx)).collect::<Vec<_>>();
+            (params.collect::<Vec<_>>(), format!("Self({})", names.join(", ")))
+        }
+        ast::StructFlavor::Unit => return None,
+    };
+
+    let module =
+        source_binder::module_from_child_node(ctx.db, ctx.frange.file_id, struct_def.syntax())?;
+    let strukt = source_binder::struct_from_module(ctx.db, module, struct_def);
+    let has_new = strukt.ty(ctx.db).iterate_impl_items(ctx.db, |item| match item {
+        ImplItem::Method(it) if it.signature(ctx.db).name().to_string() == "new" => Some(()),
+        _ => None,
+    });
+    if has_new.is_some() {
+        return None;
+    }
+
+    let indent = leading_indent(struct_def.syntax()).unwrap_or("").to_string();
+    let method = format!(
+        "{indent}    pub fn new({}) -> Self {{\n{indent}        {}\n{indent}    }}",
+        params.join(", "),
+        construction,
+        indent = indent,
+    );
+    let nominal = ast::NominalDef::cast(struct_def.syntax())?;
+    let header = impl_header(nominal)?;
+    let impl_block = inherent_impl(struct_def, &name);
+    ctx.add_action(AssistId("generate_new"), "generate new", |edit| {
+        edit.target(struct_def.syntax().range());
+        let (range, prefix, suffix) = match impl_block.and_then(|it| it.item_list()) {
+            Some(item_list) => match item_list.impl_items().last() {
+                Some(last) => {
+                    let range = TextRange::offset_len(last.syntax().range().end(), 0.into());
+                    (range, "\n\n".to_string(), String::new())
+                }
+                None => (item_list.syntax().range(), "{\n".to_string(), format!("\n{}}}", indent)),
+            },
+            None => (
+                TextRange::offset_len(struct_def.syntax().range().end(), 0.into()),
+                format!("\n\n{}{} {{\n", indent, header),
+                format!("\n{}}}", indent),
+            ),
+        };
+        let cursor = range.start()
+            + TextUnit::of_str(&prefix)
+            + TextUnit::of_str(&indent)
+            + TextUnit::from(4);
+        edit.replace(range, format!("{}{}{}", prefix, method, suffix));
+        edit.set_cursor(cursor);
+    });
+
+    ctx.build()
+}
+
+/// Finds an `impl Name {}` next to the struct.
+fn inherent_impl<'a>(struct_def: &'a ast::StructDef, name: &str) -> Option<&'a ast::ImplBlock> {
+    struct_def.syntax().parent()?.children().filter_map(ast::ImplBlock::cast).find(|it| {
+        if it.target_trait().is_some() {
+            return false;
+        }
+        let path = match it.target_type().map(|it| it.kind()) {
+            Some(ast::TypeRefKind::PathType(it)) => it.path(),
+            _ => None,
+        };
+        path.and_then(|it| it.segment())
+            .and_then(|it| it.name_ref())
+            .map_or(false, |it| it.text() == name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn generate_new_for_generic_struct() {
+        check_assist(
+            generate_new,
+            "
+struct Foo<'a, T: Clone> {<|>
+    name: &'a str,
+    value: T,
+}",
+            "
+struct Foo<'a, T: Clone> {
+    name: &'a str,
+    value: T,
+}
+
+impl<'a, T: Clone> Foo<'a, T> {
+    <|>pub fn new(name: &'a str, value: T) -> Self {
+        Self { name, value }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn generate_new_for_tuple_struct() {
+        check_assist(
+            generate_new,
+            "struct Pair<|>(u32, String);",
+            "struct Pair(u32, String);
+
+impl Pair {
+    <|>pub fn new(field0: u32, field1: String) -> Self {
+        Self(field0, field1)
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn generate_new_in_existing_impl() {
+        check_assist(
+            generate_new,
+            "
+struct Foo<|> { x: u32 }
+impl Foo {
+    fn get(&self) -> u32 { self.x }
+}
+impl Clone for Foo {}",
+            "
+struct Foo { x: u32 }
+impl Foo {
+    fn get(&self) -> u32 { self.x }
+
+    <|>pub fn new(x: u32) -> Self {
+        Self { x }
+    }
+}
+impl Clone for Foo {}",
+        );
+        check_assist(
+            generate_new,
+            "
+mod m {
+    struct Foo<|> { x: u32 }
+    impl Foo {}
+}",
+            "
+mod m {
+    struct Foo { x: u32 }
+    impl Foo {
+        <|>pub fn new(x: u32) -> Self {
+            Self { x }
+        }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn generate_new_not_applicable() {
+        check_assist_not_applicable(
+            generate_new,
+            "
+struct Foo<|> { x: u32 }
+impl Foo {
+    fn new() -> Foo { Foo { x: 0 } }
+}",
+        );
+        check_assist_not_applicable(generate_new, "struct Unit<|>;");
+    }
+
+    #[test]
+    fn generate_new_target() {
+        check_assist_target(
+            generate_new,
+            "struct Foo { x: u32<|> } struct Bar;",
+            "struct Foo { x: u32 }",
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    AstNode, TextUnit,
+    ast::{self, NameOwner},
+};
+
+use crate::{AssistCtx, Assist, AssistId, utils::trait_impl_header};
+
+/// A well-known trait, with the items an impl of it has to define. `$0` marks
+/// where the cursor goes.
+struct TraitStub {
+    id: &'static str,
+    label: &'static str,
+    name: &'static str,
+    path: &'static str,
+    items: &'static str,
+}
+
+const TRAIT_STUBS: &[TraitStub] = &[
+    TraitStub {
+        id: "generate_display_impl",
+        label: "generate `Display` impl",
+        name: "Display",
+        path: "std::fmt::Display",
+        items: "fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, \"$0\")
+}",
+    },
+    TraitStub {
+        id: "generate_from_str_impl",
+        label: "generate `FromStr` impl",
+        name: "FromStr",
+        path: "std::str::FromStr",
+        items: "type Err = $0();
+
+fn from_str(s: &str) -> Result<Self, Self::Err> {
+    unimplemented!()
+}",
+    },
+];
+
+pub(crate) fn generate_trait_impl(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let nominal = ctx.node_at_offset::<ast::NominalDef>()?;
+    let name = nominal.name()?.text().to_string();
+    let indent = leading_indent(nominal.syntax()).unwrap_or("").to_string();
+    let start_offset = nominal.syntax().range().end();
+
+    let stubs = TRAIT_STUBS
+        .iter()
+        .filter(|it| !has_trait_impl(nominal, &name, it.name))
+        .collect::<Vec<_>>();
+    if stubs.is_empty() {
+        return None;
+    }
+
+    for stub in stubs {
+        let header = trait_impl_header(nominal, stub.path)?;
+        let items = stub
+            .items
+            .lines()
+            .map(|it| if it.is_empty() { String::new() } else { format!("{}    {}", indent, it) })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let text = format!("\n\n{}{} {{\n{}\n{}}}", indent, header, items, indent);
+        let cursor = start_offset + TextUnit::of_str(&text[..text.find("$0")?]);
+        let text = text.replace("$0", "");
+        ctx.add_action(AssistId(stub.id), stub.label, |edit| {
+            edit.target(nominal.syntax().range());
+            edit.insert(start_offset, text);
+            edit.set_cursor(cursor);
+        });
+    }
+
+    ctx.build()
+}
+
+/// Whether there already is an `impl Trait for Name` next to the type.
+fn has_trait_impl(nominal: &ast::NominalDef, name: &str, trait_name: &str) -> bool {
+    let last_segment = |type_ref: Option<&ast::TypeRef>| {
+        let path = match type_ref.map(|it| it.kind()) {
+            Some(ast::TypeRefKind::PathType(it)) => it.path(),
+            _ => None,
+        };
+        path.and_then(|it| it.segment())
+            .and_then(|it| it.name_ref())
+            .map(|it| it.text().to_string())
+    };
+    let mut siblings = match nominal.syntax().parent() {
+        Some(it) => it.children().filter_map(ast::ImplBlock::cast),
+        None => return false,
+    };
+    siblings.any(|it| {
+        last_segment(it.target_trait()).map_or(false, |it| it == trait_name)
+            && last_segment(it.target_type()).map_or(false, |it| it == name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_nth_action, check_assist_not_applicable, check_assist_target,
+    };
+
+    #[test]
+    fn generate_display_impl() {
+        check_assist(
+            generate_trait_impl,
+            "struct Point<|> { x: i32, y: i32 }",
+            "struct Point { x: i32, y: i32 }
+
+impl std::fmt::Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, \"<|>\")
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn generate_from_str_impl_for_generic_type() {
+        check_assist_nth_action(
+            generate_trait_impl,
+            "
+mod m {
+    enum E<|><T: Clone> { A(T) }
+}",
+            "
+mod m {
+    enum E<T: Clone> { A(T) }
+
+    impl<T: Clone> std::str::FromStr for E<T> {
+        type Err = <|>();
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            unimplemented!()
+        }
+    }
+}",
+            1,
+        );
+    }
+
+    #[test]
+    fn generate_trait_impl_skips_existing_impls() {
+        check_assist(
+            generate_trait_impl,
+            "
+struct S<|>;
+impl fmt::Display for S {}",
+            "
+struct S;
+
+impl std::str::FromStr for S {
+    type Err = <|>();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        unimplemented!()
+    }
+}
+impl fmt::Display for S {}",
+        );
+        check_assist_not_applicable(
+            generate_trait_impl,
+            "
+struct S<|>;
+impl Display for S {}
+impl std::str::FromStr for S {}",
+        );
+    }
+
+    #[test]
+    fn generate_trait_impl_target() {
+        check_assist_target(generate_trait_impl, "struct S<|>;", "struct S;");
+    }
+}
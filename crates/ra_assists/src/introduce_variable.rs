@@ -3,7 +3,8 @@ use hir::db::HirDatabase;
 use ra_syntax::{
     ast::{self, AstNode},
     SyntaxKind::{
-        WHITESPACE, MATCH_ARM, LAMBDA_EXPR, PATH_EXPR, BREAK_EXPR, LOOP_EXPR, RETURN_EXPR, COMMENT
+        WHITESPACE, MATCH_ARM, LAMBDA_EXPR, PATH_EXPR, BREAK_EXPR, LOOP_EXPR, RETURN_EXPR, COMMENT,
+        ARG_LIST, BLOCK,
     }, SyntaxNode, TextUnit,
 };
 
@@ -19,19 +20,66 @@ pub(crate) fn introduce_variable(mut ctx: AssistCtx<impl HirDatabase>) -> Option
         return None;
     }
     let expr = node.ancestors().find_map(valid_target_expr)?;
+    add_variable_action(
+        &mut ctx,
+        AssistId("introduce_variable"),
+        "introduce variable",
+        expr,
+        "var_name",
+    )?;
+    ctx.build()
+}
+
+/// Like `introduce_variable`, but for the innermost call under the cursor,
+/// when it is nested in an argument of another call, as in
+/// `outer(inner(x))`. Naming the variable after the called function would
+/// shadow it, so it gets the same placeholder name.
+pub(crate) fn extract_subexpression(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let expr = ctx.node_at_offset::<ast::Expr>()?;
+    let call =
+        expr.syntax().ancestors().filter_map(ast::Expr::cast).find(|it| match it.kind() {
+            ast::ExprKind::CallExpr(_) | ast::ExprKind::MethodCallExpr(_) => true,
+            _ => false,
+        })?;
+    // Only calls nested in the arguments of another call, within the statement.
+    let is_nested = call
+        .syntax()
+        .ancestors()
+        .take_while(|it| ast::Stmt::cast(it).is_none() && it.kind() != BLOCK)
+        .any(|it| it.kind() == ARG_LIST);
+    if !is_nested {
+        return None;
+    }
+    add_variable_action(
+        &mut ctx,
+        AssistId("extract_subexpression"),
+        "extract subexpression",
+        call,
+        "var_name",
+    )?;
+    ctx.build()
+}
+
+fn add_variable_action<'a>(
+    ctx: &mut AssistCtx<'a, impl HirDatabase>,
+    id: AssistId,
+    label: &str,
+    expr: &'a ast::Expr,
+    var_name: &str,
+) -> Option<()> {
     let (anchor_stmt, wrap_in_block) = anchor_stmt(expr)?;
     let indent = anchor_stmt.prev_sibling()?;
     if indent.kind() != WHITESPACE {
         return None;
     }
-    ctx.add_action(AssistId("introduce_variable"), "introduce variable", move |edit| {
+    ctx.add_action(id, label, move |edit| {
         let mut buf = String::new();
 
         let cursor_offset = if wrap_in_block {
-            buf.push_str("{ let var_name = ");
+            buf.push_str(&format!("{{ let {} = ", var_name));
             TextUnit::of_str("{ let ")
         } else {
-            buf.push_str("let var_name = ");
+            buf.push_str(&format!("let {} = ", var_name));
             TextUnit::of_str("let ")
         };
 
@@ -67,7 +115,7 @@ pub(crate) fn introduce_variable(mut ctx: AssistCtx<impl HirDatabase>) -> Option
             }
 
             edit.target(expr.syntax().range());
-            edit.replace(expr.syntax().range(), "var_name".to_string());
+            edit.replace(expr.syntax().range(), var_name.to_string());
             edit.insert(anchor_stmt.range().start(), buf);
             if wrap_in_block {
                 edit.insert(anchor_stmt.range().end(), " }");
@@ -75,8 +123,7 @@ pub(crate) fn introduce_variable(mut ctx: AssistCtx<impl HirDatabase>) -> Option
         }
         edit.set_cursor(anchor_stmt.range().start() + cursor_offset);
     });
-
-    ctx.build()
+    Some(())
 }
 
 /// Check whether the node is a valid expression which can be extracted to a variable.
@@ -123,7 +170,10 @@ fn anchor_stmt(expr: &ast::Expr) -> Option<(&SyntaxNode, bool)> {
 mod tests {
     use test_utils::covers;
 
-    use crate::helpers::{check_assist_range_not_applicable, check_assist_range, check_assist_range_target};
+    use crate::helpers::{
+        check_assist_range_not_applicable, check_assist_range, check_assist_range_target,
+        check_assist, check_assist_not_applicable,
+    };
 
     use super::*;
 
@@ -511,4 +561,43 @@ fn main() {
             "2 + 2",
         );
     }
+
+    #[test]
+    fn extract_nested_call() {
+        check_assist(
+            extract_subexpression,
+            "
+fn foo() {
+    outer(inner(<|>x));
+}",
+            "
+fn foo() {
+    let <|>var_name = inner(x);
+    outer(var_name);
+}",
+        );
+        check_assist(
+            extract_subexpression,
+            "
+fn foo() {
+    outer(1, s.inner(<|>x).len())
+}",
+            "
+fn foo() {
+    let <|>var_name = s.inner(x);
+    outer(1, var_name.len())
+}",
+        );
+    }
+
+    #[test]
+    fn extract_subexpression_not_applicable() {
+        check_assist_not_applicable(
+            extract_subexpression,
+            "
+fn foo() {
+    out<|>er(inner(x));
+}",
+        );
+    }
 }
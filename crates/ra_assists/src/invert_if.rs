@@ -0,0 +1,90 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    AstNode, TextRange,
+    ast,
+};
+
+use crate::{AssistCtx, Assist, AssistId, utils::negate};
+
+pub(crate) fn invert_if(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let if_expr = ctx.node_at_offset::<ast::IfExpr>()?;
+    let cond = if_expr.condition()?;
+    // `if let` can't be inverted.
+    if cond.pat().is_some() {
+        return None;
+    }
+    let cond_expr = cond.expr()?;
+    let then_block = if_expr.then_branch()?;
+    let else_block = match if_expr.else_branch()? {
+        ast::ElseBranchFlavor::Block(it) => it,
+        ast::ElseBranchFlavor::IfExpr(_) => return None,
+    };
+
+    // Only offer the assist on the `if` keyword and the condition, not in the
+    // branches.
+    let head =
+        TextRange::from_to(if_expr.syntax().range().start(), then_block.syntax().range().start());
+    if !head.contains_inclusive(ctx.frange.range.start()) {
+        return None;
+    }
+
+    ctx.add_action(AssistId("invert_if"), "invert boolean expression", |edit| {
+        edit.target(head);
+        edit.replace(cond_expr.syntax().range(), negate(cond_expr));
+        edit.replace(then_block.syntax().range(), else_block.syntax().text().to_string());
+        edit.replace(else_block.syntax().range(), then_block.syntax().text().to_string());
+        edit.set_cursor(if_expr.syntax().range().start());
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    #[test]
+    fn invert_if_swaps_branches() {
+        check_assist(
+            invert_if,
+            "fn f() { i<|>f cond { 3 * 2 } else { 1 } }",
+            "fn f() { <|>if !cond { 1 } else { 3 * 2 } }",
+        )
+    }
+
+    #[test]
+    fn invert_if_removes_negation() {
+        check_assist(
+            invert_if,
+            "fn f() { <|>if !cond { 3 * 2 } else { 1 } }",
+            "fn f() { <|>if cond { 1 } else { 3 * 2 } }",
+        )
+    }
+
+    #[test]
+    fn invert_if_flips_comparisons() {
+        check_assist(
+            invert_if,
+            "fn f() { if a <|>< b && c != d { foo() } else { bar() } }",
+            "fn f() { <|>if a >= b || c == d { bar() } else { foo() } }",
+        )
+    }
+
+    #[test]
+    fn invert_if_nested_conjunction_disjunction() {
+        check_assist(
+            invert_if,
+            "fn f() { <|>if a || b && !c { foo() } else { bar() } }",
+            "fn f() { <|>if !a && (!b || c) { bar() } else { foo() } }",
+        )
+    }
+
+    #[test]
+    fn invert_if_not_applicable() {
+        check_assist_not_applicable(invert_if, "fn f() { <|>if cond { 1 } }");
+        check_assist_not_applicable(invert_if, "fn f() { <|>if let Some(x) = y { 1 } else { 2 } }");
+        check_assist_not_applicable(invert_if, "fn f() { <|>if a { 1 } else if b { 2 } }");
+        check_assist_not_applicable(invert_if, "fn f() { if a { <|>1 } else { 2 } }");
+    }
+}
@@ -7,12 +7,14 @@
 
 mod assist_ctx;
 mod marks;
+mod utils;
 
 use itertools::Itertools;
 
 use ra_text_edit::TextEdit;
 use ra_syntax::{TextRange, TextUnit};
-use ra_db::FileRange;
+use ra_db::{FileId, FileRange, SourceRootId};
+use relative_path::RelativePathBuf;
 use hir::db::HirDatabase;
 
 pub(crate) use crate::assist_ctx::{AssistCtx, Assist};
@@ -34,6 +36,14 @@ pub struct AssistAction {
     pub edit: TextEdit,
     pub cursor_position: Option<TextUnit>,
     pub target: Option<TextRange>,
+    pub file_system_edits: Vec<FileSystemEdit>,
+}
+
+/// Changes to other files than the one the assist is applied to.
+#[derive(Debug, Clone)]
+pub enum FileSystemEdit {
+    CreateFile { source_root: SourceRootId, path: RelativePathBuf, contents: String },
+    DeleteFile { file_id: FileId },
 }
 
 /// Return all the assists applicable at the given position.
@@ -93,8 +103,52 @@ mod fill_match_arms;
 mod introduce_variable;
 mod replace_if_let_with_match;
 mod split_import;
-mod remove_dbg;
+mod add_remove_dbg;
 mod auto_import;
+mod string_concat_to_format;
+mod apply_demorgan;
+mod invert_if;
+mod reformat_number_literal;
+mod replace_with_try;
+mod expand_glob;
+mod add_loop_label;
+mod extract_type_alias;
+mod convert_ufcs;
+mod move_bounds;
+mod replace_unwrap;
+mod change_self_receiver;
+mod generate_new;
+mod convert_for_loop;
+mod add_test_module;
+mod reorder_params;
+mod replace_impl_trait;
+mod extract_module;
+mod toggle_closure_braces;
+mod split_tuple_binding;
+mod convert_if_chain;
+mod edit_bounds;
+mod convert_vec_array;
+mod surround_with;
+mod extract_constant;
+mod move_guard;
+mod add_all_type_annotations;
+mod destructure_struct;
+mod convert_cast;
+mod loop_to_while_let;
+mod remove_unnecessary_clone;
+mod generate_trait_impl;
+mod add_map_err;
+mod option_map;
+mod merge_impls;
+mod extract_trait;
+mod use_field_shorthand;
+mod add_allow;
+mod change_string_param;
+mod add_accumulator;
+mod convert_let_else;
+mod collapse_nested_if;
+mod extract_closure_to_fn;
+mod add_explicit_type;
 
 fn all_assists<DB: HirDatabase>() -> &'static [fn(AssistCtx<DB>) -> Option<Assist>] {
     &[
@@ -102,12 +156,73 @@ fn all_assists<DB: HirDatabase>() -> &'static [fn(AssistCtx<DB>) -> Option<Assis
         add_impl::add_impl,
         change_visibility::change_visibility,
         fill_match_arms::fill_match_arms,
+        fill_match_arms::expand_wildcard_arm,
         flip_comma::flip_comma,
         introduce_variable::introduce_variable,
+        introduce_variable::extract_subexpression,
         replace_if_let_with_match::replace_if_let_with_match,
         split_import::split_import,
-        remove_dbg::remove_dbg,
+        add_remove_dbg::remove_dbg,
         auto_import::auto_import,
+        string_concat_to_format::string_concat_to_format,
+        apply_demorgan::apply_demorgan,
+        invert_if::invert_if,
+        reformat_number_literal::reformat_number_literal,
+        replace_with_try::replace_with_try,
+        expand_glob::expand_glob,
+        expand_glob::collapse_to_glob,
+        add_loop_label::add_loop_label,
+        add_remove_dbg::add_dbg,
+        extract_type_alias::extract_type_alias,
+        convert_ufcs::convert_to_ufcs,
+        convert_ufcs::convert_to_method_call,
+        move_bounds::move_bounds_to_where_clause,
+        move_bounds::move_where_clause_to_bounds,
+        replace_unwrap::replace_unwrap_with_try,
+        replace_unwrap::replace_unwrap_with_match,
+        change_self_receiver::change_self_receiver,
+        generate_new::generate_new,
+        convert_for_loop::convert_for_loop_to_iterator_chain,
+        convert_for_loop::convert_for_each_to_for_loop,
+        add_test_module::add_test_module,
+        reorder_params::reorder_params,
+        replace_impl_trait::replace_impl_trait_with_generic,
+        replace_impl_trait::replace_generic_with_impl_trait,
+        extract_module::extract_module,
+        extract_module::inline_module,
+        toggle_closure_braces::add_closure_braces,
+        toggle_closure_braces::remove_closure_braces,
+        split_tuple_binding::split_tuple_binding,
+        split_tuple_binding::merge_tuple_binding,
+        convert_if_chain::convert_if_chain_to_match,
+        edit_bounds::add_bound,
+        edit_bounds::add_maybe_sized,
+        edit_bounds::remove_bound,
+        convert_vec_array::convert_vec_to_array,
+        convert_vec_array::convert_array_to_vec,
+        surround_with::surround_with,
+        extract_constant::extract_constant,
+        move_guard::move_arm_cond_to_match_guard,
+        add_all_type_annotations::add_all_type_annotations,
+        destructure_struct::destructure_struct_binding,
+        convert_cast::convert_cast,
+        loop_to_while_let::convert_loop_to_while_let,
+        loop_to_while_let::convert_while_let_to_loop,
+        remove_unnecessary_clone::remove_unnecessary_clone,
+        generate_trait_impl::generate_trait_impl,
+        add_map_err::add_map_err,
+        option_map::convert_match_to_option_map,
+        merge_impls::merge_impls,
+        extract_trait::extract_trait,
+        use_field_shorthand::use_field_shorthand,
+        use_field_shorthand::expand_field_shorthand,
+        add_allow::add_allow,
+        change_string_param::change_string_param,
+        add_accumulator::add_accumulator,
+        convert_let_else::convert_let_else,
+        collapse_nested_if::collapse_nested_if,
+        extract_closure_to_fn::extract_closure_to_fn,
+        add_explicit_type::add_explicit_type,
     ]
 }
 
@@ -311,6 +426,10 @@ mod tests {
         let mut assists = assists.iter();
 
         assert_eq!(assists.next().expect("expected assist").0.label, "introduce variable");
+        assert_eq!(assists.next().expect("expected assist").0.label, "convert to hexadecimal");
+        assert_eq!(assists.next().expect("expected assist").0.label, "convert to binary");
+        assert_eq!(assists.next().expect("expected assist").0.label, "wrap in dbg!()");
+        assert_eq!(assists.next().expect("expected assist").0.label, "extract constant");
         assert_eq!(assists.next().expect("expected assist").0.label, "replace with match");
     }
 
@@ -0,0 +1,273 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    AstNode, SyntaxNode,
+    ast::{self, LoopBodyOwner},
+    SyntaxKind::{LABEL, LIFETIME},
+};
+
+use crate::{AssistCtx, Assist, AssistId, utils::shift_indent};
+
+pub(crate) fn convert_loop_to_while_let(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let loop_expr = ctx.node_at_offset::<ast::LoopExpr>()?;
+    let loop_body = loop_expr.loop_body()?;
+    let mut exprs = loop_body
+        .statements()
+        .map(|stmt| match stmt.kind() {
+            ast::StmtKind::ExprStmt(it) => it.expr(),
+            ast::StmtKind::LetStmt(_) => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    exprs.extend(loop_body.expr());
+    let match_expr = match exprs.as_slice() {
+        [expr] => match expr.kind() {
+            ast::ExprKind::MatchExpr(it) => it,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let scrutinee = match_expr.expr()?;
+    let arms = match_expr.match_arm_list()?.arms().collect::<Vec<_>>();
+    let (arm, break_arm) = match arms.as_slice() {
+        [first, second] if is_break_arm(second) => (*first, *second),
+        [first, second] if is_break_arm(first) => (*second, *first),
+        _ => return None,
+    };
+    if arm.guard().is_some() || break_arm.guard().is_some() {
+        return None;
+    }
+    let mut pats = arm.pats();
+    let pat = pats.next()?;
+    if pats.next().is_some() {
+        return None;
+    }
+
+    let indent = leading_indent(loop_expr.syntax()).unwrap_or("");
+    let arm_indent = leading_indent(arm.syntax()).unwrap_or("");
+    let body = match arm.expr()?.kind() {
+        ast::ExprKind::BlockExpr(it) => {
+            shift_indent(&it.block()?.syntax().text().to_string(), arm_indent, indent)
+        }
+        _ => format!("{{\n{}    {}\n{}}}", indent, arm.expr()?.syntax().text(), indent),
+    };
+    let replacement = format!(
+        "{}while let {} = {} {}",
+        label(loop_expr.syntax()),
+        pat.syntax().text(),
+        scrutinee.syntax().text(),
+        body
+    );
+
+    let range = loop_expr.syntax().range();
+    ctx.add_action(AssistId("convert_loop_to_while_let"), "convert to while let", |edit| {
+        edit.target(range);
+        edit.replace(range, replacement);
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+pub(crate) fn convert_while_let_to_loop(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let while_expr = ctx.node_at_offset::<ast::WhileExpr>()?;
+    let condition = while_expr.condition()?;
+    let pat = condition.pat()?;
+    let expr = condition.expr()?;
+    let body = while_expr.loop_body()?;
+
+    // `Some(..)` can't be anything else than an `Option`.
+    let is_option = match pat.kind() {
+        ast::PatKind::TupleStructPat(it) => {
+            it.path().map_or(false, |it| it.syntax().text() == "Some")
+        }
+        _ => false,
+    };
+    let break_pat = if is_option { "None" } else { "_" };
+    let indent = leading_indent(while_expr.syntax()).unwrap_or("");
+    let arm_indent = format!("{}        ", indent);
+    let body = shift_indent(&body.syntax().text().to_string(), indent, &arm_indent);
+    let replacement = format!(
+        "{label}loop {{\n{indent}    match {} {{\n{arm}{} => {}\n{arm}{} => break,\n{indent}    }}\n{indent}}}",
+        expr.syntax().text(),
+        pat.syntax().text(),
+        body,
+        break_pat,
+        label = label(while_expr.syntax()),
+        indent = indent,
+        arm = arm_indent,
+    );
+
+    let range = while_expr.syntax().range();
+    ctx.add_action(AssistId("convert_while_let_to_loop"), "convert to loop with match", |edit| {
+        edit.target(range);
+        edit.replace(range, replacement);
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+/// Whether `arm` is `None => break` or `_ => break`, which is the condition of
+/// the `while let`. Anything else happening when the iteration stops keeps
+/// the loop as it is.
+fn is_break_arm(arm: &ast::MatchArm) -> bool {
+    let pat = match arm.pats().collect::<Vec<_>>().as_slice() {
+        [pat] => pat.syntax().text(),
+        _ => return false,
+    };
+    if pat != "None" && pat != "_" {
+        return false;
+    }
+    let expr = match arm.expr() {
+        Some(it) => it,
+        None => return false,
+    };
+    let expr = match expr.kind() {
+        ast::ExprKind::BlockExpr(it) => {
+            let block = match it.block() {
+                Some(it) => it,
+                None => return false,
+            };
+            let mut exprs = block.statements().map(|stmt| match stmt.kind() {
+                ast::StmtKind::ExprStmt(it) => it.expr(),
+                ast::StmtKind::LetStmt(_) => None,
+            });
+            match (exprs.next(), exprs.next(), block.expr()) {
+                (Some(Some(expr)), None, None) | (None, None, Some(expr)) => expr,
+                _ => return false,
+            }
+        }
+        _ => expr,
+    };
+    match expr.kind() {
+        // A labeled `break` might leave an outer loop.
+        ast::ExprKind::BreakExpr(it) => {
+            it.expr().is_none() && it.syntax().children().all(|it| it.kind() != LIFETIME)
+        }
+        _ => false,
+    }
+}
+
+/// The label of a loop, with its colon and the space after it.
+fn label(loop_expr: &SyntaxNode) -> String {
+    match loop_expr.children().find(|it| it.kind() == LABEL) {
+        Some(label) => format!("{} ", label.text()),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_loop_to_while_let_simple() {
+        check_assist(
+            convert_loop_to_while_let,
+            "
+fn f(mut it: impl Iterator<Item = u32>) {
+    lo<|>op {
+        match it.next() {
+            Some(x) => {
+                let y = x * 2;
+                g(y);
+            }
+            None => break,
+        }
+    }
+}",
+            "
+fn f(mut it: impl Iterator<Item = u32>) {
+    <|>while let Some(x) = it.next() {
+        let y = x * 2;
+        g(y);
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn convert_loop_to_while_let_keeps_label_and_wraps_expression() {
+        check_assist(
+            convert_loop_to_while_let,
+            "
+fn f() {
+    'outer: loop<|> {
+        match it.next() {
+            None => { break; }
+            Some((a, b)) => g(a, b),
+        };
+    }
+}",
+            "
+fn f() {
+    <|>'outer: while let Some((a, b)) = it.next() {
+        g(a, b)
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn convert_loop_to_while_let_not_applicable() {
+        // The `None` arm does more than break.
+        check_assist_not_applicable(
+            convert_loop_to_while_let,
+            "fn f() { loop<|> { match it.next() { Some(x) => g(x), None => { done(); break } } } }",
+        );
+        check_assist_not_applicable(
+            convert_loop_to_while_let,
+            "fn f() { 'a: loop { loop<|> { match it.next() { Some(x) => g(x), None => break 'a } } } }",
+        );
+        check_assist_not_applicable(
+            convert_loop_to_while_let,
+            "fn f() { loop<|> { g(); match it.next() { Some(x) => g(x), None => break } } }",
+        );
+        check_assist_not_applicable(
+            convert_loop_to_while_let,
+            "fn f() { loop<|> { match it.next() { Some(x) if x > 0 => g(x), _ => break } } }",
+        );
+    }
+
+    #[test]
+    fn convert_while_let_to_loop_simple() {
+        check_assist(
+            convert_while_let_to_loop,
+            "
+fn f() {
+    while<|> let Some(x) = it.next() {
+        g(x);
+    }
+}",
+            "
+fn f() {
+    <|>loop {
+        match it.next() {
+            Some(x) => {
+                g(x);
+            }
+            None => break,
+        }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn convert_while_let_to_loop_not_applicable() {
+        check_assist_not_applicable(
+            convert_while_let_to_loop,
+            "fn f() { while<|> x > 0 { g(); } }",
+        );
+    }
+
+    #[test]
+    fn convert_loop_to_while_let_target() {
+        check_assist_target(
+            convert_loop_to_while_let,
+            "fn f() { loop<|> { match it.next() { Some(x) => g(x), None => break } } }",
+            "loop { match it.next() { Some(x) => g(x), None => break } }",
+        );
+    }
+}
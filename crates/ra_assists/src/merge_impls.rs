@@ -0,0 +1,176 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    AstNode, SyntaxNode, TextRange,
+    ast::{self, TypeParamsOwner},
+    SyntaxKind::WHITESPACE,
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn merge_impls(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let impl_block = ctx.node_at_offset::<ast::ImplBlock>()?;
+    // The cursor has to be on the header, not in some method.
+    if impl_block.item_list()?.syntax().range().contains_inclusive(ctx.frange.range.start()) {
+        return None;
+    }
+    let key = inherent_impl_key(impl_block)?;
+    let other =
+        impl_block.syntax().parent()?.children().filter_map(ast::ImplBlock::cast).find(|it| {
+            it.syntax() != impl_block.syntax() && inherent_impl_key(it) == Some(key.clone())
+        })?;
+    let (first, second) = if impl_block.syntax().range().start() < other.syntax().range().start() {
+        (impl_block, other)
+    } else {
+        (other, impl_block)
+    };
+
+    let first_items = first.item_list()?;
+    let second_items = second.item_list()?;
+    let indent = leading_indent(first.syntax()).unwrap_or("");
+    let item_indent = second_items
+        .impl_items()
+        .next()
+        .and_then(|it| leading_indent(it.syntax()))
+        .map_or_else(|| format!("{}    ", indent), |it| it.to_string());
+    let first_inner = inner_text(first_items);
+    let first_inner = first_inner.trim();
+    let second_inner = inner_text(second_items);
+    let second_inner = second_inner.trim();
+    if second_inner.is_empty() {
+        return None;
+    }
+    let mut merged = String::from("{\n");
+    if !first_inner.is_empty() {
+        merged.push_str(&format!("{}{}\n\n", item_indent, first_inner));
+    }
+    merged.push_str(&format!("{}{}\n{}}}", item_indent, second_inner, indent));
+    // Take the blank lines before the second impl along.
+    let second_range = match second.syntax().prev_sibling() {
+        Some(ws) if ws.kind() == WHITESPACE => {
+            TextRange::from_to(ws.range().start(), second.syntax().range().end())
+        }
+        _ => second.syntax().range(),
+    };
+
+    ctx.add_action(AssistId("merge_impls"), "merge impl blocks", |edit| {
+        edit.target(impl_block.syntax().range());
+        edit.replace(first_items.syntax().range(), merged);
+        edit.delete(second_range);
+        edit.set_cursor(first.syntax().range().start());
+    });
+
+    ctx.build()
+}
+
+/// What two impls need to have in common to be merged: the generic
+/// parameters, the type and the where clause. `None` for trait impls.
+fn inherent_impl_key(impl_block: &ast::ImplBlock) -> Option<(String, String, String)> {
+    if impl_block.target_trait().is_some() {
+        return None;
+    }
+    let text = |node: Option<&SyntaxNode>| {
+        node.map(|it| it.text().to_string().split_whitespace().collect::<String>())
+            .unwrap_or_default()
+    };
+    Some((
+        text(impl_block.type_param_list().map(|it| it.syntax())),
+        text(Some(impl_block.target_type()?.syntax())),
+        text(impl_block.where_clause().map(|it| it.syntax())),
+    ))
+}
+
+/// The text between the braces of an item list.
+fn inner_text(item_list: &ast::ItemList) -> String {
+    let text = item_list.syntax().text().to_string();
+    let inner = text.trim_start_matches('{').trim_end_matches('}');
+    inner.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn merge_inherent_impls() {
+        check_assist(
+            merge_impls,
+            "
+struct S;
+
+impl<|> S {
+    /// Makes an `S`.
+    fn new() -> S { S }
+}
+
+struct T;
+
+impl S {
+    fn a(&self) {}
+
+    /// Does `b`.
+    fn b(&self) {}
+}",
+            "
+struct S;
+
+<|>impl S {
+    /// Makes an `S`.
+    fn new() -> S { S }
+
+    fn a(&self) {}
+
+    /// Does `b`.
+    fn b(&self) {}
+}
+
+struct T;",
+        );
+    }
+
+    #[test]
+    fn merge_into_preceding_generic_impl() {
+        check_assist(
+            merge_impls,
+            "
+mod m {
+    impl<T> S<T> {}
+    impl <T>   S<|><T> {
+        fn f() {}
+    }
+}",
+            "
+mod m {
+    <|>impl<T> S<T> {
+        fn f() {}
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn merge_impls_not_applicable() {
+        check_assist_not_applicable(
+            merge_impls,
+            "struct S; impl<|> S { fn a() {} } impl Clone for S { fn clone(&self) -> S { S } }",
+        );
+        check_assist_not_applicable(
+            merge_impls,
+            "struct S<T>(T); impl<|> S<u32> { fn a() {} } impl<T> S<T> { fn b() {} }",
+        );
+        check_assist_not_applicable(
+            merge_impls,
+            "struct S; impl S { fn a() {<|>} } impl S { fn b() {} }",
+        );
+    }
+
+    #[test]
+    fn merge_impls_target() {
+        check_assist_target(
+            merge_impls,
+            "struct S; impl<|> S {} impl S { fn b() {} }",
+            "impl S {}",
+        );
+    }
+}
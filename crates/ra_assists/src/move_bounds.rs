@@ -0,0 +1,275 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    AstNode, SyntaxNode, TextRange, TextUnit, ast,
+    algo::non_trivia_sibling,
+    Direction,
+    SyntaxKind::{
+        TYPE_PARAM, LIFETIME_PARAM, LIFETIME, COLON, SEMI, WHERE_KW, WHERE_PRED, COMMA, WHITESPACE,
+        PATH_TYPE, BLOCK, ITEM_LIST, NAMED_FIELD_DEF_LIST, ENUM_VARIANT_LIST,
+    },
+};
+
+use crate::{
+    AssistCtx, Assist, AssistId,
+    utils::{bounds_after, param_name_node, where_clause_with_whitespace},
+};
+
+pub(crate) fn move_bounds_to_where_clause(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let type_param_list = ctx.node_at_offset::<ast::TypeParamList>()?;
+    let owner = type_param_list.syntax().parent()?;
+    let where_clause = owner.children().find_map(ast::WhereClause::cast);
+
+    let params = type_param_list
+        .syntax()
+        .children()
+        .filter(|it| it.kind() == TYPE_PARAM || it.kind() == LIFETIME_PARAM)
+        .filter_map(inline_bounds)
+        .collect::<Vec<_>>();
+    if params.is_empty() {
+        return None;
+    }
+    let predicates = params
+        .iter()
+        .map(|it| format!("{}: {}", it.name.text(), it.bounds_text()))
+        .collect::<Vec<_>>();
+
+    let (offset, insert) = match where_clause {
+        Some(it) => append_predicates(it.syntax(), &predicates)?,
+        None => new_where_clause(owner, &predicates)?,
+    };
+    ctx.add_action(
+        AssistId("move_bounds_to_where_clause"),
+        "move bounds to where clause",
+        |edit| {
+            edit.target(type_param_list.syntax().range());
+            for param in params.iter() {
+                edit.delete(TextRange::from_to(param.name.range().end(), param.bounds.end()));
+            }
+            edit.insert(offset, insert);
+            edit.set_cursor(type_param_list.syntax().range().start());
+        },
+    );
+
+    ctx.build()
+}
+
+pub(crate) fn move_where_clause_to_bounds(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let where_clause = ctx.node_at_offset::<ast::WhereClause>()?;
+    let owner = where_clause.syntax().parent()?;
+    let type_param_list = owner.children().find_map(ast::TypeParamList::cast)?;
+
+    // Only a single `T: Bound` predicate, for a parameter of this very item.
+    let mut predicates = where_clause.syntax().children().filter(|it| it.kind() == WHERE_PRED);
+    let predicate = predicates.next()?;
+    if predicates.next().is_some() {
+        return None;
+    }
+    let bounded = predicate.first_child()?;
+    let colon = predicate.children().find(|it| it.kind() == COLON)?;
+    let bounds = bounds_after(colon)?;
+    let param_name = match bounded.kind() {
+        PATH_TYPE | LIFETIME => bounded.text().to_string(),
+        _ => return None,
+    };
+    let param = type_param_list
+        .syntax()
+        .children()
+        .filter(|it| it.kind() == TYPE_PARAM || it.kind() == LIFETIME_PARAM)
+        .find(|it| param_name_node(it).map_or(false, |name| name.text() == param_name.as_str()))?;
+    let bounds_text = where_clause.syntax().text().slice(bounds).to_string();
+    let (offset, insert) = match inline_bounds(param) {
+        Some(existing) => (existing.bounds.end(), format!(" + {}", bounds_text)),
+        None => (param_name_node(param)?.range().end(), format!(": {}", bounds_text)),
+    };
+
+    let delete = where_clause_with_whitespace(where_clause.syntax());
+    let body_follows = owner.last_child().map_or(false, |it| it.kind() != SEMI);
+    let cursor = param.range().start();
+    ctx.add_action(
+        AssistId("move_where_clause_to_bounds"),
+        "move where clause to bounds",
+        |edit| {
+            edit.target(where_clause.syntax().range());
+            edit.insert(offset, insert);
+            edit.replace(delete, if body_follows { " " } else { "" });
+            edit.set_cursor(cursor);
+        },
+    );
+
+    ctx.build()
+}
+
+/// A type or lifetime parameter with bounds, as in `T: Clone + Debug`.
+struct InlineBounds<'a> {
+    param: &'a SyntaxNode,
+    name: &'a SyntaxNode,
+    bounds: TextRange,
+}
+
+impl InlineBounds<'_> {
+    fn bounds_text(&self) -> String {
+        self.param.text().slice(self.bounds).to_string()
+    }
+}
+
+fn inline_bounds(param: &SyntaxNode) -> Option<InlineBounds<'_>> {
+    let name = param_name_node(param)?;
+    let colon = param.children().find(|it| it.kind() == COLON)?;
+    let bounds = bounds_after(colon)?;
+    Some(InlineBounds { param, name, bounds })
+}
+
+fn append_predicates(
+    where_clause: &SyntaxNode,
+    predicates: &[String],
+) -> Option<(TextUnit, String)> {
+    let last_predicate = match where_clause.children().filter(|it| it.kind() == WHERE_PRED).last() {
+        Some(it) => it,
+        None => {
+            // A lone `where`.
+            let where_kw = where_clause.children().find(|it| it.kind() == WHERE_KW)?;
+            return Some((where_kw.range().end(), format!(" {}", predicates.join(", "))));
+        }
+    };
+    let trailing_comma =
+        non_trivia_sibling(last_predicate, Direction::Next).filter(|it| it.kind() == COMMA);
+
+    let is_multiline = where_clause.text().contains('\n');
+    if !is_multiline {
+        let insert = predicates.iter().map(|it| format!(", {}", it)).collect::<String>();
+        return Some((last_predicate.range().end(), insert));
+    }
+    // Put each predicate on its own line, like the existing ones.
+    let indent = match last_predicate.prev_sibling() {
+        Some(ws) if ws.kind() == WHITESPACE => {
+            let ws = ws.leaf_text()?;
+            ws.rfind('\n').map(|idx| ws[idx + 1..].to_string()).unwrap_or_default()
+        }
+        _ => String::new(),
+    };
+    match trailing_comma {
+        Some(comma) => {
+            let insert =
+                predicates.iter().map(|it| format!("\n{}{},", indent, it)).collect::<String>();
+            Some((comma.range().end(), insert))
+        }
+        None => {
+            let insert =
+                predicates.iter().map(|it| format!(",\n{}{}", indent, it)).collect::<String>();
+            Some((last_predicate.range().end(), insert))
+        }
+    }
+}
+
+fn new_where_clause(owner: &SyntaxNode, predicates: &[String]) -> Option<(TextUnit, String)> {
+    let where_clause = format!("where {}", predicates.join(", "));
+    let body = owner.children().find(|it| {
+        [BLOCK, ITEM_LIST, NAMED_FIELD_DEF_LIST, ENUM_VARIANT_LIST].contains(&it.kind())
+    });
+    if let Some(body) = body {
+        return Some((body.range().start(), format!("{} ", where_clause)));
+    }
+    // Unit and tuple structs, and functions without a body.
+    let semi = owner.last_child().filter(|it| it.kind() == SEMI)?;
+    Some((semi.range().start(), format!(" {}", where_clause)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn move_bounds_to_new_where_clause() {
+        check_assist(
+            move_bounds_to_where_clause,
+            "fn foo<T: Clone + Debug<|>>(t: T) -> T {}",
+            "fn foo<|><T>(t: T) -> T where T: Clone + Debug {}",
+        );
+        check_assist(
+            move_bounds_to_where_clause,
+            "fn foo<'a: 'b, 'b, <|>T: 'a, U = u32, V: Copy = u32>() {}",
+            "fn foo<|><'a, 'b, T, U = u32, V = u32>() where 'a: 'b, T: 'a, V: Copy {}",
+        );
+        check_assist(
+            move_bounds_to_where_clause,
+            "struct Pair<<|>T: Copy>(T, T);",
+            "struct Pair<|><T>(T, T) where T: Copy;",
+        );
+        check_assist(
+            move_bounds_to_where_clause,
+            "impl<T: Clone<|>> Foo for Vec<T> { fn foo() {} }",
+            "impl<|><T> Foo for Vec<T> where T: Clone { fn foo() {} }",
+        );
+    }
+
+    #[test]
+    fn move_bounds_to_existing_where_clause() {
+        check_assist(
+            move_bounds_to_where_clause,
+            "fn foo<T: Clone<|>, U>(t: T, u: U) where U: Copy {}",
+            "fn foo<|><T, U>(t: T, u: U) where U: Copy, T: Clone {}",
+        );
+        check_assist(
+            move_bounds_to_where_clause,
+            "
+fn foo<T: Clone<|>, U: Debug>(t: T, u: U)
+where
+    U: Copy,
+{
+}",
+            "
+fn foo<|><T, U>(t: T, u: U)
+where
+    U: Copy,
+    T: Clone,
+    U: Debug,
+{
+}",
+        );
+    }
+
+    #[test]
+    fn move_bounds_to_where_clause_not_applicable() {
+        check_assist_not_applicable(move_bounds_to_where_clause, "fn foo<<|>T, U>() {}");
+        check_assist_not_applicable(
+            move_bounds_to_where_clause,
+            "fn foo<T>() where T: Clone { <|> }",
+        );
+    }
+
+    #[test]
+    fn move_where_clause_to_bounds_simple() {
+        check_assist(
+            move_where_clause_to_bounds,
+            "fn foo<T>(t: T) where <|>T: Clone + Debug {}",
+            "fn foo<<|>T: Clone + Debug>(t: T) {}",
+        );
+        check_assist(
+            move_where_clause_to_bounds,
+            "
+struct Pair<T: Debug>(T, T)
+where
+    T: Copy<|>;",
+            "
+struct Pair<<|>T: Debug + Copy>(T, T);",
+        );
+    }
+
+    #[test]
+    fn move_where_clause_to_bounds_not_applicable() {
+        check_assist_not_applicable(
+            move_where_clause_to_bounds,
+            "fn foo<T, U>() where T: Clone, <|>U: Copy {}",
+        );
+        check_assist_not_applicable(
+            move_where_clause_to_bounds,
+            "fn foo<T>() where <|>Vec<T>: Clone {}",
+        );
+    }
+
+    #[test]
+    fn move_bounds_to_where_clause_target() {
+        check_assist_target(move_bounds_to_where_clause, "fn foo<T: Clone<|>>() {}", "<T: Clone>");
+    }
+}
@@ -0,0 +1,133 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{AstNode, TextRange, ast, SyntaxKind::FAT_ARROW};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn move_arm_cond_to_match_guard(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let arm = ctx.node_at_offset::<ast::MatchArm>()?;
+    if arm.guard().is_some() {
+        return None;
+    }
+    let if_expr = ast::IfExpr::cast(arm.expr()?.syntax())?;
+    // The arm would evaluate to `()` when the condition does not hold, so
+    // there must not be anything else to evaluate to.
+    if if_expr.else_branch().is_some() {
+        return None;
+    }
+    let pats = arm.pats().map(|it| it.syntax().text().to_string()).collect::<Vec<_>>();
+    if pats.is_empty() {
+        return None;
+    }
+    let cond = if_expr.condition()?;
+    // `if let` guards are not stable.
+    if cond.pat().is_some() {
+        return None;
+    }
+    let then_block = if_expr.then_branch()?;
+    let fat_arrow = arm.syntax().children().find(|it| it.kind() == FAT_ARROW)?;
+
+    let body = match then_block.expr() {
+        Some(tail) if then_block.statements().next().is_none() => tail.syntax().text().to_string(),
+        _ => then_block.syntax().text().to_string(),
+    };
+    // The guarded arm is followed by one evaluating to `()` for the values
+    // which don't meet the condition, so that the match stays exhaustive.
+    let separator = match leading_indent(arm.syntax()) {
+        Some(indent) => format!("\n{}", indent),
+        None => " ".to_string(),
+    };
+    let fallback = format!("{}{} => {{}}", separator, pats.join(" | "));
+
+    let range = TextRange::from_to(fat_arrow.range().start(), if_expr.syntax().range().end());
+    ctx.add_action(
+        AssistId("move_arm_cond_to_match_guard"),
+        "move condition to match guard",
+        |edit| {
+            edit.target(if_expr.syntax().range());
+            edit.replace(range, format!("if {} => {},{}", cond.syntax().text(), body, fallback));
+            edit.set_cursor(range.start());
+        },
+    );
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn move_arm_cond_to_match_guard_simple() {
+        check_assist(
+            move_arm_cond_to_match_guard,
+            "
+fn f(x: Option<u32>) {
+    match x {
+        Some(n) => if n > 1<|>0 { big(n) }
+        None => {}
+    }
+}",
+            "
+fn f(x: Option<u32>) {
+    match x {
+        Some(n) <|>if n > 10 => big(n),
+        Some(n) => {}
+        None => {}
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn move_arm_cond_to_match_guard_keeps_block_with_statements() {
+        check_assist(
+            move_arm_cond_to_match_guard,
+            "
+fn f(x: u32) {
+    match x {
+        _ => <|>if x > 10 {
+            let y = x * 2;
+            big(y);
+        },
+    }
+}",
+            "
+fn f(x: u32) {
+    match x {
+        _ <|>if x > 10 => {
+            let y = x * 2;
+            big(y);
+        },
+        _ => {},
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn move_arm_cond_to_match_guard_not_applicable() {
+        check_assist_not_applicable(
+            move_arm_cond_to_match_guard,
+            "fn f(x: u32) { match x { _ => if x > 1<|>0 { a() } else { b() } } }",
+        );
+        check_assist_not_applicable(
+            move_arm_cond_to_match_guard,
+            "fn f(x: Option<u32>) { match x { _ => if let Some(<|>y) = x { a(y) } } }",
+        );
+        check_assist_not_applicable(
+            move_arm_cond_to_match_guard,
+            "fn f(x: u32) { match x { _ if x > 0 => if x > 1<|>0 { a() } } }",
+        );
+    }
+
+    #[test]
+    fn move_arm_cond_to_match_guard_target() {
+        check_assist_target(
+            move_arm_cond_to_match_guard,
+            "fn f(x: u32) { match x { _ => if x > 1<|>0 { a() } } }",
+            "if x > 10 { a() }",
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use hir::{db::HirDatabase, source_binder};
+use ra_syntax::{
+    AstNode,
+    ast::{self, ArgListOwner},
+    SyntaxKind::{BREAK_EXPR, CONTINUE_EXPR, RETURN_EXPR, TRY_EXPR},
+};
+
+use crate::{
+    AssistCtx, Assist, AssistId,
+    utils::{enum_name, receiver_text, tuple_struct_pat},
+};
+
+pub(crate) fn convert_match_to_option_map(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let match_expr = ctx.node_at_offset::<ast::MatchExpr>()?;
+    let expr = match_expr.expr()?;
+    let arms = match_expr.match_arm_list()?.arms().collect::<Vec<_>>();
+    let some_arm = match arms.as_slice() {
+        [first, second] if is_none_arm(second) => *first,
+        [first, second] if is_none_arm(first) => *second,
+        _ => return None,
+    };
+    if some_arm.guard().is_some() {
+        return None;
+    }
+    let mut pats = some_arm.pats();
+    let (name, arg) = tuple_struct_pat(pats.next()?)?;
+    if name != "Some" || pats.next().is_some() {
+        return None;
+    }
+    let binding = match arg.kind() {
+        ast::PatKind::BindPat(it) if it.pat().is_none() && !it.is_ref() => it,
+        _ => return None,
+    };
+    let body = some_arm.expr()?;
+    // Control flow would leave the closure instead of the function.
+    let has_control_flow = body.syntax().descendants().any(|it| match it.kind() {
+        RETURN_EXPR | BREAK_EXPR | CONTINUE_EXPR | TRY_EXPR => true,
+        _ => false,
+    });
+    if has_control_flow {
+        return None;
+    }
+
+    let (method, closure_body) = match some_argument(body) {
+        Some(value) => ("map", value),
+        None if returns_option(&ctx, body)? => ("and_then", body),
+        None => return None,
+    };
+    let replacement = format!(
+        "{}.{}(|{}| {})",
+        receiver_text(expr),
+        method,
+        binding.syntax().text(),
+        closure_body.syntax().text()
+    );
+
+    let range = match_expr.syntax().range();
+    let (id, label) = match method {
+        "map" => ("convert_match_to_map", "convert to `map`"),
+        _ => ("convert_match_to_and_then", "convert to `and_then`"),
+    };
+    ctx.add_action(AssistId(id), label, |edit| {
+        edit.target(range);
+        edit.replace(range, replacement);
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+/// Matches `None => None`.
+fn is_none_arm(arm: &ast::MatchArm) -> bool {
+    let pats = arm.pats().collect::<Vec<_>>();
+    arm.guard().is_none()
+        && pats.len() == 1
+        && pats[0].syntax().text() == "None"
+        && arm.expr().map_or(false, |it| it.syntax().text() == "None")
+}
+
+/// Returns `x` for `Some(x)`.
+fn some_argument(expr: &ast::Expr) -> Option<&ast::Expr> {
+    let call = match expr.kind() {
+        ast::ExprKind::CallExpr(it) => it,
+        _ => return None,
+    };
+    if call.expr()?.syntax().text() != "Some" {
+        return None;
+    }
+    let mut args = call.arg_list()?.args();
+    let arg = args.next()?;
+    if args.next().is_some() {
+        return None;
+    }
+    Some(arg)
+}
+
+fn returns_option(ctx: &AssistCtx<impl HirDatabase>, expr: &ast::Expr) -> Option<bool> {
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, expr.syntax())?;
+    let infer = function.infer(ctx.db);
+    let source_map = function.body_source_map(ctx.db);
+    Some(enum_name(ctx.db, &infer[source_map.node_expr(expr)?]).map_or(false, |it| it == "Option"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_match_to_map() {
+        check_assist(
+            convert_match_to_option_map,
+            "
+fn f(opt: Option<u32>) -> Option<u32> {
+    ma<|>tch opt {
+        Some(x) => Some(x * 2),
+        None => None,
+    }
+}",
+            "
+fn f(opt: Option<u32>) -> Option<u32> {
+    <|>opt.map(|x| x * 2)
+}",
+        );
+    }
+
+    #[test]
+    fn convert_match_to_and_then() {
+        check_assist(
+            convert_match_to_option_map,
+            "
+enum Option<T> { Some(T), None }
+fn g(x: u32) -> Option<u32> { None }
+fn f(a: u32) -> Option<u32> {
+    match<|> g(a + 1) {
+        None => None,
+        Some(x) => g(x),
+    }
+}",
+            "
+enum Option<T> { Some(T), None }
+fn g(x: u32) -> Option<u32> { None }
+fn f(a: u32) -> Option<u32> {
+    <|>g(a + 1).and_then(|x| g(x))
+}",
+        );
+    }
+
+    #[test]
+    fn convert_match_to_option_map_not_applicable() {
+        check_assist_not_applicable(
+            convert_match_to_option_map,
+            "fn f() { match<|> opt { Some(x) => Some(x?), None => None } }",
+        );
+        check_assist_not_applicable(
+            convert_match_to_option_map,
+            "fn f() { match<|> opt { Some(x) => { return Some(x) } None => None } }",
+        );
+        check_assist_not_applicable(
+            convert_match_to_option_map,
+            "fn f() { match<|> opt { Some(x) => Some(x), None => Some(0) } }",
+        );
+        check_assist_not_applicable(
+            convert_match_to_option_map,
+            "fn f() -> u32 { match<|> opt { Some(x) => x, None => None } }",
+        );
+    }
+
+    #[test]
+    fn convert_match_to_option_map_target() {
+        check_assist_target(
+            convert_match_to_option_map,
+            "fn f() { let y = match<|> opt { Some(x) => Some(x), None => None }; }",
+            "match opt { Some(x) => Some(x), None => None }",
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use hir::db::HirDatabase;
+use ra_syntax::{AstNode, ast, SyntaxKind::INT_NUMBER};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn reformat_number_literal(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let literal = ctx.node_at_offset::<ast::Literal>()?;
+    let token = literal.syntax().first_child()?;
+    if token.kind() != INT_NUMBER {
+        return None;
+    }
+    let number = NumberLiteral::parse(token.leaf_text()?)?;
+    let range = literal.syntax().range();
+
+    let conversions = [
+        (Radix::Hexadecimal, "convert_to_hexadecimal", "convert to hexadecimal"),
+        (Radix::Binary, "convert_to_binary", "convert to binary"),
+        (Radix::Decimal, "convert_to_decimal", "convert to decimal"),
+    ];
+    for &(radix, id, label) in conversions.iter() {
+        if radix == number.radix {
+            continue;
+        }
+        ctx.add_action(AssistId(id), label, |edit| {
+            edit.target(range);
+            edit.replace(range, number.to_text(radix, number.has_separators));
+            edit.set_cursor(range.start());
+        });
+    }
+
+    if number.has_separators {
+        ctx.add_action(AssistId("remove_digit_separators"), "remove digit separators", |edit| {
+            edit.target(range);
+            edit.replace(range, number.to_text(number.radix, false));
+            edit.set_cursor(range.start());
+        });
+    } else if number.digits.len() > number.radix.group_size() {
+        ctx.add_action(AssistId("add_digit_separators"), "add digit separators", |edit| {
+            edit.target(range);
+            edit.replace(range, number.to_text(number.radix, true));
+            edit.set_cursor(range.start());
+        });
+    }
+
+    ctx.build()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Radix {
+    Binary,
+    Octal,
+    Decimal,
+    Hexadecimal,
+}
+
+impl Radix {
+    fn value(self) -> u32 {
+        match self {
+            Radix::Binary => 2,
+            Radix::Octal => 8,
+            Radix::Decimal => 10,
+            Radix::Hexadecimal => 16,
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            Radix::Binary => "0b",
+            Radix::Octal => "0o",
+            Radix::Decimal => "",
+            Radix::Hexadecimal => "0x",
+        }
+    }
+
+    /// How many digits go between two `_` separators.
+    fn group_size(self) -> usize {
+        match self {
+            Radix::Binary | Radix::Hexadecimal => 4,
+            Radix::Octal | Radix::Decimal => 3,
+        }
+    }
+}
+
+/// An integer literal, like `0xFF_u8`, split into its parts.
+#[derive(Debug)]
+struct NumberLiteral<'a> {
+    radix: Radix,
+    /// The digits, without separators.
+    digits: String,
+    value: u128,
+    suffix: &'a str,
+    /// Whether there's an `_` between the digits, as in `1_000`.
+    has_separators: bool,
+    /// Whether the suffix is separated from the digits, as in `92_u32`.
+    suffix_separated: bool,
+}
+
+impl<'a> NumberLiteral<'a> {
+    fn parse(text: &'a str) -> Option<NumberLiteral<'a>> {
+        let radix = match text.get(..2) {
+            Some("0b") => Radix::Binary,
+            Some("0o") => Radix::Octal,
+            Some("0x") => Radix::Hexadecimal,
+            _ => Radix::Decimal,
+        };
+        let rest = &text[radix.prefix().len()..];
+        let suffix_start = rest
+            .find(|c: char| c != '_' && !c.is_digit(radix.value()))
+            .unwrap_or_else(|| rest.len());
+        let (body, suffix) = rest.split_at(suffix_start);
+
+        let digits: String = body.chars().filter(|&c| c != '_').collect();
+        let value = u128::from_str_radix(&digits, radix.value()).ok()?;
+        if !fits_in_suffix(value, suffix)? {
+            return None;
+        }
+        Some(NumberLiteral {
+            radix,
+            digits,
+            value,
+            suffix,
+            has_separators: body.trim_end_matches('_').contains('_'),
+            suffix_separated: !suffix.is_empty() && body.ends_with('_'),
+        })
+    }
+
+    fn to_text(&self, radix: Radix, with_separators: bool) -> String {
+        let digits = match radix {
+            Radix::Binary => format!("{:b}", self.value),
+            Radix::Octal => format!("{:o}", self.value),
+            Radix::Decimal => format!("{}", self.value),
+            Radix::Hexadecimal => format!("{:X}", self.value),
+        };
+        let mut res = radix.prefix().to_string();
+        if with_separators {
+            let group_size = radix.group_size();
+            for (i, c) in digits.chars().enumerate() {
+                if i > 0 && (digits.len() - i) % group_size == 0 {
+                    res.push('_');
+                }
+                res.push(c);
+            }
+        } else {
+            res.push_str(&digits);
+        }
+        if self.suffix_separated {
+            res.push('_');
+        }
+        res.push_str(self.suffix);
+        res
+    }
+}
+
+/// Returns `None` for unknown suffixes.
+fn fits_in_suffix(value: u128, suffix: &str) -> Option<bool> {
+    let max = match suffix {
+        // The type is inferred, any value might be valid.
+        "" => u128::max_value(),
+        "u8" => u128::from(u8::max_value()),
+        "u16" => u128::from(u16::max_value()),
+        "u32" => u128::from(u32::max_value()),
+        "u64" | "usize" => u128::from(u64::max_value()),
+        "u128" => u128::max_value(),
+        "i8" => i8::max_value() as u128,
+        "i16" => i16::max_value() as u128,
+        "i32" => i32::max_value() as u128,
+        "i64" | "isize" => i64::max_value() as u128,
+        "i128" => i128::max_value() as u128,
+        _ => return None,
+    };
+    Some(value <= max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_nth_action, check_assist_not_applicable, check_assist_target,
+    };
+
+    #[test]
+    fn reformat_decimal_to_hexadecimal() {
+        check_assist(
+            reformat_number_literal,
+            "fn f() { let x = 25<|>5u32; }",
+            "fn f() { let x = <|>0xFFu32; }",
+        );
+    }
+
+    #[test]
+    fn reformat_hexadecimal_to_binary_and_decimal() {
+        check_assist_nth_action(
+            reformat_number_literal,
+            "fn f() { let x = 0x1<|>0_i64; }",
+            "fn f() { let x = <|>0b10000_i64; }",
+            0,
+        );
+        check_assist_nth_action(
+            reformat_number_literal,
+            "fn f() { let x = 0x1<|>0_i64; }",
+            "fn f() { let x = <|>16_i64; }",
+            1,
+        );
+    }
+
+    #[test]
+    fn reformat_keeps_separators() {
+        check_assist_nth_action(
+            reformat_number_literal,
+            "fn f() { let x = 0b1111_<|>0000; }",
+            "fn f() { let x = <|>240; }",
+            1,
+        );
+        check_assist(
+            reformat_number_literal,
+            "fn f() { let x = 0b1111_<|>0000; }",
+            "fn f() { let x = <|>0xF0; }",
+        );
+        check_assist(
+            reformat_number_literal,
+            "fn f() { let x = 1_000_<|>000; }",
+            "fn f() { let x = <|>0xF_4240; }",
+        );
+    }
+
+    #[test]
+    fn reformat_adds_digit_separators() {
+        check_assist_nth_action(
+            reformat_number_literal,
+            "fn f() { let x = 10<|>00000; }",
+            "fn f() { let x = <|>1_000_000; }",
+            2,
+        );
+        check_assist_nth_action(
+            reformat_number_literal,
+            "fn f() { let x = 0x<|>FFFFFu64; }",
+            "fn f() { let x = <|>0xF_FFFFu64; }",
+            2,
+        );
+    }
+
+    #[test]
+    fn reformat_removes_digit_separators() {
+        check_assist_nth_action(
+            reformat_number_literal,
+            "fn f() { let x = 1_00<|>0_i32; }",
+            "fn f() { let x = <|>1000_i32; }",
+            2,
+        );
+    }
+
+    #[test]
+    fn reformat_not_applicable() {
+        check_assist_not_applicable(reformat_number_literal, "fn f() { let x = 25<|>6u8; }");
+        check_assist_not_applicable(reformat_number_literal, "fn f() { let x = 0x8<|>0i8; }");
+        check_assist_not_applicable(reformat_number_literal, "fn f() { let x = 1.<|>5; }");
+        check_assist_not_applicable(reformat_number_literal, "fn f() { let x = \"1<|>0\"; }");
+    }
+
+    #[test]
+    fn reformat_target() {
+        check_assist_target(reformat_number_literal, "fn f() { let x = 9<|>2u8; }", "92u8");
+    }
+}
@@ -0,0 +1,137 @@
+use hir::{Resolution, Ty, db::HirDatabase, source_binder};
+use ra_syntax::{
+    AstNode,
+    ast::{self, ArgListOwner},
+    SyntaxKind::{ARG_LIST, NAME_REF},
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn remove_unnecessary_clone(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = ctx.node_at_offset::<ast::MethodCallExpr>()?;
+    if call.name_ref()?.text() != "clone" || call.arg_list()?.args().next().is_some() {
+        return None;
+    }
+    let receiver = call.expr()?;
+    let path = match receiver.kind() {
+        ast::ExprKind::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    if path.qualifier().is_some() {
+        return None;
+    }
+    let name = path.segment()?.name_ref()?.text().to_string();
+    // Only locals, which we know are not borrowed elsewhere.
+    let resolver = source_binder::resolver_for_node(ctx.db, ctx.frange.file_id, call.syntax());
+    match resolver.resolve_path(ctx.db, &hir::Path::from_ast(path)?).take_values() {
+        Some(Resolution::LocalBinding(_)) => (),
+        _ => return None,
+    }
+
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, call.syntax())?;
+    let infer = function.infer(ctx.db);
+    let source_map = function.body_source_map(ctx.db);
+    let receiver_ty = &infer[source_map.node_expr(receiver)?];
+    let clone_ty = &infer[source_map.node_expr(ast::Expr::cast(call.syntax())?)?];
+    if *clone_ty == Ty::Unknown {
+        return None;
+    }
+
+    let ref_expr = call.syntax().parent().and_then(ast::RefExpr::cast).filter(|it| !it.is_mut());
+    let (node, replacement) = match ref_expr {
+        // `&x.clone()` borrows a copy of `x` just as long as `&x` would.
+        Some(ref_expr) if clone_ty == receiver_ty => (ref_expr.syntax(), format!("&{}", name)),
+        Some(ref_expr) => match receiver_ty {
+            Ty::Ref(inner, _) if **inner == *clone_ty => (ref_expr.syntax(), name.clone()),
+            _ => return None,
+        },
+        // Cloning a reference is copying it.
+        None => match clone_ty {
+            Ty::Ref(..) if clone_ty == receiver_ty => (call.syntax(), name.clone()),
+            _ => return None,
+        },
+    };
+    // The borrow must end with the call it is passed to, which must not use
+    // `x` otherwise, like by borrowing it mutably.
+    if node.parent().map(|it| it.kind()) != Some(ARG_LIST) {
+        return None;
+    }
+    let consumer = node.parent()?.parent()?;
+    let mentions = consumer
+        .descendants()
+        .filter(|it| it.kind() == NAME_REF && it.text() == name.as_str())
+        .count();
+    if mentions > 1 {
+        return None;
+    }
+
+    let range = node.range();
+    ctx.add_action(AssistId("remove_unnecessary_clone"), "remove unnecessary clone", |edit| {
+        edit.target(range);
+        edit.replace(range, replacement);
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    const CLONE: &str = "
+trait Clone { fn clone(&self) -> Self; }
+struct S;
+impl Clone for S { fn clone(&self) -> S { S } }
+fn take(s: &S) {}
+fn take_two(s: &S, t: &mut S) {}";
+
+    #[test]
+    fn remove_clone_of_borrowed_argument() {
+        check_assist(
+            remove_unnecessary_clone,
+            &format!("{}\nfn f(x: S) {{ take(&x.clo<|>ne()); }}", CLONE),
+            &format!("{}\nfn f(x: S) {{ take(<|>&x); }}", CLONE),
+        );
+        check_assist(
+            remove_unnecessary_clone,
+            &format!("{}\nfn f(x: &S) {{ take(&x.clo<|>ne()); }}", CLONE),
+            &format!("{}\nfn f(x: &S) {{ take(<|>x); }}", CLONE),
+        );
+    }
+
+    #[test]
+    fn remove_unnecessary_clone_not_applicable() {
+        // The clone is moved.
+        check_assist_not_applicable(
+            remove_unnecessary_clone,
+            &format!("{}\nfn f(x: S) {{ let y = x.clo<|>ne(); }}", CLONE),
+        );
+        // The borrow outlives the call.
+        check_assist_not_applicable(
+            remove_unnecessary_clone,
+            &format!("{}\nfn f(x: S) {{ let y = &x.clo<|>ne(); }}", CLONE),
+        );
+        // `x` is borrowed mutably as well.
+        check_assist_not_applicable(
+            remove_unnecessary_clone,
+            &format!("{}\nfn f(mut x: S) {{ take_two(&x.clo<|>ne(), &mut x); }}", CLONE),
+        );
+        // Not a local.
+        check_assist_not_applicable(
+            remove_unnecessary_clone,
+            &format!("{}\nstatic X: S = S;\nfn f() {{ take(&X.clo<|>ne()); }}", CLONE),
+        );
+    }
+
+    #[test]
+    fn remove_unnecessary_clone_target() {
+        check_assist_target(
+            remove_unnecessary_clone,
+            &format!("{}\nfn f(x: S) {{ take(&x.clo<|>ne()); }}", CLONE),
+            "&x.clone()",
+        );
+    }
+}
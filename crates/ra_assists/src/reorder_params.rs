@@ -0,0 +1,174 @@
+use hir::{Function, db::HirDatabase, source_binder};
+use ra_db::FileId;
+use ra_syntax::{
+    AstNode, TextRange,
+    ast::{self, VisibilityOwner},
+};
+
+use crate::{AssistCtx, Assist, AssistId, utils::for_each_call};
+
+pub(crate) fn reorder_params(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let param = ctx.node_at_offset::<ast::Param>()?;
+    let param_list = ast::ParamList::cast(param.syntax().parent()?)?;
+    let params = param_list.params().collect::<Vec<_>>();
+    if params.len() < 2 {
+        return None;
+    }
+    let idx = params.iter().position(|it| it.syntax() == param.syntax())?;
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, param.syntax())?;
+
+    let is_public =
+        function.source(ctx.db).1.visibility().map_or(false, |it| it.syntax().text() == "pub");
+    let moves = [(idx.checked_sub(1), "left"), (Some(idx + 1), "right")];
+    for &(other, direction) in moves.iter() {
+        let other = match other.and_then(|it| params.get(it).map(|param| (it, param))) {
+            Some(it) => it,
+            None => continue,
+        };
+        // Public functions may be called from other crates, so we can't know
+        // all the call sites.
+        let call_edits = if is_public {
+            None
+        } else {
+            call_site_edits(ctx.db, function, ctx.frange.file_id, idx, other.0)
+        };
+        let label = match call_edits {
+            Some(_) => format!("move parameter {}", direction),
+            None => format!("move parameter {} (call sites are not updated)", direction),
+        };
+        let (range, other_range) = (param.syntax().range(), other.1.syntax().range());
+        ctx.add_action(AssistId("reorder_params"), label, |edit| {
+            edit.target(range);
+            edit.replace(range, other.1.syntax().text());
+            edit.replace(other_range, param.syntax().text());
+            for (range, arg) in call_edits.into_iter().flatten() {
+                edit.replace(range, arg);
+            }
+            // The moved parameter ends where its neighbour did.
+            let cursor = if other_range.start() < range.start() {
+                other_range.start()
+            } else {
+                other_range.end() - range.len()
+            };
+            edit.set_cursor(cursor);
+        });
+    }
+
+    ctx.build()
+}
+
+/// Swaps the arguments for the parameters `a` and `b` of the calls of
+/// `function` in its crate. Only the arguments themselves are swapped, so
+/// that comments and whitespace around them stay in place. Returns `None` if
+/// some call is not in `file_id` or doesn't have an argument for each
+/// parameter.
+fn call_site_edits(
+    db: &impl HirDatabase,
+    function: Function,
+    file_id: FileId,
+    a: usize,
+    b: usize,
+) -> Option<Vec<(TextRange, String)>> {
+    let mut res = Vec::new();
+    for_each_call(db, function, file_id, |args| {
+        let (arg_a, arg_b) = (args.get(a)?, args.get(b)?);
+        res.push((arg_a.syntax().range(), arg_b.syntax().text().to_string()));
+        res.push((arg_b.syntax().range(), arg_a.syntax().text().to_string()));
+        Some(())
+    })?;
+    Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_nth_action, check_assist_not_applicable};
+
+    #[test]
+    fn move_parameter_and_update_call_sites() {
+        check_assist(
+            reorder_params,
+            "
+fn foo(a: u32, b: <|>bool, c: char) {}
+fn bar() {
+    foo(1, true, 'c');
+}
+fn baz() {
+    foo(2 /* two */, false, 'z');
+}",
+            "
+fn foo(<|>b: bool, a: u32, c: char) {}
+fn bar() {
+    foo(true, 1, 'c');
+}
+fn baz() {
+    foo(false /* two */, 2, 'z');
+}",
+        );
+        check_assist_nth_action(
+            reorder_params,
+            "
+fn foo(a: u32, b: <|>bool, c: char) {}
+fn bar() {
+    foo(1, true, 'c');
+}
+fn baz() {
+    foo(2 /* two */, false, 'z');
+}",
+            "
+fn foo(a: u32, c: char, <|>b: bool) {}
+fn bar() {
+    foo(1, 'c', true);
+}
+fn baz() {
+    foo(2 /* two */, 'z', false);
+}",
+            1,
+        );
+    }
+
+    #[test]
+    fn move_parameter_of_method() {
+        check_assist(
+            reorder_params,
+            "
+struct S;
+impl S {
+    fn foo(&self, a: u32, <|>b: bool) {}
+}
+fn bar(s: S) {
+    s.foo(1, true);
+    S::foo(&s, 2, false);
+}",
+            "
+struct S;
+impl S {
+    fn foo(&self, <|>b: bool, a: u32) {}
+}
+fn bar(s: S) {
+    s.foo(true, 1);
+    S::foo(&s, false, 2);
+}",
+        );
+    }
+
+    #[test]
+    fn move_parameter_of_public_function() {
+        check_assist(
+            reorder_params,
+            "
+pub fn foo(a: u32, <|>b: bool) {}
+fn bar() { foo(1, true); }",
+            "
+pub fn foo(<|>b: bool, a: u32) {}
+fn bar() { foo(1, true); }",
+        );
+    }
+
+    #[test]
+    fn reorder_params_not_applicable() {
+        check_assist_not_applicable(reorder_params, "fn foo(<|>a: u32) {}");
+        check_assist_not_applicable(reorder_params, "struct S; impl S { fn foo(&se<|>lf) {} }");
+    }
+}
@@ -0,0 +1,268 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    AstNode, Direction, SyntaxKind, SyntaxNode, TextRange, TextUnit,
+    ast::{self, NameOwner, TypeParamsOwner},
+    algo::non_trivia_sibling,
+    SyntaxKind::{COLON, COMMA, IMPL_KW, NAME_REF, PARAM, PAREN_TYPE, REFERENCE_TYPE, WHERE_PRED},
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn replace_impl_trait_with_generic(
+    mut ctx: AssistCtx<impl HirDatabase>,
+) -> Option<Assist> {
+    let impl_trait = ctx.node_at_offset::<ast::ImplTraitType>()?;
+    let param = impl_trait.syntax().ancestors().find(|it| it.kind() == PARAM)?;
+    // `impl Trait` is not allowed in the parameters of closures.
+    let fn_def = ast::FnDef::cast(param.parent()?.parent()?)?;
+    let bounds = text_after(impl_trait.syntax(), IMPL_KW)?;
+
+    let fn_name = fn_def.name()?;
+    let name = fresh_type_param_name(fn_def);
+    // The parentheses of `&(impl A + B)` aren't needed around a name.
+    let replaced = match impl_trait.syntax().parent() {
+        Some(parent) if parent.kind() == PAREN_TYPE => parent,
+        _ => impl_trait.syntax(),
+    };
+    ctx.add_action(
+        AssistId("replace_impl_trait_with_generic"),
+        "replace impl Trait with generic",
+        |edit| {
+            edit.target(impl_trait.syntax().range());
+            edit.replace(replaced.range(), name.clone());
+            let new_param = format!("{}: {}", name, bounds);
+            let (offset, text) = match fn_def.type_param_list() {
+                Some(list) => (
+                    list.syntax().range().end() - TextUnit::of_char('>'),
+                    format!(", {}", new_param),
+                ),
+                None => (fn_name.syntax().range().end(), format!("<{}>", new_param)),
+            };
+            // The generic parameters come before the parameter.
+            edit.set_cursor(replaced.range().start() + TextUnit::of_str(&text));
+            edit.insert(offset, text);
+        },
+    );
+
+    ctx.build()
+}
+
+pub(crate) fn replace_generic_with_impl_trait(
+    mut ctx: AssistCtx<impl HirDatabase>,
+) -> Option<Assist> {
+    let type_param = ctx.node_at_offset::<ast::TypeParam>()?;
+    let type_param_list = ast::TypeParamList::cast(type_param.syntax().parent()?)?;
+    let fn_def = ast::FnDef::cast(type_param_list.syntax().parent()?)?;
+    let name = type_param.name()?.text().clone();
+
+    let mut bounds = Vec::new();
+    bounds.extend(text_after(type_param.syntax(), COLON));
+    let mut preds = Vec::new();
+    if let Some(where_clause) = fn_def.where_clause() {
+        for pred in where_clause.syntax().children().filter(|it| it.kind() == WHERE_PRED) {
+            if pred.first_child().map_or(false, |it| it.text() == name.as_str()) {
+                bounds.extend(text_after(pred, COLON));
+                preds.push(pred);
+            }
+        }
+    }
+    if bounds.is_empty() {
+        return None;
+    }
+
+    // The parameter has to be used exactly once, in the type of a parameter.
+    let mut uses = fn_def.syntax().descendants().filter(|it| {
+        it.kind() == NAME_REF
+            && it.text() == name.as_str()
+            && !it.ancestors().any(|it| preds.contains(&it))
+    });
+    let name_ref = uses.next()?;
+    if uses.next().is_some() {
+        return None;
+    }
+    let path = name_ref.ancestors().find_map(ast::PathType::cast)?.path()?;
+    if path.qualifier().is_some() || path.segment()?.type_arg_list().is_some() {
+        return None;
+    }
+    let param = path.syntax().ancestors().find(|it| it.kind() == PARAM)?;
+    if param.parent()?.parent()? != fn_def.syntax() {
+        return None;
+    }
+    let path_type = path.syntax().parent()?;
+
+    let bounds = bounds.join(" + ");
+    let impl_trait = match path_type.parent() {
+        Some(parent) if parent.kind() == REFERENCE_TYPE && bounds.contains('+') => {
+            format!("(impl {})", bounds)
+        }
+        _ => format!("impl {}", bounds),
+    };
+    ctx.add_action(
+        AssistId("replace_generic_with_impl_trait"),
+        "replace generic with impl Trait",
+        |edit| {
+            edit.target(type_param.syntax().range());
+            edit.replace(path_type.range(), impl_trait);
+            let param_count =
+                type_param_list.type_params().count() + type_param_list.lifetime_params().count();
+            let deleted = if param_count == 1 {
+                type_param_list.syntax().range()
+            } else {
+                list_item_range(type_param.syntax())
+            };
+            edit.delete(deleted);
+            edit.set_cursor(path_type.range().start() - deleted.len());
+            if let Some(where_clause) = fn_def.where_clause() {
+                let pred_count =
+                    where_clause.syntax().children().filter(|it| it.kind() == WHERE_PRED).count();
+                if pred_count == preds.len() {
+                    let start = where_clause
+                        .syntax()
+                        .prev_sibling()
+                        .filter(|it| it.kind().is_trivia())
+                        .unwrap_or_else(|| where_clause.syntax())
+                        .range()
+                        .start();
+                    edit.delete(TextRange::from_to(start, where_clause.syntax().range().end()));
+                } else {
+                    for pred in preds.iter() {
+                        edit.delete(list_item_range(pred));
+                    }
+                }
+            }
+        },
+    );
+
+    ctx.build()
+}
+
+/// The trimmed text of `node` after its first `kind` child.
+fn text_after(node: &SyntaxNode, kind: SyntaxKind) -> Option<String> {
+    let token = node.children().find(|it| it.kind() == kind)?;
+    let text = node.text().slice(token.range().end()..).to_string();
+    let text = text.trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
+    }
+}
+
+/// The range to delete to remove `node` and a neighbouring comma from a
+/// comma-separated list.
+fn list_item_range(node: &SyntaxNode) -> TextRange {
+    match non_trivia_sibling(node, Direction::Next) {
+        Some(comma) if comma.kind() == COMMA => {
+            let end = non_trivia_sibling(comma, Direction::Next)
+                .map_or(comma.range().end(), |it| it.range().start());
+            TextRange::from_to(node.range().start(), end)
+        }
+        _ => match non_trivia_sibling(node, Direction::Prev) {
+            Some(comma) if comma.kind() == COMMA => {
+                TextRange::from_to(comma.range().start(), node.range().end())
+            }
+            _ => node.range(),
+        },
+    }
+}
+
+/// Picks a name which none of the type parameters in scope has.
+fn fresh_type_param_name(fn_def: &ast::FnDef) -> String {
+    let taken = fn_def
+        .syntax()
+        .ancestors()
+        .filter_map(|it| it.children().find_map(ast::TypeParamList::cast))
+        .flat_map(|it| it.type_params())
+        .filter_map(|it| it.name())
+        .map(|it| it.text().to_string())
+        .collect::<Vec<_>>();
+    let candidates = ["T", "U", "V", "W"].iter().map(|it| it.to_string());
+    candidates.chain((1..).map(|idx| format!("T{}", idx))).find(|it| !taken.contains(it)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    #[test]
+    fn replace_impl_trait_with_generic_param() {
+        check_assist(
+            replace_impl_trait_with_generic,
+            "fn foo(x: impl<|> Clone + Send) {}",
+            "fn foo<T: Clone + Send>(x: <|>T) {}",
+        );
+        check_assist(
+            replace_impl_trait_with_generic,
+            "fn foo<'a>(x: &'a (impl Clone<|> + 'a)) where Self: Sized {}",
+            "fn foo<'a, T: Clone + 'a>(x: &'a <|>T) where Self: Sized {}",
+        );
+    }
+
+    #[test]
+    fn replace_multiple_impl_traits_with_distinct_generics() {
+        check_assist(
+            replace_impl_trait_with_generic,
+            "fn foo(a: impl Clone, b: impl <|>Copy) {}",
+            "fn foo<T: Copy>(a: impl Clone, b: <|>T) {}",
+        );
+        check_assist(
+            replace_impl_trait_with_generic,
+            "fn foo<T: Copy>(a: impl<|> Clone, b: T) {}",
+            "fn foo<T: Copy, U: Clone>(a: <|>U, b: T) {}",
+        );
+        check_assist(
+            replace_impl_trait_with_generic,
+            "impl<T> S<T> { fn foo(a: impl<|> Clone) {} }",
+            "impl<T> S<T> { fn foo<U: Clone>(a: <|>U) {} }",
+        );
+    }
+
+    #[test]
+    fn replace_impl_trait_not_applicable() {
+        check_assist_not_applicable(
+            replace_impl_trait_with_generic,
+            "fn foo() -> impl<|> Clone {}",
+        );
+        check_assist_not_applicable(
+            replace_impl_trait_with_generic,
+            "fn foo() { let f = |x: impl<|> Clone| (); }",
+        );
+    }
+
+    #[test]
+    fn replace_generic_with_impl_trait_param() {
+        check_assist(
+            replace_generic_with_impl_trait,
+            "fn foo<T<|>: Clone>(x: T) {}",
+            "fn foo(x: <|>impl Clone) {}",
+        );
+        check_assist(
+            replace_generic_with_impl_trait,
+            "fn foo<'a, <|>T: Clone, U>(x: &'a T, y: U) where T: Send, U: Copy {}",
+            "fn foo<'a, U>(x: &'a <|>(impl Clone + Send), y: U) where U: Copy {}",
+        );
+        check_assist(
+            replace_generic_with_impl_trait,
+            "fn foo<<|>T>(x: T) where T: Clone {}",
+            "fn foo(x: <|>impl Clone) {}",
+        );
+    }
+
+    #[test]
+    fn replace_generic_with_impl_trait_not_applicable() {
+        check_assist_not_applicable(
+            replace_generic_with_impl_trait,
+            "fn foo<T<|>: Clone>(x: T) -> T { x }",
+        );
+        check_assist_not_applicable(
+            replace_generic_with_impl_trait,
+            "fn foo<T<|>: Clone>(x: T, y: T) {}",
+        );
+        check_assist_not_applicable(
+            replace_generic_with_impl_trait,
+            "fn foo<T<|>: Default>(x: T) { T::default(); }",
+        );
+        check_assist_not_applicable(replace_generic_with_impl_trait, "fn foo<T<|>>(x: T) {}");
+    }
+}
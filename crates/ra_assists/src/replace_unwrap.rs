@@ -0,0 +1,289 @@
+use hir::{TypeRef, source_binder, db::HirDatabase};
+use ra_syntax::{
+    AstNode,
+    ast::{self, ArgListOwner},
+    SyntaxKind::{FN_DEF, LAMBDA_EXPR},
+};
+
+use crate::{
+    AssistCtx, Assist, AssistId,
+    utils::{enum_name, receiver_text},
+};
+
+pub(crate) fn replace_unwrap_with_try(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = UnwrapCall::at(&ctx)?;
+    // `?` inside a closure doesn't propagate out of the function.
+    let node = call.method_call.syntax();
+    if node.ancestors().take_while(|it| it.kind() != FN_DEF).any(|it| it.kind() == LAMBDA_EXPR) {
+        return None;
+    }
+    let function = source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, node)?;
+    let ret_type = match function.signature(ctx.db).ret_type() {
+        TypeRef::Path(path) => path.segments.last()?.name.to_string(),
+        _ => return None,
+    };
+
+    let receiver = receiver_text(call.receiver);
+    let replacement = match (call.kind, ret_type.as_str(), call.message) {
+        (UnwrapKind::Option, "Option", _) => format!("{}?", receiver),
+        (UnwrapKind::Result, "Result", None) => format!("{}?", receiver),
+        (UnwrapKind::Result, "Result", Some(msg)) => {
+            format!("{}.map_err(|_| {})?", receiver, msg.syntax().text())
+        }
+        (UnwrapKind::Option, "Result", Some(msg)) => {
+            format!("{}.ok_or({})?", receiver, msg.syntax().text())
+        }
+        _ => return None,
+    };
+
+    let range = node.range();
+    ctx.add_action(AssistId("replace_unwrap_with_try"), "replace unwrap with ?", |edit| {
+        edit.target(range);
+        edit.replace(range, replacement);
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+pub(crate) fn replace_unwrap_with_match(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = UnwrapCall::at(&ctx)?;
+    let (success, failure) = match call.kind {
+        UnwrapKind::Option => {
+            let panic = match call.message {
+                Some(msg) => format_panic(msg, None),
+                None => "panic!()".to_string(),
+            };
+            ("Some(value)", format!("None => {},", panic))
+        }
+        UnwrapKind::Result => {
+            let panic = match call.message {
+                Some(msg) => format_panic(msg, Some("err")),
+                None => "panic!(\"{:?}\", err)".to_string(),
+            };
+            ("Ok(value)", format!("Err(err) => {},", panic))
+        }
+    };
+    let match_expr = format!(
+        "match {} {{\n    {} => value,\n    {}\n}}",
+        call.receiver.syntax().text(),
+        success,
+        failure
+    );
+
+    let node = call.method_call.syntax();
+    ctx.add_action(AssistId("replace_unwrap_with_match"), "replace unwrap with match", |edit| {
+        edit.target(node.range());
+        edit.replace_node_and_indent(node, match_expr);
+        edit.set_cursor(node.range().start());
+    });
+
+    ctx.build()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnwrapKind {
+    Option,
+    Result,
+}
+
+/// A call of `unwrap` or `expect` on an `Option` or a `Result`.
+struct UnwrapCall<'a> {
+    method_call: &'a ast::MethodCallExpr,
+    receiver: &'a ast::Expr,
+    kind: UnwrapKind,
+    /// The argument of `expect`.
+    message: Option<&'a ast::Expr>,
+}
+
+impl<'a> UnwrapCall<'a> {
+    fn at(ctx: &AssistCtx<'a, impl HirDatabase>) -> Option<UnwrapCall<'a>> {
+        let method_call = ctx.node_at_offset::<ast::MethodCallExpr>()?;
+        let receiver = method_call.expr()?;
+        let mut args = method_call.arg_list()?.args();
+        let message = match method_call.name_ref()?.text().as_str() {
+            "unwrap" => None,
+            "expect" => Some(args.next()?),
+            _ => return None,
+        };
+        if args.next().is_some() {
+            return None;
+        }
+
+        let function = source_binder::function_from_child_node(
+            ctx.db,
+            ctx.frange.file_id,
+            method_call.syntax(),
+        )?;
+        let infer = function.infer(ctx.db);
+        let source_map = function.body_source_map(ctx.db);
+        let kind = match enum_name(ctx.db, &infer[source_map.node_expr(receiver)?])?.as_str() {
+            "Option" => UnwrapKind::Option,
+            "Result" => UnwrapKind::Result,
+            _ => return None,
+        };
+        Some(UnwrapCall { method_call, receiver, kind, message })
+    }
+}
+
+/// Builds a `panic!` with the message of `expect`, followed by the error, if
+/// any, like `expect` does.
+fn format_panic(msg: &ast::Expr, err: Option<&str>) -> String {
+    let text = msg.syntax().text().to_string();
+    // Plain string literals can be used as the format string directly.
+    let is_plain_literal = text.starts_with('"') && !text.contains('{') && !text.contains('}');
+    match err {
+        Some(err) if is_plain_literal => {
+            format!("panic!(\"{}: {{:?}}\", {})", &text[1..text.len() - 1], err)
+        }
+        Some(err) => format!("panic!(\"{{}}: {{:?}}\", {}, {})", text, err),
+        None if is_plain_literal => format!("panic!({})", text),
+        None => format!("panic!(\"{{}}\", {})", text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    const TYPES: &str = "
+enum Option<T> { Some(T), None }
+enum Result<T, E> { Ok(T), Err(E) }
+use Option::*;
+use Result::*;
+";
+
+    fn with_types(code: &str) -> String {
+        format!("{}{}", TYPES, code)
+    }
+
+    #[test]
+    fn replace_unwrap_with_try_in_option() {
+        check_assist(
+            replace_unwrap_with_try,
+            &with_types("fn f(x: Option<u32>) -> Option<u32> { let y = x.unw<|>rap(); Some(y) }"),
+            &with_types("fn f(x: Option<u32>) -> Option<u32> { let y = <|>x?; Some(y) }"),
+        );
+    }
+
+    #[test]
+    fn replace_unwrap_with_try_in_result() {
+        check_assist(
+            replace_unwrap_with_try,
+            &with_types(
+                "fn f(x: Result<u32, ()>) -> Result<u32, ()> { let y = x.unwrap<|>(); Ok(y) }",
+            ),
+            &with_types("fn f(x: Result<u32, ()>) -> Result<u32, ()> { let y = <|>x?; Ok(y) }"),
+        );
+    }
+
+    #[test]
+    fn replace_expect_with_try_preserves_message() {
+        check_assist(
+            replace_unwrap_with_try,
+            &with_types(
+                "fn f(x: Result<u32, ()>) -> Result<u32, &'static str> { x.exp<|>ect(\"no x\"); }",
+            ),
+            &with_types(
+                "fn f(x: Result<u32, ()>) -> Result<u32, &'static str> { <|>x.map_err(|_| \"no x\")?; }",
+            ),
+        );
+        check_assist(
+            replace_unwrap_with_try,
+            &with_types(
+                "fn f(x: Option<u32>) -> Result<u32, &'static str> { x.exp<|>ect(\"no x\"); }",
+            ),
+            &with_types(
+                "fn f(x: Option<u32>) -> Result<u32, &'static str> { <|>x.ok_or(\"no x\")?; }",
+            ),
+        );
+    }
+
+    #[test]
+    fn replace_unwrap_with_try_not_applicable() {
+        // The function doesn't return an `Option`.
+        check_assist_not_applicable(
+            replace_unwrap_with_try,
+            &with_types("fn f(x: Option<u32>) -> u32 { x.unwrap<|>() }"),
+        );
+        // The result's error would be lost.
+        check_assist_not_applicable(
+            replace_unwrap_with_try,
+            &with_types("fn f(x: Result<u32, ()>) -> Option<u32> { x.unwrap<|>() }"),
+        );
+        check_assist_not_applicable(
+            replace_unwrap_with_try,
+            &with_types("fn f(x: Option<u32>) -> Option<u32> { let g = || x.unwrap<|>(); }"),
+        );
+        check_assist_not_applicable(
+            replace_unwrap_with_try,
+            &with_types(
+                "struct S; impl S { fn unwrap(self) {} } fn f() -> Option<()> { S.unwrap<|>() }",
+            ),
+        );
+    }
+
+    #[test]
+    fn replace_unwrap_with_match_for_option() {
+        check_assist(
+            replace_unwrap_with_match,
+            &with_types(
+                "
+fn f(x: Option<u32>) {
+    let y = x.unwrap<|>();
+}",
+            ),
+            &with_types(
+                "
+fn f(x: Option<u32>) {
+    let y = <|>match x {
+        Some(value) => value,
+        None => panic!(),
+    };
+}",
+            ),
+        );
+    }
+
+    #[test]
+    fn replace_expect_with_match() {
+        check_assist(
+            replace_unwrap_with_match,
+            &with_types(
+                "
+fn f(x: Result<u32, ()>) {
+    let y = x.expect<|>(\"no x\");
+}",
+            ),
+            &with_types(
+                "
+fn f(x: Result<u32, ()>) {
+    let y = <|>match x {
+        Ok(value) => value,
+        Err(err) => panic!(\"no x: {:?}\", err),
+    };
+}",
+            ),
+        );
+        check_assist(
+            replace_unwrap_with_match,
+            &with_types("fn f(x: Option<u32>, msg: &str) { x.expect<|>(msg) }"),
+            &with_types(
+                "fn f(x: Option<u32>, msg: &str) { <|>match x {
+    Some(value) => value,
+    None => panic!(\"{}\", msg),
+} }",
+            ),
+        );
+    }
+
+    #[test]
+    fn replace_unwrap_with_match_target() {
+        check_assist_target(
+            replace_unwrap_with_match,
+            &with_types("fn f(x: Option<u32>) { let y = x.unwrap<|>() + 1; }"),
+            "x.unwrap()",
+        );
+    }
+}
@@ -0,0 +1,357 @@
+use hir::{TypeRef, source_binder, db::HirDatabase};
+use ra_syntax::{
+    AstNode,
+    ast::{self, NameOwner},
+    SyntaxKind::{FN_DEF, LAMBDA_EXPR},
+};
+
+use crate::{
+    AssistCtx, Assist, AssistId,
+    utils::{receiver_text, tuple_struct_pat},
+};
+
+pub(crate) fn replace_with_try(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let (node, shape) = match ctx.node_at_offset::<ast::MatchExpr>() {
+        Some(match_expr) => (match_expr.syntax(), match_shape(match_expr)?),
+        None => {
+            let if_expr = ctx.node_at_offset::<ast::IfExpr>()?;
+            (if_expr.syntax(), if_let_shape(if_expr)?)
+        }
+    };
+
+    // `return` inside a closure doesn't propagate out of the function.
+    if node.ancestors().take_while(|it| it.kind() != FN_DEF).any(|it| it.kind() == LAMBDA_EXPR) {
+        return None;
+    }
+    let function = source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, node)?;
+    let ret_type = match function.signature(ctx.db).ret_type() {
+        TypeRef::Path(path) => path.segments.last()?.name.to_string(),
+        _ => return None,
+    };
+    if ret_type != shape.kind.type_name() {
+        return None;
+    }
+
+    let replacement = shape.replacement()?;
+    ctx.add_action(AssistId("replace_with_try"), "replace with ?", |edit| {
+        edit.target(node.range());
+        edit.replace(node.range(), replacement);
+        edit.set_cursor(node.range().start());
+    });
+
+    ctx.build()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TryKind {
+    Option,
+    Result,
+}
+
+impl TryKind {
+    fn type_name(self) -> &'static str {
+        match self {
+            TryKind::Option => "Option",
+            TryKind::Result => "Result",
+        }
+    }
+}
+
+/// A `match` or `if let` which does what `?` does: unwraps the success value
+/// of `expr` into `binding`, and returns early otherwise.
+struct TryShape<'a> {
+    kind: TryKind,
+    expr: &'a ast::Expr,
+    binding: &'a ast::BindPat,
+    /// What the whole expression evaluates to if `expr` succeeds.
+    success: &'a ast::Expr,
+}
+
+impl<'a> TryShape<'a> {
+    fn replacement(&self) -> Option<String> {
+        let try_expr = format!("{}?", receiver_text(self.expr));
+
+        // Substitute the only use of the binding in the success expression.
+        let name = self.binding.name()?.text();
+        let mut uses = self
+            .success
+            .syntax()
+            .descendants()
+            .filter_map(ast::PathExpr::cast)
+            .filter(|it| it.syntax().text() == name.as_str());
+        let usage = uses.next()?;
+        if uses.next().is_some() {
+            return None;
+        }
+
+        let start = self.success.syntax().range().start();
+        let usage_range = usage.syntax().range();
+        let usage_start = (usage_range.start() - start).to_usize();
+        let usage_end = (usage_range.end() - start).to_usize();
+        let mut res = self.success.syntax().text().to_string();
+        res.replace_range(usage_start..usage_end, &try_expr);
+        Some(res)
+    }
+}
+
+fn match_shape(match_expr: &ast::MatchExpr) -> Option<TryShape<'_>> {
+    let expr = match_expr.expr()?;
+    let mut arms = match_expr.match_arm_list()?.arms();
+    let (first, second) = (arms.next()?, arms.next()?);
+    if arms.next().is_some() {
+        return None;
+    }
+
+    let mut success = None;
+    let mut failure = None;
+    for arm in [first, second].iter() {
+        if arm.guard().is_some() {
+            return None;
+        }
+        let mut pats = arm.pats();
+        let pat = pats.next()?;
+        if pats.next().is_some() {
+            return None;
+        }
+        let body = arm.expr()?;
+        if let Some((kind, binding)) = success_pat(pat) {
+            success = Some((kind, binding, body));
+        } else {
+            failure = Some(failure_kind(pat, body)?);
+        }
+    }
+
+    let (kind, binding, success) = success?;
+    if failure? != kind {
+        return None;
+    }
+    Some(TryShape { kind, expr, binding, success })
+}
+
+fn if_let_shape(if_expr: &ast::IfExpr) -> Option<TryShape<'_>> {
+    let cond = if_expr.condition()?;
+    let (kind, binding) = success_pat(cond.pat()?)?;
+    // The error value is not accessible in the `else` branch.
+    if kind != TryKind::Option {
+        return None;
+    }
+    let success = single_expr(if_expr.then_branch()?)?;
+    let else_block = match if_expr.else_branch()? {
+        ast::ElseBranchFlavor::Block(it) => it,
+        ast::ElseBranchFlavor::IfExpr(_) => return None,
+    };
+    let failure = single_expr(else_block)?;
+    if !is_return_of(failure, "None") {
+        return None;
+    }
+    Some(TryShape { kind, expr: cond.expr()?, binding, success })
+}
+
+/// Matches `Some(x)` and `Ok(x)`.
+fn success_pat(pat: &ast::Pat) -> Option<(TryKind, &ast::BindPat)> {
+    let (name, args) = tuple_struct_pat(pat)?;
+    let kind = match name.as_str() {
+        "Some" => TryKind::Option,
+        "Ok" => TryKind::Result,
+        _ => return None,
+    };
+    let binding = match args.kind() {
+        ast::PatKind::BindPat(it) if it.pat().is_none() && !it.is_ref() => it,
+        _ => return None,
+    };
+    Some((kind, binding))
+}
+
+/// Matches `None => return None` and `Err(e) => return Err(e)`, as well as
+/// `Err(e) => return Err(e.into())`.
+fn failure_kind(pat: &ast::Pat, body: &ast::Expr) -> Option<TryKind> {
+    let body = match body.kind() {
+        ast::ExprKind::BlockExpr(block) => single_expr(block.block()?)?,
+        _ => body,
+    };
+    if pat.syntax().text() == "None" {
+        return if is_return_of(body, "None") { Some(TryKind::Option) } else { None };
+    }
+
+    let (name, arg) = tuple_struct_pat(pat)?;
+    let err = match arg.kind() {
+        ast::PatKind::BindPat(it) if it.pat().is_none() => it.name()?.text().to_string(),
+        _ => return None,
+    };
+    if name != "Err" {
+        return None;
+    }
+    let returns_err = [format!("Err({})", err), format!("Err({}.into())", err)]
+        .iter()
+        .any(|it| is_return_of(body, it));
+    if returns_err {
+        Some(TryKind::Result)
+    } else {
+        None
+    }
+}
+
+fn is_return_of(expr: &ast::Expr, value: &str) -> bool {
+    match expr.kind() {
+        ast::ExprKind::ReturnExpr(ret) => {
+            ret.expr().map(|it| it.syntax().text() == value).unwrap_or(false)
+        }
+        _ => false,
+    }
+}
+
+/// Returns the only expression in a block, either as the tail expression, or
+/// as the only statement.
+fn single_expr(block: &ast::Block) -> Option<&ast::Expr> {
+    let mut stmts = block.statements();
+    let (first, second) = (stmts.next(), stmts.next());
+    match (first, second, block.expr()) {
+        (None, _, Some(expr)) => Some(expr),
+        (Some(stmt), None, None) => match stmt.kind() {
+            ast::StmtKind::ExprStmt(it) => it.expr(),
+            ast::StmtKind::LetStmt(_) => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn replace_match_on_result_with_try() {
+        check_assist(
+            replace_with_try,
+            r#"
+fn foo() -> Result<u32, String> {
+    let x = ma<|>tch bar() {
+        Ok(v) => v,
+        Err(e) => return Err(e),
+    };
+    Ok(x)
+}"#,
+            r#"
+fn foo() -> Result<u32, String> {
+    let x = <|>bar()?;
+    Ok(x)
+}"#,
+        );
+    }
+
+    #[test]
+    fn replace_match_on_option_with_try() {
+        check_assist(
+            replace_with_try,
+            r#"
+fn foo(x: Option<u32>) -> Option<u32> {
+    let y = <|>match x {
+        None => { return None; }
+        Some(it) => it + 1,
+    };
+    Some(y)
+}"#,
+            r#"
+fn foo(x: Option<u32>) -> Option<u32> {
+    let y = <|>x? + 1;
+    Some(y)
+}"#,
+        );
+    }
+
+    #[test]
+    fn replace_match_converting_error_with_try() {
+        check_assist(
+            replace_with_try,
+            r#"
+fn foo() -> io::Result<()> {
+    match a + <|>b {
+        Ok(v) => v.frobnicate(),
+        Err(err) => return Err(err.into()),
+    }
+}"#,
+            r#"
+fn foo() -> io::Result<()> {
+    <|>(a + b)?.frobnicate()
+}"#,
+        );
+    }
+
+    #[test]
+    fn replace_if_let_with_try() {
+        check_assist(
+            replace_with_try,
+            r#"
+fn foo(x: Option<u32>) -> Option<u32> {
+    <|>if let Some(v) = x { v } else { return None }
+}"#,
+            r#"
+fn foo(x: Option<u32>) -> Option<u32> {
+    <|>x?
+}"#,
+        );
+    }
+
+    #[test]
+    fn replace_with_try_not_applicable_for_wrong_return_type() {
+        check_assist_not_applicable(
+            replace_with_try,
+            r#"
+fn foo(x: Option<u32>) -> Result<u32, ()> {
+    <|>match x { Some(v) => v, None => return None }
+}"#,
+        );
+        check_assist_not_applicable(
+            replace_with_try,
+            r#"
+fn foo(x: Option<u32>) {
+    <|>match x { Some(v) => v, None => return None }
+}"#,
+        );
+        check_assist_not_applicable(
+            replace_with_try,
+            r#"
+fn foo(x: Option<u32>) -> Option<u32> {
+    let f = || <|>match x { Some(v) => v, None => return None };
+}"#,
+        );
+    }
+
+    #[test]
+    fn replace_with_try_not_applicable_for_other_shapes() {
+        check_assist_not_applicable(
+            replace_with_try,
+            r#"
+fn foo(x: Result<u32, ()>) -> Result<u32, ()> {
+    <|>match x { Ok(v) => v, Err(e) => return Err(()) }
+}"#,
+        );
+        check_assist_not_applicable(
+            replace_with_try,
+            r#"
+fn foo(x: Option<u32>) -> Option<u32> {
+    <|>match x { Some(v) => v + v, None => return None }
+}"#,
+        );
+        check_assist_not_applicable(
+            replace_with_try,
+            r#"
+fn foo(x: Option<u32>) -> Option<u32> {
+    <|>match x { Some(v) => v, None => 0 }
+}"#,
+        );
+    }
+
+    #[test]
+    fn replace_with_try_target() {
+        check_assist_target(
+            replace_with_try,
+            r#"
+fn foo(x: Option<u32>) -> Option<u32> {
+    <|>if let Some(v) = x { v } else { return None }
+}"#,
+            "if let Some(v) = x { v } else { return None }",
+        );
+    }
+}
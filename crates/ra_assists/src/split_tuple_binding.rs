@@ -0,0 +1,157 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    AstNode, Direction, TextRange,
+    ast::{self, NameOwner, TypeAscriptionOwner},
+    SyntaxKind::{DOTDOT, NAME_REF, WHITESPACE},
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn split_tuple_binding(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let let_stmt = ctx.node_at_offset::<ast::LetStmt>()?;
+    if let_stmt.ascribed_type().is_some() || !let_stmt.has_semi() {
+        return None;
+    }
+    let tuple_pat = match let_stmt.pat()?.kind() {
+        ast::PatKind::TuplePat(it) => it,
+        _ => return None,
+    };
+    if tuple_pat.syntax().children().any(|it| it.kind() == DOTDOT) {
+        return None;
+    }
+    let tuple_expr = match let_stmt.initializer()?.kind() {
+        ast::ExprKind::TupleExpr(it) => it,
+        _ => return None,
+    };
+    let pats = tuple_pat.args().map(simple_binding).collect::<Option<Vec<_>>>()?;
+    let exprs = tuple_expr.exprs().collect::<Vec<_>>();
+    if pats.len() != exprs.len() {
+        return None;
+    }
+    // The tuple is evaluated before any of the names is bound.
+    let names = pats.iter().filter_map(|it| it.name()).collect::<Vec<_>>();
+    if names.iter().any(|name| exprs.iter().any(|it| mentions(it, name.text()))) {
+        return None;
+    }
+
+    let separator = match leading_indent(let_stmt.syntax()) {
+        Some(indent) => format!("\n{}", indent),
+        None => " ".to_string(),
+    };
+    let lets = pats
+        .iter()
+        .zip(exprs.iter())
+        .map(|(pat, expr)| format!("let {} = {};", pat.syntax().text(), expr.syntax().text()))
+        .collect::<Vec<_>>();
+    ctx.add_action(AssistId("split_tuple_binding"), "split tuple binding", |edit| {
+        edit.target(let_stmt.syntax().range());
+        edit.replace(let_stmt.syntax().range(), lets.join(&separator));
+        edit.set_cursor(let_stmt.syntax().range().start());
+    });
+
+    ctx.build()
+}
+
+pub(crate) fn merge_tuple_binding(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let first = ctx.node_at_offset::<ast::LetStmt>()?;
+    let next = first.syntax().siblings(Direction::Next).skip(1).find(|it| it.kind() != WHITESPACE);
+    let second = ast::LetStmt::cast(next?)?;
+    let (first_pat, first_expr) = simple_let(first)?;
+    let (second_pat, second_expr) = simple_let(second)?;
+    if mentions(second_expr, first_pat.name()?.text()) {
+        return None;
+    }
+
+    let range = TextRange::from_to(first.syntax().range().start(), second.syntax().range().end());
+    ctx.add_action(AssistId("merge_tuple_binding"), "merge into tuple binding", |edit| {
+        edit.target(range);
+        edit.replace(
+            range,
+            format!(
+                "let ({}, {}) = ({}, {});",
+                first_pat.syntax().text(),
+                second_pat.syntax().text(),
+                first_expr.syntax().text(),
+                second_expr.syntax().text(),
+            ),
+        );
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+/// Matches `let name = expr;`, maybe with `mut` or `ref`.
+fn simple_let(let_stmt: &ast::LetStmt) -> Option<(&ast::BindPat, &ast::Expr)> {
+    if let_stmt.ascribed_type().is_some() || !let_stmt.has_semi() {
+        return None;
+    }
+    Some((simple_binding(let_stmt.pat()?)?, let_stmt.initializer()?))
+}
+
+fn simple_binding(pat: &ast::Pat) -> Option<&ast::BindPat> {
+    match pat.kind() {
+        ast::PatKind::BindPat(it) if it.pat().is_none() => Some(it),
+        _ => None,
+    }
+}
+
+fn mentions(expr: &ast::Expr, name: &str) -> bool {
+    expr.syntax().descendants().any(|it| it.kind() == NAME_REF && it.text() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    #[test]
+    fn split_tuple_binding_into_lets() {
+        check_assist(
+            split_tuple_binding,
+            "
+fn f() {
+    let (a, mut b)<|> = (1, foo(2));
+}",
+            "
+fn f() {
+    <|>let a = 1;
+    let mut b = foo(2);
+}",
+        );
+    }
+
+    #[test]
+    fn split_tuple_binding_not_applicable() {
+        check_assist_not_applicable(split_tuple_binding, "fn f() { let (a, b)<|> = pair; }");
+        check_assist_not_applicable(split_tuple_binding, "fn f() { let (a, b)<|> = (1, 2, 3); }");
+        check_assist_not_applicable(split_tuple_binding, "fn f() { let (a, b)<|> = (b, a); }");
+        check_assist_not_applicable(
+            split_tuple_binding,
+            "fn f() { let (a, (b, c))<|> = (1, (2, 3)); }",
+        );
+        check_assist_not_applicable(
+            split_tuple_binding,
+            "fn f() { let (a, b): (u8, u8)<|> = (1, 2); }",
+        );
+    }
+
+    #[test]
+    fn merge_lets_into_tuple_binding() {
+        check_assist(
+            merge_tuple_binding,
+            "
+fn f() {
+    let <|>a = 1;
+    let mut b = foo(2);
+}",
+            "
+fn f() {
+    <|>let (a, mut b) = (1, foo(2));
+}",
+        );
+        check_assist_not_applicable(merge_tuple_binding, "fn f() { let <|>a = 1; let b = a; }");
+        check_assist_not_applicable(merge_tuple_binding, "fn f() { let <|>a = 1; foo(); }");
+    }
+}
@@ -0,0 +1,231 @@
+use hir::{Ty, source_binder, db::HirDatabase};
+use ra_syntax::{
+    AstNode,
+    ast::{self, ArgListOwner, BinOp},
+    SyntaxKind::STRING,
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn string_concat_to_format(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let bin_expr = ctx.node_at_offset::<ast::BinExpr>()?;
+    if bin_expr.op() != Some(BinOp::Addition) {
+        return None;
+    }
+    let mut concat = bin_expr;
+    while let Some(parent) = concat.syntax().parent().and_then(ast::BinExpr::cast) {
+        if parent.op() != Some(BinOp::Addition) {
+            break;
+        }
+        concat = parent;
+    }
+
+    let mut operands = Vec::new();
+    collect_operands(concat, &mut operands)?;
+    if !is_string_like(&ctx, operands[0])? {
+        return None;
+    }
+
+    let mut template = String::new();
+    let mut args = Vec::new();
+    for &operand in operands.iter() {
+        let operand = strip_borrow(operand);
+        match string_literal_text(operand) {
+            Some(text) => template.push_str(&escape_braces(text)),
+            None => {
+                template.push_str("{}");
+                args.push(operand.syntax().text().to_string());
+            }
+        }
+    }
+
+    ctx.add_action(AssistId("string_concat_to_format"), "convert to format!", |edit| {
+        let mut buf = format!("format!(\"{}\"", template);
+        for arg in args {
+            buf.push_str(", ");
+            buf.push_str(&arg);
+        }
+        buf.push_str(")");
+        edit.target(concat.syntax().range());
+        edit.replace(concat.syntax().range(), buf);
+        edit.set_cursor(concat.syntax().range().start());
+    });
+
+    ctx.build()
+}
+
+/// Flattens the left-associative chain `((a + b) + c)` into `[a, b, c]`.
+fn collect_operands<'a>(expr: &'a ast::BinExpr, acc: &mut Vec<&'a ast::Expr>) -> Option<()> {
+    let (lhs, rhs) = expr.sub_exprs();
+    let (lhs, rhs) = (lhs?, rhs?);
+    match lhs.kind() {
+        ast::ExprKind::BinExpr(lhs) if lhs.op() == Some(BinOp::Addition) => {
+            collect_operands(lhs, acc)?
+        }
+        _ => acc.push(lhs),
+    }
+    acc.push(rhs);
+    Some(())
+}
+
+fn is_string_like(ctx: &AssistCtx<impl HirDatabase>, expr: &ast::Expr) -> Option<bool> {
+    if string_literal_text(expr).is_some() {
+        return Some(true);
+    }
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, expr.syntax())?;
+    let infer_result = function.infer(ctx.db);
+    let source_map = function.body_source_map(ctx.db);
+    let ty = infer_result[source_map.node_expr(expr)?].clone();
+    let res = match ty {
+        Ty::Str => true,
+        Ty::Ref(inner, _) => *inner == Ty::Str,
+        Ty::Adt { def_id, .. } => match def_id {
+            hir::AdtDef::Struct(s) => {
+                s.name(ctx.db).map(|it| it.to_string()) == Some("String".into())
+            }
+            hir::AdtDef::Enum(_) => false,
+        },
+        _ => false,
+    };
+    Some(res)
+}
+
+fn strip_borrow(expr: &ast::Expr) -> &ast::Expr {
+    match expr.kind() {
+        ast::ExprKind::RefExpr(ref_expr) if !ref_expr.is_mut() => ref_expr.expr().unwrap_or(expr),
+        _ => expr,
+    }
+}
+
+/// Returns the text of a plain string literal, possibly converted to a
+/// `String` via `.to_string()`, `.to_owned()` or `String::from`.
+fn string_literal_text(expr: &ast::Expr) -> Option<&str> {
+    let literal = match expr.kind() {
+        ast::ExprKind::Literal(it) => it,
+        ast::ExprKind::MethodCallExpr(call) => {
+            let name = call.name_ref()?.text();
+            if !(name == "to_string" || name == "to_owned" || name == "into") {
+                return None;
+            }
+            if call.arg_list()?.args().count() != 0 {
+                return None;
+            }
+            match call.expr()?.kind() {
+                ast::ExprKind::Literal(it) => it,
+                _ => return None,
+            }
+        }
+        ast::ExprKind::CallExpr(call) => {
+            let callee = call.expr()?.syntax().text();
+            if callee != "String::from" {
+                return None;
+            }
+            let mut args = call.arg_list()?.args();
+            let arg = args.next()?;
+            if args.next().is_some() {
+                return None;
+            }
+            match arg.kind() {
+                ast::ExprKind::Literal(it) => it,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    let token = literal.syntax().first_child()?;
+    if token.kind() != STRING {
+        return None;
+    }
+    let text = token.leaf_text()?.as_str();
+    Some(&text[1..text.len() - 1])
+}
+
+fn escape_braces(text: &str) -> String {
+    text.replace('{', "{{").replace('}', "}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn string_concat_to_format_three_parts() {
+        check_assist(
+            string_concat_to_format,
+            r#"
+fn main() {
+    let b = "b";
+    let s = "a".to_string() +<|> &b + "c";
+}"#,
+            r#"
+fn main() {
+    let b = "b";
+    let s = <|>format!("a{}c", b);
+}"#,
+        );
+    }
+
+    #[test]
+    fn string_concat_to_format_from_inner_operand() {
+        check_assist(
+            string_concat_to_format,
+            r#"
+fn main() {
+    let b = "b";
+    let s = String::from("{a}") + &b + <|>"c";
+}"#,
+            r#"
+fn main() {
+    let b = "b";
+    let s = <|>format!("{{a}}{}c", b);
+}"#,
+        );
+    }
+
+    #[test]
+    fn string_concat_to_format_with_typed_lhs() {
+        check_assist(
+            string_concat_to_format,
+            r#"
+struct String;
+fn main() {
+    let a: String = String;
+    let b = "b";
+    let s = a +<|> b;
+}"#,
+            r#"
+struct String;
+fn main() {
+    let a: String = String;
+    let b = "b";
+    let s = <|>format!("{}{}", a, b);
+}"#,
+        );
+    }
+
+    #[test]
+    fn string_concat_to_format_not_applicable_for_numbers() {
+        check_assist_not_applicable(
+            string_concat_to_format,
+            r#"
+fn main() {
+    let s = 1 +<|> 2;
+}"#,
+        );
+    }
+
+    #[test]
+    fn string_concat_to_format_target() {
+        check_assist_target(
+            string_concat_to_format,
+            r#"
+fn main() {
+    let b = "b";
+    let s = "a".to_string() +<|> &b + "c";
+}"#,
+            r#""a".to_string() + &b + "c""#,
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{AstNode, SyntaxNode, TextRange, TextUnit, ast, SyntaxKind::BLOCK};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn surround_with(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let selection = ctx.frange.range;
+    if selection.is_empty() {
+        return None;
+    }
+    let block = ctx.covering_node().ancestors().find(|it| it.kind() == BLOCK)?;
+    let stmts = block
+        .children()
+        .filter(|it| ast::Stmt::cast(it).is_some() || ast::Expr::cast(it).is_some())
+        .filter(|it| it.range().intersection(&selection).map_or(false, |it| !it.is_empty()))
+        .collect::<Vec<_>>();
+    // Only whole statements can be surrounded.
+    if stmts.is_empty() || stmts.iter().any(|it| !it.range().is_subrange(&selection)) {
+        return None;
+    }
+    let range = TextRange::from_to(stmts[0].range().start(), stmts.last()?.range().end());
+    let text = block.text().slice(range).to_string();
+
+    // The keyword to put before the block, and whether it is followed by a
+    // `true` placeholder for a condition.
+    let wraps = [
+        (AssistId("surround_with_block"), "surround with block", "", false),
+        (AssistId("surround_with_unsafe"), "surround with `unsafe`", "unsafe", false),
+        (AssistId("surround_with_if"), "surround with `if`", "if", true),
+        (AssistId("surround_with_loop"), "surround with `loop`", "loop", false),
+        (AssistId("surround_with_while"), "surround with `while`", "while", true),
+    ];
+    for &(id, label, keyword, has_condition) in wraps.iter() {
+        let prefix = match (keyword, has_condition) {
+            ("", _) => String::new(),
+            (_, false) => format!("{} ", keyword),
+            (_, true) => format!("{} true ", keyword),
+        };
+        ctx.add_action(id, label, |edit| {
+            edit.target(range);
+            edit.replace(range, format!("{}{}", prefix, wrap_in_block(stmts[0], &text)));
+            // Put the cursor on the `true` placeholder of the condition.
+            let cursor = if has_condition {
+                range.start() + TextUnit::of_str(keyword) + TextUnit::of_char(' ')
+            } else {
+                range.start()
+            };
+            edit.set_cursor(cursor);
+        });
+    }
+
+    ctx.build()
+}
+
+/// Wraps `text`, the statements starting with `first`, in braces, indenting
+/// them one more level.
+fn wrap_in_block(first: &SyntaxNode, text: &str) -> String {
+    let indent = match leading_indent(first) {
+        Some(it) => it,
+        None => return format!("{{ {} }}", text),
+    };
+    let mut buf = format!("{{\n{}    ", indent);
+    for (i, line) in text.lines().enumerate() {
+        if i > 0 {
+            buf.push('\n');
+            if !line.trim().is_empty() {
+                buf.push_str("    ");
+            }
+        }
+        buf.push_str(line);
+    }
+    buf.push_str(&format!("\n{}}}", indent));
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist_range, check_assist_range_nth_action, check_assist_range_not_applicable,
+        check_assist_range_target,
+    };
+
+    #[test]
+    fn surround_statements_with_block() {
+        check_assist_range(
+            surround_with,
+            "fn f() { <|>let x = 1; g(x);<|> }",
+            "fn f() { <|>{ let x = 1; g(x); } }",
+        );
+    }
+
+    #[test]
+    fn surround_statements_with_unsafe() {
+        check_assist_range_nth_action(
+            surround_with,
+            "
+fn f() {
+    let p = &0 as *const u32;
+    <|>let x = *p;
+    if x > 0 {
+        g(x);
+    }<|>
+    h();
+}",
+            "
+fn f() {
+    let p = &0 as *const u32;
+    <|>unsafe {
+        let x = *p;
+        if x > 0 {
+            g(x);
+        }
+    }
+    h();
+}",
+            1,
+        );
+    }
+
+    #[test]
+    fn surround_tail_expression_with_if() {
+        check_assist_range_nth_action(
+            surround_with,
+            "
+fn f() {
+    <|>g();
+    h()<|>
+}",
+            "
+fn f() {
+    if <|>true {
+        g();
+        h()
+    }
+}",
+            2,
+        );
+    }
+
+    #[test]
+    fn surround_with_not_applicable_to_partial_statements() {
+        check_assist_range_not_applicable(surround_with, "fn f() { let x = <|>1; g(x)<|>; }");
+        check_assist_range_not_applicable(surround_with, "fn f() { let x = 1 + <|>2<|>; }");
+    }
+
+    #[test]
+    fn surround_with_target() {
+        check_assist_range_target(surround_with, "fn f() { a(); <|> b(); c(); <|> }", "b(); c();");
+    }
+
+    #[test]
+    fn surround_statements_with_loop() {
+        check_assist_range_nth_action(
+            surround_with,
+            "
+fn f() {
+    <|>let x = next();
+    if x == 0 { break; }<|>
+}",
+            "
+fn f() {
+    <|>loop {
+        let x = next();
+        if x == 0 { break; }
+    }
+}",
+            3,
+        );
+    }
+
+    #[test]
+    fn surround_statements_with_while() {
+        check_assist_range_nth_action(
+            surround_with,
+            "fn f() { <|>g(); h();<|> }",
+            "fn f() { while <|>true { g(); h(); } }",
+            4,
+        );
+    }
+}
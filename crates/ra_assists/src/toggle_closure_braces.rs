@@ -0,0 +1,101 @@
+use hir::db::HirDatabase;
+use ra_fmt::extract_trivial_expression;
+use ra_syntax::{
+    AstNode,
+    ast,
+    SyntaxKind::RET_TYPE,
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn add_closure_braces(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let lambda = ctx.node_at_offset::<ast::LambdaExpr>()?;
+    let body = lambda.body()?;
+    if let ast::ExprKind::BlockExpr(_) = body.kind() {
+        return None;
+    }
+
+    ctx.add_action(AssistId("add_closure_braces"), "add braces to closure", |edit| {
+        edit.target(lambda.syntax().range());
+        edit.replace(body.syntax().range(), format!("{{ {} }}", body.syntax().text()));
+    });
+
+    ctx.build()
+}
+
+pub(crate) fn remove_closure_braces(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let lambda = ctx.node_at_offset::<ast::LambdaExpr>()?;
+    // The body of a closure with a return type has to be a block.
+    if lambda.syntax().children().any(|it| it.kind() == RET_TYPE) {
+        return None;
+    }
+    let body = lambda.body()?;
+    let block = match body.kind() {
+        ast::ExprKind::BlockExpr(it) => it.block()?,
+        _ => return None,
+    };
+    // Comments in the block would be lost.
+    if block.syntax().descendants().any(|it| ast::Comment::cast(it).is_some()) {
+        return None;
+    }
+    let expr = extract_trivial_expression(block)?;
+
+    ctx.add_action(AssistId("remove_closure_braces"), "remove braces from closure", |edit| {
+        edit.target(lambda.syntax().range());
+        edit.replace(body.syntax().range(), expr.syntax().text());
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    #[test]
+    fn add_braces_to_closure() {
+        check_assist(
+            add_closure_braces,
+            "fn f() { let g = move |x<|>: u32| x + 1; }",
+            "fn f() { let g = move |x<|>: u32| { x + 1 }; }",
+        );
+        check_assist_not_applicable(add_closure_braces, "fn f() { let g = |x<|>| { x }; }");
+    }
+
+    #[test]
+    fn remove_braces_from_closure() {
+        check_assist(
+            remove_closure_braces,
+            "fn f() { let g = move |<|>x, y| { x * y }; }",
+            "fn f() { let g = move |<|>x, y| x * y; }",
+        );
+        check_assist(
+            remove_closure_braces,
+            "
+fn f() {
+    let g = |<|>x| {
+        x.len()
+    };
+}",
+            "
+fn f() {
+    let g = |<|>x| x.len();
+}",
+        );
+    }
+
+    #[test]
+    fn remove_closure_braces_not_applicable() {
+        check_assist_not_applicable(remove_closure_braces, "fn f() { let g = |<|>x| { x; }; }");
+        check_assist_not_applicable(
+            remove_closure_braces,
+            "fn f() { let g = |<|>x| { let y = x; y }; }",
+        );
+        check_assist_not_applicable(
+            remove_closure_braces,
+            "fn f() { let g = |<|>x| -> u32 { x }; }",
+        );
+        check_assist_not_applicable(remove_closure_braces, "fn f() { let g = |<|>x| x; }");
+    }
+}
@@ -0,0 +1,103 @@
+use hir::db::HirDatabase;
+use ra_syntax::{AstNode, ast};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+pub(crate) fn use_field_shorthand(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let struct_lit = ctx.node_at_offset::<ast::StructLit>()?;
+    let fields = struct_lit
+        .named_field_list()?
+        .fields()
+        .filter(|field| {
+            let (name, expr) = match (field.name_ref(), field.expr()) {
+                (Some(name), Some(expr)) => (name, expr),
+                _ => return false,
+            };
+            let path = match expr.kind() {
+                ast::ExprKind::PathExpr(it) => it.path(),
+                _ => None,
+            };
+            path.filter(|it| it.qualifier().is_none())
+                .and_then(|it| it.segment())
+                .and_then(|it| it.name_ref())
+                .map_or(false, |it| it.text() == name.text())
+        })
+        .collect::<Vec<_>>();
+    if fields.is_empty() {
+        return None;
+    }
+
+    ctx.add_action(AssistId("use_field_shorthand"), "use field init shorthand", |edit| {
+        edit.target(struct_lit.syntax().range());
+        edit.set_cursor(struct_lit.syntax().range().start());
+        for field in fields {
+            if let Some(name) = field.name_ref() {
+                edit.replace(field.syntax().range(), name.text().to_string());
+            }
+        }
+    });
+
+    ctx.build()
+}
+
+pub(crate) fn expand_field_shorthand(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let struct_lit = ctx.node_at_offset::<ast::StructLit>()?;
+    let fields = struct_lit
+        .named_field_list()?
+        .fields()
+        .filter(|it| it.expr().is_none())
+        .filter_map(|it| it.name_ref())
+        .collect::<Vec<_>>();
+    if fields.is_empty() {
+        return None;
+    }
+
+    ctx.add_action(AssistId("expand_field_shorthand"), "expand field init shorthand", |edit| {
+        edit.target(struct_lit.syntax().range());
+        edit.set_cursor(struct_lit.syntax().range().start());
+        for name in fields {
+            edit.insert(name.syntax().range().end(), format!(": {}", name.text()));
+        }
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn use_field_shorthand_for_eligible_fields() {
+        check_assist(
+            use_field_shorthand,
+            "fn f(x: u32, y: u32) { Foo { x: x, y: y + 1, <|>z: self::z }; }",
+            "fn f(x: u32, y: u32) { <|>Foo { x, y: y + 1, z: self::z }; }",
+        );
+    }
+
+    #[test]
+    fn expand_field_shorthand_for_shorthand_fields() {
+        check_assist(
+            expand_field_shorthand,
+            "fn f(x: u32, y: u32) { Foo {<|> x, y: 1, z }; }",
+            "fn f(x: u32, y: u32) { <|>Foo { x: x, y: 1, z: z }; }",
+        );
+    }
+
+    #[test]
+    fn field_shorthand_not_applicable() {
+        check_assist_not_applicable(use_field_shorthand, "fn f() { Foo {<|> x: y, z }; }");
+        check_assist_not_applicable(expand_field_shorthand, "fn f() { Foo {<|> x: x }; }");
+    }
+
+    #[test]
+    fn use_field_shorthand_target() {
+        check_assist_target(
+            use_field_shorthand,
+            "fn f() { let s = Foo {<|> x: x }; }",
+            "Foo { x: x }",
+        );
+    }
+}
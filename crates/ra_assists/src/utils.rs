@@ -0,0 +1,434 @@
+//! Syntax helpers shared by several assists.
+
+use hir::{
+    AdtDef, CallableDef, Crate, Function, HirDisplay, ImplItem, Module, ModuleDef, Ty,
+    db::HirDatabase,
+};
+use join_to_string::join;
+use ra_db::FileId;
+use ra_syntax::{
+    AstNode, SyntaxNode, TextRange, TextUnit,
+    ast::{
+        self, ArgListOwner, AstToken, BinOp, NameOwner, PrefixOp, TypeAscriptionOwner,
+        TypeParamsOwner,
+    },
+    algo::non_trivia_sibling,
+    Direction,
+    SyntaxKind::{
+        EQ, FALSE_KW, ITEM_LIST, LIFETIME, MODULE, NAME, SOURCE_FILE, TRUE_KW, WHITESPACE,
+    },
+};
+
+/// Checks whether `expr.method()` would parse differently than intended.
+pub(crate) fn needs_parens_as_receiver(expr: &ast::Expr) -> bool {
+    match expr.kind() {
+        ast::ExprKind::PathExpr(_)
+        | ast::ExprKind::Literal(_)
+        | ast::ExprKind::ParenExpr(_)
+        | ast::ExprKind::TupleExpr(_)
+        | ast::ExprKind::ArrayExpr(_)
+        | ast::ExprKind::CallExpr(_)
+        | ast::ExprKind::MethodCallExpr(_)
+        | ast::ExprKind::FieldExpr(_)
+        | ast::ExprKind::IndexExpr(_)
+        | ast::ExprKind::TryExpr(_)
+        | ast::ExprKind::StructLit(_) => false,
+        _ => true,
+    }
+}
+
+/// The text of `expr`, parenthesized if it is to be followed by a method call,
+/// a field access or `?`.
+pub(crate) fn receiver_text(expr: &ast::Expr) -> String {
+    if needs_parens_as_receiver(expr) {
+        format!("({})", expr.syntax().text())
+    } else {
+        expr.syntax().text().to_string()
+    }
+}
+
+/// The name of the enum `ty` is an instance of, like `Option` or `Result`.
+pub(crate) fn enum_name(db: &impl HirDatabase, ty: &Ty) -> Option<String> {
+    match ty {
+        Ty::Adt { def_id: AdtDef::Enum(e), .. } => Some(e.name(db)?.to_string()),
+        _ => None,
+    }
+}
+
+/// Returns the name and the only argument of a tuple struct pattern.
+pub(crate) fn tuple_struct_pat(pat: &ast::Pat) -> Option<(String, &ast::Pat)> {
+    let pat = match pat.kind() {
+        ast::PatKind::TupleStructPat(it) => it,
+        _ => return None,
+    };
+    let mut args = pat.args();
+    let arg = args.next()?;
+    if args.next().is_some() {
+        return None;
+    }
+    Some((pat.path()?.syntax().text().to_string(), arg))
+}
+
+/// Whether `node` is an item of a module rather than of an impl, a trait or
+/// a block.
+pub(crate) fn is_module_item(node: &SyntaxNode) -> bool {
+    match node.parent() {
+        Some(parent) if parent.kind() == SOURCE_FILE => true,
+        Some(parent) if parent.kind() == ITEM_LIST => {
+            parent.parent().map(|it| it.kind() == MODULE).unwrap_or(false)
+        }
+        _ => false,
+    }
+}
+
+/// Picks a name based on `base` which isn't used for anything else in the file.
+pub(crate) fn fresh_name(file: &SyntaxNode, base: &str) -> String {
+    let used = file
+        .descendants()
+        .filter(|it| it.kind() == NAME)
+        .map(|it| it.text().to_string())
+        .collect::<Vec<_>>();
+    let mut name = base.to_string();
+    let mut idx = 1;
+    while used.contains(&name) {
+        idx += 1;
+        name = format!("{}{}", base, idx);
+    }
+    name
+}
+
+/// The indentation of the item `node`, used to line up new items before it.
+pub(crate) fn item_indent(node: &SyntaxNode) -> String {
+    match node.prev_sibling() {
+        Some(ws) if ws.kind() == WHITESPACE => {
+            let ws = ws.leaf_text().map(|it| it.as_str()).unwrap_or_default();
+            ws.rfind('\n').map(|idx| ws[idx + 1..].to_string()).unwrap_or_default()
+        }
+        _ => String::new(),
+    }
+}
+
+/// Moves the lines of `text` after the first from the `from` indentation to
+/// the `to` one.
+pub(crate) fn shift_indent(text: &str, from: &str, to: &str) -> String {
+    let mut lines = text.lines();
+    let mut res = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        res.push('\n');
+        if line.starts_with(from) {
+            res.push_str(to);
+            res.push_str(&line[from.len()..]);
+        } else {
+            res.push_str(line);
+        }
+    }
+    res
+}
+
+/// The name of a type parameter, or the lifetime of a lifetime parameter.
+pub(crate) fn param_name_node(param: &SyntaxNode) -> Option<&SyntaxNode> {
+    param.children().find(|it| it.kind() == NAME || it.kind() == LIFETIME)
+}
+
+/// Finds the bounds following `colon`, up to the default of a type parameter,
+/// if any.
+pub(crate) fn bounds_after(colon: &SyntaxNode) -> Option<TextRange> {
+    let first = non_trivia_sibling(colon, Direction::Next).filter(|it| it.kind() != EQ)?;
+    let mut last = first;
+    while let Some(next) = non_trivia_sibling(last, Direction::Next) {
+        if next.kind() == EQ {
+            break;
+        }
+        last = next;
+    }
+    Some(TextRange::from_to(first.range().start(), last.range().end()))
+}
+
+/// The range of the where clause together with the whitespace around it.
+pub(crate) fn where_clause_with_whitespace(where_clause: &SyntaxNode) -> TextRange {
+    let start = match where_clause.prev_sibling() {
+        Some(ws) if ws.kind() == WHITESPACE => ws.range().start(),
+        _ => where_clause.range().start(),
+    };
+    let end = match where_clause.next_sibling() {
+        Some(ws) if ws.kind() == WHITESPACE => ws.range().end(),
+        _ => where_clause.range().end(),
+    };
+    TextRange::from_to(start, end)
+}
+
+/// Collects the modules of `krate`, starting with its root.
+pub(crate) fn crate_modules(db: &impl HirDatabase, krate: Crate) -> Option<Vec<Module>> {
+    let mut res = vec![krate.root_module(db)?];
+    let mut idx = 0;
+    while idx < res.len() {
+        let children = res[idx].children(db);
+        res.extend(children);
+        idx += 1;
+    }
+    Some(res)
+}
+
+/// Collects the free functions and the methods of inherent and trait impls
+/// of `krate`, which are all the bodies calls can be in.
+pub(crate) fn crate_functions(db: &impl HirDatabase, krate: Crate) -> Option<Vec<Function>> {
+    let mut res = Vec::new();
+    for module in crate_modules(db, krate)? {
+        res.extend(module.declarations(db).into_iter().filter_map(|it| match it {
+            ModuleDef::Function(it) => Some(it),
+            _ => None,
+        }));
+        for impl_block in module.impl_blocks(db) {
+            res.extend(impl_block.items(db).into_iter().filter_map(|it| match it {
+                ImplItem::Method(it) => Some(it),
+                _ => None,
+            }));
+        }
+    }
+    Some(res)
+}
+
+/// Calls `f` with the arguments of each call of `function` in its crate,
+/// without the receiver of a method. Returns `None` if some call is not in
+/// `file_id`, or if `f` does.
+pub(crate) fn for_each_call(
+    db: &impl HirDatabase,
+    function: Function,
+    file_id: FileId,
+    mut f: impl FnMut(&[&ast::Expr]) -> Option<()>,
+) -> Option<()> {
+    let has_self_param = function.signature(db).has_self_param();
+    let krate = function.module(db).krate(db)?;
+    for caller in crate_functions(db, krate)? {
+        let (file, fn_def) = caller.source(db);
+        let infer = caller.infer(db);
+        let source_map = caller.body_source_map(db);
+        for node in fn_def.syntax().descendants() {
+            let (arg_list, self_offset) = if let Some(call) = ast::CallExpr::cast(node) {
+                let callee = match call.expr().and_then(|it| source_map.node_expr(it)) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                match &infer[callee] {
+                    Ty::FnDef { def: CallableDef::Function(it), .. } if *it == function => {}
+                    _ => continue,
+                }
+                // `Type::method(receiver, ..)` has the receiver among the arguments.
+                (call.arg_list()?, if has_self_param { 1 } else { 0 })
+            } else if let Some(call) = ast::MethodCallExpr::cast(node) {
+                let expr =
+                    match ast::Expr::cast(call.syntax()).and_then(|it| source_map.node_expr(it)) {
+                        Some(it) => it,
+                        None => continue,
+                    };
+                if infer.method_resolution(expr) != Some(function) {
+                    continue;
+                }
+                (call.arg_list()?, 0)
+            } else {
+                continue;
+            };
+            if file.original_file(db) != file_id {
+                return None;
+            }
+            let args = arg_list.args().skip(self_offset).collect::<Vec<_>>();
+            f(&args)?;
+        }
+    }
+    Some(())
+}
+
+/// Builds `impl<'a, T: Clone> Foo<'a, T>`, an inherent impl header for the
+/// type with its generic parameters.
+pub(crate) fn impl_header(nominal: &ast::NominalDef) -> Option<String> {
+    header(nominal, None)
+}
+
+/// Builds `impl<'a, T: Clone> Trait for Foo<'a, T>`.
+pub(crate) fn trait_impl_header(nominal: &ast::NominalDef, trait_path: &str) -> Option<String> {
+    header(nominal, Some(trait_path))
+}
+
+fn header(nominal: &ast::NominalDef, trait_path: Option<&str>) -> Option<String> {
+    let name = nominal.name()?;
+    let type_params = nominal.type_param_list();
+    let mut buf = String::new();
+    buf.push_str("impl");
+    if let Some(type_params) = type_params {
+        type_params.syntax().text().push_to(&mut buf);
+    }
+    buf.push_str(" ");
+    if let Some(trait_path) = trait_path {
+        buf.push_str(trait_path);
+        buf.push_str(" for ");
+    }
+    buf.push_str(name.text().as_str());
+    if let Some(type_params) = type_params {
+        let lifetime_params =
+            type_params.lifetime_params().filter_map(|it| it.lifetime()).map(|it| it.text());
+        let type_params = type_params.type_params().filter_map(|it| it.name()).map(|it| it.text());
+        join(lifetime_params.chain(type_params)).surround_with("<", ">").to_buf(&mut buf);
+    }
+    Some(buf)
+}
+
+/// Returns the source text of the logical negation of `expr`.
+///
+/// Double negations are removed, comparison operators are flipped, and `&&`
+/// and `||` are rewritten using De Morgan's laws, so that `!` is only added
+/// in front of otherwise opaque operands.
+pub(crate) fn negate(expr: &ast::Expr) -> String {
+    negate_with_prec(expr).0
+}
+
+/// The binding power of the top-level operator of an expression, used to
+/// decide where parentheses are necessary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Prec {
+    Or,
+    And,
+    Comparison,
+    Atom,
+}
+
+pub(crate) fn negate_with_prec(expr: &ast::Expr) -> (String, Prec) {
+    match expr.kind() {
+        ast::ExprKind::PrefixExpr(prefix) if prefix.op() == Some(PrefixOp::Not) => {
+            match prefix.expr() {
+                Some(inner) => match inner.kind() {
+                    ast::ExprKind::ParenExpr(paren) => match paren.expr() {
+                        Some(inner) => (inner.syntax().text().to_string(), prec_of(inner)),
+                        None => (inner.syntax().text().to_string(), Prec::Atom),
+                    },
+                    _ => (inner.syntax().text().to_string(), prec_of(inner)),
+                },
+                None => wrap_in_not(expr),
+            }
+        }
+        ast::ExprKind::ParenExpr(paren) => match paren.expr() {
+            Some(inner) => negate_with_prec(inner),
+            None => wrap_in_not(expr),
+        },
+        ast::ExprKind::BinExpr(bin) => {
+            let (lhs, rhs, op) = match (bin.lhs(), bin.rhs(), bin.op()) {
+                (Some(lhs), Some(rhs), Some(op)) => (lhs, rhs, op),
+                _ => return wrap_in_not(expr),
+            };
+            let (new_op, prec) = match op {
+                BinOp::BooleanAnd => ("||", Prec::Or),
+                BinOp::BooleanOr => ("&&", Prec::And),
+                _ => match flip_comparison(op) {
+                    Some(flipped) => {
+                        let text =
+                            format!("{} {} {}", lhs.syntax().text(), flipped, rhs.syntax().text());
+                        return (text, Prec::Comparison);
+                    }
+                    None => return wrap_in_not(expr),
+                },
+            };
+            let lhs = operand(negate_with_prec(lhs), prec);
+            let rhs = operand(negate_with_prec(rhs), prec);
+            (format!("{} {} {}", lhs, new_op, rhs), prec)
+        }
+        ast::ExprKind::Literal(literal) => {
+            match literal.syntax().first_child().map(SyntaxNode::kind) {
+                Some(TRUE_KW) => ("false".to_string(), Prec::Atom),
+                Some(FALSE_KW) => ("true".to_string(), Prec::Atom),
+                _ => wrap_in_not(expr),
+            }
+        }
+        _ => wrap_in_not(expr),
+    }
+}
+
+fn wrap_in_not(expr: &ast::Expr) -> (String, Prec) {
+    let text = if prec_of(expr) == Prec::Atom {
+        format!("!{}", expr.syntax().text())
+    } else {
+        format!("!({})", expr.syntax().text())
+    };
+    (text, Prec::Atom)
+}
+
+/// Mixing `&&` and `||` without parentheses is legal, but hard to read, so we
+/// parenthesize any operand whose operator differs from the enclosing one.
+fn operand((text, prec): (String, Prec), parent: Prec) -> String {
+    if prec < Prec::Comparison && prec != parent {
+        format!("({})", text)
+    } else {
+        text
+    }
+}
+
+fn prec_of(expr: &ast::Expr) -> Prec {
+    match expr.kind() {
+        ast::ExprKind::BinExpr(bin) => match bin.op() {
+            Some(BinOp::BooleanOr) => Prec::Or,
+            Some(BinOp::BooleanAnd) => Prec::And,
+            _ => Prec::Comparison,
+        },
+        ast::ExprKind::RangeExpr(_) | ast::ExprKind::CastExpr(_) => Prec::Comparison,
+        ast::ExprKind::LambdaExpr(_)
+        | ast::ExprKind::ReturnExpr(_)
+        | ast::ExprKind::BreakExpr(_) => Prec::Or,
+        _ => Prec::Atom,
+    }
+}
+
+fn flip_comparison(op: BinOp) -> Option<&'static str> {
+    let res = match op {
+        BinOp::EqualityTest => "!=",
+        BinOp::NegatedEqualityTest => "==",
+        BinOp::LesserTest => ">=",
+        BinOp::LesserEqualTest => ">",
+        BinOp::GreaterTest => "<=",
+        BinOp::GreaterEqualTest => "<",
+        _ => return None,
+    };
+    Some(res)
+}
+
+/// The pattern of a `let` or of a closure parameter without a type annotation.
+pub(crate) fn unannotated_pat(node: &SyntaxNode) -> Option<&ast::Pat> {
+    if let Some(let_stmt) = ast::LetStmt::cast(node) {
+        return if let_stmt.ascribed_type().is_none() { let_stmt.pat() } else { None };
+    }
+    let param = ast::Param::cast(node)?;
+    let closure =
+        param.syntax().parent().and_then(|it| it.parent()).and_then(ast::LambdaExpr::cast);
+    if closure.is_none() || param.ascribed_type().is_some() {
+        return None;
+    }
+    param.pat()
+}
+
+/// The annotation to insert after `pat` to spell out its inferred type, if
+/// the type can be written down.
+pub(crate) fn type_annotation(
+    db: &impl HirDatabase,
+    function: Function,
+    pat: &ast::Pat,
+) -> Option<(TextUnit, String)> {
+    let infer = function.infer(db);
+    let source_map = function.body_source_map(db);
+    let ty = &infer[source_map.node_pat(pat)?];
+    let ty_text = ty.display(db).to_string();
+    // `{integer}`, `{float}` and `{unknown}` can't be written down.
+    if !is_nameable(ty) || ty_text.contains('{') || *ty == Ty::unit() {
+        return None;
+    }
+    Some((pat.syntax().range().end(), format!(": {}", ty_text)))
+}
+
+/// Whether `ty` can be written in a type annotation: closures and function
+/// items can't, and arrays are displayed without their length.
+fn is_nameable(ty: &Ty) -> bool {
+    let mut res = true;
+    ty.walk(&mut |ty| match ty {
+        Ty::Array(_) | Ty::Never | Ty::Closure { .. } | Ty::FnDef { .. } | Ty::Opaque(_) => {
+            res = false
+        }
+        _ => (),
+    });
+    res
+}
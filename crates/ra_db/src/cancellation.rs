@@ -31,6 +31,17 @@ impl Canceled {
         // hook (that is, to avoid collecting and printing backtrace).
         std::panic::resume_unwind(Box::new(Canceled::new()))
     }
+
+    /// Runs `f`, turning a `Canceled` thrown by it into an error.
+    pub fn catch<F, T>(f: F) -> Result<T, Canceled>
+    where
+        F: FnOnce() -> T + std::panic::UnwindSafe,
+    {
+        std::panic::catch_unwind(f).map_err(|err| match err.downcast::<Canceled>() {
+            Ok(canceled) => *canceled,
+            Err(payload) => std::panic::resume_unwind(payload),
+        })
+    }
 }
 
 impl std::fmt::Display for Canceled {
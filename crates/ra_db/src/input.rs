@@ -76,11 +76,12 @@ struct CrateData {
     file_id: FileId,
     edition: Edition,
     dependencies: Vec<Dependency>,
+    features: Vec<SmolStr>,
 }
 
 impl CrateData {
     fn new(file_id: FileId, edition: Edition) -> CrateData {
-        CrateData { file_id, edition, dependencies: Vec::new() }
+        CrateData { file_id, edition, dependencies: Vec::new(), features: Vec::new() }
     }
 
     fn add_dep(&mut self, name: SmolStr, crate_id: CrateId) {
@@ -124,6 +125,10 @@ impl CrateGraph {
         self.arena.is_empty()
     }
 
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
     pub fn crate_root(&self, crate_id: CrateId) -> FileId {
         self.arena[&crate_id].file_id
     }
@@ -132,6 +137,16 @@ impl CrateGraph {
         self.arena[&crate_id].edition
     }
 
+    /// Sets the features declared by the package of the crate, which can be
+    /// tested with `cfg(feature = "...")`.
+    pub fn set_features(&mut self, crate_id: CrateId, features: Vec<SmolStr>) {
+        self.arena.get_mut(&crate_id).unwrap().features = features;
+    }
+
+    pub fn features(&self, crate_id: CrateId) -> &[SmolStr] {
+        &self.arena[&crate_id].features
+    }
+
     // TODO: this only finds one crate with the given root; we could have multiple
     pub fn crate_id_for_crate_root(&self, file_id: FileId) -> Option<CrateId> {
         let (&crate_id, _) = self.arena.iter().find(|(_crate_id, data)| data.file_id == file_id)?;
@@ -145,6 +160,23 @@ impl CrateGraph {
         self.arena[&crate_id].dependencies.iter()
     }
 
+    /// The crates which depend on `crate_id`, each with the name it gives to
+    /// `crate_id`.
+    pub fn dependents(&self, crate_id: CrateId) -> Vec<Dependency> {
+        let mut res = self
+            .arena
+            .iter()
+            .flat_map(|(&dependent, data)| {
+                data.dependencies
+                    .iter()
+                    .filter(|dep| dep.crate_id == crate_id)
+                    .map(move |dep| Dependency { crate_id: dependent, name: dep.name.clone() })
+            })
+            .collect::<Vec<_>>();
+        res.sort_by_key(|dep| dep.crate_id);
+        res
+    }
+
     /// Extends this crate graph by adding a complete disjoint second crate
     /// graph.
     pub fn extend(&mut self, other: CrateGraph) {
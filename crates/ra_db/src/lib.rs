@@ -40,10 +40,7 @@ pub trait CheckCanceled: panic::RefUnwindSafe {
         Self: Sized,
         F: FnOnce(&Self) -> T + panic::UnwindSafe,
     {
-        panic::catch_unwind(|| f(self)).map_err(|err| match err.downcast::<Canceled>() {
-            Ok(canceled) => *canceled,
-            Err(payload) => panic::resume_unwind(payload),
-        })
+        Canceled::catch(|| f(self))
     }
 }
 
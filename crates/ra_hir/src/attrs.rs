@@ -0,0 +1,84 @@
+use ra_syntax::{
+    AstNode, SmolStr,
+    ast::{self, AttrsOwner},
+    SyntaxKind::{IDENT, STRING},
+};
+
+use crate::{HirDatabase, PersistentHirDatabase, Crate, ModuleSource};
+
+/// Attributes which affect how an item is presented to the user, like
+/// `#[doc(hidden)]` and `#[unstable]`, or how it is laid out, like `#[repr]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Attrs {
+    doc_hidden: bool,
+    unstable_feature: Option<SmolStr>,
+    repr: Vec<SmolStr>,
+}
+
+impl Attrs {
+    /// Whether the item is marked with `#[doc(hidden)]`.
+    pub fn is_doc_hidden(&self) -> bool {
+        self.doc_hidden
+    }
+
+    /// The feature gate of an `#[unstable(feature = "...")]` item.
+    pub fn unstable_feature(&self) -> Option<&SmolStr> {
+        self.unstable_feature.as_ref()
+    }
+
+    /// The hints of `#[repr(...)]` attributes, like `C` or `packed`.
+    pub fn repr(&self) -> &[SmolStr] {
+        &self.repr
+    }
+}
+
+pub trait HasAttrs {
+    fn attrs(&self, db: &impl HirDatabase) -> Attrs;
+}
+
+pub(crate) fn attrs_from_ast(node: &impl AttrsOwner) -> Attrs {
+    let mut res = Attrs::default();
+    for (name, tt) in node.attrs().filter_map(ast::Attr::as_call) {
+        match name.as_str() {
+            "doc" => res.doc_hidden |= idents(tt).any(|it| it == "hidden"),
+            "unstable" => res.unstable_feature = unstable_feature(tt),
+            "repr" => res.repr.extend(idents(tt).cloned()),
+            _ => (),
+        }
+    }
+    res
+}
+
+/// Features enabled by `#![feature(...)]` attributes in the crate root.
+pub(crate) fn crate_features(krate: Crate, db: &impl PersistentHirDatabase) -> Vec<SmolStr> {
+    let root = match krate.root_module(db) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let source_file = match root.definition_source(db).1 {
+        ModuleSource::SourceFile(it) => it,
+        ModuleSource::Module(_) => return Vec::new(),
+    };
+    source_file
+        .syntax()
+        .children()
+        .filter_map(ast::Attr::cast)
+        .filter(|attr| attr.is_inner())
+        .filter_map(ast::Attr::as_call)
+        .filter(|(name, _)| name == "feature")
+        .flat_map(|(_, tt)| idents(tt).cloned().collect::<Vec<_>>())
+        .collect()
+}
+
+fn idents(tt: &ast::TokenTree) -> impl Iterator<Item = &SmolStr> {
+    tt.syntax().children().filter(|it| it.kind() == IDENT).filter_map(|it| it.leaf_text())
+}
+
+/// Extracts `foo` from `(feature = "foo", issue = "92")`.
+fn unstable_feature(tt: &ast::TokenTree) -> Option<SmolStr> {
+    let mut tokens = tt.syntax().children();
+    tokens.find(|it| it.kind() == IDENT && it.leaf_text().map(|t| t == "feature") == Some(true))?;
+    let string = tokens.find(|it| it.kind() == STRING)?;
+    let text = string.leaf_text()?;
+    Some(text.trim_matches('"').into())
+}
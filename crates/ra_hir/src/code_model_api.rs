@@ -2,10 +2,10 @@ use std::sync::Arc;
 
 use relative_path::RelativePathBuf;
 use ra_db::{CrateId, SourceRootId, Edition};
-use ra_syntax::{ast::self, TreeArc, SyntaxNode};
+use ra_syntax::{ast::{self, NameOwner, VisibilityOwner}, TreeArc, SyntaxNode, SmolStr};
 
 use crate::{
-    Name, ScopesWithSourceMap, Ty, HirFileId,
+    Name, AsName, ScopesWithSourceMap, Ty, HirFileId,
     HirDatabase, PersistentHirDatabase,
     type_ref::TypeRef,
     nameres::{ModuleScope, Namespace, lower::ImportId},
@@ -14,8 +14,11 @@ use crate::{
     adt::{EnumVariantId, StructFieldId, VariantDef},
     generics::GenericParams,
     docs::{Documentation, Docs, docs_from_ast},
+    attrs::{Attrs, HasAttrs, attrs_from_ast, crate_features},
     module_tree::ModuleId,
-    ids::{FunctionId, StructId, EnumId, AstItemDef, ConstId, StaticId, TraitId, TypeId},
+    ids::{
+        FunctionId, StructId, EnumId, AstItemDef, ConstId, StaticId, TraitId, TypeId, LocationCtx,
+    },
     impl_block::ImplBlock,
     resolve::Resolver,
 };
@@ -52,6 +55,11 @@ impl Crate {
         crate_graph.edition(self.crate_id)
     }
 
+    /// Features enabled with `#![feature(...)]` in the crate root.
+    pub fn features(&self, db: &impl PersistentHirDatabase) -> Vec<SmolStr> {
+        crate_features(*self, db)
+    }
+
     // TODO: should this be in source_binder?
     pub fn source_root_crates(
         db: &impl PersistentHirDatabase,
@@ -171,6 +179,19 @@ impl Module {
         res
     }
 
+    /// Checks whether an item of this module with the visibility `vis` can be
+    /// named in `from`. Name resolution ignores visibility, so this only looks
+    /// at the visibility of the item itself, and not at the modules on the way
+    /// to it, which might be re-exporting it.
+    pub fn is_item_visible_from(
+        &self,
+        db: &impl HirDatabase,
+        vis: Option<&ast::Visibility>,
+        from: Module,
+    ) -> bool {
+        self.is_item_visible_from_impl(db, vis, from)
+    }
+
     /// Returns a `ModuleScope`: a set of items, visible in this module.
     pub fn scope(&self, db: &impl HirDatabase) -> ModuleScope {
         db.item_map(self.krate)[self.module_id].clone()
@@ -213,6 +234,28 @@ impl Docs for Module {
     }
 }
 
+impl HasAttrs for ModuleDef {
+    fn attrs(&self, db: &impl HirDatabase) -> Attrs {
+        match self {
+            ModuleDef::Module(it) => it.attrs(db),
+            ModuleDef::Function(it) => it.attrs(db),
+            ModuleDef::Struct(it) => it.attrs(db),
+            ModuleDef::Enum(it) => it.attrs(db),
+            ModuleDef::EnumVariant(it) => it.attrs(db),
+            ModuleDef::Const(it) => it.attrs(db),
+            ModuleDef::Static(it) => it.attrs(db),
+            ModuleDef::Trait(it) => it.attrs(db),
+            ModuleDef::TypeAlias(it) => it.attrs(db),
+        }
+    }
+}
+
+impl HasAttrs for Module {
+    fn attrs(&self, db: &impl HirDatabase) -> Attrs {
+        self.declaration_source(db).map(|it| attrs_from_ast(&*it.1)).unwrap_or_default()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct StructField {
     pub(crate) parent: VariantDef,
@@ -252,6 +295,15 @@ impl Docs for StructField {
     }
 }
 
+impl HasAttrs for StructField {
+    fn attrs(&self, db: &impl HirDatabase) -> Attrs {
+        match self.source(db).1 {
+            FieldSource::Named(named) => attrs_from_ast(&*named),
+            FieldSource::Pos(pos) => attrs_from_ast(&*pos),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Struct {
     pub(crate) id: StructId,
@@ -270,6 +322,12 @@ impl Struct {
         db.struct_data(*self).name.clone()
     }
 
+    /// Checks whether the struct can be named in `module`.
+    pub fn is_visible_from(&self, db: &impl HirDatabase, module: Module) -> bool {
+        let (_, source) = self.source(db);
+        self.module(db).is_item_visible_from(db, source.visibility(), module)
+    }
+
     pub fn fields(&self, db: &impl HirDatabase) -> Vec<StructField> {
         db.struct_data(*self)
             .variant_data
@@ -320,6 +378,12 @@ impl Docs for Struct {
     }
 }
 
+impl HasAttrs for Struct {
+    fn attrs(&self, db: &impl HirDatabase) -> Attrs {
+        attrs_from_ast(&*self.source(db).1)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Enum {
     pub(crate) id: EnumId,
@@ -380,6 +444,12 @@ impl Docs for Enum {
     }
 }
 
+impl HasAttrs for Enum {
+    fn attrs(&self, db: &impl HirDatabase) -> Attrs {
+        attrs_from_ast(&*self.source(db).1)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct EnumVariant {
     pub(crate) parent: Enum,
@@ -429,6 +499,12 @@ impl Docs for EnumVariant {
     }
 }
 
+impl HasAttrs for EnumVariant {
+    fn attrs(&self, db: &impl HirDatabase) -> Attrs {
+        attrs_from_ast(&*self.source(db).1)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Function {
     pub(crate) id: FunctionId,
@@ -508,6 +584,36 @@ impl Function {
         db.generic_params((*self).into())
     }
 
+    /// Returns the type parameters of the function itself, not counting those
+    /// of the surrounding impl, together with whether they can likely be
+    /// inferred from the arguments of a call. Parameters which only appear in
+    /// the return type, like `B` in `fn collect<B>(self) -> B`, often have to
+    /// be given explicitly.
+    pub fn type_params_inferable_from_args(&self, db: &impl HirDatabase) -> Vec<(Name, bool)> {
+        let sig = self.signature(db);
+        self.generic_params(db)
+            .params
+            .iter()
+            .map(|param| {
+                let inferable = sig.params().iter().any(|it| it.mentions_type_param(&param.name));
+                (param.name.clone(), inferable)
+            })
+            .collect()
+    }
+
+    /// Checks whether `pat`, a pattern in the body of this function, can fail
+    /// to match, like `Some(x)` or `1`. Returns `None` if we can't tell, for
+    /// example because the type of the pattern is unknown.
+    pub fn is_refutable_pat(&self, db: &impl HirDatabase, pat: &ast::Pat) -> Option<bool> {
+        self.is_refutable_pat_impl(db, pat)
+    }
+
+    /// Checks whether the function can be named in `module`.
+    pub fn is_visible_from(&self, db: &impl HirDatabase, module: Module) -> bool {
+        let (_, source) = self.source(db);
+        self.module(db).is_item_visible_from(db, source.visibility(), module)
+    }
+
     /// The containing impl block, if this is a method.
     pub fn impl_block(&self, db: &impl PersistentHirDatabase) -> Option<ImplBlock> {
         let module_impls = db.impls_in_module(self.module(db));
@@ -535,6 +641,12 @@ impl Docs for Function {
     }
 }
 
+impl HasAttrs for Function {
+    fn attrs(&self, db: &impl HirDatabase) -> Attrs {
+        attrs_from_ast(&*self.source(db).1)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Const {
     pub(crate) id: ConstId,
@@ -577,6 +689,12 @@ impl Docs for Const {
     }
 }
 
+impl HasAttrs for Const {
+    fn attrs(&self, db: &impl HirDatabase) -> Attrs {
+        attrs_from_ast(&*self.source(db).1)
+    }
+}
+
 /// The declared signature of a const.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ConstSignature {
@@ -625,6 +743,12 @@ impl Docs for Static {
     }
 }
 
+impl HasAttrs for Static {
+    fn attrs(&self, db: &impl HirDatabase) -> Attrs {
+        attrs_from_ast(&*self.source(db).1)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Trait {
     pub(crate) id: TraitId,
@@ -635,6 +759,10 @@ impl Trait {
         self.id.source(db)
     }
 
+    pub fn name(&self, db: &impl PersistentHirDatabase) -> Option<Name> {
+        self.source(db).1.name().map(|it| it.as_name())
+    }
+
     pub fn module(&self, db: &impl PersistentHirDatabase) -> Module {
         self.id.module(db)
     }
@@ -642,7 +770,33 @@ impl Trait {
     pub fn generic_params(&self, db: &impl PersistentHirDatabase) -> Arc<GenericParams> {
         db.generic_params((*self).into())
     }
+
+    /// The associated functions, consts and types declared in the trait.
+    pub fn items(&self, db: &impl PersistentHirDatabase) -> Vec<TraitItem> {
+        let (file_id, trait_def) = self.source(db);
+        let ctx = LocationCtx::new(db, self.module(db), file_id);
+        let item_list = match trait_def.item_list() {
+            Some(it) => it,
+            None => return Vec::new(),
+        };
+        item_list
+            .impl_items()
+            .map(|item| match item.kind() {
+                ast::ImplItemKind::FnDef(it) => Function { id: ctx.to_def(it) }.into(),
+                ast::ImplItemKind::ConstDef(it) => Const { id: ctx.to_def(it) }.into(),
+                ast::ImplItemKind::TypeAliasDef(it) => TypeAlias { id: ctx.to_def(it) }.into(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraitItem {
+    Function(Function),
+    Const(Const),
+    TypeAlias(TypeAlias),
 }
+impl_froms!(TraitItem: Function, Const, TypeAlias);
 
 impl Docs for Trait {
     fn docs(&self, db: &impl HirDatabase) -> Option<Documentation> {
@@ -650,6 +804,12 @@ impl Docs for Trait {
     }
 }
 
+impl HasAttrs for Trait {
+    fn attrs(&self, db: &impl HirDatabase) -> Attrs {
+        attrs_from_ast(&*self.source(db).1)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TypeAlias {
     pub(crate) id: TypeId,
@@ -700,3 +860,9 @@ impl Docs for TypeAlias {
         docs_from_ast(&*self.source(db).1)
     }
 }
+
+impl HasAttrs for TypeAlias {
+    fn attrs(&self, db: &impl HirDatabase) -> Attrs {
+        attrs_from_ast(&*self.source(db).1)
+    }
+}
@@ -3,9 +3,11 @@ use std::sync::Arc;
 use ra_syntax::ast::{self, NameOwner, TypeAscriptionOwner};
 
 use crate::{
-    Name, AsName, Function, FnSignature,
+    Name, AsName, Function, FnSignature, Ty, AdtDef,
     type_ref::{TypeRef, Mutability},
-    PersistentHirDatabase,
+    expr::{Body, Pat, PatId},
+    ty::InferenceResult,
+    HirDatabase, PersistentHirDatabase,
 };
 
 impl FnSignature {
@@ -51,3 +53,48 @@ impl FnSignature {
         Arc::new(sig)
     }
 }
+
+impl Function {
+    pub(crate) fn is_refutable_pat_impl(
+        &self,
+        db: &impl HirDatabase,
+        pat: &ast::Pat,
+    ) -> Option<bool> {
+        let pat = self.body_source_map(db).node_pat(pat)?;
+        is_refutable(db, &self.body(db), &self.infer(db), pat)
+    }
+}
+
+fn is_refutable(
+    db: &impl HirDatabase,
+    body: &Body,
+    infer: &InferenceResult,
+    pat: PatId,
+) -> Option<bool> {
+    let refutable = match &body[pat] {
+        Pat::Missing => return None,
+        Pat::Lit(_) | Pat::Range { .. } => true,
+        Pat::Wild | Pat::Bind { .. } | Pat::Tuple(_) | Pat::Ref { .. } => false,
+        Pat::Path(_) | Pat::TupleStruct { .. } | Pat::Struct { .. } => match &infer[pat] {
+            Ty::Adt { def_id: AdtDef::Enum(e), .. } => e.variants(db).len() != 1,
+            Ty::Adt { def_id: AdtDef::Struct(_), .. } => false,
+            _ => return None,
+        },
+        Pat::Slice { prefix, rest, suffix } => match &infer[pat] {
+            // Only `[..]` matches slices of any length.
+            Ty::Slice(_) => !prefix.is_empty() || !suffix.is_empty() || rest.is_none(),
+            Ty::Array(_) => false,
+            _ => return None,
+        },
+    };
+    if refutable {
+        return Some(true);
+    }
+    let mut res = Some(false);
+    body[pat].walk_child_pats(|child| match is_refutable(db, body, infer, child) {
+        Some(true) => res = Some(true),
+        None if res == Some(false) => res = None,
+        _ => (),
+    });
+    res
+}
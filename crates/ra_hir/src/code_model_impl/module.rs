@@ -1,4 +1,4 @@
-use ra_syntax::{ast, SyntaxNode, TreeArc};
+use ra_syntax::{AstNode, ast, SyntaxNode, TreeArc};
 
 use crate::{
     Module, ModuleSource, Problem,
@@ -10,6 +10,31 @@ use crate::{
 };
 
 impl Module {
+    pub(crate) fn is_item_visible_from_impl(
+        &self,
+        db: &impl HirDatabase,
+        vis: Option<&ast::Visibility>,
+        from: Module,
+    ) -> bool {
+        let vis = vis.map(|it| {
+            it.syntax()
+                .text()
+                .to_string()
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect::<String>()
+        });
+        let scope = match vis.as_ref().map(String::as_str) {
+            None | Some("pub(self)") => *self,
+            Some("pub(super)") => self.parent(db).unwrap_or(*self),
+            Some("pub(crate)") | Some("crate") => return from.krate == self.krate,
+            // `pub`, and `pub(in path)`, which we don't resolve.
+            _ => return true,
+        };
+        // Private items are visible in the descendants of their module.
+        from.krate == scope.krate && from.path_to_root(db).contains(&scope)
+    }
+
     fn with_module_id(&self, module_id: ModuleId) -> Module {
         Module { module_id, krate: self.krate }
     }
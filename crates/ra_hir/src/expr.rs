@@ -661,7 +661,10 @@ impl ExprCollector {
             }
             ast::ExprKind::FieldExpr(e) => {
                 let expr = self.collect_expr_opt(e.expr());
-                let name = e.name_ref().map(|nr| nr.as_name()).unwrap_or_else(Name::missing);
+                let name = match e.index() {
+                    Some(index) => Name::tuple_field_name(index),
+                    None => e.name_ref().map(|nr| nr.as_name()).unwrap_or_else(Name::missing),
+                };
                 self.alloc_expr(Expr::Field { expr, name }, syntax_ptr)
             }
             ast::ExprKind::TryExpr(e) => {
@@ -36,6 +36,7 @@ mod impl_block;
 mod expr;
 mod generics;
 mod docs;
+mod attrs;
 mod resolve;
 
 mod code_model_api;
@@ -52,13 +53,15 @@ use crate::{
 
 pub use self::{
     path::{Path, PathKind},
+    type_ref::{TypeRef, Mutability},
     name::Name,
     ids::{HirFileId, MacroCallId, MacroCallLoc, HirInterner},
     macros::{MacroDef, MacroInput, MacroExpansion},
     nameres::{ItemMap, PerNs, Namespace},
-    ty::{Ty, Substs, display::HirDisplay},
+    ty::{Ty, Substs, FnSig, ClosureKind, TraitBound, CallableDef, Layout, display::HirDisplay},
     impl_block::{ImplBlock, ImplItem},
     docs::{Docs, Documentation},
+    attrs::{Attrs, HasAttrs},
     adt::AdtDef,
     expr::{ExprScopes, ScopesWithSourceMap, ScopeEntryWithSyntax},
     resolve::{Resolver, Resolution},
@@ -71,5 +74,5 @@ pub use self::code_model_api::{
     Function, FnSignature,
     StructField, FieldSource,
     Static, Const, ConstSignature,
-    Trait, TypeAlias,
+    Trait, TraitItem, TypeAlias,
 };
@@ -17,12 +17,12 @@ pub struct PathSegment {
 }
 
 /// Generic arguments to a path segment (e.g. the `i32` in `Option<i32>`). This
-/// can (in the future) also include bindings of associated types, like in
-/// `Iterator<Item = Foo>`.
+/// also includes bindings of associated types, like in `Iterator<Item = Foo>`.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct GenericArgs {
     pub args: Vec<GenericArg>,
-    // someday also bindings
+    /// The associated type bindings, like `Item = Foo`.
+    pub bindings: Vec<(Name, TypeRef)>,
 }
 
 /// A single generic argument.
@@ -135,9 +135,16 @@ impl GenericArgs {
             let type_ref = TypeRef::from_ast_opt(type_arg.type_ref());
             args.push(GenericArg::Type(type_ref));
         }
-        // lifetimes and assoc type args ignored for now
-        if args.len() > 0 {
-            Some(GenericArgs { args })
+        let mut bindings = Vec::new();
+        for assoc_type_arg in node.assoc_type_args() {
+            if let Some(name_ref) = assoc_type_arg.name_ref() {
+                let type_ref = TypeRef::from_ast_opt(assoc_type_arg.type_ref());
+                bindings.push((name_ref.as_name(), type_ref));
+            }
+        }
+        // lifetimes ignored for now
+        if !args.is_empty() || !bindings.is_empty() {
+            Some(GenericArgs { args, bindings })
         } else {
             None
         }
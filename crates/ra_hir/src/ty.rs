@@ -9,14 +9,17 @@ pub(crate) mod method_resolution;
 mod op;
 mod lower;
 mod infer;
+mod layout;
 pub(crate) mod display;
 
 use std::sync::Arc;
-use std::{fmt, mem};
+use std::{fmt, mem, ops::Deref};
 
-use crate::{Name, AdtDef, type_ref::Mutability, db::HirDatabase};
+use crate::{Name, AdtDef, Function, Trait, type_ref::Mutability, expr::ExprId, db::HirDatabase};
 
-pub(crate) use lower::{TypableDef, CallableDef, type_for_def, type_for_field, callable_item_sig};
+pub use lower::CallableDef;
+pub use layout::Layout;
+pub(crate) use lower::{TypableDef, type_for_def, type_for_field, callable_item_sig};
 pub(crate) use infer::{infer, InferenceResult, InferTy};
 use display::{HirDisplay, HirFormatter};
 
@@ -91,9 +94,28 @@ pub enum Ty {
     /// ```
     FnPtr(FnSig),
 
+    /// The anonymous type of a closure. Closure types can't be named, so this
+    /// is written as `impl Fn(u32) -> u32`.
+    Closure {
+        /// The function containing the closure.
+        def: Function,
+        /// The closure expression in the body of `def`.
+        expr: ExprId,
+        /// The most general `Fn` trait the closure implements.
+        kind: ClosureKind,
+        sig: FnSig,
+    },
+
     /// The never type `!`.
     Never,
 
+    /// A trait object, like `dyn Iterator<Item = u32>`.
+    Dyn(Arc<[TraitBound]>),
+
+    /// An opaque type implementing the given traits, like the return type of
+    /// `fn f() -> impl Iterator<Item = u32>`.
+    Opaque(Arc<[TraitBound]>),
+
     /// A tuple type.  For example, `(i32, bool)`.
     Tuple(Arc<[Ty]>),
 
@@ -118,6 +140,78 @@ pub enum Ty {
     Unknown,
 }
 
+/// Which of the `Fn` traits a closure implements, depending on how it uses the
+/// variables it captures. Every `Fn` closure is also `FnMut`, and every `FnMut`
+/// closure is also `FnOnce`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClosureKind {
+    /// Only reads the captured variables.
+    Fn,
+    /// Mutates the captured variables.
+    FnMut,
+    /// Moves out of the captured variables.
+    FnOnce,
+}
+
+impl fmt::Display for ClosureKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ClosureKind::Fn => "Fn",
+            ClosureKind::FnMut => "FnMut",
+            ClosureKind::FnOnce => "FnOnce",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A trait bound of a `dyn Trait` or `impl Trait` type, like
+/// `Iterator<Item = u32>`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TraitBound {
+    pub trait_: Trait,
+    /// The generic arguments of the trait.
+    pub substs: Substs,
+    /// The bindings of associated types, like `Item = u32`.
+    pub assoc_types: Arc<[(Name, Ty)]>,
+}
+
+impl TraitBound {
+    fn walk(&self, f: &mut impl FnMut(&Ty)) {
+        for t in self.substs.0.iter().chain(self.assoc_types.iter().map(|(_, t)| t)) {
+            t.walk(f);
+        }
+    }
+
+    fn walk_mut(&mut self, f: &mut impl FnMut(&mut Ty)) {
+        self.substs.walk_mut(f);
+        // Without an Arc::make_mut_slice, we can't avoid the clone here:
+        let mut v: Vec<_> = self.assoc_types.iter().cloned().collect();
+        for (_, t) in &mut v {
+            t.walk_mut(f);
+        }
+        self.assoc_types = v.into();
+    }
+}
+
+impl HirDisplay for TraitBound {
+    fn hir_fmt(&self, f: &mut HirFormatter<impl HirDatabase>) -> fmt::Result {
+        let name = self.trait_.name(f.db).unwrap_or_else(Name::missing);
+        write!(f, "{}", name)?;
+        if self.substs.0.is_empty() && self.assoc_types.is_empty() {
+            return Ok(());
+        }
+        write!(f, "<")?;
+        f.write_joined(&*self.substs.0, ", ")?;
+        for (idx, (name, ty)) in self.assoc_types.iter().enumerate() {
+            if idx > 0 || !self.substs.0.is_empty() {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} = {}", name, ty.display(f.db))?;
+        }
+        write!(f, ">")
+    }
+}
+
 /// A list of substitutions for generic parameters.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Substs(Arc<[Ty]>);
@@ -137,6 +231,14 @@ impl Substs {
     }
 }
 
+impl Deref for Substs {
+    type Target = [Ty];
+
+    fn deref(&self) -> &[Ty] {
+        &self.0
+    }
+}
+
 /// A function signature.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct FnSig {
@@ -181,7 +283,7 @@ impl Ty {
                     t.walk(f);
                 }
             }
-            Ty::FnPtr(sig) => {
+            Ty::FnPtr(sig) | Ty::Closure { sig, .. } => {
                 for input in sig.params() {
                     input.walk(f);
                 }
@@ -197,6 +299,11 @@ impl Ty {
                     t.walk(f);
                 }
             }
+            Ty::Dyn(bounds) | Ty::Opaque(bounds) => {
+                for bound in bounds.iter() {
+                    bound.walk(f);
+                }
+            }
             Ty::Bool
             | Ty::Char
             | Ty::Int(_)
@@ -223,7 +330,7 @@ impl Ty {
                 }
                 *ts = v.into();
             }
-            Ty::FnPtr(sig) => {
+            Ty::FnPtr(sig) | Ty::Closure { sig, .. } => {
                 sig.walk_mut(f);
             }
             Ty::FnDef { substs, .. } => {
@@ -232,6 +339,14 @@ impl Ty {
             Ty::Adt { substs, .. } => {
                 substs.walk_mut(f);
             }
+            Ty::Dyn(bounds) | Ty::Opaque(bounds) => {
+                // Without an Arc::make_mut_slice, we can't avoid the clone here:
+                let mut v: Vec<_> = bounds.iter().cloned().collect();
+                for bound in &mut v {
+                    bound.walk_mut(f);
+                }
+                *bounds = v.into();
+            }
             Ty::Bool
             | Ty::Char
             | Ty::Int(_)
@@ -253,6 +368,85 @@ impl Ty {
         self
     }
 
+    /// Whether the type is `Copy`. Without trait solving, this is only an
+    /// approximation: ADTs and type parameters are assumed not to be `Copy`,
+    /// while types we don't know anything about are assumed to be.
+    pub fn is_copy(&self) -> bool {
+        match self {
+            Ty::Bool
+            | Ty::Char
+            | Ty::Int(_)
+            | Ty::Float(_)
+            | Ty::Never
+            | Ty::RawPtr(..)
+            | Ty::Ref(_, Mutability::Shared)
+            | Ty::FnDef { .. }
+            | Ty::FnPtr(_)
+            | Ty::Infer(_)
+            | Ty::Unknown => true,
+            Ty::Array(t) => t.is_copy(),
+            Ty::Tuple(ts) => ts.iter().all(Ty::is_copy),
+            Ty::Adt { .. }
+            | Ty::Str
+            | Ty::Slice(_)
+            | Ty::Ref(_, Mutability::Mut)
+            | Ty::Closure { .. }
+            | Ty::Dyn(_)
+            | Ty::Opaque(_)
+            | Ty::Param { .. } => false,
+        }
+    }
+
+    /// Returns the signature of this type if it's a function or a closure,
+    /// with the generic parameters substituted.
+    pub fn callable_sig(&self, db: &impl HirDatabase) -> Option<FnSig> {
+        match self {
+            Ty::FnDef { def, substs } => {
+                let sig = db.callable_item_signature(*def);
+                let params = sig.params().iter().map(|it| it.clone().subst(substs)).collect();
+                Some(FnSig::from_params_and_return(params, sig.ret().clone().subst(substs)))
+            }
+            Ty::FnPtr(sig) | Ty::Closure { sig, .. } => Some(sig.clone()),
+            _ => None,
+        }
+    }
+
+    /// Checks whether the two types could be the same. Unknown types and type
+    /// parameters nested in them are treated as wildcards, so `Option<T>`
+    /// could be `Option<u32>`, but an unknown type itself matches nothing.
+    pub fn could_unify_with(&self, other: &Ty) -> bool {
+        fn nested(a: &Ty, b: &Ty) -> bool {
+            match (a, b) {
+                (Ty::Unknown, _) | (_, Ty::Unknown) => true,
+                (Ty::Infer(_), _) | (_, Ty::Infer(_)) => true,
+                (Ty::Param { .. }, _) | (_, Ty::Param { .. }) => true,
+                _ => a.could_unify_with(b),
+            }
+        }
+        match (self, other) {
+            (Ty::Adt { def_id: a, substs: a_substs }, Ty::Adt { def_id: b, substs: b_substs }) => {
+                a == b && a_substs.0.iter().zip(b_substs.0.iter()).all(|(a, b)| nested(a, b))
+            }
+            (Ty::Ref(a, a_mut), Ty::Ref(b, b_mut)) => a_mut == b_mut && nested(a, b),
+            (Ty::Slice(a), Ty::Slice(b)) | (Ty::Array(a), Ty::Array(b)) => nested(a, b),
+            (Ty::Tuple(a), Ty::Tuple(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| nested(a, b))
+            }
+            (Ty::Int(a), Ty::Int(b)) => {
+                a == b
+                    || *a == primitive::UncertainIntTy::Unknown
+                    || *b == primitive::UncertainIntTy::Unknown
+            }
+            (Ty::Float(a), Ty::Float(b)) => {
+                a == b
+                    || *a == primitive::UncertainFloatTy::Unknown
+                    || *b == primitive::UncertainFloatTy::Unknown
+            }
+            (Ty::Unknown, _) | (_, Ty::Unknown) => false,
+            _ => self == other,
+        }
+    }
+
     fn builtin_deref(&self) -> Option<Ty> {
         match self {
             Ty::Ref(t, _) => Some(Ty::clone(t)),
@@ -299,12 +493,6 @@ impl Ty {
     }
 }
 
-impl HirDisplay for &Ty {
-    fn hir_fmt(&self, f: &mut HirFormatter<impl HirDatabase>) -> fmt::Result {
-        HirDisplay::hir_fmt(*self, f)
-    }
-}
-
 impl HirDisplay for Ty {
     fn hir_fmt(&self, f: &mut HirFormatter<impl HirDatabase>) -> fmt::Result {
         match self {
@@ -337,6 +525,14 @@ impl HirDisplay for Ty {
                 f.write_joined(sig.params(), ", ")?;
                 write!(f, ") -> {}", sig.ret().display(f.db))?;
             }
+            Ty::Closure { kind, sig, .. } => {
+                write!(f, "impl {}(", kind)?;
+                f.write_joined(sig.params(), ", ")?;
+                write!(f, ")")?;
+                if *sig.ret() != Ty::unit() {
+                    write!(f, " -> {}", sig.ret().display(f.db))?;
+                }
+            }
             Ty::FnDef { def, substs, .. } => {
                 let sig = f.db.callable_item_signature(*def);
                 let name = match def {
@@ -370,6 +566,14 @@ impl HirDisplay for Ty {
                     write!(f, ">")?;
                 }
             }
+            Ty::Dyn(bounds) => {
+                write!(f, "dyn ")?;
+                f.write_joined(&**bounds, " + ")?;
+            }
+            Ty::Opaque(bounds) => {
+                write!(f, "impl ")?;
+                f.write_joined(&**bounds, " + ")?;
+            }
             Ty::Param { name, .. } => write!(f, "{}", name)?,
             Ty::Unknown => write!(f, "{{unknown}}")?,
             Ty::Infer(..) => write!(f, "_")?,
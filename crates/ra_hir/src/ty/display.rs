@@ -17,6 +17,12 @@ pub trait HirDisplay {
     }
 }
 
+impl<T: HirDisplay> HirDisplay for &T {
+    fn hir_fmt(&self, f: &mut HirFormatter<impl HirDatabase>) -> fmt::Result {
+        HirDisplay::hir_fmt(*self, f)
+    }
+}
+
 impl<'a, 'b, DB> HirFormatter<'a, 'b, DB>
 where
     DB: HirDatabase,
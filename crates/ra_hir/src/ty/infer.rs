@@ -38,7 +38,7 @@ use crate::{
     resolve::{Resolver, Resolution},
     nameres::Namespace
 };
-use super::{Ty, TypableDef, Substs, primitive, op};
+use super::{Ty, TypableDef, Substs, FnSig, ClosureKind, primitive, op};
 
 /// The entry point of type inference.
 pub fn infer(db: &impl HirDatabase, func: Function) -> Arc<InferenceResult> {
@@ -234,6 +234,14 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
             }
             (Ty::Ref(t1, m1), Ty::Ref(t2, m2)) if m1 == m2 => self.unify_inner(t1, t2, depth + 1),
             (Ty::FnPtr(sig1), Ty::FnPtr(sig2)) if sig1 == sig2 => true,
+            (
+                Ty::Closure { def: def1, expr: expr1, sig: sig1, .. },
+                Ty::Closure { def: def2, expr: expr2, sig: sig2, .. },
+            ) if def1 == def2 && expr1 == expr2 => {
+                let tys1 = sig1.params().iter().chain(Some(sig1.ret()));
+                let tys2 = sig2.params().iter().chain(Some(sig2.ret()));
+                tys1.zip(tys2).all(|(t1, t2)| self.unify_inner(t1, t2, depth + 1))
+            }
             (Ty::Tuple(ts1), Ty::Tuple(ts2)) if ts1.len() == ts2.len() => {
                 ts1.iter().zip(ts2.iter()).all(|(t1, t2)| self.unify_inner(t1, t2, depth + 1))
             }
@@ -707,24 +715,27 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
             Expr::Lambda { body, args, arg_types } => {
                 assert_eq!(args.len(), arg_types.len());
 
+                let mut param_tys = Vec::with_capacity(args.len());
                 for (arg_pat, arg_type) in args.iter().zip(arg_types.iter()) {
                     let expected = if let Some(type_ref) = arg_type {
-                        let ty = self.make_ty(type_ref);
-                        ty
+                        self.make_ty(type_ref)
                     } else {
-                        Ty::Unknown
+                        self.new_type_var()
                     };
-                    self.infer_pat(*arg_pat, &expected);
+                    param_tys.push(self.infer_pat(*arg_pat, &expected));
                 }
 
-                // TODO: infer lambda type etc.
-                let _body_ty = self.infer_expr(*body, &Expectation::none());
-                Ty::Unknown
+                let ret_ty = self.infer_expr(*body, &Expectation::none());
+                let kind = self.infer_closure_kind(tgt_expr, *body);
+                let sig = FnSig::from_params_and_return(param_tys, ret_ty);
+                Ty::Closure { def: self.body.owner(), expr: tgt_expr, kind, sig }
             }
             Expr::Call { callee, args } => {
                 let callee_ty = self.infer_expr(*callee, &Expectation::none());
                 let (param_tys, ret_ty) = match &callee_ty {
-                    Ty::FnPtr(sig) => (sig.params().to_vec(), sig.ret().clone()),
+                    Ty::FnPtr(sig) | Ty::Closure { sig, .. } => {
+                        (sig.params().to_vec(), sig.ret().clone())
+                    }
                     Ty::FnDef { substs, def, .. } => {
                         let sig = self.db.callable_item_signature(*def);
                         let ret_ty = sig.ret().clone().subst(&substs);
@@ -1028,6 +1039,109 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         self.return_ty = self.make_ty(signature.ret_type());
     }
 
+    /// Determines which `Fn` trait the closure `lambda` implements, by looking
+    /// at how the variables captured from the enclosing function are used in
+    /// its body.
+    ///
+    /// Whether moving a captured variable moves out of the closure depends on
+    /// the variable's type being `Copy`, which we approximate without trait
+    /// solving.
+    fn infer_closure_kind(&mut self, lambda: ExprId, lambda_body: ExprId) -> ClosureKind {
+        let body = Arc::clone(&self.body);
+        let outer_resolver = expr::resolver_for_expr(Arc::clone(&body), self.db, lambda);
+        let captured = |ctx: &mut Self, expr: ExprId| -> Option<PatId> {
+            let path = match &body[expr] {
+                Expr::Path(path) => path,
+                _ => return None,
+            };
+            let inner_resolver = expr::resolver_for_expr(Arc::clone(&body), ctx.db, expr);
+            // A binding from outside the closure resolves to the same pattern
+            // on both sides of the closure's boundary.
+            match inner_resolver.resolve_path(ctx.db, path).take_values()? {
+                Resolution::LocalBinding(pat)
+                    if outer_resolver.resolve_path(ctx.db, path).take_values()
+                        == Some(Resolution::LocalBinding(pat)) =>
+                {
+                    Some(pat)
+                }
+                _ => None,
+            }
+        };
+        // The variable a place expression like `x.field` or `*x` belongs to.
+        let place_root = |ctx: &mut Self, mut expr: ExprId| -> Option<PatId> {
+            loop {
+                match &body[expr] {
+                    Expr::Field { expr: inner, .. }
+                    | Expr::UnaryOp { expr: inner, op: UnaryOp::Deref } => expr = *inner,
+                    _ => return captured(ctx, expr),
+                }
+            }
+        };
+
+        let mut kind = ClosureKind::Fn;
+        let mut moved = vec![lambda_body];
+        let mut stack = vec![lambda_body];
+        while let Some(expr) = stack.pop() {
+            match &body[expr] {
+                Expr::BinaryOp { lhs, op: Some(op), .. } if op::is_assignment(*op) => {
+                    if place_root(self, *lhs).is_some() {
+                        kind = kind.max(ClosureKind::FnMut);
+                    }
+                }
+                Expr::Ref { expr: inner, mutability: Mutability::Mut } => {
+                    if place_root(self, *inner).is_some() {
+                        kind = kind.max(ClosureKind::FnMut);
+                    }
+                }
+                Expr::MethodCall { receiver, args, .. } => {
+                    let self_param = self
+                        .method_resolutions
+                        .get(&expr)
+                        .map(|func| func.signature(self.db))
+                        .filter(|sig| sig.has_self_param())
+                        .map(|sig| sig.params()[0].clone());
+                    match self_param {
+                        Some(TypeRef::Reference(_, Mutability::Mut)) => {
+                            if place_root(self, *receiver).is_some() {
+                                kind = kind.max(ClosureKind::FnMut);
+                            }
+                        }
+                        Some(TypeRef::Reference(..)) | None => {}
+                        Some(_) => moved.push(*receiver),
+                    }
+                    moved.extend(args);
+                }
+                Expr::Call { args, .. }
+                | Expr::Tuple { exprs: args }
+                | Expr::Array { exprs: args } => moved.extend(args),
+                Expr::Return { expr: Some(inner) } => moved.push(*inner),
+                Expr::Block { statements, tail } => {
+                    for stmt in statements {
+                        if let Statement::Let { initializer: Some(init), .. } = stmt {
+                            moved.push(*init);
+                        }
+                    }
+                    if moved.contains(&expr) {
+                        moved.extend(tail);
+                    }
+                }
+                Expr::StructLit { fields, .. } => moved.extend(fields.iter().map(|it| it.expr)),
+                _ => {}
+            }
+            body[expr].walk_child_exprs(|it| stack.push(it));
+        }
+
+        for expr in moved {
+            if let Some(pat) = captured(self, expr) {
+                let ty = self.type_of_pat.get(pat).cloned().unwrap_or(Ty::Unknown);
+                if !self.resolve_ty_as_possible(&mut vec![], ty).is_copy() {
+                    kind = kind.max(ClosureKind::FnOnce);
+                }
+            }
+        }
+        kind
+    }
+
     fn infer_body(&mut self) {
         self.infer_expr(self.body.body_expr(), &Expectation::has_type(self.return_ty.clone()));
     }
@@ -0,0 +1,106 @@
+//! Approximates the layout rustc picks for a type, for the IDE to show. We
+//! assume a 64-bit target and model only the layout of primitives, pointers,
+//! tuples and structs.
+
+use std::cmp::Reverse;
+
+use crate::{AdtDef, HasAttrs, db::HirDatabase};
+use super::{Ty, primitive::{FloatTy, IntTy, UintTy, UncertainFloatTy, UncertainIntTy}};
+
+const POINTER_SIZE: u64 = 8;
+
+/// The size and alignment of a type, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    pub size: u64,
+    pub align: u64,
+}
+
+impl Layout {
+    fn scalar(size: u64) -> Layout {
+        Layout { size, align: size }
+    }
+
+    fn zero_sized() -> Layout {
+        Layout { size: 0, align: 1 }
+    }
+}
+
+impl Ty {
+    /// The layout of the type, if it doesn't depend on unknown or generic
+    /// types, or on optimizations which we don't model, like those of enums.
+    pub fn layout(&self, db: &impl HirDatabase) -> Option<Layout> {
+        let layout = match self {
+            Ty::Bool => Layout::scalar(1),
+            Ty::Char => Layout::scalar(4),
+            Ty::Int(UncertainIntTy::Signed(it)) => Layout::scalar(match it {
+                IntTy::Isize => POINTER_SIZE,
+                IntTy::I8 => 1,
+                IntTy::I16 => 2,
+                IntTy::I32 => 4,
+                IntTy::I64 => 8,
+                IntTy::I128 => 16,
+            }),
+            Ty::Int(UncertainIntTy::Unsigned(it)) => Layout::scalar(match it {
+                UintTy::Usize => POINTER_SIZE,
+                UintTy::U8 => 1,
+                UintTy::U16 => 2,
+                UintTy::U32 => 4,
+                UintTy::U64 => 8,
+                UintTy::U128 => 16,
+            }),
+            Ty::Float(UncertainFloatTy::Known(it)) => Layout::scalar(match it {
+                FloatTy::F32 => 4,
+                FloatTy::F64 => 8,
+            }),
+            Ty::Ref(pointee, _) | Ty::RawPtr(pointee, _) => match &**pointee {
+                // Pointers to unsized types carry a length or a vtable.
+                Ty::Str | Ty::Slice(_) | Ty::Dyn(_) => {
+                    Layout { size: 2 * POINTER_SIZE, align: POINTER_SIZE }
+                }
+                Ty::Param { .. } | Ty::Infer(_) | Ty::Unknown => return None,
+                _ => Layout::scalar(POINTER_SIZE),
+            },
+            Ty::FnPtr(_) => Layout::scalar(POINTER_SIZE),
+            Ty::FnDef { .. } | Ty::Never => Layout::zero_sized(),
+            Ty::Tuple(tys) => {
+                let fields = tys.iter().map(|it| it.layout(db)).collect::<Option<Vec<_>>>()?;
+                struct_layout(fields, true)
+            }
+            Ty::Adt { def_id: AdtDef::Struct(s), substs } => {
+                let reorder = match &s.attrs(db).repr()[..] {
+                    [] => true,
+                    [repr] if repr.as_str() == "C" => false,
+                    _ => return None,
+                };
+                let fields = s
+                    .fields(db)
+                    .into_iter()
+                    .map(|field| field.ty(db).subst(substs).layout(db))
+                    .collect::<Option<Vec<_>>>()?;
+                struct_layout(fields, reorder)
+            }
+            _ => return None,
+        };
+        Some(layout)
+    }
+}
+
+/// Lays out the fields in order, unless `reorder` is set, in which case the
+/// fields with the largest alignment come first, like rustc does without
+/// `#[repr(C)]`.
+fn struct_layout(mut fields: Vec<Layout>, reorder: bool) -> Layout {
+    if reorder {
+        fields.sort_by_key(|it| Reverse(it.align));
+    }
+    let align = fields.iter().map(|it| it.align).max().unwrap_or(1);
+    let mut size = 0;
+    for field in fields {
+        size = round_up(size, field.align) + field.size;
+    }
+    Layout { size: round_up(size, align), align }
+}
+
+fn round_up(size: u64, align: u64) -> u64 {
+    (size + align - 1) / align * align
+}
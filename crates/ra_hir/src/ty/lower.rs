@@ -21,10 +21,10 @@ use crate::{
     generics::GenericParams,
     adt::VariantDef,
 };
-use super::{Ty, primitive, FnSig, Substs};
+use super::{Ty, primitive, FnSig, Substs, TraitBound};
 
 impl Ty {
-    pub(crate) fn from_hir(db: &impl HirDatabase, resolver: &Resolver, type_ref: &TypeRef) -> Self {
+    pub fn from_hir(db: &impl HirDatabase, resolver: &Resolver, type_ref: &TypeRef) -> Self {
         match type_ref {
             TypeRef::Never => Ty::Never,
             TypeRef::Tuple(inner) => {
@@ -56,10 +56,66 @@ impl Ty {
                 let sig = FnSig { params_and_return: inner_tys.into() };
                 Ty::FnPtr(sig)
             }
+            TypeRef::ImplTrait(paths) => match Ty::trait_bounds(db, resolver, paths) {
+                Some(bounds) => Ty::Opaque(bounds),
+                None => Ty::Unknown,
+            },
+            TypeRef::DynTrait(paths) => match Ty::trait_bounds(db, resolver, paths) {
+                Some(bounds) => Ty::Dyn(bounds),
+                None => Ty::Unknown,
+            },
             TypeRef::Error => Ty::Unknown,
         }
     }
 
+    /// Lowers the bounds of an `impl Trait` or `dyn Trait` type. Paths which
+    /// don't resolve to traits are skipped; if none does, there are no bounds
+    /// to speak of.
+    fn trait_bounds(
+        db: &impl HirDatabase,
+        resolver: &Resolver,
+        paths: &[Path],
+    ) -> Option<Arc<[TraitBound]>> {
+        let bounds = paths
+            .iter()
+            .filter_map(|path| {
+                let trait_ = match resolver.resolve_path(db, path).take_types()? {
+                    Resolution::Def(ModuleDef::Trait(it)) => it,
+                    _ => return None,
+                };
+                let segment = path.segments.last()?;
+                let param_count = trait_.generic_params(db).params.len();
+                let mut substs = Vec::new();
+                let mut assoc_types = Vec::new();
+                if let Some(generic_args) = &segment.args_and_bindings {
+                    for arg in generic_args.args.iter().take(param_count) {
+                        match arg {
+                            GenericArg::Type(type_ref) => {
+                                substs.push(Ty::from_hir(db, resolver, type_ref))
+                            }
+                        }
+                    }
+                    for (name, type_ref) in generic_args.bindings.iter() {
+                        assoc_types.push((name.clone(), Ty::from_hir(db, resolver, type_ref)));
+                    }
+                }
+                for _ in substs.len()..param_count {
+                    substs.push(Ty::Unknown);
+                }
+                Some(TraitBound {
+                    trait_,
+                    substs: Substs(substs.into()),
+                    assoc_types: assoc_types.into(),
+                })
+            })
+            .collect::<Vec<_>>();
+        if bounds.is_empty() {
+            None
+        } else {
+            Some(bounds.into())
+        }
+    }
+
     pub(crate) fn from_hir_path(db: &impl HirDatabase, resolver: &Resolver, path: &Path) -> Self {
         if let Some(name) = path.as_ident() {
             // TODO handle primitive type names in resolver as well?
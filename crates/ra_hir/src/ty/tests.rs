@@ -809,8 +809,8 @@ fn test(x: &i32) {
 [177; 205) '{     ...     }': ()
 [191; 192) 'h': {unknown}
 [195; 198) 'val': {unknown}
-[215; 221) 'lambda': {unknown}
-[224; 256) '|a: u6...b; c }': {unknown}
+[215; 221) 'lambda': impl Fn(u64, u64, i32) -> i32
+[224; 256) '|a: u6...b; c }': impl Fn(u64, u64, i32) -> i32
 [225; 226) 'a': u64
 [233; 234) 'b': u64
 [236; 237) 'c': i32
@@ -830,6 +830,78 @@ fn test(x: &i32) {
     );
 }
 
+#[test]
+fn infer_closure() {
+    assert_snapshot_matches!(
+        infer(r#"
+struct S;
+impl S {
+    fn by_ref(&self) {}
+    fn by_mut(&mut self) {}
+    fn by_value(self) {}
+}
+
+fn test() {
+    let mut count = 0u32;
+    let mut s = S;
+    let read = |x: u32| x + count;
+    let write = || count += 1;
+    let write_method = || s.by_mut();
+    let read_method = || s.by_ref();
+    let consume = || s.by_value();
+    let copy = || count;
+    let inferred = |x| x;
+    inferred(1u8);
+}
+"#),
+        @r###"
+[35; 39) 'self': &S
+[41; 43) '{}': ()
+[63; 67) 'self': &mut S
+[69; 71) '{}': ()
+[88; 92) 'self': S
+[94; 96) '{}': ()
+[110; 404) '{     ...u8); }': ()
+[120; 129) 'mut count': u32
+[132; 136) '0u32': u32
+[146; 151) 'mut s': S
+[154; 155) 'S': S
+[165; 169) 'read': impl Fn(u32) -> u32
+[172; 190) '|x: u3... count': impl Fn(u32) -> u32
+[173; 174) 'x': u32
+[181; 182) 'x': u32
+[181; 190) 'x + count': u32
+[185; 190) 'count': u32
+[200; 205) 'write': impl FnMut()
+[208; 221) '|| count += 1': impl FnMut()
+[211; 216) 'count': u32
+[211; 221) 'count += 1': ()
+[220; 221) '1': u32
+[231; 243) 'write_method': impl FnMut()
+[246; 259) '|| s.by_mut()': impl FnMut()
+[249; 250) 's': S
+[249; 259) 's.by_mut()': ()
+[269; 280) 'read_method': impl Fn()
+[283; 296) '|| s.by_ref()': impl Fn()
+[286; 287) 's': S
+[286; 296) 's.by_ref()': ()
+[306; 313) 'consume': impl FnOnce()
+[316; 331) '|| s.by_value()': impl FnOnce()
+[319; 320) 's': S
+[319; 331) 's.by_value()': ()
+[341; 345) 'copy': impl Fn() -> u32
+[348; 356) '|| count': impl Fn() -> u32
+[351; 356) 'count': u32
+[366; 374) 'inferred': impl Fn(u8) -> u8
+[377; 382) '|x| x': impl Fn(u8) -> u8
+[378; 379) 'x': u8
+[381; 382) 'x': u8
+[388; 396) 'inferred': impl Fn(u8) -> u8
+[388; 401) 'inferred(1u8)': u8
+[397; 400) '1u8': u8"###
+    );
+}
+
 #[test]
 fn infer_adt_pattern() {
     assert_snapshot_matches!(
@@ -921,6 +993,33 @@ fn test(a1: A<u32>, i: i32) {
     );
 }
 
+#[test]
+fn infer_tuple_field_access() {
+    assert_snapshot_matches!(
+        infer(r#"
+struct Pair(u32, (bool, char));
+
+fn test(p: Pair) {
+    p.0;
+    p.1.1;
+    (1u8, 2u16).1;
+}
+"#),
+        @r###"
+[42; 43) 'p': Pair
+[51; 93) '{     ...).1; }': ()
+[57; 58) 'p': Pair
+[57; 60) 'p.0': u32
+[66; 67) 'p': Pair
+[66; 69) 'p.1': (bool, char)
+[66; 71) 'p.1.1': char
+[77; 88) '(1u8, 2u16)': (u8, u16)
+[77; 90) '(1u8, 2u16).1': u16
+[78; 81) '1u8': u8
+[83; 87) '2u16': u16"###
+    );
+}
+
 #[test]
 fn infer_tuple_struct_generics() {
     assert_snapshot_matches!(
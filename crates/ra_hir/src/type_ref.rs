@@ -1,9 +1,9 @@
 //! HIR for references to types. Paths in these are not yet resolved. They can
 //! be directly created from an ast::TypeRef, without further queries.
 
-use ra_syntax::ast::{self, TypeAscriptionOwner};
+use ra_syntax::{AstNode, SyntaxNode, ast::{self, TypeAscriptionOwner}};
 
-use crate::Path;
+use crate::{Name, Path, PathKind, path::GenericArg};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Mutability {
@@ -48,15 +48,16 @@ pub enum TypeRef {
     Slice(Box<TypeRef>),
     /// A fn pointer. Last element of the vector is the return type.
     Fn(Vec<TypeRef>),
-    // For
-    // ImplTrait,
-    // DynTrait,
+    /// An `impl Trait` type, with the paths of the bounding traits.
+    ImplTrait(Vec<Path>),
+    /// A `dyn Trait` type, with the paths of the bounding traits.
+    DynTrait(Vec<Path>),
     Error,
 }
 
 impl TypeRef {
     /// Converts an `ast::TypeRef` to a `hir::TypeRef`.
-    pub(crate) fn from_ast(node: &ast::TypeRef) -> Self {
+    pub fn from_ast(node: &ast::TypeRef) -> Self {
         use ra_syntax::ast::TypeRefKind::*;
         match node.kind() {
             ParenType(inner) => TypeRef::from_ast_opt(inner.type_ref()),
@@ -90,8 +91,8 @@ impl TypeRef {
             }
             // for types are close enough for our purposes to the inner type for now...
             ForType(inner) => TypeRef::from_ast_opt(inner.type_ref()),
-            ImplTraitType(_inner) => TypeRef::Error,
-            DynTraitType(_inner) => TypeRef::Error,
+            ImplTraitType(inner) => TypeRef::ImplTrait(bound_paths(inner.syntax())),
+            DynTraitType(inner) => TypeRef::DynTrait(bound_paths(inner.syntax())),
         }
     }
 
@@ -106,4 +107,58 @@ impl TypeRef {
     pub fn unit() -> TypeRef {
         TypeRef::Tuple(Vec::new())
     }
+
+    /// Checks whether the type parameter `name` appears anywhere in this type.
+    pub(crate) fn mentions_type_param(&self, name: &Name) -> bool {
+        match self {
+            TypeRef::Path(path) => {
+                let is_param = path.kind == PathKind::Plain
+                    && path.segments.len() == 1
+                    && path.segments[0].name == *name;
+                is_param || path_args_mention_type_param(path, name)
+            }
+            TypeRef::ImplTrait(paths) | TypeRef::DynTrait(paths) => {
+                paths.iter().any(|it| path_args_mention_type_param(it, name))
+            }
+            TypeRef::RawPtr(inner, _)
+            | TypeRef::Reference(inner, _)
+            | TypeRef::Array(inner)
+            | TypeRef::Slice(inner) => inner.mentions_type_param(name),
+            TypeRef::Tuple(types) | TypeRef::Fn(types) => {
+                types.iter().any(|it| it.mentions_type_param(name))
+            }
+            TypeRef::Never | TypeRef::Placeholder | TypeRef::Error => false,
+        }
+    }
+}
+
+fn path_args_mention_type_param(path: &Path, name: &Name) -> bool {
+    path.segments.iter().filter_map(|it| it.args_and_bindings.as_ref()).any(|args| {
+        let in_args = args.args.iter().any(|arg| match arg {
+            GenericArg::Type(type_ref) => type_ref.mentions_type_param(name),
+        });
+        in_args || args.bindings.iter().any(|(_, type_ref)| type_ref.mentions_type_param(name))
+    })
+}
+
+/// Collects the paths of the traits bounding an `impl Trait` or `dyn Trait`
+/// type. The parser nests each bound after a `+` in the preceding path type,
+/// so `impl A + B` has a `B` path type inside the `A` one.
+fn bound_paths(node: &SyntaxNode) -> Vec<Path> {
+    let mut res = Vec::new();
+    collect_bound_paths(node, &mut res);
+    res
+}
+
+fn collect_bound_paths(node: &SyntaxNode, acc: &mut Vec<Path>) {
+    for type_ref in node.children().filter_map(ast::TypeRef::cast) {
+        match type_ref.kind() {
+            ast::TypeRefKind::PathType(it) => {
+                acc.extend(it.path().and_then(Path::from_ast));
+                collect_bound_paths(it.syntax(), acc);
+            }
+            ast::TypeRefKind::ForType(it) => collect_bound_paths(it.syntax(), acc),
+            _ => {}
+        }
+    }
 }
@@ -1,6 +1,6 @@
 use ra_db::{FileRange, FilePosition};
 
-use crate::{SourceFileEdit, SourceChange, db::RootDatabase};
+use crate::{FileSystemEdit, SourceFileEdit, SourceChange, db::RootDatabase};
 
 pub use ra_assists::AssistId;
 
@@ -16,11 +16,23 @@ pub(crate) fn assists(db: &RootDatabase, frange: FileRange) -> Vec<Assist> {
         .map(|(label, action)| {
             let file_id = frange.file_id;
             let file_edit = SourceFileEdit { file_id, edit: action.edit };
+            let file_system_edits = action
+                .file_system_edits
+                .into_iter()
+                .map(|it| match it {
+                    ra_assists::FileSystemEdit::CreateFile { source_root, path, contents } => {
+                        FileSystemEdit::CreateFile { source_root, path, contents }
+                    }
+                    ra_assists::FileSystemEdit::DeleteFile { file_id } => {
+                        FileSystemEdit::DeleteFile { file_id }
+                    }
+                })
+                .collect();
             let id = label.id;
             let change = SourceChange {
                 label: label.label,
                 source_file_edits: vec![file_edit],
-                file_system_edits: vec![],
+                file_system_edits,
                 cursor_position: action
                     .cursor_position
                     .map(|offset| FilePosition { offset, file_id }),
@@ -1,36 +1,61 @@
 use test_utils::tested_by;
 use ra_db::SourceDatabase;
 use ra_syntax::{
-    AstNode, SyntaxNode, TextUnit,
-    SyntaxKind::FN_DEF,
-    ast::{self, ArgListOwner},
+    AstNode, SyntaxNode, TextRange, TextUnit,
+    SyntaxKind::{FN_DEF, NAME_REF},
+    ast::{self, ArgListOwner, NameOwner, TypeAscriptionOwner, TypeParamsOwner},
     algo::find_node_at_offset,
 };
-use hir::Docs;
+use hir::{Docs, HirDisplay, Resolution, ModuleDef, FieldSource, Ty, CallableDef, db::HirDatabase};
+use join_to_string::join;
 
 use crate::{FilePosition, CallInfo, db::RootDatabase};
 
+/// Settings which change what call info shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CallInfoOptions {
+    /// Whether to show the types inferred at the call site for the type
+    /// parameters of a generic function, like `-> Vec<u32>` for `-> B`.
+    pub substitute_generics: bool,
+}
+
 /// Computes parameter information for the given call expression.
-pub(crate) fn call_info(db: &RootDatabase, position: FilePosition) -> Option<CallInfo> {
+pub(crate) fn call_info(
+    db: &RootDatabase,
+    position: FilePosition,
+    options: CallInfoOptions,
+) -> Option<CallInfo> {
     let file = db.parse(position.file_id);
     let syntax = file.syntax();
 
     // Find the calling expression and it's NameRef
     let calling_node = FnCallNode::with_node(syntax, position.offset)?;
-    let name_ref = calling_node.name_ref()?;
-
-    // Resolve the function's NameRef (NOTE: this isn't entirely accurate).
-    let file_symbols = crate::symbol_index::index_resolve(db, name_ref);
-    let symbol = file_symbols.into_iter().find(|it| it.ptr.kind() == FN_DEF)?;
-    let fn_file = db.parse(symbol.file_id);
-    let fn_def = symbol.ptr.to_node(&fn_file);
-    let fn_def = ast::FnDef::cast(fn_def).unwrap();
-    let function = hir::source_binder::function_from_source(db, symbol.file_id, fn_def)?;
-
-    let mut call_info = CallInfo::new(db, function, fn_def)?;
+    let (mut call_info, has_self) = match constructor_call_info(db, position, &calling_node) {
+        Some(call_info) => (call_info, false),
+        None => {
+            let name_ref = calling_node.name_ref()?;
+
+            // Resolve the function's NameRef (NOTE: this isn't entirely accurate).
+            let file_symbols = crate::symbol_index::index_resolve(db, name_ref);
+            let symbol = file_symbols.into_iter().find(|it| it.ptr.kind() == FN_DEF)?;
+            let fn_file = db.parse(symbol.file_id);
+            let fn_def = symbol.ptr.to_node(&fn_file);
+            let fn_def = ast::FnDef::cast(fn_def).unwrap();
+            let function = hir::source_binder::function_from_source(db, symbol.file_id, fn_def)?;
+
+            let has_self = fn_def.param_list().and_then(|l| l.self_param()).is_some();
+            let mut call_info = CallInfo::new(db, function, fn_def)?;
+            if options.substitute_generics {
+                let types = substituted_types(db, position, &calling_node, function, fn_def);
+                if let Some(label) = types.and_then(|it| substituted_label(fn_def, &it)) {
+                    call_info.label = label;
+                }
+            }
+            (call_info, has_self)
+        }
+    };
     // If we have a calling expression let's find which argument we are on
     let num_params = call_info.parameters.len();
-    let has_self = fn_def.param_list().and_then(|l| l.self_param()).is_some();
 
     if num_params == 1 {
         if !has_self {
@@ -58,6 +83,133 @@ pub(crate) fn call_info(db: &RootDatabase, position: FilePosition) -> Option<Cal
     Some(call_info)
 }
 
+/// Tuple structs and tuple enum variants can be "called" like functions; their
+/// fields become the parameters.
+fn constructor_call_info(
+    db: &RootDatabase,
+    position: FilePosition,
+    calling_node: &FnCallNode,
+) -> Option<CallInfo> {
+    let call_expr = match calling_node {
+        FnCallNode::CallExpr(it) => it,
+        FnCallNode::MethodCallExpr(_) => return None,
+    };
+    let path = match call_expr.expr()?.kind() {
+        ast::ExprKind::PathExpr(path_expr) => hir::Path::from_ast(path_expr.path()?)?,
+        _ => return None,
+    };
+    let resolver = hir::source_binder::resolver_for_position(db, position);
+    let (label, fields, doc) = match resolver.resolve_path(db, &path).take_values()? {
+        Resolution::Def(ModuleDef::Struct(s)) => {
+            (format!("struct {}", s.name(db)?), s.fields(db), s.docs(db))
+        }
+        Resolution::Def(ModuleDef::EnumVariant(v)) => {
+            let name = format!("{}::{}", v.parent_enum(db).name(db)?, v.name(db)?);
+            (name, v.fields(db), v.docs(db))
+        }
+        _ => return None,
+    };
+    let is_tuple = fields.iter().all(|field| match field.source(db).1 {
+        FieldSource::Pos(_) => true,
+        FieldSource::Named(_) => false,
+    });
+    if fields.is_empty() || !is_tuple {
+        return None;
+    }
+
+    let types = fields.iter().map(|field| field.ty(db).display(db).to_string());
+    let label = format!("{}({})", label, join(types).separator(", ").to_string());
+    let parameters = fields
+        .iter()
+        .map(|field| format!("{}: {}", field.name(db), field.ty(db).display(db)))
+        .collect();
+    Some(CallInfo { parameters, label, doc, active_parameter: None })
+}
+
+/// The types inferred at the call for the parameter and return types of
+/// `fn_def` which mention its type parameters. Method calls only get their
+/// return type.
+fn substituted_types(
+    db: &RootDatabase,
+    position: FilePosition,
+    calling_node: &FnCallNode,
+    function: hir::Function,
+    fn_def: &ast::FnDef,
+) -> Option<Vec<(TextRange, String)>> {
+    let type_params = fn_def
+        .type_param_list()?
+        .type_params()
+        .filter_map(|it| it.name())
+        .map(|it| it.text().clone())
+        .collect::<Vec<_>>();
+    let caller =
+        hir::source_binder::function_from_child_node(db, position.file_id, calling_node.syntax())?;
+    let infer = caller.infer(db);
+    let source_map = caller.body_source_map(db);
+
+    let ret_ty = infer[source_map.node_expr(ast::Expr::cast(calling_node.syntax())?)?].clone();
+    let mut types = Vec::new();
+    if let FnCallNode::CallExpr(call) = calling_node {
+        let (sig, substs) = match &infer[source_map.node_expr(call.expr()?)?] {
+            Ty::FnDef { def: def @ CallableDef::Function(it), substs, .. } if *it == function => {
+                (db.callable_item_signature(*def), substs.clone())
+            }
+            _ => return None,
+        };
+        let param_list = fn_def.param_list()?;
+        let skip = if param_list.self_param().is_some() { 1 } else { 0 };
+        for (param, ty) in param_list.params().zip(sig.params().iter().skip(skip)) {
+            types.extend(param.ascribed_type().map(|it| (it, ty.clone().subst(&substs))));
+        }
+    }
+    types.extend(fn_def.ret_type().and_then(|it| it.type_ref()).map(|it| (it, ret_ty)));
+
+    let res = types
+        .into_iter()
+        .filter(|(type_ref, _)| {
+            type_ref.syntax().descendants().any(|it| {
+                it.kind() == NAME_REF && type_params.iter().any(|p| it.text() == p.as_str())
+            })
+        })
+        .filter(|(_, ty)| {
+            let mut is_known = true;
+            ty.walk(&mut |it| is_known &= *it != Ty::Unknown);
+            is_known
+        })
+        .map(|(type_ref, ty)| (type_ref.syntax().range(), ty.display(db).to_string()))
+        .collect();
+    Some(res)
+}
+
+/// The label `function_label` gives `fn_def`, with the text at the given ranges
+/// replaced.
+fn substituted_label(fn_def: &ast::FnDef, types: &[(TextRange, String)]) -> Option<String> {
+    if types.is_empty() {
+        return None;
+    }
+    let body_range = fn_def.body().map(|it| it.syntax().range());
+    let label: String = fn_def
+        .syntax()
+        .children()
+        .filter(|child| Some(child.range()) != body_range)
+        .filter(|child| ast::Comment::cast(child).is_none())
+        .filter(|child| ast::Attr::cast(child).is_none())
+        .map(|child| {
+            let slice = |from, to| child.text().slice(TextRange::from_to(from, to)).to_string();
+            let mut res = String::new();
+            let mut offset = child.range().start();
+            for (range, text) in types.iter().filter(|(it, _)| it.is_subrange(&child.range())) {
+                res.push_str(&slice(offset, range.start()));
+                res.push_str(text);
+                offset = range.end();
+            }
+            res.push_str(&slice(offset, child.range().end()));
+            res
+        })
+        .collect();
+    Some(label.trim().to_owned())
+}
+
 enum FnCallNode<'a> {
     CallExpr(&'a ast::CallExpr),
     MethodCallExpr(&'a ast::MethodCallExpr),
@@ -74,6 +226,13 @@ impl<'a> FnCallNode<'a> {
         None
     }
 
+    pub fn syntax(&self) -> &'a SyntaxNode {
+        match *self {
+            FnCallNode::CallExpr(it) => it.syntax(),
+            FnCallNode::MethodCallExpr(it) => it.syntax(),
+        }
+    }
+
     pub fn name_ref(&self) -> Option<&'a ast::NameRef> {
         match *self {
             FnCallNode::CallExpr(call_expr) => Some(match call_expr.expr()?.kind() {
@@ -366,6 +525,59 @@ By default this method stops actor's `Context`."#
         );
     }
 
+    #[test]
+    fn test_tuple_struct_constructor() {
+        let info = call_info(
+            r#"
+/// A point in 2D.
+struct Point(i32, u32);
+fn main() {
+    Point(1, <|>);
+}"#,
+        );
+
+        assert_eq!(info.parameters, vec!["0: i32".to_string(), "1: u32".to_string()]);
+        assert_eq!(info.active_parameter, Some(1));
+        assert_eq!(info.label, "struct Point(i32, u32)".to_string());
+        assert_eq!(info.doc.map(|it| it.into()), Some("A point in 2D.".to_string()));
+    }
+
+    #[test]
+    fn test_option_some_constructor() {
+        let info = call_info(
+            r#"
+enum Option<T> { Some(T), None }
+use Option::Some;
+fn main() {
+    Some(<|>);
+}"#,
+        );
+
+        assert_eq!(info.parameters, vec!["0: T".to_string()]);
+        assert_eq!(info.active_parameter, Some(0));
+        assert_eq!(info.label, "Option::Some(T)".to_string());
+    }
+
+    #[test]
+    fn test_tuple_enum_variant_constructor() {
+        let info = call_info(
+            r#"
+enum Shape {
+    /// A rectangle.
+    Rect(u32, u32),
+    Circle { radius: u32 },
+}
+fn main() {
+    Shape::Rect(<|>1, 2);
+}"#,
+        );
+
+        assert_eq!(info.parameters, vec!["0: u32".to_string(), "1: u32".to_string()]);
+        assert_eq!(info.active_parameter, Some(0));
+        assert_eq!(info.label, "Shape::Rect(u32, u32)".to_string());
+        assert_eq!(info.doc.map(|it| it.into()), Some("A rectangle.".to_string()));
+    }
+
     #[test]
     fn call_info_bad_offset() {
         covers!(call_info_bad_offset);
@@ -376,4 +588,37 @@ By default this method stops actor's `Context`."#
         let call_info = analysis.call_info(position).unwrap();
         assert!(call_info.is_none());
     }
+
+    #[test]
+    fn test_fn_signature_with_substituted_generics() {
+        let code = r#"
+struct Vec<T>;
+fn collect<B>(len: usize, first: B) -> Vec<B> { Vec }
+fn bar() { let v = collect(3, <|>1u32); }
+"#;
+        let (analysis, position) = single_file_with_position(code);
+        let info = analysis.call_info(position).unwrap().unwrap();
+        assert_eq!(info.label, "fn collect<B>(len: usize, first: B) -> Vec<B>");
+
+        let options = CallInfoOptions { substitute_generics: true };
+        let info = analysis.call_info_with_options(position, options).unwrap().unwrap();
+        assert_eq!(info.label, "fn collect<B>(len: usize, first: u32) -> Vec<u32>");
+        assert_eq!(info.parameters, vec!["len".to_string(), "first".to_string()]);
+    }
+
+    #[test]
+    fn test_method_signature_with_substituted_generics() {
+        let (analysis, position) = single_file_with_position(
+            r#"
+struct S;
+impl S {
+    fn pick<T>(&self, t: T) -> T { t }
+}
+fn bar() { S.pick(<|>1u8); }
+"#,
+        );
+        let options = CallInfoOptions { substitute_generics: true };
+        let info = analysis.call_info_with_options(position, options).unwrap().unwrap();
+        assert_eq!(info.label, "fn pick<T>(&self, t: T) -> u8");
+    }
 }
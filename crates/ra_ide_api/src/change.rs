@@ -5,7 +5,7 @@ use std::{
 
 use rustc_hash::FxHashMap;
 use ra_db::{
-    SourceRootId, FileId, CrateGraph, SourceDatabase, SourceRoot,
+    SourceRootId, FileId, CrateGraph, SourceDatabase, SourceRoot, Canceled,
     salsa::{Database, SweepStrategy},
 };
 use ra_syntax::SourceFile;
@@ -13,6 +13,7 @@ use relative_path::RelativePathBuf;
 use rayon::prelude::*;
 
 use crate::{
+    Cancelable,
     db::RootDatabase,
     symbol_index::{SymbolIndex, SymbolsDatabase},
     status::syntax_tree_stats,
@@ -135,8 +136,29 @@ impl LibraryData {
     pub fn prepare(
         root_id: SourceRootId,
         files: Vec<(FileId, RelativePathBuf, Arc<String>)>,
+    ) -> LibraryData {
+        LibraryData::build(root_id, files, &|| ())
+    }
+
+    /// Like `prepare`, but calls `poll` before indexing each file. `poll` runs
+    /// on the indexing threads, and can stop the indexing with
+    /// `Canceled::throw`.
+    pub fn prepare_with_poll(
+        root_id: SourceRootId,
+        files: Vec<(FileId, RelativePathBuf, Arc<String>)>,
+        poll: &(dyn Fn() + Sync),
+    ) -> Cancelable<LibraryData> {
+        let poll = std::panic::AssertUnwindSafe(poll);
+        Canceled::catch(move || LibraryData::build(root_id, files, poll.0))
+    }
+
+    fn build(
+        root_id: SourceRootId,
+        files: Vec<(FileId, RelativePathBuf, Arc<String>)>,
+        poll: &(dyn Fn() + Sync),
     ) -> LibraryData {
         let symbol_index = SymbolIndex::for_files(files.par_iter().map(|(file_id, _, text)| {
+            poll();
             let file = SourceFile::parse(text);
             (*file_id, file)
         }));
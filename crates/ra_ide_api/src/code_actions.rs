@@ -0,0 +1,95 @@
+use ra_db::FileRange;
+
+use crate::{SourceChange, assists, diagnostics, db::RootDatabase};
+
+pub(crate) fn code_actions(db: &RootDatabase, frange: FileRange) -> Vec<SourceChange> {
+    let mut res: Vec<SourceChange> =
+        assists::assists(db, frange).into_iter().map(|assist| assist.change).collect();
+
+    let fixes = diagnostics::diagnostics(db, frange.file_id)
+        .into_iter()
+        .filter(|diag| diag.range.intersection(&frange.range).is_some())
+        .filter_map(|diag| diag.fix);
+    for fix in fixes {
+        if !res.iter().any(|it| is_same_change(it, &fix)) {
+            res.push(fix);
+        }
+    }
+    res
+}
+
+/// Two changes are the same if they perform identical text edits. File system
+/// edits are never considered equal, so changes with them are always kept.
+fn is_same_change(a: &SourceChange, b: &SourceChange) -> bool {
+    a.file_system_edits.is_empty()
+        && b.file_system_edits.is_empty()
+        && a.source_file_edits.len() == b.source_file_edits.len()
+        && a.source_file_edits
+            .iter()
+            .zip(b.source_file_edits.iter())
+            .all(|(a, b)| a.file_id == b.file_id && a.edit == b.edit)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::single_file_with_range;
+
+    #[test]
+    fn code_actions_include_diagnostic_fixes() {
+        let (analysis, frange) = single_file_with_range(
+            "
+            struct A { a: u32 }
+            fn main() {
+                let a = 92;
+                A { <|>a: a<|> };
+            }
+            ",
+        );
+        let actions = analysis.code_actions(frange).unwrap();
+        let labels = actions.iter().map(|it| it.label.as_str()).collect::<Vec<_>>();
+        assert_eq!(labels.last(), Some(&"use struct shorthand initialization"));
+    }
+
+    #[test]
+    fn code_actions_put_assists_before_fixes() {
+        let (analysis, frange) = single_file_with_range(
+            "
+            struct A { a: u32 }
+            fn main() {
+                let a = 92;
+                A { a: <|>a<|> };
+            }
+            ",
+        );
+        let actions = analysis.code_actions(frange).unwrap();
+        let labels = actions.iter().map(|it| it.label.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![
+                "wrap in dbg!()",
+                "use field init shorthand",
+                "introduce variable",
+                "use struct shorthand initialization",
+            ]
+        );
+    }
+
+    #[test]
+    fn code_actions_put_hir_fixes_after_assists_without_duplicates() {
+        let (analysis, frange) = single_file_with_range(
+            "
+            enum Option<T> { Some(T), None }
+            use Option::*;
+            mod a {
+                <|>fn foo() -> u32 { 92 }
+            }
+            fn main() {
+                let Some(x) = Some(a::foo());<|>
+            }
+            ",
+        );
+        let actions = analysis.code_actions(frange).unwrap();
+        let labels = actions.iter().map(|it| it.label.as_str()).collect::<Vec<_>>();
+        assert_eq!(labels, vec!["make pub(crate)", "add test module", "convert to if let"]);
+    }
+}
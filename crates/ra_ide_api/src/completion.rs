@@ -11,6 +11,7 @@ mod complete_snippet;
 mod complete_path;
 mod complete_scope;
 mod complete_postfix;
+mod complete_attribute;
 
 use ra_db::SourceDatabase;
 use ra_syntax::ast::{self, AstNode};
@@ -25,9 +26,14 @@ use crate::{
 
 };
 #[cfg(test)]
-use crate::completion::completion_item::{do_completion, check_completion};
+use crate::completion::completion_item::{
+    do_completion, do_completion_with_filter, do_completion_with_options, check_completion,
+    test_options,
+};
 
-pub use crate::completion::completion_item::{CompletionItem, CompletionItemKind, InsertTextFormat};
+pub use crate::completion::completion_item::{
+    CompletionItem, CompletionItemKind, CompletionScore, InsertTextFormat,
+};
 
 /// Main entry point for completion. We run completion as a two-phase process.
 ///
@@ -51,13 +57,81 @@ pub use crate::completion::completion_item::{CompletionItem, CompletionItemKind,
 /// `foo` *should* be present among the completion variants. Filtering by
 /// identifier prefix/fuzzy match should be done higher in the stack, together
 /// with ordering of completions (currently this is done by the client).
-pub(crate) fn completions(db: &db::RootDatabase, position: FilePosition) -> Option<Completions> {
+pub(crate) fn completions(
+    db: &db::RootDatabase,
+    position: FilePosition,
+    options: CompletionOptions,
+) -> Option<Completions> {
+    completions_impl(db, position, options, true)
+}
+
+/// Settings which change how completion items are presented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompletionOptions {
+    /// Whether to insert a turbofish with placeholders when completing a call
+    /// to a function whose type parameters can't be inferred from the
+    /// arguments, like `collect::<${1:B}>()`.
+    pub add_turbofish: bool,
+    /// Whether to leave the documentation out of the items, so that it is only
+    /// computed by `Analysis::resolve_completion` for the items the user
+    /// actually looks at.
+    pub resolve_lazily: bool,
+    /// Whether to offer fields before inherent methods, and those before trait
+    /// methods, after a `.`.
+    pub group_dot_completions: bool,
+    /// How many lines around the cursor to look at for names, which are
+    /// preferred to those mentioned further away. `0` disables this.
+    pub nearby_lines: u32,
+}
+
+impl Default for CompletionOptions {
+    fn default() -> CompletionOptions {
+        CompletionOptions {
+            add_turbofish: false,
+            resolve_lazily: false,
+            group_dot_completions: true,
+            nearby_lines: 5,
+        }
+    }
+}
+
+/// Identifies a completion item which was computed with
+/// `CompletionOptions::resolve_lazily`, to fill in its documentation later.
+#[derive(Debug, Clone)]
+pub struct CompletionResolveId {
+    position: FilePosition,
+    options: CompletionOptions,
+    index: usize,
+    label: String,
+}
+
+/// Computes the completion item identified by `id` in full. Returns `None` if
+/// the file has changed so that the item is gone.
+pub(crate) fn resolve_completion(
+    db: &db::RootDatabase,
+    id: &CompletionResolveId,
+) -> Option<CompletionItem> {
+    let options = CompletionOptions { resolve_lazily: false, ..id.options };
+    let items: Vec<CompletionItem> = completions(db, id.position, options)?.into();
+    items.into_iter().nth(id.index).filter(|it| it.label() == id.label)
+}
+
+/// `hide_unavailable` is exposed separately so that tests can check what is
+/// being filtered out.
+fn completions_impl(
+    db: &db::RootDatabase,
+    position: FilePosition,
+    options: CompletionOptions,
+    hide_unavailable: bool,
+) -> Option<Completions> {
     let original_file = db.parse(position.file_id);
-    let ctx = CompletionContext::new(db, &original_file, position)?;
+    let mut ctx = CompletionContext::new(db, &original_file, position, options)?;
+    ctx.hide_unavailable = hide_unavailable;
 
     let mut acc = Completions::default();
 
     complete_fn_param::complete_fn_param(&mut acc, &ctx);
+    complete_fn_param::complete_self_param(&mut acc, &ctx);
     complete_keyword::complete_expr_keyword(&mut acc, &ctx);
     complete_keyword::complete_use_tree_keyword(&mut acc, &ctx);
     complete_snippet::complete_expr_snippet(&mut acc, &ctx);
@@ -68,6 +142,16 @@ pub(crate) fn completions(db: &db::RootDatabase, position: FilePosition) -> Opti
     complete_struct_literal::complete_struct_literal(&mut acc, &ctx);
     complete_pattern::complete_pattern(&mut acc, &ctx);
     complete_postfix::complete_postfix(&mut acc, &ctx);
+    complete_attribute::complete_attribute(&mut acc, &ctx);
+    acc.sort_by_score();
+    if options.resolve_lazily {
+        acc.set_resolve_ids(|index, label| CompletionResolveId {
+            position,
+            options,
+            index,
+            label: label.to_string(),
+        });
+    }
     Some(acc)
 }
 
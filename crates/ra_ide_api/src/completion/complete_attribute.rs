@@ -0,0 +1,417 @@
+use ra_db::SourceDatabase;
+use ra_syntax::{
+    AstNode, SyntaxNode, SyntaxKind, Direction, TextRange, TextUnit, algo::non_trivia_sibling, ast,
+    SyntaxKind::*,
+};
+
+use crate::completion::{
+    CompletionItem, Completions, CompletionKind, CompletionItemKind, CompletionContext,
+};
+
+/// Complete built-in attributes, as in `#[inl<|>]`, lint names in
+/// `#[allow(<|>)]` and friends, and `cfg` predicates.
+pub(super) fn complete_attribute(acc: &mut Completions, ctx: &CompletionContext) {
+    let attr = match ctx.attribute_syntax {
+        Some(it) => it,
+        None => return,
+    };
+    let tt = match attr.value() {
+        Some(it) => it.syntax(),
+        None => return,
+    };
+    let parent = match ctx.leaf.parent() {
+        Some(it) => it,
+        None => return,
+    };
+
+    if parent == tt {
+        if is_first_token(ctx.leaf, L_BRACK) {
+            complete_attribute_name(acc, ctx, attr);
+        }
+        return;
+    }
+
+    if parent.kind() == TOKEN_TREE && is_in_cfg(parent, tt) {
+        complete_cfg(acc, ctx, parent, tt);
+        return;
+    }
+
+    if parent.kind() == TOKEN_TREE && parent.parent() == Some(tt) {
+        let name = non_trivia_sibling(parent, Direction::Prev).and_then(SyntaxNode::leaf_text);
+        let is_lint_attr = name.map(|it| LINT_ATTRIBUTES.contains(&it.as_str())).unwrap_or(false);
+        if is_lint_attr && (is_first_token(ctx.leaf, L_PAREN) || is_first_token(ctx.leaf, COMMA)) {
+            for &lint in LINTS.iter() {
+                CompletionItem::new(CompletionKind::Attribute, ctx.source_range(), lint)
+                    .add_to(acc);
+            }
+        }
+    }
+}
+
+fn complete_attribute_name(acc: &mut Completions, ctx: &CompletionContext, attr: &ast::Attr) {
+    // Attributes which are not attached to an item yet could end up anywhere.
+    let target = match attr.syntax().parent().map(SyntaxNode::kind) {
+        Some(SOURCE_FILE) if attr.is_inner() => Some(SOURCE_FILE),
+        Some(SOURCE_FILE) | Some(ITEM_LIST) | Some(ERROR) | None => None,
+        Some(kind) => Some(kind),
+    };
+    for attr_completion in ATTRIBUTES.iter() {
+        let is_applicable = match target {
+            _ if attr_completion.targets.is_empty() => true,
+            Some(kind) => attr_completion.targets.contains(&kind),
+            None => !attr_completion.targets.contains(&SOURCE_FILE),
+        };
+        if !is_applicable {
+            continue;
+        }
+        let item = CompletionItem::new(
+            CompletionKind::Attribute,
+            ctx.source_range(),
+            attr_completion.label,
+        )
+        .kind(CompletionItemKind::Attribute);
+        match attr_completion.snippet {
+            Some(snippet) => item.insert_snippet(snippet).add_to(acc),
+            None => item.add_to(acc),
+        }
+    }
+}
+
+/// Completes the predicates in `#[cfg(<|>)]`, including nested `all(<|>)` and
+/// friends, and the features of the crate in `#[cfg(feature = "<|>")]`.
+fn complete_cfg(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    parent: &SyntaxNode,
+    tt: &SyntaxNode,
+) {
+    if ctx.leaf.kind() == STRING {
+        let key = non_trivia_sibling(ctx.leaf, Direction::Prev)
+            .filter(|it| it.kind() == EQ)
+            .and_then(|it| non_trivia_sibling(it, Direction::Prev))
+            .and_then(SyntaxNode::leaf_text);
+        if key.map(|it| it.as_str()) != Some("feature") {
+            return;
+        }
+        // Replace the contents of the string, up to the closing quote.
+        let range = ctx.leaf.range();
+        let start = range.start() + TextUnit::from(1);
+        let text = ctx.leaf.leaf_text().map(|it| it.as_str()).unwrap_or_default();
+        let end = if text.len() > 1 && text.ends_with('"') {
+            range.end() - TextUnit::from(1)
+        } else {
+            ctx.offset
+        };
+        if ctx.offset < start || ctx.offset > end {
+            return;
+        }
+        let krate = match ctx.module.and_then(|it| it.krate(ctx.db)) {
+            Some(it) => it,
+            None => return,
+        };
+        for feature in ctx.db.crate_graph().features(krate.crate_id()) {
+            CompletionItem::new(
+                CompletionKind::Attribute,
+                TextRange::from_to(start, end),
+                feature.as_str(),
+            )
+            .add_to(acc);
+        }
+        return;
+    }
+
+    // Only the first argument of `cfg_attr` is a predicate.
+    let is_predicate_start = if parent.parent() == Some(tt) && tt_name(parent) == Some("cfg_attr") {
+        is_first_token(ctx.leaf, L_PAREN)
+    } else {
+        is_first_token(ctx.leaf, L_PAREN) || is_first_token(ctx.leaf, COMMA)
+    };
+    if !is_predicate_start {
+        return;
+    }
+    for predicate in CFG_PREDICATES.iter() {
+        let item =
+            CompletionItem::new(CompletionKind::Attribute, ctx.source_range(), predicate.label);
+        match predicate.snippet {
+            Some(snippet) => item.insert_snippet(snippet).add_to(acc),
+            None => item.add_to(acc),
+        }
+    }
+}
+
+/// Whether the token tree `node` holds `cfg` predicates: it is the argument
+/// of `cfg` or `cfg_attr`, or of `all`, `any` or `not` within one.
+fn is_in_cfg(node: &SyntaxNode, tt: &SyntaxNode) -> bool {
+    let mut names = node.ancestors().take_while(|it| it != &tt).map(tt_name).collect::<Vec<_>>();
+    match names.pop() {
+        Some(Some("cfg")) | Some(Some("cfg_attr")) => {
+            names.iter().all(|it| it.map_or(false, |it| CFG_OPERATORS.contains(&it)))
+        }
+        _ => false,
+    }
+}
+
+/// The name before a token tree, as in `name(...)`.
+fn tt_name(tt: &SyntaxNode) -> Option<&str> {
+    non_trivia_sibling(tt, Direction::Prev).and_then(SyntaxNode::leaf_text).map(|it| it.as_str())
+}
+
+/// Checks whether `leaf` is the token `after`, or the first token after it in
+/// its token tree.
+fn is_first_token(leaf: &SyntaxNode, after: SyntaxKind) -> bool {
+    let prev = match leaf.kind() {
+        kind if kind == after => return true,
+        WHITESPACE => leaf.prev_sibling(),
+        IDENT => non_trivia_sibling(leaf, Direction::Prev),
+        _ => None,
+    };
+    prev.map(|it| it.kind() == after).unwrap_or(false)
+}
+
+struct AttributeCompletion {
+    label: &'static str,
+    snippet: Option<&'static str>,
+    /// The kinds of items the attribute applies to, or any item if empty.
+    /// `SOURCE_FILE` means an inner, crate-level attribute.
+    targets: &'static [SyntaxKind],
+}
+
+const fn attr(
+    label: &'static str,
+    snippet: Option<&'static str>,
+    targets: &'static [SyntaxKind],
+) -> AttributeCompletion {
+    AttributeCompletion { label, snippet, targets }
+}
+
+const ATTRIBUTES: &[AttributeCompletion] = &[
+    attr("allow", Some("allow(${0:lint})"), &[]),
+    attr("warn", Some("warn(${0:lint})"), &[]),
+    attr("deny", Some("deny(${0:lint})"), &[]),
+    attr("forbid", Some("forbid(${0:lint})"), &[]),
+    attr("cfg", Some("cfg(${0:predicate})"), &[]),
+    attr("cfg_attr", Some("cfg_attr(${1:predicate}, ${0:attr})"), &[]),
+    attr("doc", Some("doc = \"${0:docs}\""), &[]),
+    attr("deprecated", None, &[]),
+    attr("inline", None, &[FN_DEF]),
+    attr("cold", None, &[FN_DEF]),
+    attr("no_mangle", None, &[FN_DEF, STATIC_DEF]),
+    attr("test", None, &[FN_DEF]),
+    attr("ignore", None, &[FN_DEF]),
+    attr("should_panic", None, &[FN_DEF]),
+    attr("must_use", None, &[FN_DEF, STRUCT_DEF, ENUM_DEF, TRAIT_DEF]),
+    attr("derive", Some("derive(${0:Debug})"), &[STRUCT_DEF, ENUM_DEF]),
+    attr("repr", Some("repr(${0:C})"), &[STRUCT_DEF, ENUM_DEF]),
+    attr("non_exhaustive", None, &[STRUCT_DEF, ENUM_DEF]),
+    attr("macro_use", None, &[MODULE, EXTERN_CRATE_ITEM]),
+    attr("path", Some("path = \"${0:path}\""), &[MODULE]),
+    attr("macro_export", None, &[MACRO_CALL]),
+    attr("feature", Some("feature(${0:flag})"), &[SOURCE_FILE]),
+    attr("no_std", None, &[SOURCE_FILE]),
+    attr("no_main", None, &[SOURCE_FILE]),
+    attr("recursion_limit", Some("recursion_limit = \"${0:128}\""), &[SOURCE_FILE]),
+];
+
+const CFG_OPERATORS: &[&str] = &["all", "any", "not"];
+
+const CFG_PREDICATES: &[AttributeCompletion] = &[
+    attr("test", None, &[]),
+    attr("debug_assertions", None, &[]),
+    attr("unix", None, &[]),
+    attr("windows", None, &[]),
+    attr("feature", Some("feature = \"${0:name}\""), &[]),
+    attr("target_os", Some("target_os = \"${0:linux}\""), &[]),
+    attr("target_family", Some("target_family = \"${0:unix}\""), &[]),
+    attr("target_arch", Some("target_arch = \"${0:x86_64}\""), &[]),
+    attr("target_env", Some("target_env = \"${0:gnu}\""), &[]),
+    attr("target_endian", Some("target_endian = \"${0:little}\""), &[]),
+    attr("target_pointer_width", Some("target_pointer_width = \"${0:64}\""), &[]),
+    attr("target_vendor", Some("target_vendor = \"${0:unknown}\""), &[]),
+    attr("all", Some("all(${0:predicates})"), &[]),
+    attr("any", Some("any(${0:predicates})"), &[]),
+    attr("not", Some("not(${0:predicate})"), &[]),
+];
+
+const LINT_ATTRIBUTES: &[&str] = &["allow", "warn", "deny", "forbid"];
+
+const LINTS: &[&str] = &[
+    "warnings",
+    "bare_trait_objects",
+    "dead_code",
+    "deprecated",
+    "missing_debug_implementations",
+    "missing_docs",
+    "non_camel_case_types",
+    "non_snake_case",
+    "non_upper_case_globals",
+    "unreachable_code",
+    "unreachable_patterns",
+    "unsafe_code",
+    "unused_assignments",
+    "unused_attributes",
+    "unused_extern_crates",
+    "unused_imports",
+    "unused_macros",
+    "unused_must_use",
+    "unused_mut",
+    "unused_parens",
+    "unused_qualifications",
+    "unused_results",
+    "unused_unsafe",
+    "unused_variables",
+    "while_true",
+];
+
+#[cfg(test)]
+mod tests {
+    use ra_syntax::TextRange;
+
+    use crate::{
+        AnalysisChange, CrateGraph,
+        Edition::Edition2018,
+        completion::{CompletionKind, do_completion},
+        mock_analysis::MockAnalysis,
+    };
+
+    fn attribute_labels(code: &str) -> Vec<String> {
+        let completions = do_completion(code, CompletionKind::Attribute);
+        completions.into_iter().map(|it| it.label().to_string()).collect()
+    }
+
+    #[test]
+    fn completes_lints_in_allow() {
+        let labels = attribute_labels(
+            r"
+            #[allow(unu<|>)]
+            fn foo() {}
+            ",
+        );
+        assert!(labels.iter().any(|it| it == "unused_variables"));
+        assert!(labels.iter().any(|it| it == "dead_code"));
+        assert!(!labels.iter().any(|it| it == "inline"));
+
+        let labels = attribute_labels(
+            r"
+            #[deny(missing_docs, <|>)]
+            struct S;
+            ",
+        );
+        assert!(labels.iter().any(|it| it == "unused_variables"));
+    }
+
+    #[test]
+    fn completes_attributes_for_item() {
+        let labels = attribute_labels(
+            r"
+            #[inl<|>]
+            fn foo() {}
+            ",
+        );
+        assert!(labels.iter().any(|it| it == "inline"));
+        assert!(labels.iter().any(|it| it == "allow"));
+        assert!(!labels.iter().any(|it| it == "derive"));
+        assert!(!labels.iter().any(|it| it == "no_std"));
+
+        let labels = attribute_labels(
+            r"
+            #[<|>]
+            struct S;
+            ",
+        );
+        assert!(labels.iter().any(|it| it == "derive"));
+        assert!(!labels.iter().any(|it| it == "inline"));
+
+        let labels = attribute_labels(
+            r"
+            #![no<|>]
+            ",
+        );
+        assert!(labels.iter().any(|it| it == "no_std"));
+        assert!(!labels.iter().any(|it| it == "inline"));
+    }
+
+    #[test]
+    fn completes_attribute_snippets() {
+        let completions = do_completion(
+            r"
+            #[cf<|>]
+            fn foo() {}
+            ",
+            CompletionKind::Attribute,
+        );
+        let cfg = completions.iter().find(|it| it.label() == "cfg").unwrap();
+        assert_eq!(cfg.text_edit().as_atoms()[0].insert, "cfg(${0:predicate})");
+    }
+
+    #[test]
+    fn dont_complete_attributes_in_arguments() {
+        assert!(attribute_labels(
+            r"
+            #[cfg(feature = <|>)]
+            fn foo() {}
+            "
+        )
+        .is_empty());
+        assert!(attribute_labels(
+            r"
+            #[allow(dead_code)]
+            fn foo() { unu<|> }
+            "
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn completes_cfg_predicates() {
+        let labels = attribute_labels(
+            r"
+            #[cfg(<|>)]
+            fn foo() {}
+            ",
+        );
+        assert!(labels.iter().any(|it| it == "target_os"));
+        assert!(labels.iter().any(|it| it == "feature"));
+        assert!(labels.iter().any(|it| it == "debug_assertions"));
+        assert!(!labels.iter().any(|it| it == "dead_code"));
+
+        let labels = attribute_labels(
+            r"
+            #[cfg_attr(all(unix, te<|>), derive(Debug))]
+            struct S;
+            ",
+        );
+        assert!(labels.iter().any(|it| it == "test"));
+
+        assert!(attribute_labels(
+            r"
+            #[cfg_attr(unix, <|>)]
+            struct S;
+            "
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn completes_crate_features_in_cfg() {
+        let mut mock = MockAnalysis::new();
+        let position = mock.add_file_with_position(
+            "/lib.rs",
+            r#"
+            #[cfg(feature = "se<|>")]
+            fn foo() {}
+            "#,
+        );
+        let mut host = mock.analysis_host();
+        let mut crate_graph = CrateGraph::default();
+        let crate_id = crate_graph.add_crate_root(position.file_id, Edition2018);
+        crate_graph.set_features(crate_id, vec!["serde".into(), "std".into()]);
+        let mut change = AnalysisChange::new();
+        change.set_crate_graph(crate_graph);
+        host.apply_change(change);
+
+        let completions = host.analysis().completions(position).unwrap().unwrap();
+        let labels = completions.iter().map(|it| it.label()).collect::<Vec<_>>();
+        assert_eq!(labels, vec!["serde", "std"]);
+        assert_eq!(completions[0].source_range(), TextRange::from_to(30.into(), 32.into()));
+    }
+}
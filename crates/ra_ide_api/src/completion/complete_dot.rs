@@ -1,33 +1,70 @@
-use hir::{Ty, AdtDef};
+use hir::{Ty, AdtDef, HasAttrs, CallableDef, Function};
+use ra_syntax::{AstNode, ast, SyntaxKind::ASYNC_KW};
 
-use crate::completion::{CompletionContext, Completions};
+use crate::completion::{
+    CompletionContext, Completions, CompletionItem, CompletionKind, CompletionItemKind,
+};
 
 /// Complete dot accesses, i.e. fields or methods (currently only fields).
 pub(super) fn complete_dot(acc: &mut Completions, ctx: &CompletionContext) {
-    let (function, receiver) = match (&ctx.function, ctx.dot_receiver) {
-        (Some(function), Some(receiver)) => (function, receiver),
-        _ => return,
-    };
-    let infer_result = function.infer(ctx.db);
-    let source_map = function.body_source_map(ctx.db);
-    let expr = match source_map.node_expr(receiver) {
-        Some(expr) => expr,
+    let receiver_ty = match ctx.dot_receiver_ty() {
+        Some(it) => it,
         None => return,
     };
-    let receiver_ty = infer_result[expr].clone();
     if !ctx.is_call {
         complete_fields(acc, ctx, receiver_ty.clone());
+        if ctx.is_in_async_context() && is_future(ctx, &receiver_ty) {
+            CompletionItem::new(CompletionKind::Keyword, ctx.source_range(), "await")
+                .kind(CompletionItemKind::Keyword)
+                .detail("expr.await")
+                .add_to(acc);
+        }
     }
     complete_methods(acc, ctx, receiver_ty);
 }
 
+/// Without trait solving, we only know about `impl Future` and `dyn Future`,
+/// and the results of calling `async fn`s.
+fn is_future(ctx: &CompletionContext, receiver_ty: &Ty) -> bool {
+    if let Ty::Opaque(bounds) | Ty::Dyn(bounds) = receiver_ty {
+        return bounds.iter().any(|bound| {
+            bound.trait_.name(ctx.db).map_or(false, |name| name.to_string() == "Future")
+        });
+    }
+    let (function, receiver) = match (ctx.function, ctx.dot_receiver) {
+        (Some(function), Some(receiver)) => (function, receiver),
+        _ => return false,
+    };
+    let infer = function.infer(ctx.db);
+    let source_map = function.body_source_map(ctx.db);
+    let callee: Option<Function> = match receiver.kind() {
+        ast::ExprKind::CallExpr(call) => {
+            let callee = call.expr().and_then(|it| source_map.node_expr(it));
+            match callee.map(|it| &infer[it]) {
+                Some(Ty::FnDef { def: CallableDef::Function(it), .. }) => Some(*it),
+                _ => None,
+            }
+        }
+        ast::ExprKind::MethodCallExpr(_) => {
+            source_map.node_expr(receiver).and_then(|it| infer.method_resolution(it))
+        }
+        _ => None,
+    };
+    callee
+        .map_or(false, |it| it.source(ctx.db).1.syntax().children().any(|it| it.kind() == ASYNC_KW))
+}
+
 fn complete_fields(acc: &mut Completions, ctx: &CompletionContext, receiver: Ty) {
     for receiver in receiver.autoderef(ctx.db) {
         match receiver {
             Ty::Adt { def_id, ref substs, .. } => {
                 match def_id {
                     AdtDef::Struct(s) => {
+                        let krate = s.module(ctx.db).krate(ctx.db);
                         for field in s.fields(ctx.db) {
+                            if ctx.is_hidden(krate, &field.attrs(ctx.db)) {
+                                continue;
+                            }
                             acc.add_field(ctx, field, substs);
                         }
                     }
@@ -58,7 +95,10 @@ fn complete_methods(acc: &mut Completions, ctx: &CompletionContext, receiver: Ty
 
 #[cfg(test)]
 mod tests {
-    use crate::completion::{check_completion, CompletionKind};
+    use crate::completion::{
+        check_completion, do_completion, test_options, CompletionKind, CompletionItemKind,
+    };
+    use crate::mock_analysis::single_file_with_position;
 
     fn check_ref_completion(name: &str, code: &str) {
         check_completion(name, code, CompletionKind::Reference);
@@ -187,4 +227,177 @@ mod tests {
             ",
         );
     }
+
+    fn field_details(code: &str) -> Vec<(String, String)> {
+        let completions = do_completion(code, CompletionKind::Reference);
+        completions
+            .into_iter()
+            .filter(|it| it.kind() == Some(CompletionItemKind::Field))
+            .map(|it| (it.label().to_string(), it.detail().unwrap_or("").to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn completes_tuple_field_indices_with_types() {
+        let fields = field_details(
+            r"
+            struct String;
+            fn foo() {
+               let pair: (u32, String) = (0, String);
+               pair.<|>
+            }
+            ",
+        );
+        assert_eq!(fields, vec![("0".into(), "u32".into()), ("1".into(), "String".into())]);
+    }
+
+    #[test]
+    fn completes_nested_tuple_field_indices() {
+        let fields = field_details(
+            r"
+            struct Wrapper((bool, char), u8);
+            fn foo(w: Wrapper) {
+               w.0.<|>
+            }
+            ",
+        );
+        assert_eq!(fields, vec![("0".into(), "bool".into()), ("1".into(), "char".into())]);
+    }
+
+    fn dot_labels(code: &str) -> Vec<String> {
+        let completions = do_completion(code, CompletionKind::Keyword);
+        completions.into_iter().map(|it| it.label().to_string()).collect()
+    }
+
+    #[test]
+    fn completes_await_on_futures_in_async_fn() {
+        let code = r"
+            trait Future { type Output; }
+            async fn fetch() -> u32 { 92 }
+            fn ready() -> impl Future<Output = u32> { loop {} }
+            async fn foo() {
+                fetch().<|>
+            }
+            ";
+        assert_eq!(dot_labels(code), vec!["await"]);
+        assert_eq!(dot_labels(&code.replace("fetch().", "ready().")), vec!["await"]);
+        let code = code.replace("async fn foo", "fn foo");
+        assert!(dot_labels(&code).is_empty());
+    }
+
+    #[test]
+    fn completes_await_in_async_block() {
+        let labels = dot_labels(
+            r"
+            async fn fetch() -> u32 { 92 }
+            fn foo() {
+                async { fetch().<|> };
+            }
+            ",
+        );
+        assert_eq!(labels, vec!["await"]);
+    }
+
+    #[test]
+    fn does_not_complete_await_on_other_types() {
+        let labels = dot_labels(
+            r"
+            fn fetch() -> u32 { 92 }
+            async fn foo() {
+                fetch().<|>
+            }
+            ",
+        );
+        assert!(labels.is_empty());
+    }
+
+    #[test]
+    fn ranks_and_marks_builder_methods() {
+        let (analysis, position) = single_file_with_position(
+            r"
+            struct Builder { size: u32 }
+            impl Builder {
+                fn build(self) -> u32 { self.size }
+                fn with_size(self, size: u32) -> Self { self }
+                fn with_name(&mut self, name: &str) -> &mut Builder { self }
+            }
+            fn foo(b: Builder) {
+               b.<|>
+            }
+            ",
+        );
+        let completions =
+            analysis.completions_with_options(position, test_options()).unwrap().unwrap();
+        let items = completions
+            .iter()
+            .filter(|it| match it.kind() {
+                Some(CompletionItemKind::Field) | Some(CompletionItemKind::Method) => true,
+                _ => false,
+            })
+            .map(|it| (it.label(), it.detail().unwrap_or("")))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            vec![
+                ("with_size", "fn with_size(self, size: u32) -> Self (builder)"),
+                ("with_name", "fn with_name(&mut self, name: &str) -> &mut Builder (builder)"),
+                ("size", "u32"),
+                ("build", "fn build(self) -> u32"),
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_fields_before_inherent_and_trait_methods() {
+        let completions = do_completion(
+            r"
+            trait Tr { fn a_trait_method(&self); }
+            struct A { z_field: u32 }
+            impl A { fn m_method(&self) {} }
+            impl Tr for A { fn a_trait_method(&self) {} }
+            fn foo(a: A) {
+               a.<|>
+            }
+            ",
+            CompletionKind::Reference,
+        );
+        let sort_text = |label: &str| {
+            let item = completions.iter().find(|it| it.label() == label).unwrap();
+            item.sort_text().unwrap().to_string()
+        };
+        assert!(sort_text("z_field") < sort_text("m_method"));
+        assert!(sort_text("m_method") < sort_text("a_trait_method"));
+    }
+
+    fn apply_completion(code: &str, label: &str) -> String {
+        let (_, text) = test_utils::extract_offset(code);
+        let completions = do_completion(code, CompletionKind::Reference);
+        let item = completions.iter().find(|it| it.label() == label).unwrap();
+        item.text_edit().apply(&text)
+    }
+
+    #[test]
+    fn inserts_mut_for_mut_self_methods_on_immutable_locals() {
+        let code = r"
+            struct V;
+            impl V {
+                fn push(&mut self, x: u32) {}
+                fn len(&self) -> usize { 0 }
+            }
+            fn foo() {
+                let v = V;
+                v.<|>
+            }
+            ";
+        assert!(
+            apply_completion(code, "push").contains("let mut v = V;\n                v.push($0)")
+        );
+        assert!(apply_completion(code, "len").contains("let v = V;\n                v.len()$0"));
+        let code = code.replace("let v = V;", "let mut v = V;");
+        assert!(
+            apply_completion(&code, "push").contains("let mut v = V;\n                v.push($0)")
+        );
+        let code = code.replace("let mut v = V;", "let v = &mut V;");
+        assert!(apply_completion(&code, "push").contains("let v = &mut V;"));
+    }
 }
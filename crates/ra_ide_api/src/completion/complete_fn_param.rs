@@ -5,7 +5,9 @@ use ra_syntax::{
 };
 use rustc_hash::FxHashMap;
 
-use crate::completion::{CompletionContext, Completions, CompletionKind, CompletionItem};
+use crate::completion::{
+    CompletionContext, Completions, CompletionKind, CompletionItem, CompletionItemKind,
+};
 
 /// Complete repeated parameters, both name and type. For example, if all
 /// functions in a file have a `spam: &mut Spam` parameter, a completion with
@@ -52,9 +54,22 @@ pub(super) fn complete_fn_param(acc: &mut Completions, ctx: &CompletionContext)
     }
 }
 
+/// Complete `self` receivers as the first parameter of a method.
+pub(super) fn complete_self_param(acc: &mut Completions, ctx: &CompletionContext) {
+    if !ctx.is_first_method_param {
+        return;
+    }
+    for &receiver in ["self", "&self", "&mut self"].iter() {
+        CompletionItem::new(CompletionKind::Keyword, ctx.source_range(), receiver)
+            .kind(CompletionItemKind::Keyword)
+            .lookup_by("self")
+            .add_to(acc);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::completion::{check_completion, CompletionKind};
+    use crate::completion::{check_completion, do_completion, CompletionKind};
 
     fn check_magic_completion(name: &str, code: &str) {
         check_completion(name, code, CompletionKind::Magic);
@@ -98,4 +113,28 @@ mod tests {
             ",
         );
     }
+
+    fn self_param_labels(code: &str) -> Vec<String> {
+        let completions = do_completion(code, CompletionKind::Keyword);
+        completions.into_iter().map(|it| it.label().to_string()).collect()
+    }
+
+    #[test]
+    fn completes_self_param_in_methods() {
+        assert_eq!(
+            self_param_labels("struct S; impl S { fn foo(<|>) {} }"),
+            vec!["&mut self", "&self", "self"]
+        );
+        assert_eq!(
+            self_param_labels("trait T { fn foo(se<|>, x: u32); }"),
+            vec!["&mut self", "&self", "self"]
+        );
+    }
+
+    #[test]
+    fn does_not_complete_self_param_elsewhere() {
+        assert!(self_param_labels("fn foo(<|>) {}").is_empty());
+        assert!(self_param_labels("struct S; impl S { fn foo(x: u32, <|>) {} }").is_empty());
+        assert!(self_param_labels("struct S; impl S { fn foo(&self, <|>) {} }").is_empty());
+    }
 }
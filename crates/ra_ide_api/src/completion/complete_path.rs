@@ -27,9 +27,15 @@ pub(super) fn complete_path(acc: &mut Completions, ctx: &CompletionContext) {
                         }
                     }
                 }
+                // Only modules and types have items to continue the path with.
+                if ctx.is_path_qualifier && res.def.as_ref().take_types().is_none() {
+                    continue;
+                }
                 acc.add_resolution(ctx, name.to_string(), &res.def.map(hir::Resolution::Def));
             }
         }
+        // Variants end a path.
+        hir::ModuleDef::Enum(_) if ctx.is_path_qualifier => return,
         hir::ModuleDef::Enum(e) => {
             for variant in e.variants(ctx.db) {
                 acc.add_enum_variant(ctx, variant);
@@ -39,6 +45,8 @@ pub(super) fn complete_path(acc: &mut Completions, ctx: &CompletionContext) {
             let ty = s.ty(ctx.db);
             ty.iterate_impl_items(ctx.db, |item| {
                 match item {
+                    hir::ImplItem::TypeAlias(ty) => acc.add_type_alias(ctx, ty),
+                    _ if ctx.is_path_qualifier => (),
                     hir::ImplItem::Method(func) => {
                         let sig = func.signature(ctx.db);
                         if !sig.has_self_param() {
@@ -46,11 +54,20 @@ pub(super) fn complete_path(acc: &mut Completions, ctx: &CompletionContext) {
                         }
                     }
                     hir::ImplItem::Const(ct) => acc.add_const(ctx, ct),
-                    hir::ImplItem::TypeAlias(ty) => acc.add_type_alias(ctx, ty),
                 }
                 None::<()>
             });
         }
+        hir::ModuleDef::Trait(t) => {
+            for item in t.items(ctx.db) {
+                match item {
+                    hir::TraitItem::TypeAlias(ty) => acc.add_type_alias(ctx, ty),
+                    _ if ctx.is_path_qualifier => (),
+                    hir::TraitItem::Function(func) => acc.add_function(ctx, func),
+                    hir::TraitItem::Const(ct) => acc.add_const(ctx, ct),
+                }
+            }
+        }
         _ => return,
     };
 }
@@ -59,7 +76,10 @@ pub(super) fn complete_path(acc: &mut Completions, ctx: &CompletionContext) {
 mod tests {
     use test_utils::covers;
 
-    use crate::completion::{CompletionKind, check_completion, do_completion};
+    use crate::completion::{
+        CompletionItemKind, CompletionKind, check_completion, do_completion,
+        do_completion_with_filter,
+    };
 
     fn check_reference_completion(code: &str, expected_completions: &str) {
         check_completion(code, expected_completions, CompletionKind::Reference);
@@ -259,4 +279,113 @@ mod tests {
             ",
         );
     }
+
+    #[test]
+    fn dont_complete_doc_hidden_items_from_other_crates() {
+        let code = r#"
+            //- /main.rs
+            use foo::<|>;
+
+            //- /foo/lib.rs
+            pub fn visible() {}
+            #[doc(hidden)]
+            pub fn hidden() {}
+            #[unstable(feature = "foo_unstable", issue = "0")]
+            pub fn unstable() {}
+            "#;
+        let labels = |hide_unavailable| {
+            do_completion_with_filter(code, CompletionKind::Reference, hide_unavailable)
+                .into_iter()
+                .map(|it| it.label().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels(true), vec!["visible"]);
+        assert_eq!(labels(false), vec!["hidden", "unstable", "visible"]);
+    }
+
+    #[test]
+    fn completes_unstable_items_with_feature_enabled() {
+        let completions = do_completion(
+            r#"
+            //- /main.rs
+            #![feature(foo_unstable)]
+            use foo::<|>;
+
+            //- /foo/lib.rs
+            #[unstable(feature = "foo_unstable", issue = "0")]
+            pub fn unstable() {}
+            "#,
+            CompletionKind::Reference,
+        );
+        assert_eq!(completions.len(), 1);
+        assert_eq!(completions[0].label(), "unstable");
+    }
+
+    #[test]
+    fn completes_doc_hidden_items_from_current_crate() {
+        let completions = do_completion(
+            "
+            //- /lib.rs
+            mod m {
+                #[doc(hidden)]
+                pub fn hidden() {}
+            }
+            use crate::m::<|>;
+            ",
+            CompletionKind::Reference,
+        );
+        assert_eq!(completions.len(), 1);
+        assert_eq!(completions[0].label(), "hidden");
+    }
+
+    #[test]
+    fn completes_trait_associated_items() {
+        let code = |path| {
+            format!(
+                "
+                //- /lib.rs
+                trait Iterator {{
+                    type Item;
+                    const SIZE: usize;
+                    fn next(&mut self) -> Option<Self::Item>;
+                }}
+                fn foo<T: Iterator>() {{ let _ = {}<|> }}
+                ",
+                path
+            )
+        };
+        for path in ["Iterator::", "<T as Iterator>::"].iter() {
+            let completions = do_completion(&code(path), CompletionKind::Reference);
+            let items = completions
+                .iter()
+                .map(|it| (it.label().to_string(), it.kind()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                items,
+                vec![
+                    ("Item".to_string(), Some(CompletionItemKind::TypeAlias)),
+                    ("SIZE".to_string(), Some(CompletionItemKind::Const)),
+                    ("next".to_string(), Some(CompletionItemKind::Method)),
+                ],
+            );
+        }
+    }
+
+    #[test]
+    fn completes_middle_segment_of_path() {
+        let completions = do_completion(
+            r"
+            mod std {
+                pub mod collections { pub struct HashMap; }
+                pub struct Vec;
+                pub fn column() {}
+                pub const COUNT: usize = 0;
+            }
+            fn foo() { let _ = std::col<|>::HashMap; }
+            ",
+            CompletionKind::Reference,
+        );
+        let labels = completions.iter().map(|it| it.label().to_string()).collect::<Vec<_>>();
+        assert_eq!(labels, vec!["Vec", "collections"]);
+    }
 }
@@ -14,6 +14,7 @@ use ra_syntax::{
     TextRange
 };
 use ra_text_edit::TextEditBuilder;
+use hir::{Ty, AdtDef};
 
 fn postfix_snippet(ctx: &CompletionContext, label: &str, detail: &str, snippet: &str) -> Builder {
     let edit = {
@@ -51,12 +52,25 @@ pub(super) fn complete_postfix(acc: &mut Completions, ctx: &CompletionContext) {
         )
         .add_to(acc);
         postfix_snippet(ctx, "dbg", "dbg!(expr)", &format!("dbg!({})", receiver_text)).add_to(acc);
+        if ctx.dot_receiver_ty().map_or(false, |it| is_option_or_result(ctx, &it)) {
+            postfix_snippet(ctx, "try", "expr?", &format!("{}?", receiver_text)).add_to(acc);
+        }
+    }
+}
+
+fn is_option_or_result(ctx: &CompletionContext, ty: &Ty) -> bool {
+    match ty {
+        Ty::Adt { def_id: AdtDef::Enum(e), .. } => match e.name(ctx.db) {
+            Some(name) => ["Option", "Result"].contains(&name.to_string().as_str()),
+            None => false,
+        },
+        _ => false,
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::completion::{CompletionKind, check_completion};
+    use crate::completion::{CompletionKind, check_completion, do_completion};
 
     fn check_snippet_completion(test_name: &str, code: &str) {
         check_completion(test_name, code, CompletionKind::Postfix);
@@ -74,4 +88,22 @@ mod tests {
             "#,
         );
     }
+
+    #[test]
+    fn postfix_try_completion_for_result() {
+        let code = r#"
+            enum Result<T, E> { Ok(T), Err(E) }
+            fn bar() -> Result<u32, ()> { loop {} }
+            fn main() -> Result<(), ()> {
+                bar().<|>
+            }
+            "#;
+        let labels = |code: &str| {
+            let completions = do_completion(code, CompletionKind::Postfix);
+            completions.into_iter().map(|it| it.label().to_string()).collect::<Vec<_>>()
+        };
+        assert!(labels(code).contains(&"try".to_string()));
+        let code = code.replace("fn bar() -> Result<u32, ()>", "fn bar() -> u32");
+        assert!(!labels(&code).contains(&"try".to_string()));
+    }
 }
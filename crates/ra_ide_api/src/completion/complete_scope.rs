@@ -1,3 +1,5 @@
+use hir::{AdtDef, Enum, ModuleDef, Resolution, Ty};
+
 use crate::completion::{Completions, CompletionContext};
 
 pub(super) fn complete_scope(acc: &mut Completions, ctx: &CompletionContext) {
@@ -6,12 +8,64 @@ pub(super) fn complete_scope(acc: &mut Completions, ctx: &CompletionContext) {
     }
     let names = ctx.resolver.all_names(ctx.db);
 
+    // Offer the variants of the expected enum, even if they are not in scope.
+    if let Some(Ty::Adt { def_id: AdtDef::Enum(e), .. }) = &ctx.expected_type {
+        let enum_res = Resolution::Def(ModuleDef::Enum(*e));
+        let qualifier =
+            match names.iter().find(|(_, res)| res.as_ref().take_types() == Some(&enum_res)) {
+                Some((enum_name, _)) => Some(enum_name.to_string()),
+                None => enum_path(ctx, *e),
+            };
+        if let Some(qualifier) = qualifier {
+            for variant in e.variants(ctx.db) {
+                let variant_res = Resolution::Def(ModuleDef::EnumVariant(variant));
+                let in_scope =
+                    names.values().any(|res| res.as_ref().take_values() == Some(&variant_res));
+                if !in_scope {
+                    acc.add_qualified_enum_variant(ctx, qualifier.clone(), variant);
+                }
+            }
+        }
+    }
+
     names.into_iter().for_each(|(name, res)| acc.add_resolution(ctx, name.to_string(), &res));
 }
 
+/// A path to an enum which is not in scope: relative to the current module if
+/// the enum is defined in it or its submodules, and absolute otherwise.
+fn enum_path(ctx: &CompletionContext, e: Enum) -> Option<String> {
+    let module = ctx.module?;
+    let enum_module = e.module(ctx.db);
+    // From the module of the enum up to the crate root.
+    let modules = enum_module.path_to_root(ctx.db);
+    let mut segments = Vec::new();
+    let inner_modules = match modules.iter().position(|it| *it == module) {
+        Some(idx) => &modules[..idx],
+        None => {
+            let krate = enum_module.krate(ctx.db)?;
+            if module.krate(ctx.db) == Some(krate) {
+                segments.push("crate".to_string());
+            } else {
+                let dependencies = module.krate(ctx.db)?.dependencies(ctx.db);
+                let dependency = dependencies.into_iter().find(|it| it.krate == krate)?;
+                segments.push(dependency.name.to_string());
+            }
+            &modules[..modules.len() - 1]
+        }
+    };
+    for module in inner_modules.iter().rev() {
+        segments.push(module.name(ctx.db)?.to_string());
+    }
+    segments.push(e.name(ctx.db)?.to_string());
+    Some(segments.join("::"))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::completion::{CompletionKind, check_completion};
+    use crate::completion::{
+        CompletionKind, CompletionOptions, CompletionScore, check_completion, test_options,
+    };
+    use crate::mock_analysis::single_file_with_position;
 
     fn check_reference_completion(name: &str, code: &str) {
         check_completion(name, code, CompletionKind::Reference);
@@ -179,4 +233,183 @@ mod tests {
             ",
         );
     }
+
+    /// Completes at `<|>` and returns the labels of the scored items, which
+    /// must come first.
+    fn scored_labels(code: &str) -> Vec<String> {
+        let (analysis, position) = single_file_with_position(code);
+        let completions =
+            analysis.completions_with_options(position, test_options()).unwrap().unwrap();
+        let n_scored = completions.iter().take_while(|it| it.score().is_some()).count();
+        assert!(completions[n_scored..].iter().all(|it| it.score().is_none()));
+        assert!(completions[..n_scored]
+            .iter()
+            .all(|it| it.score() == Some(CompletionScore::TypeMatch)));
+        completions[..n_scored].iter().map(|it| it.label().to_string()).collect()
+    }
+
+    #[test]
+    fn completes_variants_of_expected_enum_first() {
+        let labels = scored_labels(
+            r"
+            enum MyEnum { A, B(u32) }
+            struct S;
+            fn foo(s: S, e: MyEnum) {}
+            fn main() {
+                let s = S;
+                foo(s, <|>)
+            }
+            ",
+        );
+        assert_eq!(labels, vec!["MyEnum::A", "MyEnum::B"]);
+    }
+
+    #[test]
+    fn completes_variants_of_expected_enum_which_is_not_in_scope() {
+        let code = r"
+            mod m {
+                pub mod n { pub enum MyEnum { A, B(u32) } }
+                pub fn foo(e: n::MyEnum) {}
+            }
+            fn main() {
+                m::foo(<|>)
+            }
+            ";
+        assert_eq!(scored_labels(code), vec!["m::n::MyEnum::A", "m::n::MyEnum::B"]);
+        let (analysis, position) = single_file_with_position(code);
+        let completions =
+            analysis.completions_with_options(position, test_options()).unwrap().unwrap();
+        let variant = completions.iter().find(|it| it.label() == "m::n::MyEnum::B").unwrap();
+        assert_eq!(variant.lookup(), "B");
+
+        let labels = scored_labels(
+            r"
+            mod m {
+                pub enum MyEnum { A }
+                fn foo(e: MyEnum) {}
+                mod inner {
+                    fn main() { super::foo(<|>) }
+                }
+            }
+            ",
+        );
+        assert_eq!(labels, vec!["crate::m::MyEnum::A"]);
+    }
+
+    #[test]
+    fn completes_matching_bindings_and_functions_first() {
+        let labels = scored_labels(
+            r"
+            struct S;
+            struct T;
+            fn make_s() -> S { S }
+            fn make_t() -> T { T }
+            fn foo(t: T, s: S) -> S {
+                <|>
+            }
+            ",
+        );
+        assert_eq!(labels.len(), 3);
+        for label in ["s", "make_s", "foo"].iter() {
+            assert!(labels.contains(&label.to_string()));
+        }
+
+        let labels = scored_labels(
+            r"
+            struct S;
+            struct T;
+            fn foo(t: T, other: S) {
+                let s: S = <|>;
+            }
+            ",
+        );
+        assert_eq!(labels, vec!["other"]);
+    }
+
+    #[test]
+    fn completes_variants_in_scope_for_method_argument() {
+        let labels = scored_labels(
+            r"
+            enum E { A, B }
+            use E::*;
+            struct S;
+            impl S { fn take(&self, e: E) {} }
+            fn main(s: S) {
+                s.take(<|>)
+            }
+            ",
+        );
+        assert_eq!(labels.len(), 2);
+        assert!(labels.contains(&"A".to_string()));
+        assert!(labels.contains(&"B".to_string()));
+    }
+
+    #[test]
+    fn does_not_score_when_unit_is_expected() {
+        assert!(scored_labels(
+            r"
+            fn foo() {}
+            fn main() {
+                let x = ();
+                <|>
+            }
+            ",
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn completes_matching_bindings_first_in_struct_update() {
+        let labels = scored_labels(
+            r"
+            struct Foo { x: u32, y: u32 }
+            struct Bar { x: u32 }
+            fn main(bar: Bar) {
+                let base = Foo { x: 0, y: 0 };
+                let foo = Foo { x: 1, ..<|> };
+            }
+            ",
+        );
+        assert_eq!(labels, vec!["base"]);
+    }
+
+    #[test]
+    fn completes_nearby_names_first() {
+        let code = r"
+            fn main() {
+                let far_value = 1;
+                let a = 1;
+                let b = 1;
+                let c = 1;
+                let d = 1;
+                let e = 1;
+                let f = 1;
+                let near_value = 2;
+                let g = near_value;
+
+                <|>
+            }
+            ";
+        let (analysis, position) = single_file_with_position(code);
+        let labels = |options| {
+            let completions = analysis.completions_with_options(position, options).unwrap();
+            completions
+                .unwrap()
+                .iter()
+                .filter(|it| it.label().ends_with("_value"))
+                .map(|it| (it.label().to_string(), it.score()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            labels(CompletionOptions::default()),
+            vec![
+                ("near_value".to_string(), Some(CompletionScore::Nearby)),
+                ("far_value".to_string(), None),
+            ]
+        );
+        assert_eq!(
+            labels(CompletionOptions { nearby_lines: 1, ..CompletionOptions::default() }),
+            vec![("far_value".to_string(), None), ("near_value".to_string(), None)]
+        );
+    }
 }
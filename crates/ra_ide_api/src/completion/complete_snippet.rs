@@ -10,9 +10,20 @@ pub(super) fn complete_expr_snippet(acc: &mut Completions, ctx: &CompletionConte
     if !(ctx.is_trivial_path && ctx.function_syntax.is_some()) {
         return;
     }
+    if ctx.is_struct_update_base {
+        snippet(ctx, "Default::default()", "Default::default()").add_to(acc);
+        return;
+    }
 
     snippet(ctx, "pd", "eprintln!(\"$0 = {:?}\", $0);").add_to(acc);
     snippet(ctx, "ppd", "eprintln!(\"$0 = {:#?}\", $0);").add_to(acc);
+
+    if ctx.is_expr {
+        snippet(ctx, "todo!()", "todo!()").add_to(acc);
+        snippet(ctx, "unimplemented!()", "unimplemented!()").add_to(acc);
+        snippet(ctx, "unreachable!()", "unreachable!()").add_to(acc);
+        snippet(ctx, "panic!()", "panic!(\"$0\")").add_to(acc);
+    }
 }
 
 pub(super) fn complete_item_snippet(acc: &mut Completions, ctx: &CompletionContext) {
@@ -36,7 +47,7 @@ fn ${1:feature}() {
 
 #[cfg(test)]
 mod tests {
-    use crate::completion::{CompletionKind, check_completion};
+    use crate::completion::{CompletionKind, check_completion, do_completion};
 
     fn check_snippet_completion(name: &str, code: &str) {
         check_completion(name, code, CompletionKind::Snippet);
@@ -71,4 +82,30 @@ mod tests {
             ",
         );
     }
+
+    #[test]
+    fn completes_default_in_struct_update() {
+        let completions = do_completion(
+            r"
+            struct Foo { x: u32, y: u32 }
+            fn main() { Foo { x: 1, ..<|> }; }
+            ",
+            CompletionKind::Snippet,
+        );
+        let labels = completions.iter().map(|it| it.label()).collect::<Vec<_>>();
+        assert_eq!(labels, vec!["Default::default()"]);
+    }
+
+    #[test]
+    fn completes_panicking_macros_in_expressions_only() {
+        let labels = |code: &str| {
+            let completions = do_completion(code, CompletionKind::Snippet);
+            completions.iter().map(|it| it.label().to_string()).collect::<Vec<_>>()
+        };
+        let in_body = labels(r"fn foo() -> u32 { <|> }");
+        for label in ["todo!()", "unimplemented!()", "unreachable!()", "panic!()"].iter() {
+            assert!(in_body.contains(&label.to_string()));
+        }
+        assert!(!labels(r"fn foo() { let x: <|> }").contains(&"todo!()".to_string()));
+    }
 }
@@ -1,13 +1,14 @@
 use ra_text_edit::AtomTextEdit;
 use ra_syntax::{
-    AstNode, SyntaxNode, SourceFile, TextUnit, TextRange,
-    ast,
+    AstNode, SyntaxNode, SourceFile, TextUnit, TextRange, SmolStr,
+    ast::{self, TypeAscriptionOwner},
     algo::{find_leaf_at_offset, find_covering_node, find_node_at_offset},
     SyntaxKind::*,
 };
-use hir::{source_binder, Resolver};
+use hir::{source_binder, Docs, Documentation, Resolver};
+use rustc_hash::FxHashSet;
 
-use crate::{db, FilePosition};
+use crate::{db, FilePosition, completion::CompletionOptions};
 
 /// `CompletionContext` is created early during completion to figure out, where
 /// exactly is the cursor, syntax-wise.
@@ -22,7 +23,12 @@ pub(crate) struct CompletionContext<'a> {
     pub(super) function_syntax: Option<&'a ast::FnDef>,
     pub(super) use_item_syntax: Option<&'a ast::UseItem>,
     pub(super) struct_lit_syntax: Option<&'a ast::StructLit>,
+    /// The attribute the cursor is in, if any.
+    pub(super) attribute_syntax: Option<&'a ast::Attr>,
     pub(super) is_param: bool,
+    /// If the parameter is the first one of a function in an impl or a
+    /// trait, where a `self` receiver can go.
+    pub(super) is_first_method_param: bool,
     /// If a name-binding or reference to a const in a pattern.
     /// Irrefutable patterns (like let) are excluded.
     pub(super) is_pat_binding: bool,
@@ -30,6 +36,9 @@ pub(crate) struct CompletionContext<'a> {
     pub(super) is_trivial_path: bool,
     /// If not a trivial, path, the prefix (qualifier).
     pub(super) path_prefix: Option<hir::Path>,
+    /// Whether more segments follow the one being completed, like `col` in
+    /// `std::col<|>::HashMap`.
+    pub(super) is_path_qualifier: bool,
     pub(super) after_if: bool,
     /// `true` if we are a statement or a last expr in the block.
     pub(super) can_be_stmt: bool,
@@ -39,6 +48,21 @@ pub(crate) struct CompletionContext<'a> {
     pub(super) dot_receiver: Option<&'a ast::Expr>,
     /// If this is a call (method or function) in particular, i.e. the () are already there.
     pub(super) is_call: bool,
+    /// The type the completed expression should have, like the type of the
+    /// parameter in `foo(<|>)`.
+    pub(super) expected_type: Option<hir::Ty>,
+    /// Whether the path is an expression, rather than a type or a pattern.
+    pub(super) is_expr: bool,
+    /// Whether this is the base of a struct update, as in `Foo { x, ..<|> }`.
+    pub(super) is_struct_update_base: bool,
+    /// Whether to hide `#[doc(hidden)]` items from other crates, and unstable
+    /// items whose feature is not enabled. Only disabled in tests.
+    pub(super) hide_unavailable: bool,
+    /// Features enabled in the crate we are completing in.
+    pub(super) features: Vec<SmolStr>,
+    /// The names in the lines around the cursor, except the one being typed.
+    pub(super) nearby_names: FxHashSet<String>,
+    pub(super) options: CompletionOptions,
 }
 
 impl<'a> CompletionContext<'a> {
@@ -46,6 +70,7 @@ impl<'a> CompletionContext<'a> {
         db: &'a db::RootDatabase,
         original_file: &'a SourceFile,
         position: FilePosition,
+        options: CompletionOptions,
     ) -> Option<CompletionContext<'a>> {
         let resolver = source_binder::resolver_for_position(db, position);
         let module = source_binder::module_from_position(db, position);
@@ -60,15 +85,28 @@ impl<'a> CompletionContext<'a> {
             function_syntax: None,
             use_item_syntax: None,
             struct_lit_syntax: None,
+            attribute_syntax: None,
             is_param: false,
+            is_first_method_param: false,
             is_pat_binding: false,
             is_trivial_path: false,
             path_prefix: None,
+            is_path_qualifier: false,
             after_if: false,
             can_be_stmt: false,
             is_new_item: false,
             dot_receiver: None,
             is_call: false,
+            expected_type: None,
+            is_expr: false,
+            is_struct_update_base: false,
+            hide_unavailable: true,
+            features: module
+                .and_then(|it| it.krate(db))
+                .map(|it| it.features(db))
+                .unwrap_or_default(),
+            nearby_names: nearby_names(original_file, position.offset, options.nearby_lines),
+            options,
         };
         ctx.fill(original_file, position.offset);
         Some(ctx)
@@ -83,7 +121,63 @@ impl<'a> CompletionContext<'a> {
         }
     }
 
+    /// The type of `dot_receiver`, if it could be inferred.
+    pub(super) fn dot_receiver_ty(&self) -> Option<hir::Ty> {
+        let function = self.function?;
+        let expr = function.body_source_map(self.db).node_expr(self.dot_receiver?)?;
+        Some(function.infer(self.db)[expr].clone())
+    }
+
+    /// The documentation of `def`, unless it is left for
+    /// `Analysis::resolve_completion` to compute.
+    pub(super) fn docs(&self, def: impl Docs) -> Option<Documentation> {
+        if self.options.resolve_lazily {
+            return None;
+        }
+        let docs = def.docs(self.db)?;
+        Some(Documentation::new(&crate::hover::rewrite_doc_links(docs.contents())))
+    }
+
+    /// Whether the cursor is in an `async fn` or an `async` block, where
+    /// futures can be awaited.
+    pub(super) fn is_in_async_context(&self) -> bool {
+        for node in self.leaf.ancestors() {
+            if node.children().any(|it| it.kind() == ASYNC_KW) {
+                return true;
+            }
+            if node.kind() == FN_DEF || node.kind() == LAMBDA_EXPR {
+                return false;
+            }
+        }
+        false
+    }
+
+    /// Checks if an item from `krate` with the given attributes should be left
+    /// out of completions.
+    pub(super) fn is_hidden(&self, krate: Option<hir::Crate>, attrs: &hir::Attrs) -> bool {
+        if !self.hide_unavailable {
+            return false;
+        }
+        let is_local = krate.is_some() && krate == self.module.and_then(|it| it.krate(self.db));
+        if is_local {
+            return false;
+        }
+        if attrs.is_doc_hidden() {
+            return true;
+        }
+        match attrs.unstable_feature() {
+            Some(feature) => !self.features.contains(feature),
+            None => false,
+        }
+    }
+
     fn fill(&mut self, original_file: &'a SourceFile, offset: TextUnit) {
+        // Attributes are token trees, so there's nothing to classify inside.
+        self.attribute_syntax = self.leaf.ancestors().find_map(ast::Attr::cast);
+        if self.attribute_syntax.is_some() {
+            return;
+        }
+
         // Insert a fake ident to get a valid parse tree. We will use this file
         // to determine context, though the original_file will be used for
         // actual completion.
@@ -98,6 +192,7 @@ impl<'a> CompletionContext<'a> {
             // See RFC#1685.
             if is_node::<ast::Param>(name_ref.syntax()) {
                 self.is_param = true;
+                self.is_first_method_param = is_first_method_param(name_ref.syntax());
                 return;
             }
             self.classify_name_ref(original_file, name_ref);
@@ -117,6 +212,7 @@ impl<'a> CompletionContext<'a> {
             }
             if is_node::<ast::Param>(name.syntax()) {
                 self.is_param = true;
+                self.is_first_method_param = is_first_method_param(name.syntax());
                 return;
             }
         }
@@ -164,6 +260,17 @@ impl<'a> CompletionContext<'a> {
                 .and_then(|it| it.syntax().parent().and_then(ast::CallExpr::cast))
                 .is_some();
 
+            let path_expr = path.syntax().parent().and_then(ast::Expr::cast);
+            self.is_expr = path_expr.is_some();
+            // The base is the only expression directly in the field list.
+            self.is_struct_update_base = path_expr
+                .and_then(|it| it.syntax().parent())
+                .map_or(false, |it| it.kind() == NAMED_FIELD_LIST);
+            if let (Some(function), Some(expr)) = (self.function, path_expr) {
+                self.expected_type = self.infer_expected_type(original_file, function, expr);
+            }
+            self.is_path_qualifier = path.syntax().parent().map_or(false, |it| it.kind() == PATH);
+
             if let Some(mut path) = hir::Path::from_ast(path) {
                 if !path.is_ident() {
                     path.segments.pop().unwrap();
@@ -171,6 +278,10 @@ impl<'a> CompletionContext<'a> {
                     return;
                 }
             }
+            if let Some(trait_path) = path.qualifier().and_then(qualified_trait_path) {
+                self.path_prefix = Some(trait_path);
+                return;
+            }
             if path.qualifier().is_none() {
                 self.is_trivial_path = true;
 
@@ -221,6 +332,93 @@ impl<'a> CompletionContext<'a> {
             self.is_call = true;
         }
     }
+
+    /// Figures out the type of `expr`, the expression being completed, from
+    /// where it is: an argument, the initializer of a `let` with a type, the
+    /// base of a struct update, or the value returned from `function`.
+    fn infer_expected_type(
+        &self,
+        original_file: &SourceFile,
+        function: hir::Function,
+        expr: &ast::Expr,
+    ) -> Option<hir::Ty> {
+        let expr_range = expr.syntax().range();
+        let parent = expr.syntax().parent()?;
+        let infer = function.infer(self.db);
+        let source_map = function.body_source_map(self.db);
+        let ty = if let Some(arg_list) = ast::ArgList::cast(parent) {
+            let idx = arg_list.args().position(|it| it.syntax().range() == expr_range)?;
+            // The call up to the `(` is the same in the original file.
+            let call = arg_list.syntax().parent()?;
+            let prefix = TextRange::from_to(
+                call.range().start(),
+                arg_list.syntax().range().start() + TextUnit::of_char('('),
+            );
+            if call.kind() == CALL_EXPR {
+                let call = find_node_with_range::<ast::CallExpr>(original_file.syntax(), prefix)?;
+                let callee = source_map.node_expr(call.expr()?)?;
+                infer[callee].callable_sig(self.db)?.params().get(idx)?.clone()
+            } else {
+                let call =
+                    find_node_with_range::<ast::MethodCallExpr>(original_file.syntax(), prefix)?;
+                let method = infer
+                    .method_resolution(source_map.node_expr(ast::Expr::cast(call.syntax())?)?)?;
+                // The first parameter is `self`.
+                method.ty(self.db).callable_sig(self.db)?.params().get(idx + 1)?.clone()
+            }
+        } else if let Some(let_stmt) = ast::LetStmt::cast(parent) {
+            let_stmt.ascribed_type()?;
+            let pat_range = let_stmt.pat()?.syntax().range();
+            let pat = find_node_with_range::<ast::Pat>(original_file.syntax(), pat_range)?;
+            infer[source_map.node_pat(pat)?].clone()
+        } else if parent.kind() == NAMED_FIELD_LIST {
+            // The struct literal up to the `{` is the same in the original file.
+            let path_range = ast::StructLit::cast(parent.parent()?)?.path()?.syntax().range();
+            let path = find_node_with_range::<ast::Path>(original_file.syntax(), path_range)?;
+            let struct_lit = path.syntax().parent().and_then(ast::StructLit::cast)?;
+            infer[source_map.node_expr(ast::Expr::cast(struct_lit.syntax())?)?].clone()
+        } else if parent.kind() == RETURN_EXPR || is_fn_body_tail(parent, expr_range) {
+            function.ty(self.db).callable_sig(self.db)?.ret().clone()
+        } else {
+            return None;
+        };
+        // Anything fits where `()` is expected, as in the tail of `fn main`.
+        if ty == hir::Ty::Unknown || ty == hir::Ty::unit() {
+            return None;
+        }
+        Some(ty)
+    }
+}
+
+/// The identifiers within `lines` lines of `offset`, other than the one at
+/// `offset`.
+fn nearby_names(file: &SourceFile, offset: TextUnit, lines: u32) -> FxHashSet<String> {
+    if lines == 0 {
+        return FxHashSet::default();
+    }
+    let text = file.syntax().text().to_string();
+    let (before, after) = text.split_at(offset.to_usize());
+    let start = before.rmatch_indices('\n').nth(lines as usize).map_or(0, |(idx, _)| idx);
+    let end = after
+        .match_indices('\n')
+        .nth(lines as usize)
+        .map_or(text.len(), |(idx, _)| before.len() + idx);
+    let range = TextRange::from_to(TextUnit::from_usize(start), TextUnit::from_usize(end));
+    find_covering_node(file.syntax(), range)
+        .descendants()
+        .filter(|it| it.kind() == IDENT && it.range().is_subrange(&range))
+        .filter(|it| !it.range().contains_inclusive(offset))
+        .filter_map(|it| it.leaf_text().map(|it| it.to_string()))
+        .collect()
+}
+
+fn is_fn_body_tail(parent: &SyntaxNode, expr_range: TextRange) -> bool {
+    let block = match ast::Block::cast(parent) {
+        Some(it) => it,
+        None => return false,
+    };
+    block.syntax().parent().map(SyntaxNode::kind) == Some(FN_DEF)
+        && block.expr().map(|it| it.syntax().range()) == Some(expr_range)
 }
 
 fn find_node_with_range<N: AstNode>(syntax: &SyntaxNode, range: TextRange) -> Option<&N> {
@@ -234,3 +432,33 @@ fn is_node<N: AstNode>(node: &SyntaxNode) -> bool {
         Some(n) => n.syntax().range() == node.range(),
     }
 }
+
+fn is_first_method_param(node: &SyntaxNode) -> bool {
+    let param = match node.ancestors().find_map(ast::Param::cast) {
+        Some(it) => it.syntax(),
+        None => return false,
+    };
+    let param_list = match param.parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    let first = param_list.children().find(|it| it.kind() == PARAM || it.kind() == SELF_PARAM);
+    if first != Some(param) {
+        return false;
+    }
+    let container = param_list.parent().and_then(|it| it.parent()).and_then(|it| it.parent());
+    match container.map(|it| it.kind()) {
+        Some(IMPL_BLOCK) | Some(TRAIT_DEF) => true,
+        _ => false,
+    }
+}
+
+/// For a `<T as Trait>` qualifier, returns the path of `Trait`.
+fn qualified_trait_path(qualifier: &ast::Path) -> Option<hir::Path> {
+    let segment = qualifier.segment()?;
+    if segment.syntax().children().all(|it| it.kind() != AS_KW) {
+        return None;
+    }
+    let trait_type = segment.syntax().children().filter_map(ast::PathType::cast).nth(1)?;
+    hir::Path::from_ast(trait_type.path()?)
+}
@@ -4,6 +4,10 @@ use hir::Documentation;
 use ra_syntax::TextRange;
 use ra_text_edit::{TextEditBuilder, TextEdit};
 
+use crate::completion::CompletionResolveId;
+#[cfg(test)]
+use crate::completion::CompletionOptions;
+
 /// `CompletionItem` describes a single completion variant in the editor pop-up.
 /// It is basically a POD with various properties. To construct a
 /// `CompletionItem`, use `new` method and the `Builder` struct.
@@ -42,6 +46,15 @@ pub struct CompletionItem {
     /// Additional info to show in the UI pop up.
     detail: Option<String>,
     documentation: Option<Documentation>,
+
+    /// How well the item fits where it is being completed. Scored items are
+    /// listed before the others.
+    score: Option<CompletionScore>,
+    /// What editors sort by instead of the label, if set.
+    sort_text: Option<String>,
+
+    /// Set if the documentation was left out, to be computed on demand.
+    resolve_id: Option<CompletionResolveId>,
 }
 
 // We use custom debug for CompletionItem to make `insta`'s diffs more readable.
@@ -68,6 +81,9 @@ impl fmt::Debug for CompletionItem {
         if let Some(documentation) = self.documentation() {
             s.field("documentation", &documentation);
         }
+        if let Some(score) = self.score() {
+            s.field("score", &score);
+        }
         s.finish()
     }
 }
@@ -89,6 +105,18 @@ pub enum CompletionItemKind {
     TypeAlias,
     Method,
     TypeParam,
+    Attribute,
+}
+
+/// Why a completion item is a good fit at the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CompletionScore {
+    /// The item has the type which is expected at the cursor.
+    TypeMatch,
+    /// The item is a method which returns `Self`, so more calls can be chained.
+    Builder,
+    /// The item is mentioned in the lines around the cursor.
+    Nearby,
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -101,6 +129,8 @@ pub(crate) enum CompletionKind {
     Magic,
     Snippet,
     Postfix,
+    /// Built-in attributes and lint names.
+    Attribute,
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -127,6 +157,8 @@ impl CompletionItem {
             lookup: None,
             kind: None,
             text_edit: None,
+            score: None,
+            sort_text: None,
         }
     }
     /// What user sees in pop-up in the UI.
@@ -161,6 +193,20 @@ impl CompletionItem {
     pub fn kind(&self) -> Option<CompletionItemKind> {
         self.kind
     }
+
+    pub fn score(&self) -> Option<CompletionScore> {
+        self.score
+    }
+
+    pub fn sort_text(&self) -> Option<&str> {
+        self.sort_text.as_ref().map(|it| it.as_str())
+    }
+
+    /// Pass this to `Analysis::resolve_completion` to get the item together
+    /// with its documentation.
+    pub fn resolve_id(&self) -> Option<&CompletionResolveId> {
+        self.resolve_id.as_ref()
+    }
 }
 
 /// A helper to make `CompletionItem`s.
@@ -176,6 +222,8 @@ pub(crate) struct Builder {
     lookup: Option<String>,
     kind: Option<CompletionItemKind>,
     text_edit: Option<TextEdit>,
+    score: Option<CompletionScore>,
+    sort_text: Option<String>,
 }
 
 impl Builder {
@@ -205,6 +253,9 @@ impl Builder {
             lookup: self.lookup,
             kind: self.kind,
             completion_kind: self.completion_kind,
+            score: self.score,
+            sort_text: self.sort_text,
+            resolve_id: None,
         }
     }
     pub(crate) fn lookup_by(mut self, lookup: impl Into<String>) -> Builder {
@@ -247,6 +298,14 @@ impl Builder {
         self.documentation = docs.map(Into::into);
         self
     }
+    pub(crate) fn set_score(mut self, score: Option<CompletionScore>) -> Builder {
+        self.score = score;
+        self
+    }
+    pub(crate) fn set_sort_text(mut self, sort_text: Option<impl Into<String>>) -> Builder {
+        self.sort_text = sort_text.map(Into::into);
+        self
+    }
 }
 
 impl<'a> Into<CompletionItem> for Builder {
@@ -272,6 +331,16 @@ impl Completions {
     {
         items.into_iter().for_each(|item| self.add(item.into()))
     }
+    /// Moves the scored items to the front, best scores first, keeping the
+    /// order of the items otherwise.
+    pub(crate) fn sort_by_score(&mut self) {
+        self.buf.sort_by_key(|item| (item.score.is_none(), item.score));
+    }
+    pub(crate) fn set_resolve_ids(&mut self, f: impl Fn(usize, &str) -> CompletionResolveId) {
+        for (index, item) in self.buf.iter_mut().enumerate() {
+            item.resolve_id = Some(f(index, &item.label));
+        }
+    }
 }
 
 impl Into<Vec<CompletionItem>> for Completions {
@@ -280,16 +349,53 @@ impl Into<Vec<CompletionItem>> for Completions {
     }
 }
 
+/// The default options, without the preference for nearby names, which would
+/// make the results depend on the code around the cursor.
+#[cfg(test)]
+pub(crate) fn test_options() -> CompletionOptions {
+    CompletionOptions { nearby_lines: 0, ..CompletionOptions::default() }
+}
+
 #[cfg(test)]
 pub(crate) fn do_completion(code: &str, kind: CompletionKind) -> Vec<CompletionItem> {
+    do_completion_with_filter(code, kind, true)
+}
+
+/// Like `do_completion`, but allows to disable filtering of `#[doc(hidden)]`
+/// and unstable items.
+#[cfg(test)]
+pub(crate) fn do_completion_with_filter(
+    code: &str,
+    kind: CompletionKind,
+    hide_unavailable: bool,
+) -> Vec<CompletionItem> {
+    do_completion_impl(code, kind, test_options(), hide_unavailable)
+}
+
+#[cfg(test)]
+pub(crate) fn do_completion_with_options(
+    code: &str,
+    kind: CompletionKind,
+    options: CompletionOptions,
+) -> Vec<CompletionItem> {
+    do_completion_impl(code, kind, options, true)
+}
+
+#[cfg(test)]
+fn do_completion_impl(
+    code: &str,
+    kind: CompletionKind,
+    options: CompletionOptions,
+    hide_unavailable: bool,
+) -> Vec<CompletionItem> {
     use crate::mock_analysis::{single_file_with_position, analysis_and_position};
-    use crate::completion::completions;
+    use crate::completion::completions_impl;
     let (analysis, position) = if code.contains("//-") {
         analysis_and_position(code)
     } else {
         single_file_with_position(code)
     };
-    let completions = completions(&analysis.db, position).unwrap();
+    let completions = completions_impl(&analysis.db, position, options, hide_unavailable).unwrap();
     let completion_items: Vec<CompletionItem> = completions.into();
     let mut kind_completions: Vec<CompletionItem> =
         completion_items.into_iter().filter(|c| c.completion_kind == kind).collect();
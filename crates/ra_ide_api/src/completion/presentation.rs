@@ -1,12 +1,16 @@
 //! This modules takes care of rendering various defenitions as completion items.
 use join_to_string::join;
 use test_utils::tested_by;
-use hir::{Docs, PerNs, Resolution, HirDisplay};
-use ra_syntax::ast::NameOwner;
+use hir::{HasAttrs, PerNs, Resolution, HirDisplay};
+use ra_syntax::{
+    AstNode, TextUnit,
+    ast::{self, NameOwner},
+};
+use ra_text_edit::TextEditBuilder;
 
 use crate::completion::{
     Completions, CompletionKind, CompletionItemKind, CompletionContext, CompletionItem,
-    function_label, const_label, type_label,
+    CompletionScore, function_label, const_label, type_label,
 };
 
 impl Completions {
@@ -23,7 +27,8 @@ impl Completions {
         )
         .kind(CompletionItemKind::Field)
         .detail(field.ty(ctx.db).subst(substs).display(ctx.db).to_string())
-        .set_documentation(field.docs(ctx.db))
+        .set_documentation(ctx.docs(field))
+        .set_sort_text(dot_sort_text(ctx, DotGroup::Field, &field.name(ctx.db).to_string()))
         .add_to(self);
     }
 
@@ -31,6 +36,7 @@ impl Completions {
         CompletionItem::new(CompletionKind::Reference, ctx.source_range(), field.to_string())
             .kind(CompletionItemKind::Field)
             .detail(ty.display(ctx.db).to_string())
+            .set_sort_text(dot_sort_text(ctx, DotGroup::Field, &field.to_string()))
             .add_to(self);
     }
 
@@ -54,18 +60,32 @@ impl Completions {
             }
             Some(it) => it,
         };
+        if let Resolution::Def(def) = def {
+            if ctx.is_hidden(def_crate(ctx, *def), &def.attrs(ctx.db)) {
+                return;
+            }
+        }
+        let score = match def {
+            Resolution::Def(EnumVariant(it)) => variant_score(ctx, *it),
+            Resolution::LocalBinding(pat) => match ctx.function {
+                Some(function) => type_score(ctx, &function.infer(ctx.db)[*pat]),
+                None => None,
+            },
+            _ => None,
+        }
+        .or_else(|| nearby_score(ctx, &local_name));
         let (kind, docs) = match def {
-            Resolution::Def(Module(it)) => (CompletionItemKind::Module, it.docs(ctx.db)),
+            Resolution::Def(Module(it)) => (CompletionItemKind::Module, ctx.docs(*it)),
             Resolution::Def(Function(func)) => {
                 return self.add_function_with_name(ctx, Some(local_name), *func);
             }
-            Resolution::Def(Struct(it)) => (CompletionItemKind::Struct, it.docs(ctx.db)),
-            Resolution::Def(Enum(it)) => (CompletionItemKind::Enum, it.docs(ctx.db)),
-            Resolution::Def(EnumVariant(it)) => (CompletionItemKind::EnumVariant, it.docs(ctx.db)),
-            Resolution::Def(Const(it)) => (CompletionItemKind::Const, it.docs(ctx.db)),
-            Resolution::Def(Static(it)) => (CompletionItemKind::Static, it.docs(ctx.db)),
-            Resolution::Def(Trait(it)) => (CompletionItemKind::Trait, it.docs(ctx.db)),
-            Resolution::Def(TypeAlias(it)) => (CompletionItemKind::TypeAlias, it.docs(ctx.db)),
+            Resolution::Def(Struct(it)) => (CompletionItemKind::Struct, ctx.docs(*it)),
+            Resolution::Def(Enum(it)) => (CompletionItemKind::Enum, ctx.docs(*it)),
+            Resolution::Def(EnumVariant(it)) => (CompletionItemKind::EnumVariant, ctx.docs(*it)),
+            Resolution::Def(Const(it)) => (CompletionItemKind::Const, ctx.docs(*it)),
+            Resolution::Def(Static(it)) => (CompletionItemKind::Static, ctx.docs(*it)),
+            Resolution::Def(Trait(it)) => (CompletionItemKind::Trait, ctx.docs(*it)),
+            Resolution::Def(TypeAlias(it)) => (CompletionItemKind::TypeAlias, ctx.docs(*it)),
             Resolution::GenericParam(..) => (CompletionItemKind::TypeParam, None),
             Resolution::LocalBinding(..) => (CompletionItemKind::Binding, None),
             Resolution::SelfType(..) => (
@@ -76,6 +96,7 @@ impl Completions {
         CompletionItem::new(CompletionKind::Reference, ctx.source_range(), local_name)
             .kind(kind)
             .set_documentation(docs)
+            .set_score(score)
             .add_to(self)
     }
 
@@ -89,34 +110,75 @@ impl Completions {
         name: Option<String>,
         func: hir::Function,
     ) {
+        if ctx.is_hidden(func.module(ctx.db).krate(ctx.db), &func.attrs(ctx.db)) {
+            return;
+        }
         let sig = func.signature(ctx.db);
         let name = name.unwrap_or_else(|| sig.name().to_string());
         let (_, ast_node) = func.source(ctx.db);
-        let detail = function_label(&ast_node);
-
-        let mut builder = CompletionItem::new(CompletionKind::Reference, ctx.source_range(), name)
-            .kind(if sig.has_self_param() {
-                CompletionItemKind::Method
-            } else {
-                CompletionItemKind::Function
-            })
-            .set_documentation(func.docs(ctx.db))
-            .set_detail(detail);
+        let mut detail = function_label(&ast_node);
+        let ret = func.ty(ctx.db).callable_sig(ctx.db).map(|it| it.ret().clone());
+        let mut score = ret.as_ref().and_then(|it| type_score(ctx, it));
+        if ctx.dot_receiver.is_some() && ret.map_or(false, |it| returns_self(ctx, func, &it)) {
+            detail = detail.map(|it| format!("{} (builder)", it));
+            score = score.or(Some(CompletionScore::Builder));
+        }
+        let score = score.or_else(|| nearby_score(ctx, &name));
+        let is_trait_method =
+            func.impl_block(ctx.db).map_or(false, |it| it.target_trait(ctx.db).is_some());
+        let group = if is_trait_method { DotGroup::TraitMethod } else { DotGroup::InherentMethod };
+        let sort_text = dot_sort_text(ctx, group, &name);
+
+        let mut builder =
+            CompletionItem::new(CompletionKind::Reference, ctx.source_range(), name.clone())
+                .kind(if sig.has_self_param() {
+                    CompletionItemKind::Method
+                } else {
+                    CompletionItemKind::Function
+                })
+                .set_documentation(ctx.docs(func))
+                .set_detail(detail)
+                .set_score(score)
+                .set_sort_text(sort_text);
+        let mut snippet = None;
         // If not an import, add parenthesis automatically.
         if ctx.use_item_syntax.is_none() && !ctx.is_call {
             tested_by!(inserts_parens_for_function_calls);
-            let snippet =
+            let turbofish =
+                if ctx.options.add_turbofish { turbofish_snippet(ctx, func) } else { None };
+            snippet = Some(
                 if sig.params().is_empty() || sig.has_self_param() && sig.params().len() == 1 {
-                    format!("{}()$0", sig.name())
+                    format!("{}{}()$0", sig.name(), turbofish.unwrap_or_default())
                 } else {
-                    format!("{}($0)", sig.name())
-                };
-            builder = builder.insert_snippet(snippet);
+                    format!("{}{}($0)", sig.name(), turbofish.unwrap_or_default())
+                },
+            );
+        }
+        match (missing_mut_offset(ctx, &ast_node), snippet) {
+            (Some(offset), snippet) => {
+                let mut edit = TextEditBuilder::default();
+                edit.insert(offset, "mut ".to_string());
+                match snippet {
+                    Some(snippet) => {
+                        edit.replace(ctx.source_range(), snippet);
+                        builder = builder.snippet_edit(edit.finish());
+                    }
+                    None => {
+                        edit.replace(ctx.source_range(), name);
+                        builder = builder.text_edit(edit.finish());
+                    }
+                }
+            }
+            (None, Some(snippet)) => builder = builder.insert_snippet(snippet),
+            (None, None) => (),
         }
         self.add(builder)
     }
 
     pub(crate) fn add_const(&mut self, ctx: &CompletionContext, constant: hir::Const) {
+        if ctx.is_hidden(constant.module(ctx.db).krate(ctx.db), &constant.attrs(ctx.db)) {
+            return;
+        }
         let (_file_id, ast_node) = constant.source(ctx.db);
         let name = match ast_node.name() {
             Some(name) => name,
@@ -127,12 +189,15 @@ impl Completions {
 
         CompletionItem::new(CompletionKind::Reference, ctx.source_range(), name.text().to_string())
             .kind(CompletionItemKind::Const)
-            .set_documentation(constant.docs(ctx.db))
+            .set_documentation(ctx.docs(constant))
             .detail(detail)
             .add_to(self);
     }
 
     pub(crate) fn add_type_alias(&mut self, ctx: &CompletionContext, type_alias: hir::TypeAlias) {
+        if ctx.is_hidden(type_alias.module(ctx.db).krate(ctx.db), &type_alias.attrs(ctx.db)) {
+            return;
+        }
         let (_file_id, type_def) = type_alias.source(ctx.db);
         let name = match type_def.name() {
             Some(name) => name,
@@ -143,12 +208,34 @@ impl Completions {
 
         CompletionItem::new(CompletionKind::Reference, ctx.source_range(), name.text().to_string())
             .kind(CompletionItemKind::TypeAlias)
-            .set_documentation(type_alias.docs(ctx.db))
+            .set_documentation(ctx.docs(type_alias))
             .detail(detail)
             .add_to(self);
     }
 
     pub(crate) fn add_enum_variant(&mut self, ctx: &CompletionContext, variant: hir::EnumVariant) {
+        self.add_enum_variant_with_qualifier(ctx, None, variant)
+    }
+
+    /// Adds `qualifier::Variant`, for variants which are not in scope.
+    pub(crate) fn add_qualified_enum_variant(
+        &mut self,
+        ctx: &CompletionContext,
+        qualifier: String,
+        variant: hir::EnumVariant,
+    ) {
+        self.add_enum_variant_with_qualifier(ctx, Some(qualifier), variant)
+    }
+
+    fn add_enum_variant_with_qualifier(
+        &mut self,
+        ctx: &CompletionContext,
+        qualifier: Option<String>,
+        variant: hir::EnumVariant,
+    ) {
+        if ctx.is_hidden(variant.module(ctx.db).krate(ctx.db), &variant.attrs(ctx.db)) {
+            return;
+        }
         let name = match variant.name(ctx.db) {
             Some(it) => it,
             None => return,
@@ -159,19 +246,154 @@ impl Completions {
             .surround_with("(", ")")
             .to_string();
 
-        CompletionItem::new(CompletionKind::Reference, ctx.source_range(), name.to_string())
+        let label = match qualifier {
+            Some(qualifier) => format!("{}::{}", qualifier, name),
+            None => name.to_string(),
+        };
+
+        // Qualified variants are still found by their bare name.
+        CompletionItem::new(CompletionKind::Reference, ctx.source_range(), label)
             .kind(CompletionItemKind::EnumVariant)
-            .set_documentation(variant.docs(ctx.db))
+            .lookup_by(name.to_string())
+            .set_documentation(ctx.docs(variant))
             .detail(detail)
+            .set_score(variant_score(ctx, variant))
             .add_to(self);
     }
 }
 
+fn def_crate(ctx: &CompletionContext, def: hir::ModuleDef) -> Option<hir::Crate> {
+    use hir::ModuleDef::*;
+    let module = match def {
+        Module(it) => it,
+        Function(it) => it.module(ctx.db),
+        Struct(it) => it.module(ctx.db),
+        Enum(it) => it.module(ctx.db),
+        EnumVariant(it) => it.module(ctx.db),
+        Const(it) => it.module(ctx.db),
+        Static(it) => it.module(ctx.db),
+        Trait(it) => it.module(ctx.db),
+        TypeAlias(it) => it.module(ctx.db),
+    };
+    module.krate(ctx.db)
+}
+
+fn type_score(ctx: &CompletionContext, ty: &hir::Ty) -> Option<CompletionScore> {
+    let expected = ctx.expected_type.as_ref()?;
+    if ty.could_unify_with(expected) {
+        Some(CompletionScore::TypeMatch)
+    } else {
+        None
+    }
+}
+
+fn nearby_score(ctx: &CompletionContext, name: &str) -> Option<CompletionScore> {
+    if ctx.nearby_names.contains(name) {
+        Some(CompletionScore::Nearby)
+    } else {
+        None
+    }
+}
+
+/// Where to insert `mut` so that a `&mut self` method can be called on the
+/// receiver, if it is an immutable local.
+fn missing_mut_offset(ctx: &CompletionContext, fn_def: &ast::FnDef) -> Option<TextUnit> {
+    let self_param = fn_def.param_list()?.self_param()?;
+    if self_param.flavor() != ast::SelfParamFlavor::MutRef {
+        return None;
+    }
+    let receiver = ctx.dot_receiver?;
+    let path = match receiver.kind() {
+        ast::ExprKind::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    let pat = match ctx.resolver.resolve_path(ctx.db, &hir::Path::from_ast(path)?).take_values()? {
+        Resolution::LocalBinding(pat) => pat,
+        _ => return None,
+    };
+    let function = ctx.function?;
+    // References are reborrowed rather than borrowed mutably.
+    if let hir::Ty::Ref(..) = function.infer(ctx.db)[pat] {
+        return None;
+    }
+    let file = receiver.syntax().ancestors().find_map(ast::SourceFile::cast)?;
+    let ptr = function.body_source_map(ctx.db).pat_syntax(pat)?;
+    let bind_pat = ast::BindPat::cast(ptr.to_node(file))?;
+    if bind_pat.is_mutable() || bind_pat.is_ref() {
+        return None;
+    }
+    Some(bind_pat.syntax().range().start())
+}
+
+/// The kinds of items completed after a `.`, in the order they are offered.
+#[derive(Clone, Copy)]
+enum DotGroup {
+    Field = 1,
+    InherentMethod = 2,
+    TraitMethod = 3,
+}
+
+/// Prefixes the label with its group, so that editors keep the groups apart
+/// when sorting.
+fn dot_sort_text(ctx: &CompletionContext, group: DotGroup, label: &str) -> Option<String> {
+    if ctx.dot_receiver.is_none() || !ctx.options.group_dot_completions {
+        return None;
+    }
+    Some(format!("{}{}", group as u8, label))
+}
+
+/// Whether the method returns `Self` or `&mut Self`, so that calls to it can
+/// be chained like on a builder.
+fn returns_self(ctx: &CompletionContext, func: hir::Function, ret: &hir::Ty) -> bool {
+    let self_ty = match func.impl_block(ctx.db) {
+        Some(it) => it.target_ty(ctx.db),
+        None => return false,
+    };
+    match ret {
+        hir::Ty::Ref(it, hir::Mutability::Mut) => **it == self_ty,
+        _ => *ret == self_ty,
+    }
+}
+
+/// Matches the variant's enum rather than its type, as tuple variants are
+/// constructor functions.
+fn variant_score(ctx: &CompletionContext, variant: hir::EnumVariant) -> Option<CompletionScore> {
+    match ctx.expected_type.as_ref()? {
+        hir::Ty::Adt { def_id: hir::AdtDef::Enum(e), .. } if *e == variant.parent_enum(ctx.db) => {
+            Some(CompletionScore::TypeMatch)
+        }
+        _ => None,
+    }
+}
+
+/// Builds a turbofish like `::<_, ${1:B}>`, with a placeholder for each type
+/// parameter which can't be inferred from the arguments. Returns `None` if
+/// all of them can.
+fn turbofish_snippet(ctx: &CompletionContext, func: hir::Function) -> Option<String> {
+    let params = func.type_params_inferable_from_args(ctx.db);
+    if params.iter().all(|(_, inferable)| *inferable) {
+        return None;
+    }
+    let mut placeholder = 0;
+    let args = params.iter().map(|(name, inferable)| {
+        if *inferable {
+            "_".to_string()
+        } else {
+            placeholder += 1;
+            format!("${{{}:{}}}", placeholder, name)
+        }
+    });
+    Some(format!("::<{}>", join(args).separator(", ").to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use test_utils::covers;
 
-    use crate::completion::{CompletionKind, check_completion};
+    use crate::completion::{
+        CompletionKind, CompletionOptions, check_completion, do_completion_with_options,
+    };
+    use crate::mock_analysis::single_file_with_position;
 
     fn check_reference_completion(code: &str, expected_completions: &str) {
         check_completion(code, expected_completions, CompletionKind::Reference);
@@ -245,4 +467,64 @@ mod tests {
         )
     }
 
+    fn method_insert_text(code: &str, name: &str, add_turbofish: bool) -> String {
+        let options = CompletionOptions { add_turbofish, ..Default::default() };
+        let completions = do_completion_with_options(code, CompletionKind::Reference, options);
+        let item = completions.iter().find(|it| it.label() == name).unwrap();
+        item.text_edit().as_atoms()[0].insert.clone()
+    }
+
+    #[test]
+    fn inserts_turbofish_for_uninferable_type_params() {
+        let code = r"
+            struct Iter {}
+            impl Iter {
+                fn collect<B>(self) -> B {}
+                fn map<F>(self, f: F) -> Iter {}
+                fn fold<B, F>(self, init: B, f: F) -> B {}
+                fn zip<A, U>(self, other: U) -> Iter {}
+            }
+            fn foo(it: Iter) {
+                it.<|>
+            }
+            ";
+        assert_eq!(method_insert_text(code, "collect", true), "collect::<${1:B}>()$0");
+        assert_eq!(method_insert_text(code, "map", true), "map($0)");
+        assert_eq!(method_insert_text(code, "fold", true), "fold($0)");
+        assert_eq!(method_insert_text(code, "zip", true), "zip::<${1:A}, _>($0)");
+    }
+
+    #[test]
+    fn dont_insert_turbofish_by_default() {
+        let code = r"
+            struct Iter {}
+            impl Iter {
+                fn collect<B>(self) -> B {}
+            }
+            fn foo(it: Iter) {
+                it.<|>
+            }
+            ";
+        assert_eq!(method_insert_text(code, "collect", false), "collect()$0");
+    }
+
+    #[test]
+    fn resolves_documentation_lazily() {
+        let (analysis, position) = single_file_with_position(
+            r"
+            /// Does the frobnication.
+            fn frobnicate() {}
+            fn main() { frob<|> }
+            ",
+        );
+        let options = CompletionOptions { resolve_lazily: true, ..Default::default() };
+        let items = analysis.completions_with_options(position, options).unwrap().unwrap();
+        let item = items.iter().find(|it| it.label() == "frobnicate").unwrap();
+        assert!(item.documentation().is_none());
+
+        let resolved = analysis.resolve_completion(item.resolve_id().unwrap()).unwrap().unwrap();
+        assert_eq!(resolved.label(), "frobnicate");
+        assert_eq!(resolved.documentation().unwrap().contents(), "Does the frobnication.");
+        assert!(resolved.resolve_id().is_none());
+    }
 }
@@ -8,7 +8,7 @@ use ra_db::{
     salsa,
 };
 
-use crate::{LineIndex, symbol_index::{self, SymbolsDatabase}};
+use crate::{LineIndex, StructureNode, symbol_index::{self, SymbolsDatabase}};
 
 #[salsa::database(
     ra_db::SourceDatabaseStorage,
@@ -60,6 +60,22 @@ impl salsa::ParallelDatabase for RootDatabase {
     }
 }
 
+impl RootDatabase {
+    /// Yields the items of `iter`, checking for cancellation and calling `poll`
+    /// before each one, so that long loops over a file stop soon after a
+    /// change.
+    pub(crate) fn poll_each<'a, T>(
+        &'a self,
+        iter: impl Iterator<Item = T> + 'a,
+        poll: &'a dyn Fn(),
+    ) -> impl Iterator<Item = T> + 'a {
+        iter.inspect(move |_| {
+            self.check_canceled();
+            poll();
+        })
+    }
+}
+
 impl AsRef<hir::HirInterner> for RootDatabase {
     fn as_ref(&self) -> &hir::HirInterner {
         &self.interner
@@ -69,9 +85,46 @@ impl AsRef<hir::HirInterner> for RootDatabase {
 #[salsa::query_group(LineIndexDatabaseStorage)]
 pub(crate) trait LineIndexDatabase: ra_db::SourceDatabase + CheckCanceled {
     fn line_index(&self, file_id: FileId) -> Arc<LineIndex>;
+    fn file_structure(&self, file_id: FileId) -> Arc<Vec<StructureNode>>;
 }
 
 fn line_index(db: &impl ra_db::SourceDatabase, file_id: FileId) -> Arc<LineIndex> {
     let text = db.file_text(file_id);
     Arc::new(LineIndex::new(&*text))
 }
+
+fn file_structure(db: &impl ra_db::SourceDatabase, file_id: FileId) -> Arc<Vec<StructureNode>> {
+    let file = db.parse(file_id);
+    Arc::new(ra_ide_api_light::file_structure(&file))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{AnalysisChange, mock_analysis::MockAnalysis};
+    use super::LineIndexDatabase;
+
+    #[test]
+    fn file_structure_is_not_recomputed_for_unrelated_changes() {
+        let mut mock = MockAnalysis::new();
+        let foo = mock.add_file("/foo.rs", "struct Foo;");
+        let bar = mock.add_file("/bar.rs", "struct Bar;");
+        let mut host = mock.analysis_host();
+
+        let before = host.analysis().db.file_structure(foo);
+
+        let mut change = AnalysisChange::new();
+        change.change_file(bar, Arc::new("struct Baz;".to_string()));
+        host.apply_change(change);
+        let after = host.analysis().db.file_structure(foo);
+        assert!(Arc::ptr_eq(&before, &after));
+
+        let mut change = AnalysisChange::new();
+        change.change_file(foo, Arc::new("struct Foo; fn foo() {}".to_string()));
+        host.apply_change(change);
+        let after = host.analysis().db.file_structure(foo);
+        assert!(!Arc::ptr_eq(&before, &after));
+        assert_eq!(after.len(), 2);
+    }
+}
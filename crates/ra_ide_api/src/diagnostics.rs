@@ -1,28 +1,44 @@
-use hir::{Problem, source_binder};
-use ra_ide_api_light::Severity;
-use ra_db::SourceDatabase;
+use hir::{ModuleDef, Problem, Resolution, Ty, source_binder};
+use ra_ide_api_light::{LocalEdit, Severity};
+use ra_db::{FileRange, SourceDatabase};
+use ra_syntax::{
+    AstNode, SourceFile, SyntaxNode, TextRange, TextUnit, ast,
+    SyntaxKind::{self, FN_DEF, FN_KW, LAMBDA_EXPR, STRUCT_KW, USE_ITEM, MACRO_CALL},
+};
+use ra_text_edit::TextEditBuilder;
 
-use crate::{Diagnostic, FileId, FileSystemEdit, SourceChange, db::RootDatabase};
+use crate::{
+    Diagnostic, FileId, FileSystemEdit, SourceChange,
+    assists::{self, AssistId},
+    db::RootDatabase,
+};
 
 pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic> {
+    diagnostics_with_poll(db, file_id, &|| ())
+}
+
+/// Computes the diagnostics, calling `poll` between the items it checks.
+pub(crate) fn diagnostics_with_poll(
+    db: &RootDatabase,
+    file_id: FileId,
+    poll: &dyn Fn(),
+) -> Vec<Diagnostic> {
     let syntax = db.parse(file_id);
 
     let mut res = ra_ide_api_light::diagnostics(&syntax)
         .into_iter()
-        .map(|d| Diagnostic {
-            range: d.range,
-            message: d.msg,
-            severity: d.severity,
-            fix: d.fix.map(|fix| SourceChange::from_local_edit(file_id, fix)),
-        })
+        .map(|d| from_light_diagnostic(file_id, d))
         .collect::<Vec<_>>();
     if let Some(m) = source_binder::module_from_file_id(db, file_id) {
-        for (name_node, problem) in m.problems(db) {
+        for (name_node, problem) in db.poll_each(m.problems(db).into_iter(), poll) {
             let source_root = db.file_source_root(file_id);
             let diag = match problem {
                 Problem::UnresolvedModule { candidate } => {
-                    let create_file =
-                        FileSystemEdit::CreateFile { source_root, path: candidate.clone() };
+                    let create_file = FileSystemEdit::CreateFile {
+                        source_root,
+                        path: candidate.clone(),
+                        contents: String::new(),
+                    };
                     let fix = SourceChange {
                         label: "create module".to_string(),
                         source_file_edits: Vec::new(),
@@ -42,8 +58,11 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
                         dst_source_root: source_root,
                         dst_path: move_to.clone(),
                     };
-                    let create_file =
-                        FileSystemEdit::CreateFile { source_root, path: move_to.join(candidate) };
+                    let create_file = FileSystemEdit::CreateFile {
+                        source_root,
+                        path: move_to.join(candidate),
+                        contents: String::new(),
+                    };
                     let fix = SourceChange {
                         label: "move file and create module".to_string(),
                         source_file_edits: Vec::new(),
@@ -61,5 +80,451 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             res.push(diag)
         }
     };
+    check_refutable_let_patterns(db, file_id, &syntax, poll, &mut res);
+    check_private_items(db, file_id, &syntax, poll, &mut res);
+    check_missing_return_values(db, file_id, &syntax, poll, &mut res);
     res
 }
+
+/// The diagnostics which only need the syntax tree of the file.
+pub(crate) fn syntax_diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic> {
+    let syntax = db.parse(file_id);
+    ra_ide_api_light::syntax_diagnostics(&syntax)
+        .into_iter()
+        .map(|d| from_light_diagnostic(file_id, d))
+        .collect()
+}
+
+fn from_light_diagnostic(file_id: FileId, d: ra_ide_api_light::Diagnostic) -> Diagnostic {
+    Diagnostic {
+        range: d.range,
+        message: d.msg,
+        severity: d.severity,
+        fix: d.fix.map(|fix| SourceChange::from_local_edit(file_id, fix)),
+    }
+}
+
+/// `let Some(x) = opt;` doesn't compile: the pattern of a `let` has to match
+/// every value of its type.
+fn check_refutable_let_patterns(
+    db: &RootDatabase,
+    file_id: FileId,
+    file: &SourceFile,
+    poll: &dyn Fn(),
+    acc: &mut Vec<Diagnostic>,
+) {
+    let let_stmts = file.syntax().descendants().filter_map(ast::LetStmt::cast);
+    for let_stmt in db.poll_each(let_stmts, poll) {
+        let pat = match let_stmt.pat() {
+            Some(it) => it,
+            None => continue,
+        };
+        let function = match source_binder::function_from_child_node(db, file_id, let_stmt.syntax())
+        {
+            Some(it) => it,
+            None => continue,
+        };
+        if function.is_refutable_pat(db, pat) != Some(true) {
+            continue;
+        }
+        let fix = let_stmt.initializer().map(|initializer| {
+            let prefix =
+                format!("if let {} = {} {{ ", pat.syntax().text(), initializer.syntax().text());
+            let range = let_stmt.syntax().range();
+            let mut edit_builder = TextEditBuilder::default();
+            edit_builder.replace(range, format!("{}}}", prefix));
+            let edit = LocalEdit {
+                label: "convert to if let".to_string(),
+                edit: edit_builder.finish(),
+                cursor_position: Some(range.start() + TextUnit::of_str(&prefix)),
+            };
+            SourceChange::from_local_edit(file_id, edit)
+        });
+        acc.push(Diagnostic {
+            range: pat.syntax().range(),
+            message: "refutable pattern in local binding".to_string(),
+            severity: Severity::Error,
+            fix,
+        });
+    }
+}
+
+/// A bare `return;` in a function which returns something else than `()`.
+/// There is no fix, as the value can't be guessed.
+fn check_missing_return_values(
+    db: &RootDatabase,
+    file_id: FileId,
+    file: &SourceFile,
+    poll: &dyn Fn(),
+    acc: &mut Vec<Diagnostic>,
+) {
+    let return_exprs = file.syntax().descendants().filter_map(ast::ReturnExpr::cast);
+    for return_expr in db.poll_each(return_exprs, poll) {
+        if return_expr.expr().is_some() {
+            continue;
+        }
+        // Closures have their own, inferred, return type.
+        let in_closure = return_expr
+            .syntax()
+            .ancestors()
+            .take_while(|it| it.kind() != FN_DEF)
+            .any(|it| it.kind() == LAMBDA_EXPR);
+        if in_closure {
+            continue;
+        }
+        let function =
+            match source_binder::function_from_child_node(db, file_id, return_expr.syntax()) {
+                Some(it) => it,
+                None => continue,
+            };
+        let ret_type = match function.ty(db).callable_sig(db) {
+            Some(sig) => sig.ret().clone(),
+            None => continue,
+        };
+        if ret_type == Ty::unit() || ret_type == Ty::Unknown {
+            continue;
+        }
+        acc.push(Diagnostic {
+            range: return_expr.syntax().range(),
+            message: "expected value after return".to_string(),
+            severity: Severity::Error,
+            fix: None,
+        });
+    }
+}
+
+/// Functions and structs referenced from outside of their visibility.
+fn check_private_items(
+    db: &RootDatabase,
+    file_id: FileId,
+    file: &SourceFile,
+    poll: &dyn Fn(),
+    acc: &mut Vec<Diagnostic>,
+) {
+    let paths = file.syntax().descendants().filter_map(ast::Path::cast);
+    for path in db.poll_each(paths, poll) {
+        // The paths in imports are relative to the whole use tree.
+        if path.syntax().ancestors().any(|it| it.kind() == USE_ITEM || it.kind() == MACRO_CALL) {
+            continue;
+        }
+        let (hir_path, module) = match (
+            hir::Path::from_ast(path),
+            source_binder::module_from_child_node(db, file_id, path.syntax()),
+        ) {
+            (Some(hir_path), Some(module)) => (hir_path, module),
+            _ => continue,
+        };
+        let resolver = source_binder::resolver_for_node(db, file_id, path.syntax());
+        let resolution = resolver.resolve_path(db, &hir_path);
+        let def = resolution.as_ref().take_values().or_else(|| resolution.as_ref().take_types());
+        let (is_visible, item) = match def {
+            Some(Resolution::Def(ModuleDef::Function(it))) => {
+                let (file, source) = it.source(db);
+                (it.is_visible_from(db, module), (file, source.syntax().to_owned(), FN_KW))
+            }
+            Some(Resolution::Def(ModuleDef::Struct(it))) => {
+                let (file, source) = it.source(db);
+                (it.is_visible_from(db, module), (file, source.syntax().to_owned(), STRUCT_KW))
+            }
+            _ => continue,
+        };
+        if is_visible {
+            continue;
+        }
+        let range = path.segment().map_or(path.syntax().range(), |it| it.syntax().range());
+        let (item_file, item_syntax, keyword) = item;
+        acc.push(Diagnostic {
+            range,
+            message: "item is private".to_string(),
+            severity: Severity::Error,
+            fix: make_pub_crate_fix(db, item_file.original_file(db), &item_syntax, keyword),
+        });
+    }
+}
+
+/// Runs the change visibility assist on the keyword of the item.
+fn make_pub_crate_fix(
+    db: &RootDatabase,
+    file_id: FileId,
+    item: &SyntaxNode,
+    keyword: SyntaxKind,
+) -> Option<SourceChange> {
+    let keyword = item.children().find(|it| it.kind() == keyword)?;
+    let range = TextRange::offset_len(keyword.range().start(), 0.into());
+    assists::assists(db, FileRange { file_id, range })
+        .into_iter()
+        .find(|it| it.id == AssistId("change_visibility") && it.change.label == "make pub(crate)")
+        .map(|it| it.change)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, mpsc},
+        thread,
+    };
+
+    use ra_db::salsa::Database;
+    use test_utils::assert_eq_text;
+
+    use crate::{
+        AnalysisChange,
+        mock_analysis::{MockAnalysis, single_file},
+    };
+
+    fn check_refutable_let(before: &str, after: &str) {
+        let (analysis, file_id) = single_file(before);
+        let diagnostic = analysis
+            .diagnostics(file_id)
+            .unwrap()
+            .into_iter()
+            .find(|it| it.message == "refutable pattern in local binding")
+            .unwrap_or_else(|| panic!("no refutable pattern in:\n{}\n", before));
+        let edit = &diagnostic.fix.unwrap().source_file_edits[0].edit;
+        assert_eq_text!(after, &edit.apply(before));
+    }
+
+    fn check_no_refutable_let(code: &str) {
+        let (analysis, file_id) = single_file(code);
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert!(diagnostics.iter().all(|it| it.message != "refutable pattern in local binding"));
+    }
+
+    #[test]
+    fn refutable_option_pattern() {
+        check_refutable_let(
+            "
+enum Option<T> { Some(T), None }
+use Option::*;
+fn f(opt: Option<u32>) {
+    let Some(x) = opt;
+}
+",
+            "
+enum Option<T> { Some(T), None }
+use Option::*;
+fn f(opt: Option<u32>) {
+    if let Some(x) = opt { }
+}
+",
+        );
+    }
+
+    #[test]
+    fn refutable_nested_pattern() {
+        check_refutable_let(
+            "
+struct S { a: u32, b: E }
+enum E { A, B(u32) }
+fn f(s: S) {
+    let S { a, b: E::B(_) } = s;
+}
+",
+            "
+struct S { a: u32, b: E }
+enum E { A, B(u32) }
+fn f(s: S) {
+    if let S { a, b: E::B(_) } = s { }
+}
+",
+        );
+        check_refutable_let(
+            "
+enum E { A, B }
+fn f(pair: (u32, E)) {
+    let (x, E::A) = pair;
+}
+",
+            "
+enum E { A, B }
+fn f(pair: (u32, E)) {
+    if let (x, E::A) = pair { }
+}
+",
+        );
+    }
+
+    #[test]
+    fn irrefutable_patterns() {
+        check_no_refutable_let(
+            "
+struct S { a: u32, b: (u32, u32) }
+struct T(u32);
+enum E { Only(u32) }
+fn f(s: S, t: &T, e: E) {
+    let S { a, b: (x, _) } = s;
+    let &T(y) = t;
+    let E::Only(z) = e;
+    let (p, q);
+    let r @ T(_) = T(1);
+}
+",
+        );
+    }
+
+    #[test]
+    fn unknown_types_are_not_refutable() {
+        check_no_refutable_let(
+            "
+fn f() {
+    let Unresolved(x) = unresolved();
+}
+",
+        );
+    }
+
+    fn missing_return_value_ranges(code: &str) -> Vec<String> {
+        let (analysis, file_id) = single_file(code);
+        analysis
+            .diagnostics(file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|it| it.message == "expected value after return")
+            .map(|it| code[it.range].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn bare_return_in_function_returning_value() {
+        let code = "
+fn f(x: u32) -> u32 {
+    if x == 0 {
+        return;
+    }
+    x
+}
+fn g() {
+    return;
+}
+fn h() -> u32 {
+    let c = || { return; };
+    return 92;
+}
+";
+        assert_eq!(missing_return_value_ranges(code), vec!["return"]);
+    }
+
+    fn private_item_ranges(code: &str) -> Vec<String> {
+        let (analysis, file_id) = single_file(code);
+        analysis
+            .diagnostics(file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|it| it.message == "item is private")
+            .map(|it| code[it.range].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn private_function_from_sibling_module() {
+        let code = "
+mod a {
+    fn foo() {}
+    pub fn bar() {}
+    struct S;
+}
+mod b {
+    fn baz() {
+        super::a::foo();
+        super::a::bar();
+        let s: super::a::S;
+    }
+}
+";
+        assert_eq!(private_item_ranges(code), vec!["foo", "S"]);
+
+        let (analysis, file_id) = single_file(code);
+        let diagnostic = analysis.diagnostics(file_id).unwrap().into_iter().next().unwrap();
+        let fix = diagnostic.fix.unwrap();
+        assert_eq!(fix.label, "make pub(crate)");
+        let edit = &fix.source_file_edits[0].edit;
+        assert_eq_text!(
+            &code.replace("    fn foo() {}", "    pub(crate) fn foo() {}"),
+            &edit.apply(code)
+        );
+    }
+
+    #[test]
+    fn visible_items_are_not_private() {
+        let code = "
+fn root() {}
+mod a {
+    mod inner {
+        pub fn reexported() {}
+    }
+    pub use self::inner::reexported;
+    pub(super) fn for_parent() {}
+    pub(crate) fn for_crate() {}
+    mod child {
+        fn f() {
+            super::super::root();
+            super::for_parent();
+        }
+    }
+}
+mod b {
+    use crate::a::reexported;
+    fn f() {
+        reexported();
+        super::a::reexported();
+        super::a::for_crate();
+    }
+}
+fn main() {
+    a::for_parent();
+}
+";
+        assert!(private_item_ranges(code).is_empty());
+    }
+
+    #[test]
+    fn private_item_fix_edits_the_defining_file() {
+        let analysis = MockAnalysis::with_files(
+            "
+            //- /lib.rs
+            mod a;
+            fn main() { a::foo(); }
+            //- /a.rs
+            /// Docs.
+            fn foo() {}
+            ",
+        );
+        let lib = analysis.id_of("/lib.rs");
+        let a = analysis.id_of("/a.rs");
+        let analysis = analysis.analysis();
+        let diagnostics = analysis.diagnostics(lib).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        let fix = diagnostics[0].fix.as_ref().unwrap();
+        assert_eq!(fix.source_file_edits[0].file_id, a);
+        let text = analysis.file_text(a);
+        assert_eq_text!(
+            &text.replace("fn foo", "pub(crate) fn foo"),
+            &fix.source_file_edits[0].edit.apply(&text)
+        );
+    }
+
+    #[test]
+    fn diagnostics_are_canceled_by_changes() {
+        let mut mock = MockAnalysis::new();
+        let file_id = mock.add_file("/lib.rs", &"fn f() { let x = 1; }\n".repeat(100));
+        let mut host = mock.analysis_host();
+        let analysis = host.analysis();
+        let (started_tx, started_rx) = mpsc::channel();
+        let worker = thread::spawn(move || {
+            let res = analysis.diagnostics_with_poll(file_id, &|| {
+                let _ = started_tx.send(());
+                // `apply_change` waits for this snapshot to go away, so it
+                // can't tell us when it is done; wait for the cancellation.
+                while !analysis.db.salsa_runtime().is_current_revision_canceled() {
+                    thread::yield_now();
+                }
+            });
+            res.is_err()
+        });
+        started_rx.recv().unwrap();
+        let mut change = AnalysisChange::new();
+        change.change_file(file_id, Arc::new(String::new()));
+        host.apply_change(change);
+        assert!(worker.join().unwrap());
+    }
+}
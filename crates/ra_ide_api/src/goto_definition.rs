@@ -1,11 +1,12 @@
 use ra_db::{FileId, SourceDatabase};
 use ra_syntax::{
-    AstNode, ast,
-    algo::{find_node_at_offset, visit::{visitor, Visitor}},
+    AstNode, SourceFile, TextUnit, ast,
+    algo::{find_leaf_at_offset, find_node_at_offset, visit::{visitor, Visitor}},
     SyntaxNode,
+    SyntaxKind::{IDENT, COLONCOLON},
 };
 use test_utils::tested_by;
-use hir::Resolution;
+use hir::{Resolution, Resolver};
 
 use crate::{FilePosition, NavigationTarget, db::RootDatabase, RangeInfo};
 
@@ -23,7 +24,7 @@ pub(crate) fn goto_definition(
         let navs = name_definition(db, position.file_id, name)?;
         return Some(RangeInfo::new(name.syntax().range(), navs));
     }
-    None
+    macro_argument_definition(db, position).or_else(|| attribute_path_definition(db, position))
 }
 
 pub(crate) enum ReferenceResult {
@@ -108,24 +109,9 @@ pub(crate) fn reference_definition(
     {
         let resolved = resolver.resolve_path(db, &path);
         match resolved.clone().take_types().or_else(|| resolved.take_values()) {
-            Some(Resolution::Def(def)) => return Exact(NavigationTarget::from_def(db, def)),
-            Some(Resolution::LocalBinding(pat)) => {
-                let body = resolver.body().expect("no body for local binding");
-                let source_map = body.owner().body_source_map(db);
-                let ptr = source_map.pat_syntax(pat).expect("pattern not found in syntax mapping");
-                let name =
-                    path.as_ident().cloned().expect("local binding from a multi-segment path");
-                let nav = NavigationTarget::from_scope_entry(file_id, name, ptr);
-                return Exact(nav);
-            }
-            Some(Resolution::GenericParam(..)) => {
-                // TODO: go to the generic param def
-            }
-            Some(Resolution::SelfType(impl_block)) => {
-                let ty = impl_block.target_ty(db);
-
-                if let hir::Ty::Adt { def_id, .. } = ty {
-                    return Exact(NavigationTarget::from_adt_def(db, def_id));
+            Some(resolution) => {
+                if let Some(nav) = resolution_target(db, file_id, &resolver, &path, resolution) {
+                    return Exact(nav);
                 }
             }
             None => {
@@ -177,6 +163,130 @@ pub(crate) fn reference_definition(
     Approximate(navs)
 }
 
+fn resolution_target(
+    db: &RootDatabase,
+    file_id: FileId,
+    resolver: &Resolver,
+    path: &hir::Path,
+    resolution: Resolution,
+) -> Option<NavigationTarget> {
+    match resolution {
+        Resolution::Def(def) => Some(NavigationTarget::from_def(db, def)),
+        Resolution::LocalBinding(pat) => {
+            let body = resolver.body().expect("no body for local binding");
+            let source_map = body.owner().body_source_map(db);
+            let ptr = source_map.pat_syntax(pat).expect("pattern not found in syntax mapping");
+            let name = path.as_ident().cloned().expect("local binding from a multi-segment path");
+            Some(NavigationTarget::from_scope_entry(file_id, name, ptr))
+        }
+        Resolution::GenericParam(..) => {
+            // TODO: go to the generic param def
+            None
+        }
+        Resolution::SelfType(impl_block) => match impl_block.target_ty(db) {
+            hir::Ty::Adt { def_id, .. } => Some(NavigationTarget::from_adt_def(db, def_id)),
+            _ => None,
+        },
+    }
+}
+
+/// The arguments of macro calls are token trees, without any `NameRef`s. As
+/// we can't expand `macro_rules!` yet, we parse the arguments as expressions,
+/// like `vec!` expands, and resolve the paths in them in the scope of the
+/// call. This at least makes the items passed to a macro resolve like they
+/// would outside of it; local variables are not in scope yet, as macro calls
+/// are not part of function bodies.
+fn macro_argument_definition(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let file = db.parse(position.file_id);
+    let ident =
+        find_leaf_at_offset(file.syntax(), position.offset).find(|it| it.kind() == IDENT)?;
+    let token_tree = ident.ancestors().find_map(ast::TokenTree::cast)?;
+    // Nested token trees are arguments too.
+    let token_tree = token_tree.syntax().ancestors().filter_map(ast::TokenTree::cast).last()?;
+    token_tree.syntax().parent().and_then(ast::MacroCall::cast)?;
+
+    // All delimiters of token trees are a single character.
+    let range = token_tree.syntax().range();
+    let (start, end) = (range.start() + TextUnit::from(1), range.end() - TextUnit::from(1));
+    if start > end {
+        return None;
+    }
+    let args = token_tree.syntax().text().slice(start..end).to_string();
+    token_path_definition(db, position, ident, start, &args)
+}
+
+/// Derives and attribute macros also name items in token trees: the traits
+/// in `#[derive(Clone, m::Debug)]` and the attribute path in
+/// `#[tokio::main]`. They resolve like macro arguments; built-in derives and
+/// macros which are not in scope don't resolve.
+fn attribute_path_definition(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let file = db.parse(position.file_id);
+    let ident =
+        find_leaf_at_offset(file.syntax(), position.offset).find(|it| it.kind() == IDENT)?;
+    let token_tree = ident.parent().and_then(ast::TokenTree::cast)?;
+    let attr = token_tree.syntax().ancestors().find_map(ast::Attr::cast)?;
+    let value = attr.value()?;
+
+    if token_tree == value {
+        // The path of the attribute, up to its arguments.
+        let path = value
+            .syntax()
+            .children()
+            .skip(1)
+            .take_while(|it| it.kind() == IDENT || it.kind() == COLONCOLON)
+            .collect::<Vec<_>>();
+        if !path.contains(&ident) {
+            return None;
+        }
+        let start = path.first()?.range().start();
+        let end = path.last()?.range().end();
+        let text = value.syntax().text().slice(start..end).to_string();
+        return token_path_definition(db, position, ident, start, &text);
+    }
+    match attr.as_call() {
+        Some((name, args)) if name == "derive" && args == token_tree => {
+            let range = args.syntax().range();
+            let start = range.start() + TextUnit::from(1);
+            let end = range.end() - TextUnit::from(1);
+            if start > end {
+                return None;
+            }
+            let text = args.syntax().text().slice(start..end).to_string();
+            token_path_definition(db, position, ident, start, &text)
+        }
+        _ => None,
+    }
+}
+
+/// Resolves the path under the cursor in `text`, the contents of a token tree
+/// starting at `start`, by parsing them as expressions.
+fn token_path_definition(
+    db: &RootDatabase,
+    position: FilePosition,
+    ident: &SyntaxNode,
+    start: TextUnit,
+    text: &str,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let prefix = "fn dummy() { [";
+    let expansion = SourceFile::parse(&format!("{}{}]; }}", prefix, text));
+    let offset = position.offset - start + TextUnit::of_str(prefix);
+    let name_ref = find_node_at_offset::<ast::NameRef>(expansion.syntax(), offset)?;
+    let path = name_ref.syntax().ancestors().find_map(ast::Path::cast)?;
+    let path = hir::Path::from_ast(path)?;
+
+    let resolver = hir::source_binder::resolver_for_position(db, position);
+    let resolved = resolver.resolve_path(db, &path);
+    let resolution = resolved.clone().take_types().or_else(|| resolved.take_values())?;
+    let nav = resolution_target(db, position.file_id, &resolver, &path, resolution)?;
+    Some(RangeInfo::new(ident.range(), vec![nav]))
+}
+
 pub(crate) fn name_definition(
     db: &RootDatabase,
     file_id: FileId,
@@ -221,7 +331,7 @@ fn named_target(file_id: FileId, node: &SyntaxNode) -> Option<NavigationTarget>
 mod tests {
     use test_utils::covers;
 
-    use crate::mock_analysis::analysis_and_position;
+    use crate::{NavigationTargetKind, mock_analysis::analysis_and_position};
 
     fn check_goto(fixture: &str, expected: &str) {
         let (analysis, pos) = analysis_and_position(fixture);
@@ -523,4 +633,102 @@ mod tests {
             "bar MODULE FileId(1) [0; 11) [4; 7)",
         );
     }
+
+    #[test]
+    fn goto_definition_works_for_macro_arguments() {
+        check_goto(
+            "
+            //- /lib.rs
+            macro_rules! call {
+                ($f:expr) => { $f() };
+            }
+            fn foo() {}
+            fn main() {
+                call!(fo<|>o);
+            }
+            ",
+            "foo FN_DEF FileId(1) [49; 60) [52; 55)",
+        );
+        check_goto(
+            "
+            //- /lib.rs
+            macro_rules! call {
+                ($f:expr) => { $f() };
+            }
+            mod m { pub struct Foo; }
+            fn main() {
+                call!(|| m::Fo<|>o);
+            }
+            ",
+            "Foo STRUCT_DEF FileId(1) [57; 72) [68; 71)",
+        );
+    }
+
+    #[test]
+    fn goto_definition_works_for_derives() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait Clone {}
+            #[derive(Cl<|>one)]
+            struct S;
+            ",
+            "Clone TRAIT_DEF FileId(1) [0; 14) [6; 11)",
+        );
+        check_goto(
+            "
+            //- /lib.rs
+            mod fmt { pub trait Debug {} }
+            #[derive(Clone, fmt::De<|>bug)]
+            struct S;
+            ",
+            "Debug TRAIT_DEF FileId(1) [10; 28) [20; 25)",
+        );
+    }
+
+    #[test]
+    fn goto_definition_ignores_unresolved_derives() {
+        let (analysis, pos) = analysis_and_position(
+            "
+            //- /lib.rs
+            #[derive(Cl<|>one)]
+            struct S;
+            ",
+        );
+        let navs = analysis.goto_definition(pos).unwrap();
+        assert!(navs.is_none());
+    }
+
+    #[test]
+    fn goto_definition_reports_target_kind() {
+        let target_kind = |fixture| {
+            let (analysis, pos) = analysis_and_position(fixture);
+            let navs = analysis.goto_definition(pos).unwrap().unwrap().info;
+            navs.iter().map(|it| it.target_kind()).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            target_kind(
+                "
+                //- /lib.rs
+                struct Foo;
+                fn new() -> Foo<|> { Foo }
+                ",
+            ),
+            vec![NavigationTargetKind::Struct]
+        );
+        assert_eq!(
+            target_kind(
+                "
+                //- /lib.rs
+                struct Foo;
+                impl Foo {
+                    fn new() -> Foo { Foo }
+                    fn frobnicate(&self) {}
+                }
+                fn bar() { Foo::new().frobnicate<|>(); }
+                ",
+            ),
+            vec![NavigationTargetKind::Method]
+        );
+    }
 }
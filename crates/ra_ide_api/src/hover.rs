@@ -1,17 +1,19 @@
 use ra_db::SourceDatabase;
 use ra_syntax::{
-    AstNode, SyntaxNode, TreeArc, ast::{self, NameOwner, VisibilityOwner, TypeAscriptionOwner},
+    AstNode, SyntaxNode, TextRange, TreeArc,
+    ast::{self, NameOwner, VisibilityOwner, TypeAscriptionOwner},
     algo::{find_covering_node, find_node_at_offset, find_leaf_at_offset, visit::{visitor, Visitor}},
 };
-use hir::HirDisplay;
+use hir::{HirDisplay, source_binder};
 
-use crate::{db::RootDatabase, RangeInfo, FilePosition, FileRange, NavigationTarget};
+use crate::{db::RootDatabase, RangeInfo, FileId, FilePosition, FileRange, NavigationTarget};
 
 /// Contains the results when hovering over an item
 #[derive(Debug, Clone)]
 pub struct HoverResult {
     results: Vec<String>,
     exact: bool,
+    target: Option<NavigationTarget>,
 }
 
 impl HoverResult {
@@ -20,6 +22,7 @@ impl HoverResult {
             results: Vec::new(),
             // We assume exact by default
             exact: true,
+            target: None,
         }
     }
 
@@ -47,6 +50,12 @@ impl HoverResult {
         &self.results
     }
 
+    /// The definition of the hovered element, for editors to peek at. `None`
+    /// if there is no single one, like for literals.
+    pub fn target(&self) -> Option<&NavigationTarget> {
+        self.target.as_ref()
+    }
+
     /// Returns the results converted into markup
     /// for displaying in a UI
     pub fn to_markup(&self) -> String {
@@ -67,7 +76,19 @@ impl HoverResult {
     }
 }
 
-pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeInfo<HoverResult>> {
+/// Settings which change what hover shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HoverOptions {
+    /// Whether to show the size and alignment of a hovered type, if its layout
+    /// is known.
+    pub show_layout: bool,
+}
+
+pub(crate) fn hover(
+    db: &RootDatabase,
+    position: FilePosition,
+    options: HoverOptions,
+) -> Option<RangeInfo<HoverResult>> {
     let file = db.parse(position.file_id);
     let mut res = HoverResult::new();
 
@@ -76,7 +97,10 @@ pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeIn
         use crate::goto_definition::{ReferenceResult::*, reference_definition};
         let ref_result = reference_definition(db, position.file_id, name_ref);
         match ref_result {
-            Exact(nav) => res.extend(doc_text_for(db, nav)),
+            Exact(nav) => {
+                res.target = Some(nav.clone());
+                res.extend(doc_text_for(db, nav))
+            }
             Approximate(navs) => {
                 // We are no longer exact
                 res.exact = false;
@@ -86,6 +110,7 @@ pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeIn
                 }
             }
         }
+        res.extend(trait_method_text(db, position.file_id, name_ref));
         if !res.is_empty() {
             range = Some(name_ref.syntax().range())
         }
@@ -93,6 +118,9 @@ pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeIn
         let navs = crate::goto_definition::name_definition(db, position.file_id, name);
 
         if let Some(navs) = navs {
+            if navs.len() == 1 {
+                res.target = Some(navs[0].clone());
+            }
             for nav in navs {
                 res.extend(doc_text_for(db, nav))
             }
@@ -103,6 +131,19 @@ pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeIn
         }
     }
 
+    if options.show_layout {
+        if let Some((ty_range, ty)) = type_at_position(db, position) {
+            if let Some(layout) = ty.layout(db) {
+                // Primitive types have no definition to show.
+                if res.is_empty() {
+                    res.extend(Some(rust_code_markup(ty.display(db).to_string())));
+                }
+                res.extend(Some(format!("size = {}, align = {}", layout.size, layout.align)));
+                range = range.or_else(|| Some(ty_range));
+            }
+        }
+    }
+
     if range.is_none() {
         let node = find_leaf_at_offset(file.syntax(), position.offset).find_map(|leaf| {
             leaf.ancestors().find(|n| ast::Expr::cast(*n).is_some() || ast::Pat::cast(*n).is_some())
@@ -143,6 +184,24 @@ pub(crate) fn type_of(db: &RootDatabase, frange: FileRange) -> Option<String> {
     }
 }
 
+/// The type written at the position, or the type of the struct whose name is
+/// at the position.
+fn type_at_position(db: &RootDatabase, position: FilePosition) -> Option<(TextRange, hir::Ty)> {
+    let file = db.parse(position.file_id);
+    if let Some(name) = find_node_at_offset::<ast::Name>(file.syntax(), position.offset) {
+        let struct_def = ast::StructDef::cast(name.syntax().parent()?)?;
+        let module =
+            source_binder::module_from_child_node(db, position.file_id, struct_def.syntax())?;
+        let strukt = source_binder::struct_from_module(db, module, struct_def);
+        return Some((name.syntax().range(), strukt.ty(db)));
+    }
+    let name_ref = find_node_at_offset::<ast::NameRef>(file.syntax(), position.offset)?;
+    let type_ref = name_ref.syntax().ancestors().find_map(ast::PathType::cast)?;
+    let type_ref = hir::TypeRef::from_ast(ast::TypeRef::cast(type_ref.syntax())?);
+    let resolver = source_binder::resolver_for_node(db, position.file_id, name_ref.syntax());
+    Some((name_ref.syntax().range(), hir::Ty::from_hir(db, &resolver, &type_ref)))
+}
+
 fn rust_code_markup<CODE: AsRef<str>>(val: CODE) -> String {
     rust_code_markup_with_doc::<_, &str>(val, None)
 }
@@ -159,16 +218,148 @@ where
     }
 }
 
+/// For a method call resolving to a method in a trait impl, the trait which
+/// the method comes from, with the signature the trait gives it.
+fn trait_method_text(
+    db: &RootDatabase,
+    file_id: FileId,
+    name_ref: &ast::NameRef,
+) -> Option<String> {
+    let call = name_ref.syntax().parent().and_then(ast::MethodCallExpr::cast)?;
+    let function = source_binder::function_from_child_node(db, file_id, call.syntax())?;
+    let expr = function.body_source_map(db).node_expr(ast::Expr::cast(call.syntax())?)?;
+    let method = function.infer(db).method_resolution(expr)?;
+    let tr = method.impl_block(db)?.target_trait(db)?;
+    let trait_fn = tr.items(db).into_iter().find_map(|item| match item {
+        hir::TraitItem::Function(it) if it.name(db) == method.name(db) => Some(it),
+        _ => None,
+    })?;
+    let signature = crate::completion::function_label(&trait_fn.source(db).1)?;
+    Some(format!("from trait `{}`\n\n{}", tr.name(db)?, rust_code_markup(signature)))
+}
+
 // FIXME: this should not really use navigation target. Rather, approximately
 // resolved symbol should return a `DefId`.
 fn doc_text_for(db: &RootDatabase, nav: NavigationTarget) -> Option<String> {
-    match (nav.description(db), nav.docs(db)) {
+    match (nav.description(db), nav.docs(db).map(|it| rewrite_doc_links(&it))) {
         (Some(desc), docs) => Some(rust_code_markup_with_doc(desc, docs)),
         (None, Some(docs)) => Some(docs),
         _ => None,
     }
 }
 
+/// Rewrites rustdoc's intra-doc links, like ``[`Vec`]`` or `[foo](crate::foo)`,
+/// into plain markdown, as editors don't know how to follow them. Links to
+/// URLs and code blocks are left alone.
+pub(crate) fn rewrite_doc_links(docs: &str) -> String {
+    let mut res = String::with_capacity(docs.len());
+    let mut in_code_block = false;
+    for line in docs.lines() {
+        let is_fence = line.trim_start().starts_with("```");
+        if is_fence {
+            in_code_block = !in_code_block;
+        }
+        if is_fence || in_code_block {
+            res.push_str(line);
+        } else if is_intra_doc_link_definition(line) {
+            continue;
+        } else {
+            rewrite_line(line, &mut res);
+        }
+        res.push('\n');
+    }
+    // Removed link definitions usually leave blank lines at the end.
+    let len = res.trim_end().len();
+    res.truncate(len);
+    res
+}
+
+fn rewrite_line(line: &str, buf: &mut String) {
+    let mut rest = line;
+    while let Some(idx) = rest.find(|c| c == '[' || c == '`') {
+        buf.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+        if rest.starts_with('`') {
+            // Brackets in inline code, as in `a[0]`, are not links.
+            let end = rest[1..].find('`').map_or(rest.len(), |it| it + 2);
+            buf.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        match intra_doc_link(rest) {
+            Some((len, text)) => {
+                buf.push_str(&text);
+                rest = &rest[len..];
+            }
+            None => {
+                buf.push('[');
+                rest = &rest[1..];
+            }
+        }
+    }
+    buf.push_str(rest);
+}
+
+/// If `s` starts with an intra-doc link, returns its length and the text to
+/// show instead of it.
+fn intra_doc_link(s: &str) -> Option<(usize, String)> {
+    let text_end = s.find(']')?;
+    let text = &s[1..text_end];
+    let after = &s[text_end + 1..];
+    let (target, len) = if after.starts_with('(') {
+        let target_end = after.find(')')?;
+        (&after[1..target_end], text_end + 1 + target_end + 1)
+    } else if after.starts_with('[') {
+        let target_end = after.find(']')?;
+        (&after[1..target_end], text_end + 1 + target_end + 1)
+    } else {
+        (text, text_end + 1)
+    };
+    let target = if target.is_empty() { text } else { target };
+    if !is_intra_doc_target(target) {
+        return None;
+    }
+    // `[Vec]` is only a link if it resolves, which we can't tell here, so
+    // only code-like shortcut links are rewritten.
+    if len == text_end + 1 && !text.starts_with('`') && !text.contains("::") {
+        return None;
+    }
+    let text = if text.starts_with('`') && text.ends_with('`') && text.len() > 1 {
+        format!("`{}`", strip_disambiguator(&text[1..text.len() - 1]))
+    } else {
+        text.to_string()
+    };
+    Some((len, text))
+}
+
+/// Whether `line` is a link reference definition, like `[Vec]: std::vec::Vec`,
+/// which points at an item.
+fn is_intra_doc_link_definition(line: &str) -> bool {
+    let line = line.trim();
+    match line.find("]: ") {
+        Some(idx) if line.starts_with('[') => is_intra_doc_target(line[idx + 3..].trim()),
+        _ => false,
+    }
+}
+
+/// Strips the kind of item a link points to, as in `struct@Foo`. Like
+/// rustdoc, we keep the `()` of `foo()` and the `!` of `foo!` for display.
+fn strip_disambiguator(path: &str) -> &str {
+    match path.find('@') {
+        Some(idx) => &path[idx + 1..],
+        None => path,
+    }
+}
+
+fn is_intra_doc_target(target: &str) -> bool {
+    let target = strip_disambiguator(target.trim_matches('`'));
+    let target = target.trim_end_matches("()").trim_end_matches('!');
+    !target.is_empty()
+        && target.split("::").all(|segment| {
+            !segment.is_empty() && segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+        })
+}
+
 impl NavigationTarget {
     fn node(&self, db: &RootDatabase) -> Option<TreeArc<SyntaxNode>> {
         let source_file = db.parse(self.file_id());
@@ -251,6 +442,7 @@ impl NavigationTarget {
 mod tests {
     use ra_syntax::TextRange;
     use crate::mock_analysis::{single_file_with_position, single_file_with_range, analysis_and_position};
+    use super::HoverOptions;
 
     fn trim_markup(s: &str) -> &str {
         s.trim_start_matches("```rust\n").trim_end_matches("\n```")
@@ -358,6 +550,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hover_returns_definition_target() {
+        let (analysis, position) = single_file_with_position(
+            "
+            /// Adds one.
+            fn inc(x: u32) -> u32 { x + 1 }
+            fn main() { in<|>c(1); }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        assert!(hover.info.first().unwrap().contains("fn inc(x: u32) -> u32"));
+        let target = hover.info.target().unwrap();
+        assert_eq!(target.name(), "inc");
+        assert_eq!(target.kind(), ra_syntax::SyntaxKind::FN_DEF);
+
+        let (analysis, position) = single_file_with_position("fn main() { let x = 9<|>2u8; }");
+        let hover = analysis.hover(position).unwrap().unwrap();
+        assert_eq!(trim_markup_opt(hover.info.first()), Some("u8"));
+        assert!(hover.info.target().is_none());
+    }
+
     #[test]
     fn hover_shows_struct_field_info() {
         // Hovering over the field when instantiating
@@ -568,6 +781,51 @@ The Some variant
         assert_eq!(trim_markup_opt(hover.info.first()), Some("Thing"));
     }
 
+    #[test]
+    fn hover_shows_trait_of_method() {
+        let (analysis, position) = single_file_with_position(
+            "
+            trait Shape {
+                fn area(&self) -> f64;
+            }
+            struct Square(f64);
+            impl Shape for Square {
+                fn area(&self) -> f64 { self.0 * self.0 }
+            }
+            impl Square {
+                fn side(&self) -> f64 { self.0 }
+            }
+
+            fn main() {
+                let a = Square(1.0).ar<|>ea();
+            }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        assert_eq!(
+            hover.info.results(),
+            &[
+                "```rust\nfn area(&self) -> f64\n```".to_string(),
+                "from trait `Shape`\n\n```rust\nfn area(&self) -> f64;\n```".to_string(),
+            ]
+        );
+
+        let (analysis, position) = single_file_with_position(
+            "
+            struct Square(f64);
+            impl Square {
+                fn side(&self) -> f64 { self.0 }
+            }
+
+            fn main() {
+                let a = Square(1.0).si<|>de();
+            }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        assert_eq!(hover.info.len(), 1);
+    }
+
     #[test]
     fn test_hover_infer_associated_method_exact() {
         let (analysis, position) = single_file_with_position(
@@ -670,4 +928,146 @@ The Some variant
         assert_eq!(trim_markup_opt(hover.info.first()), Some("enum Thing"));
         assert_eq!(hover.info.is_exact(), true);
     }
+
+    #[test]
+    fn hover_closure_which_reads_captures() {
+        let (analysis, position) = single_file_with_position(
+            "
+            fn main() {
+                let offset = 1u32;
+                let add = |x: u32| x + offset;
+                ad<|>d(2);
+            }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        assert_eq!(trim_markup_opt(hover.info.first()), Some("impl Fn(u32) -> u32"));
+    }
+
+    #[test]
+    fn hover_closure_which_mutates_captures() {
+        let (analysis, position) = single_file_with_position(
+            "
+            fn main() {
+                let mut total = 0u32;
+                let mut add = |x: u32| { total += x; total };
+                ad<|>d(2);
+            }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        assert_eq!(trim_markup_opt(hover.info.first()), Some("impl FnMut(u32) -> u32"));
+
+        let (analysis, position) = single_file_with_position(
+            "
+            fn main() {
+                let mut total = 0u32;
+                let add = <|>|x: u32| total += x;
+            }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        assert_eq!(trim_markup_opt(hover.info.first()), Some("impl FnMut(u32)"));
+    }
+
+    #[test]
+    fn test_type_of_for_impl_trait() {
+        let (analysis, range) = single_file_with_range(
+            "
+            trait Iterator { type Item; }
+            fn numbers() -> impl Iterator<Item = i32> { loop {} }
+
+            fn main() {
+                let it = <|>numbers()<|>;
+            }
+            ",
+        );
+
+        let type_name = analysis.type_of(range).unwrap().unwrap();
+        assert_eq!("impl Iterator<Item = i32>", &type_name);
+    }
+
+    #[test]
+    fn hover_for_boxed_trait_object() {
+        let (analysis, position) = single_file_with_position(
+            "
+            struct Box<T: ?Sized>(T);
+            trait Iterator { type Item; }
+            trait Send {}
+
+            fn f(it: Box<dyn Iterator<Item = u32> + Send>) {
+                let boxed = it;
+                box<|>ed;
+            }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        assert_eq!(
+            trim_markup_opt(hover.info.first()),
+            Some("Box<dyn Iterator<Item = u32> + Send>")
+        );
+    }
+
+    fn layout_results(code: &str) -> Vec<String> {
+        let (analysis, position) = single_file_with_position(code);
+        let options = HoverOptions { show_layout: true };
+        let hover = analysis.hover_with_options(position, options).unwrap().unwrap();
+        hover.info.results().iter().map(|it| trim_markup(it).to_string()).collect()
+    }
+
+    #[test]
+    fn hover_shows_layout_of_types() {
+        let results = layout_results(
+            "
+            #[repr(C)]
+            struct Fo<|>o { a: u8, b: u32, c: u16 }
+            ",
+        );
+        assert_eq!(results.last().unwrap(), "size = 12, align = 4");
+        let results = layout_results(
+            "
+            struct Foo { a: u8, b: u32, c: u16 }
+            fn f(foo: Fo<|>o) {}
+            ",
+        );
+        assert_eq!(results.last().unwrap(), "size = 8, align = 4");
+        let results = layout_results("fn f(x: u6<|>4) {}");
+        assert_eq!(results, vec!["u64", "size = 8, align = 8"]);
+    }
+
+    #[test]
+    fn hover_omits_layout_of_generic_types() {
+        let results = layout_results("struct Fo<|>o<T> { x: T }");
+        assert!(!results.iter().any(|it| it.starts_with("size")));
+        let (analysis, position) = single_file_with_position("struct Fo<|>o { a: u8 }");
+        let results = analysis.hover(position).unwrap().unwrap().info.results().to_vec();
+        assert!(!results.iter().any(|it| it.starts_with("size")));
+    }
+
+    #[test]
+    fn hover_renders_intra_doc_links() {
+        let (analysis, position) = single_file_with_position(
+            "
+            /// Wraps a [`Vec`] like [`Vec::new`] does, see [the builder](crate::Builder)
+            /// and [`struct@Builder`]. Indexing is `xs[0]`, [docs](https://docs.rs).
+            ///
+            /// [`Vec::new`]: std::vec::Vec::new
+            fn fo<|>o() {}
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        assert_eq!(
+            hover.info.first().unwrap(),
+            "```rust\nfn foo()\n```\n\nWraps a `Vec` like `Vec::new` does, see the builder\nand `Builder`. Indexing is `xs[0]`, [docs](https://docs.rs)."
+        );
+    }
+
+    #[test]
+    fn rewrite_doc_links_keeps_code_blocks() {
+        let docs = "See [`foo()`] and [`Foo`][foo].\n\n```\nlet x = [a](b);\n```\n\n[foo]: crate::Foo";
+        assert_eq!(
+            super::rewrite_doc_links(docs),
+            "See `foo()` and `Foo`.\n\n```\nlet x = [a](b);\n```"
+        );
+    }
 }
@@ -1,9 +1,9 @@
 use ra_db::SourceDatabase;
 use ra_syntax::{
-    AstNode, ast,
+    AstNode, ast::{self, NameOwner},
     algo::find_node_at_offset,
 };
-use hir::{db::HirDatabase, source_binder};
+use hir::{ImplItem, db::HirDatabase, source_binder};
 
 use crate::{FilePosition, NavigationTarget, db::RootDatabase, RangeInfo};
 
@@ -16,6 +16,12 @@ pub(crate) fn goto_implementation(
 
     let module = source_binder::module_from_position(db, position)?;
 
+    if let Some(fn_def) = find_node_at_offset::<ast::FnDef>(syntax, position.offset) {
+        if let Some(navs) = impls_for_method(db, fn_def, module) {
+            return Some(RangeInfo::new(fn_def.syntax().range(), navs));
+        }
+    }
+
     if let Some(nominal_def) = find_node_at_offset::<ast::NominalDef>(syntax, position.offset) {
         return Some(RangeInfo::new(
             nominal_def.syntax().range(),
@@ -74,6 +80,52 @@ fn impls_for_trait(
     )
 }
 
+/// Goes from a trait method to its implementations, and from a method in a
+/// trait impl to its declaration in the trait. Returns `None` for other
+/// functions.
+fn impls_for_method(
+    db: &RootDatabase,
+    node: &ast::FnDef,
+    module: hir::Module,
+) -> Option<Vec<NavigationTarget>> {
+    let name = node.name()?.text().clone();
+    let container = node.syntax().parent()?.parent()?;
+
+    if let Some(trait_def) = ast::TraitDef::cast(container) {
+        let tr = source_binder::trait_from_module(db, module, trait_def);
+
+        let krate = module.krate(db)?;
+        let impls = db.impls_in_crate(krate);
+
+        Some(
+            impls
+                .lookup_impl_blocks_for_trait(&tr)
+                .flat_map(|imp| imp.items(db))
+                .filter_map(|item| match item {
+                    ImplItem::Method(f) if f.name(db).to_string() == name.as_str() => Some(f),
+                    _ => None,
+                })
+                .map(|f| NavigationTarget::from_function(db, f))
+                .collect(),
+        )
+    } else if ast::ImplBlock::cast(container).is_some() {
+        let func = source_binder::function_from_module(db, module, node);
+        let tr = func.impl_block(db)?.target_trait(db)?;
+        let (file_id, trait_def) = tr.source(db);
+        let method = trait_def
+            .item_list()?
+            .impl_items()
+            .filter_map(|item| match item.kind() {
+                ast::ImplItemKind::FnDef(it) => Some(it),
+                _ => None,
+            })
+            .find(|it| it.name().map(|it| *it.text() == name).unwrap_or(false))?;
+        Some(vec![NavigationTarget::from_named(file_id.original_file(db), method)])
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::mock_analysis::analysis_and_position;
@@ -95,7 +147,7 @@ mod tests {
             struct Foo<|>;
             impl Foo {}
             ",
-            &["impl IMPL_BLOCK FileId(1) [12; 23)"],
+            &["impl IMPL_BLOCK FileId(1) [12; 23) [17; 20)"],
         );
     }
 
@@ -108,7 +160,10 @@ mod tests {
             impl Foo {}
             impl Foo {}
             ",
-            &["impl IMPL_BLOCK FileId(1) [12; 23)", "impl IMPL_BLOCK FileId(1) [24; 35)"],
+            &[
+                "impl IMPL_BLOCK FileId(1) [12; 23) [17; 20)",
+                "impl IMPL_BLOCK FileId(1) [24; 35) [29; 32)",
+            ],
         );
     }
 
@@ -125,7 +180,10 @@ mod tests {
                 impl super::Foo {}
             }
             ",
-            &["impl IMPL_BLOCK FileId(1) [24; 42)", "impl IMPL_BLOCK FileId(1) [57; 75)"],
+            &[
+                "impl IMPL_BLOCK FileId(1) [24; 42) [29; 39)",
+                "impl IMPL_BLOCK FileId(1) [57; 75) [62; 72)",
+            ],
         );
     }
 
@@ -142,7 +200,10 @@ mod tests {
             //- /b.rs
             impl crate::Foo {}
             ",
-            &["impl IMPL_BLOCK FileId(2) [0; 18)", "impl IMPL_BLOCK FileId(3) [0; 18)"],
+            &[
+                "impl IMPL_BLOCK FileId(2) [0; 18) [5; 15)",
+                "impl IMPL_BLOCK FileId(3) [0; 18) [5; 15)",
+            ],
         );
     }
 
@@ -155,7 +216,7 @@ mod tests {
             struct Foo;
             impl T for Foo {}
             ",
-            &["impl IMPL_BLOCK FileId(1) [23; 40)"],
+            &["impl IMPL_BLOCK FileId(1) [23; 40) [34; 37)"],
         );
     }
 
@@ -173,7 +234,66 @@ mod tests {
             //- /b.rs
             impl crate::T for crate::Foo {}
             ",
-            &["impl IMPL_BLOCK FileId(2) [0; 31)", "impl IMPL_BLOCK FileId(3) [0; 31)"],
+            &[
+                "impl IMPL_BLOCK FileId(2) [0; 31) [18; 28)",
+                "impl IMPL_BLOCK FileId(3) [0; 31) [18; 28)",
+            ],
+        );
+    }
+
+    #[test]
+    fn goto_implementation_from_impl_method_to_trait_method() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait T { fn foo(&self); fn bar(&self); }
+            struct Foo;
+            impl T for Foo {
+                fn bar(&self) {}
+                fn f<|>oo(&self) {}
+            }
+            ",
+            &["foo FN_DEF FileId(1) [10; 24) [13; 16)"],
+        );
+    }
+
+    #[test]
+    fn goto_implementation_from_trait_method_to_impl_methods() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait T { fn f<|>oo(&self) {} }
+            struct Foo;
+            struct Bar;
+            impl T for Foo { fn foo(&self) {} }
+            mod a {
+                impl crate::T for crate::Bar { fn foo(&self) {} }
+            }
+            ",
+            &[
+                "foo FN_DEF FileId(1) [70; 86) [73; 76)",
+                "foo FN_DEF FileId(1) [132; 148) [135; 138)",
+            ],
+        );
+    }
+
+    #[test]
+    fn goto_implementation_from_inherent_method() {
+        check_goto(
+            "
+            //- /lib.rs
+            struct Foo<|>;
+            impl Foo { fn foo(&self) {} }
+            ",
+            &["impl IMPL_BLOCK FileId(1) [12; 41) [17; 20)"],
+        );
+        let (analysis, pos) = analysis_and_position(
+            "
+            //- /lib.rs
+            struct Foo;
+            impl Foo { fn f<|>oo(&self) {} }
+            ",
         );
+        assert!(analysis.goto_implementation(pos).unwrap().is_none());
     }
 }
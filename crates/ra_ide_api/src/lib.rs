@@ -27,12 +27,15 @@ mod extend_selection;
 mod hover;
 mod call_info;
 mod syntax_highlighting;
+mod semantic_tokens;
 mod parent_module;
 mod references;
 mod impls;
 mod assists;
 mod diagnostics;
+mod code_actions;
 mod syntax_tree;
+mod ssr;
 
 #[cfg(test)]
 mod marks;
@@ -54,19 +57,24 @@ use crate::{
 
 pub use crate::{
     change::{AnalysisChange, LibraryData},
-    completion::{CompletionItem, CompletionItemKind, InsertTextFormat},
-    runnables::{Runnable, RunnableKind},
-    navigation_target::NavigationTarget,
+    completion::{
+        CompletionItem, CompletionItemKind, CompletionOptions, CompletionResolveId, CompletionScore,
+        InsertTextFormat,
+    },
+    runnables::{Runnable, RunnableKind, BinTarget},
+    navigation_target::{NavigationTarget, NavigationTargetKind},
     references::ReferenceSearchResult,
     assists::{Assist, AssistId},
-    hover::{HoverResult},
+    hover::{HoverResult, HoverOptions},
+    call_info::CallInfoOptions,
+    semantic_tokens::{SemanticToken, SemanticTokenType, SemanticTokenModifier},
 };
 pub use ra_ide_api_light::{
     Fold, FoldKind, HighlightedRange, Severity, StructureNode, LocalEdit,
     LineIndex, LineCol, translate_offset_with_edit,
 };
 pub use ra_db::{
-    Canceled, CrateGraph, CrateId, FileId, FilePosition, FileRange, SourceRootId,
+    Canceled, CrateGraph, CrateId, Dependency, FileId, FilePosition, FileRange, SourceRootId,
     Edition
 };
 pub use hir::Documentation;
@@ -95,8 +103,9 @@ pub struct SourceFileEdit {
 
 #[derive(Debug)]
 pub enum FileSystemEdit {
-    CreateFile { source_root: SourceRootId, path: RelativePathBuf },
+    CreateFile { source_root: SourceRootId, path: RelativePathBuf, contents: String },
     MoveFile { src: FileId, dst_source_root: SourceRootId, dst_path: RelativePathBuf },
+    DeleteFile { file_id: FileId },
 }
 
 #[derive(Debug)]
@@ -216,6 +225,14 @@ impl Analysis {
         status::status(&*self.db)
     }
 
+    /// The sizes of the biggest parts of the analysis, like the number of
+    /// cached inference results, as `(name, value)` pairs. Files, syntax trees
+    /// and library symbols are also measured in bytes; name resolution and
+    /// inference are only counted.
+    pub fn memory_usage(&self) -> Vec<(String, usize)> {
+        status::memory_usage(&*self.db)
+    }
+
     /// Gets the text of the source file.
     pub fn file_text(&self, file_id: FileId) -> Arc<String> {
         self.db.file_text(file_id)
@@ -287,8 +304,7 @@ impl Analysis {
     /// Returns a tree representation of symbols in the file. Useful to draw a
     /// file outline.
     pub fn file_structure(&self, file_id: FileId) -> Vec<StructureNode> {
-        let file = self.db.parse(file_id);
-        ra_ide_api_light::file_structure(&file)
+        self.db.file_structure(file_id).as_ref().clone()
     }
 
     /// Returns the set of folding ranges.
@@ -329,14 +345,44 @@ impl Analysis {
         self.with_db(|db| references::find_all_refs(db, position))
     }
 
+    /// Finds usages of the reference at point, but only those inside `scope`.
+    /// The declaration is included if it is inside `scope` as well.
+    pub fn find_refs_in_range(
+        &self,
+        position: FilePosition,
+        scope: FileRange,
+    ) -> Cancelable<Option<Vec<FileRange>>> {
+        self.with_db(|db| references::find_refs_in_range(db, position, scope))
+    }
+
     /// Returns a short text describing element at position.
     pub fn hover(&self, position: FilePosition) -> Cancelable<Option<RangeInfo<HoverResult>>> {
-        self.with_db(|db| hover::hover(db, position))
+        self.hover_with_options(position, HoverOptions::default())
+    }
+
+    /// Returns a short text describing element at position, with non-default
+    /// settings.
+    pub fn hover_with_options(
+        &self,
+        position: FilePosition,
+        options: HoverOptions,
+    ) -> Cancelable<Option<RangeInfo<HoverResult>>> {
+        self.with_db(|db| hover::hover(db, position, options))
     }
 
     /// Computes parameter information for the given call expression.
     pub fn call_info(&self, position: FilePosition) -> Cancelable<Option<CallInfo>> {
-        self.with_db(|db| call_info::call_info(db, position))
+        self.call_info_with_options(position, CallInfoOptions::default())
+    }
+
+    /// Computes parameter information for the given call expression, with
+    /// non-default settings.
+    pub fn call_info_with_options(
+        &self,
+        position: FilePosition,
+        options: CallInfoOptions,
+    ) -> Cancelable<Option<CallInfo>> {
+        self.with_db(|db| call_info::call_info(db, position, options))
     }
 
     /// Returns a `mod name;` declaration which created the current module.
@@ -354,6 +400,18 @@ impl Analysis {
         self.with_db(|db| db.crate_graph().crate_root(crate_id))
     }
 
+    /// Returns the direct dependencies of the given crate, with the names
+    /// under which it knows them.
+    pub fn crate_dependencies(&self, crate_id: CrateId) -> Cancelable<Vec<Dependency>> {
+        self.with_db(|db| db.crate_graph().dependencies(crate_id).cloned().collect())
+    }
+
+    /// Returns the crates which directly depend on the given crate, with the
+    /// names under which they know it.
+    pub fn crate_dependents(&self, crate_id: CrateId) -> Cancelable<Vec<Dependency>> {
+        self.with_db(|db| db.crate_graph().dependents(crate_id))
+    }
+
     /// Returns the set of possible targets to run for the current file.
     pub fn runnables(&self, file_id: FileId) -> Cancelable<Vec<Runnable>> {
         self.with_db(|db| runnables::runnables(db, file_id))
@@ -364,9 +422,45 @@ impl Analysis {
         self.with_db(|db| syntax_highlighting::highlight(db, file_id))
     }
 
+    /// Like `highlight`, but calls `poll` between the parts of the file it
+    /// computes, and stops as soon as the database changes. `poll` can report
+    /// progress, or stop the computation with `Canceled::throw`.
+    pub fn highlight_with_poll(
+        &self,
+        file_id: FileId,
+        poll: &dyn Fn(),
+    ) -> Cancelable<Vec<HighlightedRange>> {
+        let poll = std::panic::AssertUnwindSafe(poll);
+        self.with_db(move |db| syntax_highlighting::highlight_with_poll(db, file_id, poll.0))
+    }
+
+    /// Computes the highlighting of the given file as a flat list of
+    /// non-overlapping tokens, sorted by position.
+    pub fn semantic_tokens(&self, file_id: FileId) -> Cancelable<Vec<SemanticToken>> {
+        self.with_db(|db| semantic_tokens::semantic_tokens(db, file_id))
+    }
+
     /// Computes completions at the given position.
     pub fn completions(&self, position: FilePosition) -> Cancelable<Option<Vec<CompletionItem>>> {
-        self.with_db(|db| completion::completions(db, position).map(Into::into))
+        self.completions_with_options(position, CompletionOptions::default())
+    }
+
+    /// Computes completions at the given position, with non-default settings.
+    pub fn completions_with_options(
+        &self,
+        position: FilePosition,
+        options: CompletionOptions,
+    ) -> Cancelable<Option<Vec<CompletionItem>>> {
+        self.with_db(|db| completion::completions(db, position, options).map(Into::into))
+    }
+
+    /// Computes the full completion item, including the documentation which
+    /// was left out because of `CompletionOptions::resolve_lazily`.
+    pub fn resolve_completion(
+        &self,
+        id: &CompletionResolveId,
+    ) -> Cancelable<Option<CompletionItem>> {
+        self.with_db(|db| completion::resolve_completion(db, id))
     }
 
     /// Computes assists (aks code actons aka intentions) for the given
@@ -375,11 +469,35 @@ impl Analysis {
         self.with_db(|db| assists::assists(db, frange))
     }
 
+    /// Computes everything that can be done at the given range: assists
+    /// first, followed by fixes of the diagnostics intersecting the range.
+    pub fn code_actions(&self, frange: FileRange) -> Cancelable<Vec<SourceChange>> {
+        self.with_db(|db| code_actions::code_actions(db, frange))
+    }
+
     /// Computes the set of diagnostics for the given file.
     pub fn diagnostics(&self, file_id: FileId) -> Cancelable<Vec<Diagnostic>> {
         self.with_db(|db| diagnostics::diagnostics(db, file_id))
     }
 
+    /// Like `diagnostics`, but calls `poll` between the items it checks, and
+    /// stops as soon as the database changes. `poll` can report progress, or
+    /// stop the computation with `Canceled::throw`.
+    pub fn diagnostics_with_poll(
+        &self,
+        file_id: FileId,
+        poll: &dyn Fn(),
+    ) -> Cancelable<Vec<Diagnostic>> {
+        let poll = std::panic::AssertUnwindSafe(poll);
+        self.with_db(move |db| diagnostics::diagnostics_with_poll(db, file_id, poll.0))
+    }
+
+    /// Computes only the syntax errors of the given file, which doesn't need
+    /// any semantic analysis.
+    pub fn syntax_diagnostics(&self, file_id: FileId) -> Cancelable<Vec<Diagnostic>> {
+        self.with_db(|db| diagnostics::syntax_diagnostics(db, file_id))
+    }
+
     /// Computes the type of the expression at the given position.
     pub fn type_of(&self, frange: FileRange) -> Cancelable<Option<String>> {
         self.with_db(|db| hover::type_of(db, frange))
@@ -395,6 +513,14 @@ impl Analysis {
         self.with_db(|db| references::rename(db, position, new_name))
     }
 
+    /// Applies a structural search and replace query, like
+    /// `foo($a, $b) ==>> bar($b, $a)`, to all expressions in the workspace.
+    ///
+    /// The returned change has no edits if the query is malformed.
+    pub fn structural_search_replace(&self, query: &str) -> Cancelable<SourceChange> {
+        self.with_db(|db| ssr::structural_search_replace(db, query))
+    }
+
     fn with_db<F: FnOnce(&db::RootDatabase) -> T + std::panic::UnwindSafe, T>(
         &self,
         f: F,
@@ -1,7 +1,8 @@
 use ra_db::FileId;
 use ra_syntax::{
-    SyntaxNode, SyntaxNodePtr, AstNode, SmolStr, TextRange, ast,
-    SyntaxKind::{self, NAME},
+    SyntaxNode, SyntaxNodePtr, AstNode, SmolStr, TextRange,
+    ast::{self, NameOwner},
+    SyntaxKind::{self, *},
 };
 use hir::{ModuleSource, FieldSource, Name, ImplItem};
 
@@ -17,9 +18,62 @@ pub struct NavigationTarget {
     file_id: FileId,
     name: SmolStr,
     kind: SyntaxKind,
+    target_kind: NavigationTargetKind,
     full_range: TextRange,
     focus_range: Option<TextRange>,
     container_name: Option<SmolStr>,
+    container_path: Option<SmolStr>,
+}
+
+/// What a `NavigationTarget` points to, which lets editors show a fitting
+/// icon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NavigationTargetKind {
+    Module,
+    Function,
+    Method,
+    Struct,
+    Enum,
+    EnumVariant,
+    Trait,
+    TypeAlias,
+    Const,
+    Static,
+    Field,
+    Impl,
+    Macro,
+    /// A local binding, like a function parameter.
+    Local,
+}
+
+impl NavigationTargetKind {
+    pub(crate) fn from_syntax(node: &SyntaxNode) -> NavigationTargetKind {
+        match node.kind() {
+            SOURCE_FILE | MODULE => NavigationTargetKind::Module,
+            FN_DEF => {
+                let has_self = ast::FnDef::cast(node)
+                    .and_then(|it| it.param_list())
+                    .and_then(|it| it.self_param())
+                    .is_some();
+                if has_self {
+                    NavigationTargetKind::Method
+                } else {
+                    NavigationTargetKind::Function
+                }
+            }
+            STRUCT_DEF => NavigationTargetKind::Struct,
+            ENUM_DEF => NavigationTargetKind::Enum,
+            ENUM_VARIANT => NavigationTargetKind::EnumVariant,
+            TRAIT_DEF => NavigationTargetKind::Trait,
+            TYPE_ALIAS_DEF => NavigationTargetKind::TypeAlias,
+            CONST_DEF => NavigationTargetKind::Const,
+            STATIC_DEF => NavigationTargetKind::Static,
+            NAMED_FIELD_DEF | POS_FIELD_DEF => NavigationTargetKind::Field,
+            IMPL_BLOCK => NavigationTargetKind::Impl,
+            MACRO_CALL => NavigationTargetKind::Macro,
+            _ => NavigationTargetKind::Local,
+        }
+    }
 }
 
 impl NavigationTarget {
@@ -37,10 +91,21 @@ impl NavigationTarget {
         self.container_name.as_ref()
     }
 
+    /// The names of all items containing this one, like `m::S` for a method
+    /// in an `impl S` in the module `m`.
+    pub fn container_path(&self) -> Option<&SmolStr> {
+        self.container_path.as_ref()
+    }
+
     pub fn kind(&self) -> SyntaxKind {
         self.kind
     }
 
+    /// What the target is, like a method rather than just a `FN_DEF`.
+    pub fn target_kind(&self) -> NavigationTargetKind {
+        self.target_kind
+    }
+
     pub fn file_id(&self) -> FileId {
         self.file_id
     }
@@ -66,9 +131,11 @@ impl NavigationTarget {
             file_id: symbol.file_id,
             name: symbol.name.clone(),
             kind: symbol.ptr.kind(),
+            target_kind: symbol.kind,
             full_range: symbol.ptr.range(),
             focus_range: symbol.name_range,
             container_name: symbol.container_name.clone(),
+            container_path: symbol.container_path.clone(),
         }
     }
 
@@ -83,7 +150,9 @@ impl NavigationTarget {
             full_range: ptr.range(),
             focus_range: None,
             kind: NAME,
+            target_kind: NavigationTargetKind::Local,
             container_name: None,
+            container_path: None,
         }
     }
 
@@ -95,9 +164,7 @@ impl NavigationTarget {
             ModuleSource::SourceFile(node) => {
                 NavigationTarget::from_syntax(file_id, name, None, node.syntax())
             }
-            ModuleSource::Module(node) => {
-                NavigationTarget::from_syntax(file_id, name, None, node.syntax())
-            }
+            ModuleSource::Module(node) => NavigationTarget::from_named(file_id, &*node),
         }
     }
 
@@ -105,7 +172,8 @@ impl NavigationTarget {
         let name = module.name(db).map(|it| it.to_string().into()).unwrap_or_default();
         if let Some((file_id, source)) = module.declaration_source(db) {
             let file_id = file_id.as_original_file();
-            return NavigationTarget::from_syntax(file_id, name, None, source.syntax());
+            let focus_range = source.name().map(|it| it.syntax().range());
+            return NavigationTarget::from_syntax(file_id, name, focus_range, source.syntax());
         }
         NavigationTarget::from_module(db, module)
     }
@@ -179,10 +247,11 @@ impl NavigationTarget {
         impl_block: hir::ImplBlock,
     ) -> NavigationTarget {
         let (file_id, node) = impl_block.source(db);
+        let focus_range = node.target_type().map(|it| it.syntax().range());
         NavigationTarget::from_syntax(
             file_id.as_original_file(),
             "impl".into(),
-            None,
+            focus_range,
             node.syntax(),
         )
     }
@@ -242,10 +311,12 @@ impl NavigationTarget {
             file_id,
             name,
             kind: node.kind(),
+            target_kind: NavigationTargetKind::from_syntax(node),
             full_range: node.range(),
             focus_range,
             // ptr: Some(LocalSyntaxPtr::new(node)),
             container_name: None,
+            container_path: None,
         }
     }
 }
@@ -41,7 +41,7 @@ mod tests {
             ",
         );
         let nav = analysis.parent_module(pos).unwrap().pop().unwrap();
-        nav.assert_match("foo MODULE FileId(1) [0; 8)");
+        nav.assert_match("foo MODULE FileId(1) [0; 8) [4; 7)");
     }
 
     #[test]
@@ -57,6 +57,6 @@ mod tests {
             ",
         );
         let nav = analysis.parent_module(pos).unwrap().pop().unwrap();
-        nav.assert_match("baz MODULE FileId(1) [32; 44)");
+        nav.assert_match("baz MODULE FileId(1) [32; 44) [36; 39)");
     }
 }
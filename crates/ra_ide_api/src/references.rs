@@ -9,6 +9,7 @@ use ra_syntax::{
 
 use crate::{
     db::RootDatabase,
+    symbol_index::SymbolsDatabase,
     FilePosition,
     FileRange,
     FileId,
@@ -23,6 +24,9 @@ use crate::{
 pub struct ReferenceSearchResult {
     declaration: NavigationTarget,
     references: Vec<FileRange>,
+    /// Files of the declaration and the references which belong to a library
+    /// source root, so that edits can't be applied to them.
+    library_files: Vec<FileId>,
 }
 
 impl ReferenceSearchResult {
@@ -34,6 +38,17 @@ impl ReferenceSearchResult {
         &self.references
     }
 
+    /// Whether `reference` lies in local code, rather than in a library, so
+    /// that renaming can change it.
+    pub fn is_editable(&self, reference: &FileRange) -> bool {
+        !self.library_files.contains(&reference.file_id)
+    }
+
+    /// The references, not including the declaration, which lie in libraries.
+    pub fn library_references(&self) -> impl Iterator<Item = &FileRange> {
+        self.references.iter().filter(move |it| !self.is_editable(it))
+    }
+
     /// Total number of references
     /// At least 1 since all valid references should
     /// Have a declaration
@@ -71,7 +86,18 @@ pub(crate) fn find_all_refs(
         .map(move |ref_desc| FileRange { file_id: position.file_id, range: ref_desc.range })
         .collect::<Vec<_>>();
 
-    return Some(ReferenceSearchResult { declaration, references });
+    let library_roots = db.library_roots();
+    let mut library_files = Vec::new();
+    let file_ids = references.iter().map(|it| it.file_id).chain(Some(declaration.file_id()));
+    for file_id in file_ids {
+        if !library_files.contains(&file_id)
+            && library_roots.contains(&db.file_source_root(file_id))
+        {
+            library_files.push(file_id);
+        }
+    }
+
+    return Some(ReferenceSearchResult { declaration, references, library_files });
 
     fn find_binding<'a>(
         db: &RootDatabase,
@@ -95,6 +121,26 @@ pub(crate) fn find_all_refs(
     }
 }
 
+/// Like `find_all_refs`, but only returns the references (and the
+/// declaration) which lie inside `scope`.
+pub(crate) fn find_refs_in_range(
+    db: &RootDatabase,
+    position: FilePosition,
+    scope: FileRange,
+) -> Option<Vec<FileRange>> {
+    // We only find references to local bindings at the moment, so there's
+    // nothing to look for in other files.
+    if scope.file_id != position.file_id {
+        return Some(Vec::new());
+    }
+    let refs = find_all_refs(db, position)?;
+    let res = refs
+        .into_iter()
+        .filter(|it| it.file_id == scope.file_id && it.range.is_subrange(&scope.range))
+        .collect();
+    Some(res)
+}
+
 pub(crate) fn rename(
     db: &RootDatabase,
     position: FilePosition,
@@ -123,19 +169,28 @@ fn find_name_and_module_at_offset(
     None
 }
 
-fn source_edit_from_fileid_range(
-    file_id: FileId,
-    range: TextRange,
-    new_name: &str,
-) -> SourceFileEdit {
-    SourceFileEdit {
-        file_id,
-        edit: {
-            let mut builder = ra_text_edit::TextEditBuilder::default();
-            builder.replace(range, new_name.into());
-            builder.finish()
-        },
+/// Builds a single edit per file, so that editors don't have to merge
+/// several edits in the same file themselves.
+fn source_edits_from_ranges(ranges: Vec<FileRange>, new_name: &str) -> Vec<SourceFileEdit> {
+    let mut ranges_by_file: Vec<(FileId, Vec<TextRange>)> = Vec::new();
+    for FileRange { file_id, range } in ranges {
+        match ranges_by_file.iter_mut().find(|(it, _)| *it == file_id) {
+            Some((_, ranges)) => ranges.push(range),
+            None => ranges_by_file.push((file_id, vec![range])),
+        }
     }
+    ranges_by_file
+        .into_iter()
+        .map(|(file_id, mut ranges)| {
+            ranges.sort_by_key(TextRange::start);
+            ranges.dedup();
+            let mut builder = ra_text_edit::TextEditBuilder::default();
+            for range in ranges {
+                builder.replace(range, new_name.into());
+            }
+            SourceFileEdit { file_id, edit: builder.finish() }
+        })
+        .collect()
 }
 
 fn rename_mod(
@@ -201,11 +256,9 @@ fn rename_reference(
     new_name: &str,
 ) -> Option<SourceChange> {
     let refs = find_all_refs(db, position)?;
+    let ranges = refs.clone().into_iter().filter(|it| refs.is_editable(it)).collect();
 
-    let edit = refs
-        .into_iter()
-        .map(|range| source_edit_from_fileid_range(range.file_id, range.range, new_name))
-        .collect::<Vec<_>>();
+    let edit = source_edits_from_ranges(ranges, new_name);
 
     if edit.is_empty() {
         return None;
@@ -339,6 +392,29 @@ mod tests {
         assert_debug_snapshot_matches!("rename_mod_in_dir", &source_change);
     }
 
+    #[test]
+    fn test_rename_produces_one_edit_per_file() {
+        let (analysis, position) = single_file_with_position(
+            "
+    fn main() {
+        let i<|> = 1;
+        let j = i + i;
+    }",
+        );
+        let source_change = analysis.rename(position, "k").unwrap().unwrap();
+        assert_eq!(source_change.source_file_edits.len(), 1);
+        let edit = &source_change.source_file_edits[0].edit;
+        assert_eq!(edit.as_atoms().len(), 3);
+        assert_eq_text!(
+            "
+    fn main() {
+        let k = 1;
+        let j = k + k;
+    }",
+            &edit.apply(&analysis.file_text(position.file_id))
+        );
+    }
+
     fn test_rename(text: &str, new_name: &str, expected: &str) {
         let (analysis, position) = single_file_with_position(text);
         let source_change = analysis.rename(position, new_name).unwrap();
@@ -4,6 +4,7 @@ use ra_syntax::{
     ast::{self, AstNode, NameOwner, ModuleItemOwner},
 };
 use ra_db::SourceDatabase;
+use relative_path::RelativePath;
 
 use crate::{db::RootDatabase, FileId};
 
@@ -18,17 +19,59 @@ pub enum RunnableKind {
     Test { name: String },
     TestMod { path: String },
     Bench { name: String },
-    Bin,
+    /// The `main` function of a binary. `target` is `None` for the default
+    /// binary of a package.
+    Bin { target: Option<BinTarget> },
+}
+
+/// The Cargo target an executable other than the default binary belongs to.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BinTarget {
+    /// `src/bin/NAME.rs` or `src/bin/NAME/main.rs`
+    Bin { name: String },
+    /// `examples/NAME.rs` or `examples/NAME/main.rs`
+    Example { name: String },
 }
 
 pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
     let source_file = db.parse(file_id);
-    source_file.syntax().descendants().filter_map(|i| runnable(db, file_id, i)).collect()
+    let mut res = Vec::new();
+    res.extend(runnable_crate(db, file_id, &source_file));
+    res.extend(source_file.syntax().descendants().filter_map(|i| runnable(db, file_id, i)));
+    res
+}
+
+/// In the root file of a crate with tests, a runnable for all of them. It
+/// covers the whole file and has an empty module path.
+fn runnable_crate(
+    db: &RootDatabase,
+    file_id: FileId,
+    source_file: &ast::SourceFile,
+) -> Option<Runnable> {
+    db.crate_graph().crate_id_for_crate_root(file_id)?;
+    let krate = hir::source_binder::module_from_file_id(db, file_id)?.krate(db)?;
+    let mut modules = vec![krate.root_module(db)?];
+    let mut has_test_function = false;
+    while let Some(module) = modules.pop() {
+        has_test_function = module.declarations(db).into_iter().any(|it| match it {
+            hir::ModuleDef::Function(f) => f.source(db).1.has_atom_attr("test"),
+            _ => false,
+        });
+        if has_test_function {
+            break;
+        }
+        modules.extend(module.children(db));
+    }
+    if !has_test_function {
+        return None;
+    }
+    let range = source_file.syntax().range();
+    Some(Runnable { range, kind: RunnableKind::TestMod { path: String::new() } })
 }
 
 fn runnable(db: &RootDatabase, file_id: FileId, item: &SyntaxNode) -> Option<Runnable> {
     if let Some(fn_def) = ast::FnDef::cast(item) {
-        runnable_fn(fn_def)
+        runnable_fn(db, file_id, fn_def)
     } else if let Some(m) = ast::Module::cast(item) {
         runnable_mod(db, file_id, m)
     } else {
@@ -36,10 +79,13 @@ fn runnable(db: &RootDatabase, file_id: FileId, item: &SyntaxNode) -> Option<Run
     }
 }
 
-fn runnable_fn(fn_def: &ast::FnDef) -> Option<Runnable> {
+fn runnable_fn(db: &RootDatabase, file_id: FileId, fn_def: &ast::FnDef) -> Option<Runnable> {
     let name = fn_def.name()?.text();
     let kind = if name == "main" {
-        RunnableKind::Bin
+        if !is_bin_root(db, file_id, fn_def) {
+            return None;
+        }
+        RunnableKind::Bin { target: bin_target(&db.file_relative_path(file_id)) }
     } else if fn_def.has_atom_attr("test") {
         RunnableKind::Test { name: name.to_string() }
     } else if fn_def.has_atom_attr("bench") {
@@ -50,6 +96,36 @@ fn runnable_fn(fn_def: &ast::FnDef) -> Option<Runnable> {
     Some(Runnable { range: fn_def.syntax().range(), kind })
 }
 
+/// Whether `fn_def` is a `main` which can be run: at the top level of a file
+/// which is not the root of a library or a submodule.
+fn is_bin_root(db: &RootDatabase, file_id: FileId, fn_def: &ast::FnDef) -> bool {
+    let is_top_level = fn_def.syntax().parent().and_then(ast::SourceFile::cast).is_some();
+    let is_lib = db.file_relative_path(file_id).file_name() == Some("lib.rs");
+    let is_submodule = hir::source_binder::module_from_file_id(db, file_id)
+        .and_then(|it| it.parent(db))
+        .is_some();
+    is_top_level && !is_lib && !is_submodule
+}
+
+/// Figures out the target of a binary from Cargo's conventions for where
+/// binaries and examples live.
+fn bin_target(path: &RelativePath) -> Option<BinTarget> {
+    let mut components = path.components().map(|it| it.as_str()).collect::<Vec<_>>();
+    let file_name = components.pop()?;
+    let (dir, name) = if file_name == "main.rs" {
+        let name = components.pop()?;
+        (components.pop()?, name)
+    } else {
+        (components.pop()?, file_name.trim_end_matches(".rs"))
+    };
+    let name = name.to_string();
+    match dir {
+        "bin" => Some(BinTarget::Bin { name }),
+        "examples" => Some(BinTarget::Example { name }),
+        _ => None,
+    }
+}
+
 fn runnable_mod(db: &RootDatabase, file_id: FileId, module: &ast::Module) -> Option<Runnable> {
     let has_test_function = module
         .item_list()?
@@ -73,8 +149,9 @@ fn runnable_mod(db: &RootDatabase, file_id: FileId, module: &ast::Module) -> Opt
 #[cfg(test)]
 mod tests {
     use insta::assert_debug_snapshot_matches;
+    use ra_syntax::{TextRange, TextUnit};
 
-    use crate::mock_analysis::analysis_and_position;
+    use crate::{RunnableKind, BinTarget, mock_analysis::{analysis_and_position, MockAnalysis}};
 
     #[test]
     fn test_runnables() {
@@ -165,4 +242,98 @@ mod tests {
         assert!(runnables.is_empty())
     }
 
+    #[test]
+    fn test_runnables_crate() {
+        let (analysis, pos) = analysis_and_position(
+            r#"
+        //- /lib.rs
+        <|> //empty
+        mod foo;
+        fn bar() {}
+        //- /foo.rs
+        #[test]
+        fn test_foo() {}
+        "#,
+        );
+        let runnables = analysis.runnables(pos.file_id).unwrap();
+        assert_eq!(runnables.len(), 1);
+        let text = analysis.file_text(pos.file_id);
+        assert_eq!(runnables[0].range, TextRange::offset_len(0.into(), TextUnit::of_str(&text)));
+        match &runnables[0].kind {
+            RunnableKind::TestMod { path } => assert_eq!(path, ""),
+            kind => panic!("unexpected runnable {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn test_runnables_crate_without_tests() {
+        let (analysis, pos) = analysis_and_position(
+            r#"
+        //- /lib.rs
+        <|> //empty
+        mod foo;
+        //- /foo.rs
+        fn foo() {}
+        "#,
+        );
+        let runnables = analysis.runnables(pos.file_id).unwrap();
+        assert!(runnables.is_empty())
+    }
+
+    #[test]
+    fn test_runnables_bin_targets() {
+        let bin_target = |fixture: &str| {
+            let (analysis, pos) = analysis_and_position(fixture);
+            let mut runnables = analysis.runnables(pos.file_id).unwrap();
+            assert_eq!(runnables.len(), 1);
+            match runnables.pop().unwrap().kind {
+                RunnableKind::Bin { target } => target,
+                kind => panic!("unexpected runnable {:?}", kind),
+            }
+        };
+        assert_eq!(
+            bin_target(
+                r#"
+                //- /examples/demo.rs
+                <|>fn main() {}
+                "#
+            ),
+            Some(BinTarget::Example { name: "demo".to_string() })
+        );
+        assert_eq!(
+            bin_target(
+                r#"
+                //- /src/bin/tool/main.rs
+                <|>fn main() {}
+                "#
+            ),
+            Some(BinTarget::Bin { name: "tool".to_string() })
+        );
+        assert_eq!(
+            bin_target(
+                r#"
+                //- /main.rs
+                <|>fn main() {}
+                "#
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_runnables_no_bin_in_lib_or_submodule() {
+        let mock = MockAnalysis::with_files(
+            r#"
+            //- /lib.rs
+            mod foo;
+            fn main() {}
+            //- /foo.rs
+            fn main() {}
+            "#,
+        );
+        let (lib, foo) = (mock.id_of("/lib.rs"), mock.id_of("/foo.rs"));
+        let analysis = mock.analysis();
+        assert!(analysis.runnables(lib).unwrap().is_empty());
+        assert!(analysis.runnables(foo).unwrap().is_empty());
+    }
 }
@@ -0,0 +1,222 @@
+use ra_db::SourceDatabase;
+use ra_syntax::{TextRange, TextUnit};
+
+use crate::{FileId, HighlightedRange, db::RootDatabase, syntax_highlighting};
+
+/// The kind of a `SemanticToken`. The discriminants are stable, so that they
+/// can be used as indices into a legend sent to the editor once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SemanticTokenType {
+    Comment = 0,
+    String = 1,
+    Number = 2,
+    Keyword = 3,
+    Attribute = 4,
+    Function = 5,
+    Lifetime = 6,
+    Macro = 7,
+}
+
+impl SemanticTokenType {
+    /// All token types, ordered by their discriminants.
+    pub const ALL: &'static [SemanticTokenType] = &[
+        SemanticTokenType::Comment,
+        SemanticTokenType::String,
+        SemanticTokenType::Number,
+        SemanticTokenType::Keyword,
+        SemanticTokenType::Attribute,
+        SemanticTokenType::Function,
+        SemanticTokenType::Lifetime,
+        SemanticTokenType::Macro,
+    ];
+
+    fn from_tag(tag: &str) -> Option<SemanticTokenType> {
+        let res = match tag {
+            "comment" => SemanticTokenType::Comment,
+            "string" => SemanticTokenType::String,
+            "literal" => SemanticTokenType::Number,
+            "keyword" => SemanticTokenType::Keyword,
+            "attribute" => SemanticTokenType::Attribute,
+            "function" => SemanticTokenType::Function,
+            "parameter" => SemanticTokenType::Lifetime,
+            "macro" => SemanticTokenType::Macro,
+            _ => return None,
+        };
+        Some(res)
+    }
+}
+
+/// A modifier of a `SemanticToken`. Like for `SemanticTokenType`, the
+/// discriminants are stable: a token has the modifier `m` if the bit
+/// `1 << m as u32` is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SemanticTokenModifier {
+    Documentation = 0,
+}
+
+impl SemanticTokenModifier {
+    /// All modifiers, ordered by their discriminants.
+    pub const ALL: &'static [SemanticTokenModifier] = &[SemanticTokenModifier::Documentation];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub range: TextRange,
+    pub token_type: SemanticTokenType,
+    pub modifiers: u32,
+}
+
+impl SemanticToken {
+    pub fn has_modifier(&self, modifier: SemanticTokenModifier) -> bool {
+        self.modifiers & (1 << modifier as u32) != 0
+    }
+}
+
+pub(crate) fn semantic_tokens(db: &RootDatabase, file_id: FileId) -> Vec<SemanticToken> {
+    let text = db.file_text(file_id);
+    let highlights = syntax_highlighting::highlight(db, file_id);
+    flatten(highlights)
+        .into_iter()
+        .map(|(range, token_type)| {
+            let mut modifiers = 0;
+            if token_type == SemanticTokenType::Comment && is_doc_comment(&text[range]) {
+                modifiers |= 1 << SemanticTokenModifier::Documentation as u32;
+            }
+            SemanticToken { range, token_type, modifiers }
+        })
+        .collect()
+}
+
+/// Turns possibly nested highlighted ranges into position-sorted,
+/// non-overlapping ones. Where ranges overlap, the innermost one wins, and
+/// of equal ranges the last one does, as macro expansions come last.
+fn flatten(highlights: Vec<HighlightedRange>) -> Vec<(TextRange, SemanticTokenType)> {
+    let mut highlights = highlights
+        .into_iter()
+        .filter_map(|hl| Some((hl.range, SemanticTokenType::from_tag(hl.tag)?)))
+        .collect::<Vec<_>>();
+    highlights.sort_by_key(|(range, _)| (range.start(), std::cmp::Reverse(range.len())));
+
+    let mut res = Vec::new();
+    let mut emit = |start: TextUnit, end: TextUnit, token_type| {
+        if start < end {
+            res.push((TextRange::from_to(start, end), token_type));
+        }
+    };
+    // The enclosing ranges of the current position, innermost last.
+    let mut stack: Vec<(TextRange, SemanticTokenType)> = Vec::new();
+    let mut pos = TextUnit::from(0);
+    for (range, token_type) in highlights {
+        while let Some(&(outer, outer_type)) = stack.last() {
+            if outer.end() > range.start() {
+                break;
+            }
+            emit(pos, outer.end(), outer_type);
+            pos = pos.max(outer.end());
+            stack.pop();
+        }
+        if let Some(&(_, outer_type)) = stack.last() {
+            emit(pos, range.start(), outer_type);
+        }
+        pos = pos.max(range.start());
+        // Ranges which stick out of the enclosing one are cut short.
+        let end = stack.last().map_or(range.end(), |(outer, _)| range.end().min(outer.end()));
+        stack.push((TextRange::from_to(range.start(), end), token_type));
+    }
+    while let Some((outer, outer_type)) = stack.pop() {
+        emit(pos, outer.end(), outer_type);
+        pos = pos.max(outer.end());
+    }
+    res
+}
+
+fn is_doc_comment(text: &str) -> bool {
+    ["///", "//!", "/**", "/*!"].iter().any(|prefix| text.starts_with(prefix))
+        && !text.starts_with("////")
+        && !text.starts_with("/***")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::single_file;
+
+    use super::*;
+
+    fn check_sorted_and_disjoint(tokens: &[SemanticToken]) {
+        for pair in tokens.windows(2) {
+            assert!(
+                pair[0].range.end() <= pair[1].range.start(),
+                "tokens are out of order or overlap: {:?}",
+                pair
+            );
+        }
+    }
+
+    #[test]
+    fn semantic_tokens_are_sorted_and_disjoint() {
+        let code = r#"
+/// Docs
+#[doc = "more docs"]
+fn main() {
+    // comment
+    let x = 92;
+    println!("{}", 'a');
+    vec![{ let y = 1; y }];
+}
+"#;
+        let (analysis, file_id) = single_file(code);
+        let tokens = analysis.semantic_tokens(file_id).unwrap();
+        check_sorted_and_disjoint(&tokens);
+
+        let text_of = |token_type| {
+            tokens
+                .iter()
+                .filter(|it| it.token_type == token_type)
+                .map(|it| &code[it.range])
+                .collect::<Vec<_>>()
+        };
+        // The string wins over the attribute it is in.
+        assert_eq!(text_of(SemanticTokenType::Attribute), vec!["#[doc = ", "]"]);
+        assert_eq!(text_of(SemanticTokenType::String), vec!["\"more docs\"", "\"{}\""]);
+        assert_eq!(text_of(SemanticTokenType::Comment), vec!["/// Docs", "// comment"]);
+        assert_eq!(text_of(SemanticTokenType::Macro), vec!["println!", "vec!"]);
+        assert_eq!(text_of(SemanticTokenType::Keyword), vec!["fn", "let", "let"]);
+
+        let comments = tokens
+            .iter()
+            .filter(|it| it.token_type == SemanticTokenType::Comment)
+            .collect::<Vec<_>>();
+        assert!(comments[0].has_modifier(SemanticTokenModifier::Documentation));
+        assert!(!comments[1].has_modifier(SemanticTokenModifier::Documentation));
+    }
+
+    #[test]
+    fn flatten_resolves_overlaps() {
+        let hl = |start: u32, end: u32, tag| HighlightedRange {
+            range: TextRange::from_to(start.into(), end.into()),
+            tag,
+        };
+        let flat = flatten(vec![
+            hl(0, 10, "attribute"),
+            hl(2, 4, "string"),
+            hl(2, 4, "keyword"),
+            hl(6, 12, "comment"),
+            hl(12, 14, "text"),
+        ]);
+        let flat = flat
+            .into_iter()
+            .map(|(range, token_type)| {
+                (range.start().to_usize(), range.end().to_usize(), token_type)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            flat,
+            vec![
+                (0, 2, SemanticTokenType::Attribute),
+                (2, 4, SemanticTokenType::Keyword),
+                (4, 6, SemanticTokenType::Attribute),
+                (6, 10, SemanticTokenType::Comment),
+            ]
+        );
+    }
+}
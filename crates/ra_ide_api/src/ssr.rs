@@ -0,0 +1,353 @@
+//! Structural search and replace.
+//!
+//! A query like `foo($a, $b) ==>> bar($b, $a)` consists of a pattern and a
+//! template. Both are parsed as expressions, with `$name` metavariables
+//! standing in for arbitrary subexpressions. Every expression in the
+//! workspace which has the same syntax tree as the pattern (modulo
+//! whitespace and comments) is replaced with the template, where each
+//! metavariable is substituted with the subexpression it captured.
+
+use rustc_hash::FxHashMap;
+use ra_syntax::{
+    AstNode, SourceFile, SyntaxNode, SmolStr, TreeArc,
+    ast,
+    SyntaxKind::{WHITESPACE, COMMENT},
+};
+use ra_text_edit::TextEditBuilder;
+use ra_db::{FileId, SourceDatabase};
+
+use crate::{db::RootDatabase, symbol_index::SymbolsDatabase, SourceChange, SourceFileEdit};
+
+/// Metavariables are not valid Rust, so we rename them to identifiers before
+/// parsing.
+const PLACEHOLDER_PREFIX: &str = "__ssr_";
+
+pub(crate) fn structural_search_replace(db: &RootDatabase, query: &str) -> SourceChange {
+    let mut source_file_edits = Vec::new();
+    if let Some(rule) = SsrRule::parse(query) {
+        let mut files: Vec<FileId> = Vec::new();
+        for &root in db.local_roots().iter() {
+            files.extend(db.source_root(root).files.values());
+        }
+        files.sort();
+        for file_id in files {
+            let file = db.parse(file_id);
+            let matches = rule.find_matches(file.syntax());
+            if matches.is_empty() {
+                continue;
+            }
+            let mut builder = TextEditBuilder::default();
+            for m in matches {
+                builder.replace(m.node.range(), rule.replacement(&m));
+            }
+            source_file_edits.push(SourceFileEdit { file_id, edit: builder.finish() });
+        }
+    }
+
+    SourceChange {
+        label: "structural search replace".to_string(),
+        source_file_edits,
+        file_system_edits: Vec::new(),
+        cursor_position: None,
+    }
+}
+
+struct SsrRule {
+    pattern: TreeArc<ast::Expr>,
+    template: TreeArc<ast::Expr>,
+}
+
+/// An expression matching the pattern, with the subexpressions captured by
+/// each metavariable.
+struct Match<'a> {
+    node: &'a SyntaxNode,
+    captures: FxHashMap<SmolStr, &'a SyntaxNode>,
+}
+
+impl SsrRule {
+    fn parse(query: &str) -> Option<SsrRule> {
+        let mut parts = query.split("==>>");
+        let (pattern, template) = (parts.next()?, parts.next()?);
+        if parts.next().is_some() {
+            return None;
+        }
+        let pattern = parse_expr(pattern)?;
+        let template = parse_expr(template)?;
+
+        // Every metavariable of the template has to be bound by the pattern.
+        let bound: Vec<SmolStr> = placeholders(&pattern).map(|(name, _)| name).collect();
+        if placeholders(&template).any(|(name, _)| !bound.contains(&name)) {
+            return None;
+        }
+        Some(SsrRule { pattern, template })
+    }
+
+    fn find_matches<'a>(&self, root: &'a SyntaxNode) -> Vec<Match<'a>> {
+        let mut acc = Vec::new();
+        self.find_matches_in(root, &mut acc);
+        acc
+    }
+
+    fn find_matches_in<'a>(&self, node: &'a SyntaxNode, acc: &mut Vec<Match<'a>>) {
+        if ast::Expr::cast(node).is_some() {
+            let mut captures = FxHashMap::default();
+            if match_node(self.pattern.syntax(), node, &mut captures) {
+                acc.push(Match { node, captures });
+                // Matches nested inside the captures are rewritten as part
+                // of the replacement.
+                return;
+            }
+        }
+        for child in node.children() {
+            self.find_matches_in(child, acc);
+        }
+    }
+
+    fn replacement(&self, m: &Match) -> String {
+        let template = self.template.syntax();
+        let start = template.range().start();
+        let mut res = template.text().to_string();
+        let placeholders: Vec<_> = placeholders(&self.template).collect();
+        for (name, place) in placeholders.iter().rev() {
+            let capture = m.captures[name];
+            let mut text = self.rewrite(capture);
+            if needs_parens(capture, place.syntax()) {
+                text = format!("({})", text);
+            }
+            let range = place.syntax().range();
+            res.replace_range(
+                (range.start() - start).to_usize()..(range.end() - start).to_usize(),
+                &text,
+            );
+        }
+
+        if needs_parens(template, m.node) {
+            format!("({})", res)
+        } else {
+            res
+        }
+    }
+
+    /// Returns the text of `node`, with all the matches inside it replaced.
+    fn rewrite(&self, node: &SyntaxNode) -> String {
+        let start = node.range().start();
+        let mut res = node.text().to_string();
+        for m in self.find_matches(node).iter().rev() {
+            let range = m.node.range();
+            res.replace_range(
+                (range.start() - start).to_usize()..(range.end() - start).to_usize(),
+                &self.replacement(m),
+            );
+        }
+        res
+    }
+}
+
+/// Parses `text` as an expression, after turning metavariables into
+/// identifiers.
+fn parse_expr(text: &str) -> Option<TreeArc<ast::Expr>> {
+    let mut expr = String::new();
+    let mut chars = text.trim().chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            expr.push(c);
+            continue;
+        }
+        let mut name = String::new();
+        while let Some(&c) = chars.peek() {
+            if !(c.is_alphanumeric() || c == '_') {
+                break;
+            }
+            name.push(c);
+            chars.next();
+        }
+        if name.is_empty() {
+            return None;
+        }
+        expr.push_str(PLACEHOLDER_PREFIX);
+        expr.push_str(&name);
+    }
+
+    let file = SourceFile::parse(&format!("fn __ssr() {{ {} }}", expr));
+    if !file.errors().is_empty() {
+        return None;
+    }
+    let block = file.syntax().descendants().find_map(ast::Block::cast)?;
+    if block.statements().next().is_some() {
+        return None;
+    }
+    Some(block.expr()?.to_owned())
+}
+
+/// Returns the metavariables of a parsed pattern in source order.
+fn placeholders(expr: &ast::Expr) -> impl Iterator<Item = (SmolStr, &ast::PathExpr)> {
+    expr.syntax()
+        .descendants()
+        .filter_map(ast::PathExpr::cast)
+        .filter_map(|it| Some((placeholder_name(it.syntax())?, it)))
+}
+
+fn placeholder_name(node: &SyntaxNode) -> Option<SmolStr> {
+    let path_expr = ast::PathExpr::cast(node)?;
+    let text = path_expr.syntax().text().to_string();
+    if !text.starts_with(PLACEHOLDER_PREFIX) {
+        return None;
+    }
+    Some(text[PLACEHOLDER_PREFIX.len()..].into())
+}
+
+fn match_node<'a>(
+    pattern: &SyntaxNode,
+    code: &'a SyntaxNode,
+    captures: &mut FxHashMap<SmolStr, &'a SyntaxNode>,
+) -> bool {
+    if let Some(name) = placeholder_name(pattern) {
+        if ast::Expr::cast(code).is_none() {
+            return false;
+        }
+        // A metavariable used twice has to capture the same expression.
+        if let Some(prev) = captures.get(&name) {
+            return tokens(prev).eq(tokens(code));
+        }
+        captures.insert(name, code);
+        return true;
+    }
+
+    if pattern.kind() != code.kind() {
+        return false;
+    }
+    if pattern.is_leaf() {
+        return pattern.leaf_text() == code.leaf_text();
+    }
+    let mut pattern_children = significant_children(pattern);
+    let mut code_children = significant_children(code);
+    loop {
+        match (pattern_children.next(), code_children.next()) {
+            (Some(p), Some(c)) => {
+                if !match_node(p, c, captures) {
+                    return false;
+                }
+            }
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+fn significant_children(node: &SyntaxNode) -> impl Iterator<Item = &SyntaxNode> {
+    node.children().filter(|it| it.kind() != WHITESPACE && it.kind() != COMMENT)
+}
+
+fn tokens(node: &SyntaxNode) -> impl Iterator<Item = &SmolStr> {
+    node.descendants()
+        .filter(|it| it.is_leaf() && it.kind() != WHITESPACE && it.kind() != COMMENT)
+        .filter_map(SyntaxNode::leaf_text)
+}
+
+/// Checks whether `expr` would need parentheses to keep its meaning if it
+/// replaced the expression `place`.
+fn needs_parens(expr: &SyntaxNode, place: &SyntaxNode) -> bool {
+    let is_atom = match ast::Expr::cast(expr).map(ast::Expr::kind) {
+        Some(ast::ExprKind::PathExpr(_))
+        | Some(ast::ExprKind::Literal(_))
+        | Some(ast::ExprKind::ParenExpr(_))
+        | Some(ast::ExprKind::TupleExpr(_))
+        | Some(ast::ExprKind::ArrayExpr(_))
+        | Some(ast::ExprKind::CallExpr(_))
+        | Some(ast::ExprKind::MethodCallExpr(_))
+        | Some(ast::ExprKind::FieldExpr(_))
+        | Some(ast::ExprKind::IndexExpr(_))
+        | Some(ast::ExprKind::TryExpr(_))
+        | Some(ast::ExprKind::StructLit(_))
+        | Some(ast::ExprKind::BlockExpr(_)) => true,
+        _ => false,
+    };
+    if is_atom {
+        return false;
+    }
+    match place.parent() {
+        Some(parent) => ast::Expr::cast(parent).is_some() && ast::ParenExpr::cast(parent).is_none(),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::single_file;
+
+    fn check_ssr(query: &str, before: &str, after: &str) {
+        let (analysis, file_id) = single_file(before);
+        let change = analysis.structural_search_replace(query).unwrap();
+        let mut actual = before.to_string();
+        for edit in change.source_file_edits {
+            assert_eq!(edit.file_id, file_id);
+            actual = edit.edit.apply(&actual);
+        }
+        assert_eq!(actual, after);
+    }
+
+    #[test]
+    fn ssr_swaps_arguments() {
+        check_ssr(
+            "foo($a, $b) ==>> bar($b, $a)",
+            "fn main() { foo(x, 1 + 2); foo (y,z) }",
+            "fn main() { bar(1 + 2, x); bar(z, y) }",
+        );
+    }
+
+    #[test]
+    fn ssr_requires_same_structure() {
+        check_ssr(
+            "foo($a, $b) ==>> bar($b, $a)",
+            "fn main() { foo(x); foo(x, y, z); baz(x, y); s.foo(x, y) }",
+            "fn main() { foo(x); foo(x, y, z); baz(x, y); s.foo(x, y) }",
+        );
+    }
+
+    #[test]
+    fn ssr_repeated_metavariable_must_match_same_expression() {
+        check_ssr(
+            "$a == $a ==>> true",
+            "fn main() { x.y == x . y; x == y }",
+            "fn main() { true; x == y }",
+        );
+    }
+
+    #[test]
+    fn ssr_ignores_comments_and_strings() {
+        check_ssr(
+            "foo($a) ==>> bar($a)",
+            r#"fn main() {
+    // foo(x)
+    let s = "foo(x)";
+    foo(/* arg */ x)
+}"#,
+            r#"fn main() {
+    // foo(x)
+    let s = "foo(x)";
+    bar(x)
+}"#,
+        );
+    }
+
+    #[test]
+    fn ssr_adds_parentheses() {
+        check_ssr(
+            "double($a) ==>> $a * 2",
+            "fn main() { let x = double(a + 1) + 1; let y = double(b); }",
+            "fn main() { let x = ((a + 1) * 2) + 1; let y = b * 2; }",
+        );
+    }
+
+    #[test]
+    fn ssr_replaces_nested_matches() {
+        check_ssr("foo($a) ==>> bar($a)", "fn main() { foo(foo(x)) }", "fn main() { bar(bar(x)) }");
+    }
+
+    #[test]
+    fn ssr_invalid_query() {
+        for query in ["foo($a)", "foo($a) ==>> bar($b)", "foo( ==>> bar()", "$ ==>> x"].iter() {
+            check_ssr(query, "fn main() { foo(x) }", "fn main() { foo(x) }");
+        }
+    }
+}
@@ -6,9 +6,10 @@ use std::{
 
 use ra_syntax::{AstNode, TreeArc, SourceFile};
 use ra_db::{
-    ParseQuery, FileTextQuery, SourceRootId,
+    ParseQuery, FileTextQuery, SourceRootId, SourceDatabase,
     salsa::{Database, debug::{DebugQueryTable, TableEntry}},
 };
+use hir::db::{ItemMapQuery, LowerModuleWithSourceMapQuery, BodyWithSourceMapQuery, InferQuery};
 
 use crate::{
     FileId, db::RootDatabase,
@@ -20,24 +21,83 @@ pub(crate) fn syntax_tree_stats(db: &RootDatabase) -> SyntaxTreeStats {
 }
 
 pub(crate) fn status(db: &RootDatabase) -> String {
-    let files_stats = db.query(FileTextQuery).entries::<FilesStats>();
-    let syntax_tree_stats = syntax_tree_stats(db);
-    let symbols_stats = db.query(LibrarySymbolsQuery).entries::<LibrarySymbolsStats>();
-    let n_defs = {
-        let interner: &hir::HirInterner = db.as_ref();
-        interner.len()
-    };
+    let stats = Stats::current(db);
     format!(
-        "{}\n{}\n{}\n{} defs\n\nmemory:\n{}\ngc {:?} seconds ago",
-        files_stats,
-        symbols_stats,
-        syntax_tree_stats,
-        n_defs,
-        MemoryStats::current(),
+        "{}\n{} crates\n{}\n{}\n{} defs\n\nqueries:\n{} item maps\n{} lowered modules\n\
+         {} bodies\n{} inference results\n\nmemory:\n{}\ngc {:?} seconds ago",
+        stats.files,
+        stats.n_crates,
+        stats.symbols,
+        stats.syntax_trees,
+        stats.n_defs,
+        stats.item_maps,
+        stats.lowered_modules,
+        stats.bodies,
+        stats.inference_results,
+        stats.memory,
         db.last_gc.elapsed().as_secs(),
     )
 }
 
+/// Lists the same statistics as `status`, as pairs of a name and a number.
+/// The names say what the number counts: entries, or bytes if they end in
+/// `bytes`. Only the values which are actually cached count as entries.
+///
+/// Item maps, lowered modules, bodies and inference results only get a count:
+/// there is no way to measure their values, so their memory only shows in the
+/// allocated and resident totals, which need the `jemalloc` feature.
+pub(crate) fn memory_usage(db: &RootDatabase) -> Vec<(String, usize)> {
+    let stats = Stats::current(db);
+    let res = vec![
+        ("files", stats.files.total),
+        ("file text bytes", stats.files.size.0),
+        ("crates", stats.n_crates),
+        ("syntax trees", stats.syntax_trees.retained),
+        ("syntax tree bytes", stats.syntax_trees.retained_size.0),
+        ("library symbols", stats.symbols.total),
+        ("library symbol bytes", stats.symbols.size.0),
+        ("defs", stats.n_defs),
+        ("item maps", stats.item_maps.retained),
+        ("lowered modules", stats.lowered_modules.retained),
+        ("bodies", stats.bodies.retained),
+        ("inference results", stats.inference_results.retained),
+        ("allocated bytes", stats.memory.allocated.0),
+        ("resident bytes", stats.memory.resident.0),
+    ];
+    res.into_iter().map(|(name, value)| (name.to_string(), value)).collect()
+}
+
+struct Stats {
+    files: FilesStats,
+    n_crates: usize,
+    syntax_trees: SyntaxTreeStats,
+    symbols: LibrarySymbolsStats,
+    n_defs: usize,
+    item_maps: EntriesStats,
+    lowered_modules: EntriesStats,
+    bodies: EntriesStats,
+    inference_results: EntriesStats,
+    memory: MemoryStats,
+}
+
+impl Stats {
+    fn current(db: &RootDatabase) -> Stats {
+        let interner: &hir::HirInterner = db.as_ref();
+        Stats {
+            files: db.query(FileTextQuery).entries::<FilesStats>(),
+            n_crates: db.crate_graph().len(),
+            syntax_trees: syntax_tree_stats(db),
+            symbols: db.query(LibrarySymbolsQuery).entries::<LibrarySymbolsStats>(),
+            n_defs: interner.len(),
+            item_maps: db.query(ItemMapQuery).entries::<EntriesStats>(),
+            lowered_modules: db.query(LowerModuleWithSourceMapQuery).entries::<EntriesStats>(),
+            bodies: db.query(BodyWithSourceMapQuery).entries::<EntriesStats>(),
+            inference_results: db.query(InferQuery).entries::<EntriesStats>(),
+            memory: MemoryStats::current(),
+        }
+    }
+}
+
 #[derive(Default)]
 struct FilesStats {
     total: usize,
@@ -121,6 +181,35 @@ impl FromIterator<TableEntry<SourceRootId, Arc<SymbolIndex>>> for LibrarySymbols
     }
 }
 
+/// The number of entries of a query whose values we can't measure.
+#[derive(Default)]
+struct EntriesStats {
+    total: usize,
+    retained: usize,
+}
+
+impl fmt::Display for EntriesStats {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{} ({} retained)", self.total, self.retained)
+    }
+}
+
+impl<K, V> FromIterator<TableEntry<K, V>> for EntriesStats {
+    fn from_iter<T>(iter: T) -> EntriesStats
+    where
+        T: IntoIterator<Item = TableEntry<K, V>>,
+    {
+        let mut res = EntriesStats::default();
+        for entry in iter {
+            res.total += 1;
+            if entry.value.is_some() {
+                res.retained += 1;
+            }
+        }
+        res
+    }
+}
+
 struct MemoryStats {
     allocated: Bytes,
     resident: Bytes,
@@ -171,3 +260,28 @@ impl std::ops::AddAssign<usize> for Bytes {
         self.0 += x;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ra_syntax::TextRange;
+
+    use crate::{FileRange, mock_analysis::single_file};
+
+    #[test]
+    fn memory_usage_counts_cached_queries() {
+        let (analysis, file_id) = single_file("struct S; fn foo() { let s = S; }");
+        let value_of = |name: &str| {
+            let usage = analysis.memory_usage();
+            usage.iter().find(|(it, _)| it == name).map(|(_, value)| *value).unwrap()
+        };
+        assert_eq!(value_of("files"), 1);
+        assert_eq!(value_of("crates"), 1);
+        assert_eq!(value_of("inference results"), 0);
+
+        let range = TextRange::from_to(25.into(), 26.into());
+        analysis.type_of(FileRange { file_id, range }).unwrap();
+        assert_eq!(value_of("syntax trees"), 1);
+        assert!(value_of("syntax tree bytes") > 0);
+        assert_eq!(value_of("inference results"), 1);
+    }
+}
@@ -28,6 +28,8 @@ use std::{
 };
 
 use fst::{self, Streamer};
+use itertools::Itertools;
+use join_to_string::join;
 use ra_syntax::{
     SyntaxNode, SyntaxNodePtr, SourceFile, SmolStr, TreeArc, AstNode,
     algo::{visit::{visitor, Visitor}, find_covering_node},
@@ -43,7 +45,7 @@ use ra_db::{
 use rayon::prelude::*;
 
 use crate::{
-    FileId, Query,
+    FileId, Query, NavigationTargetKind,
     db::RootDatabase,
 };
 
@@ -72,7 +74,15 @@ fn file_symbols(db: &impl SymbolsDatabase, file_id: FileId) -> Arc<SymbolIndex>
         let node = find_covering_node(source_file.syntax(), text_range);
         let ptr = SyntaxNodePtr::new(node);
         // TODO: Should we get container name for macro symbols?
-        symbols.push(FileSymbol { file_id, name, ptr, name_range: None, container_name: None })
+        symbols.push(FileSymbol {
+            file_id,
+            name,
+            ptr,
+            kind: NavigationTargetKind::Macro,
+            name_range: None,
+            container_name: None,
+            container_path: None,
+        })
     }
 
     Arc::new(SymbolIndex::new(symbols))
@@ -142,13 +152,27 @@ impl Hash for SymbolIndex {
 
 impl SymbolIndex {
     fn new(mut symbols: Vec<FileSymbol>) -> SymbolIndex {
-        fn cmp(s1: &FileSymbol, s2: &FileSymbol) -> Ordering {
-            unicase::Ascii::new(s1.name.as_str()).cmp(&unicase::Ascii::new(s2.name.as_str()))
+        symbols.par_sort_by(compare_names);
+        SymbolIndex::from_sorted(symbols)
+    }
+
+    /// Builds the index of symbols which are already sorted by name.
+    fn from_sorted(symbols: Vec<FileSymbol>) -> SymbolIndex {
+        // Symbols sharing a name, like `new` methods of different types, get
+        // a single key, which maps to the `start..end` range of them.
+        let mut builder = fst::MapBuilder::memory();
+        let mut start = 0;
+        while start < symbols.len() {
+            let end = start
+                + symbols[start..]
+                    .iter()
+                    .take_while(|it| compare_names(it, &symbols[start]) == Ordering::Equal)
+                    .count();
+            let key = symbols[start].name.as_str().to_lowercase();
+            builder.insert(key, ((start as u64) << 32) | end as u64).unwrap();
+            start = end;
         }
-        symbols.par_sort_by(cmp);
-        symbols.dedup_by(|s1, s2| cmp(s1, s2) == Ordering::Equal);
-        let names = symbols.iter().map(|it| it.name.as_str().to_lowercase());
-        let map = fst::Map::from_iter(names.zip(0u64..)).unwrap();
+        let map = fst::Map::from_bytes(builder.into_inner().unwrap()).unwrap();
         SymbolIndex { symbols, map }
     }
 
@@ -160,13 +184,25 @@ impl SymbolIndex {
         self.map.as_fst().size() + self.symbols.len() * mem::size_of::<FileSymbol>()
     }
 
+    /// Builds the index of several files: the table of each file is built
+    /// and sorted on its own, in parallel, and the sorted tables are merged
+    /// pairwise.
     pub(crate) fn for_files(
         files: impl ParallelIterator<Item = (FileId, TreeArc<SourceFile>)>,
     ) -> SymbolIndex {
         let symbols = files
-            .flat_map(|(file_id, file)| source_file_to_file_symbols(&file, file_id))
-            .collect::<Vec<_>>();
-        SymbolIndex::new(symbols)
+            .map(|(file_id, file)| {
+                let mut symbols = source_file_to_file_symbols(&file, file_id);
+                symbols.sort_by(compare_names);
+                symbols
+            })
+            .reduce(Vec::new, |left, right| {
+                // Ties are taken from the left, to keep the order of the files.
+                left.into_iter()
+                    .merge_by(right, |l, r| compare_names(l, r) != Ordering::Greater)
+                    .collect()
+            });
+        SymbolIndex::from_sorted(symbols)
     }
 }
 
@@ -185,22 +221,33 @@ impl Query {
             }
             for indexed_value in indexed_values {
                 let file_symbols = &indices[indexed_value.index];
-                let idx = indexed_value.value as usize;
-
-                let symbol = &file_symbols.symbols[idx];
-                if self.only_types && !is_type(symbol.ptr.kind()) {
-                    continue;
+                let (start, end) = unpack_range(indexed_value.value);
+
+                for symbol in &file_symbols.symbols[start..end] {
+                    if self.only_types && !is_type(symbol.ptr.kind()) {
+                        continue;
+                    }
+                    if self.exact && symbol.name != self.query {
+                        continue;
+                    }
+                    res.push(symbol.clone());
                 }
-                if self.exact && symbol.name != self.query {
-                    continue;
-                }
-                res.push(symbol.clone());
             }
         }
         res
     }
 }
 
+/// The order of the symbols in the index: by name, ignoring case.
+fn compare_names(s1: &FileSymbol, s2: &FileSymbol) -> Ordering {
+    unicase::Ascii::new(s1.name.as_str()).cmp(&unicase::Ascii::new(s2.name.as_str()))
+}
+
+/// The inverse of packing a range into a value of the `fst` map.
+fn unpack_range(value: u64) -> (usize, usize) {
+    ((value >> 32) as usize, value as u32 as usize)
+}
+
 fn is_type(kind: SyntaxKind) -> bool {
     match kind {
         STRUCT_DEF | ENUM_DEF | TRAIT_DEF | TYPE_ALIAS_DEF => true,
@@ -215,8 +262,12 @@ pub(crate) struct FileSymbol {
     pub(crate) file_id: FileId,
     pub(crate) name: SmolStr,
     pub(crate) ptr: SyntaxNodePtr,
+    pub(crate) kind: NavigationTargetKind,
     pub(crate) name_range: Option<TextRange>,
     pub(crate) container_name: Option<SmolStr>,
+    /// The names of all enclosing items, joined with `::`, to tell apart
+    /// symbols with the same name, like `new` methods of different types.
+    pub(crate) container_path: Option<SmolStr>,
 }
 
 fn source_file_to_file_symbols(source_file: &SourceFile, file_id: FileId) -> Vec<FileSymbol> {
@@ -228,14 +279,19 @@ fn source_file_to_file_symbols(source_file: &SourceFile, file_id: FileId) -> Vec
             WalkEvent::Enter(node) => {
                 if let Some(mut symbol) = to_file_symbol(node, file_id) {
                     symbol.container_name = stack.last().cloned();
-
-                    stack.push(symbol.name.clone());
+                    if !stack.is_empty() {
+                        symbol.container_path =
+                            Some(join(stack.iter()).separator("::").to_string().into());
+                    }
                     symbols.push(symbol);
                 }
+                if let Some(name) = container_name(node) {
+                    stack.push(name);
+                }
             }
 
             WalkEvent::Leave(node) => {
-                if to_symbol(node).is_some() {
+                if container_name(node).is_some() {
                     stack.pop();
                 }
             }
@@ -245,6 +301,20 @@ fn source_file_to_file_symbols(source_file: &SourceFile, file_id: FileId) -> Vec
     symbols
 }
 
+/// The name `node` contributes to the container path of the symbols inside
+/// it. Impl blocks contribute the name of their type.
+fn container_name(node: &SyntaxNode) -> Option<SmolStr> {
+    if let Some(impl_block) = ast::ImplBlock::cast(node) {
+        let type_ref = impl_block.target_type()?;
+        let name = match type_ref.kind() {
+            ast::TypeRefKind::PathType(it) => it.path()?.segment()?.name_ref()?.text().clone(),
+            _ => type_ref.syntax().text().to_string().into(),
+        };
+        return Some(name);
+    }
+    to_symbol(node).map(|(name, _, _)| name)
+}
+
 fn to_symbol(node: &SyntaxNode) -> Option<(SmolStr, SyntaxNodePtr, TextRange)> {
     fn decl<N: NameOwner>(node: &N) -> Option<(SmolStr, SyntaxNodePtr, TextRange)> {
         let name = node.name()?;
@@ -270,8 +340,62 @@ fn to_file_symbol(node: &SyntaxNode, file_id: FileId) -> Option<FileSymbol> {
     to_symbol(node).map(move |(name, ptr, name_range)| FileSymbol {
         name,
         ptr,
+        kind: NavigationTargetKind::from_syntax(node),
         file_id,
         name_range: Some(name_range),
         container_name: None,
+        container_path: None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use ra_syntax::SourceFile;
+    use rayon::prelude::*;
+
+    use crate::{FileId, Query, mock_analysis::MockAnalysis};
+    use super::{SymbolIndex, source_file_to_file_symbols, world_symbols};
+
+    fn many_files() -> Vec<(FileId, String)> {
+        (0..200u32)
+            .map(|i| {
+                let text = format!(
+                    "struct Struct{i} {{}}\nfn func_{i}() {{}}\nmod module_{i} {{ fn inner_{i}() {{}} }}",
+                    i = i
+                );
+                (FileId(i + 1), text)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parallel_index_equals_serial_index() {
+        let files = many_files();
+        let parallel = SymbolIndex::for_files(
+            files.par_iter().map(|(file_id, text)| (*file_id, SourceFile::parse(text))),
+        );
+        let serial = SymbolIndex::new(
+            files
+                .iter()
+                .flat_map(|(file_id, text)| {
+                    source_file_to_file_symbols(&SourceFile::parse(text), *file_id)
+                })
+                .collect(),
+        );
+        assert_eq!(parallel.len(), 800);
+        assert!(parallel == serial);
+    }
+
+    #[test]
+    fn world_symbols_sees_all_files() {
+        let mut mock = MockAnalysis::new();
+        for (file_id, text) in many_files() {
+            mock.add_file(&format!("/file_{}.rs", file_id.0), &text);
+        }
+        let analysis = mock.analysis();
+        let mut query = Query::new("inner_".to_string());
+        query.limit(1000);
+        let symbols = analysis.with_db(|db| world_symbols(db, query)).unwrap();
+        assert_eq!(symbols.len(), 200);
+    }
+}
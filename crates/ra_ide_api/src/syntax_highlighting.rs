@@ -7,9 +7,20 @@ use crate::{
 };
 
 pub(crate) fn highlight(db: &RootDatabase, file_id: FileId) -> Vec<HighlightedRange> {
+    highlight_with_poll(db, file_id, &|| ())
+}
+
+/// Computes the highlighting, calling `poll` between the macro calls it
+/// expands.
+pub(crate) fn highlight_with_poll(
+    db: &RootDatabase,
+    file_id: FileId,
+    poll: &dyn Fn(),
+) -> Vec<HighlightedRange> {
     let source_file = db.parse(file_id);
     let mut res = ra_ide_api_light::highlight(source_file.syntax());
-    for macro_call in source_file.syntax().descendants().filter_map(ast::MacroCall::cast) {
+    let macro_calls = source_file.syntax().descendants().filter_map(ast::MacroCall::cast);
+    for macro_call in db.poll_each(macro_calls, poll) {
         if let Some((off, exp)) = hir::MacroDef::ast_expand(macro_call) {
             let mapped_ranges =
                 ra_ide_api_light::highlight(&exp.syntax()).into_iter().filter_map(|r| {
@@ -1,10 +1,18 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
 use insta::assert_debug_snapshot_matches;
 use ra_ide_api::{
     mock_analysis::{single_file, single_file_with_position, single_file_with_range, MockAnalysis},
-    AnalysisChange, CrateGraph, Edition::Edition2018, Query, NavigationTarget,
-    ReferenceSearchResult,
+    AnalysisChange, AnalysisHost, CrateGraph, Dependency,
+    Edition::Edition2018,
+    Query, NavigationTarget, ReferenceSearchResult, FileRange, Severity, LibraryData, SourceRootId,
+    FileId, FilePosition, Canceled,
 };
-use ra_syntax::SmolStr;
+use relative_path::RelativePathBuf;
+use ra_syntax::{SmolStr, TextRange, TextUnit};
 
 #[test]
 fn test_unresolved_module_diagnostic() {
@@ -45,6 +53,31 @@ fn test_resolve_crate_root() {
     assert_eq!(host.analysis().crate_for(mod_file).unwrap(), vec![crate_id]);
 }
 
+#[test]
+fn test_crate_dependencies_and_dependents() {
+    let mock = MockAnalysis::with_files(
+        "
+        //- /lib.rs
+        extern crate b;
+        //- /b/lib.rs
+        pub struct S;
+    ",
+    );
+    let a_file = mock.id_of("/lib.rs");
+    let b_file = mock.id_of("/b/lib.rs");
+    let analysis = mock.analysis();
+    let a = analysis.crate_for(a_file).unwrap()[0];
+    let b = analysis.crate_for(b_file).unwrap()[0];
+
+    let deps = analysis.crate_dependencies(a).unwrap();
+    assert_eq!(deps, vec![Dependency { crate_id: b, name: SmolStr::new("b") }]);
+    assert!(analysis.crate_dependencies(b).unwrap().is_empty());
+
+    let dependents = analysis.crate_dependents(b).unwrap();
+    assert_eq!(dependents, vec![Dependency { crate_id: a, name: SmolStr::new("b") }]);
+    assert!(analysis.crate_dependents(a).unwrap().is_empty());
+}
+
 fn get_all_refs(text: &str) -> ReferenceSearchResult {
     let (analysis, position) = single_file_with_position(text);
     analysis.find_all_refs(position).unwrap().unwrap()
@@ -96,6 +129,104 @@ fn test_find_all_refs_for_fn_param() {
     assert_eq!(refs.len(), 2);
 }
 
+#[test]
+fn test_find_refs_in_range() {
+    let code = r#"
+    fn main() {
+        let mut i = 1;
+        let j = 1;
+        i = i<|> + j;
+
+        {
+            i = 0;
+        }
+
+        i = 5;
+    }"#;
+    let (analysis, position) = single_file_with_position(code);
+    let text = analysis.file_text(position.file_id);
+    let range_of = |start: &str, end: &str| {
+        let start = text.find(start).unwrap();
+        let end = text[start..].find(end).unwrap() + start + end.len();
+        FileRange {
+            file_id: position.file_id,
+            range: TextRange::from_to(TextUnit::from_usize(start), TextUnit::from_usize(end)),
+        }
+    };
+
+    let refs = analysis.find_refs_in_range(position, range_of("        {", "}")).unwrap().unwrap();
+    assert_eq!(refs.len(), 1);
+    assert_eq!(refs[0].range.start(), TextUnit::from_usize(text.find("i = 0").unwrap()));
+
+    // The declaration is included when it's in the scope.
+    let refs = analysis.find_refs_in_range(position, range_of("let mut", "+ j")).unwrap().unwrap();
+    assert_eq!(refs.len(), 3);
+
+    let refs = analysis.find_refs_in_range(position, range_of("let j", "1;")).unwrap().unwrap();
+    assert!(refs.is_empty());
+}
+
+#[test]
+fn test_find_all_refs_marks_library_references() {
+    let code = "fn foo() { let x = 1; let y = x + x; }";
+    let mock = MockAnalysis::with_files(&format!("//- /main.rs\n{}", code));
+    let local_file = mock.id_of("/main.rs");
+    let mut host = mock.analysis_host();
+    let library_file = FileId(2);
+    let mut change = AnalysisChange::new();
+    change.add_library(LibraryData::prepare(
+        SourceRootId(1),
+        vec![(library_file, RelativePathBuf::from("lib.rs"), Arc::new(code.to_string()))],
+    ));
+    let mut crate_graph = CrateGraph::default();
+    crate_graph.add_crate_root(local_file, Edition2018);
+    crate_graph.add_crate_root(library_file, Edition2018);
+    change.set_crate_graph(crate_graph);
+    host.apply_change(change);
+    let analysis = host.analysis();
+    let offset = TextUnit::from_usize(code.rfind('x').unwrap());
+
+    let position = FilePosition { file_id: local_file, offset };
+    let refs = analysis.find_all_refs(position).unwrap().unwrap();
+    assert_eq!(refs.len(), 3);
+    assert!(refs.references().iter().all(|it| refs.is_editable(it)));
+    assert_eq!(refs.library_references().count(), 0);
+
+    let position = FilePosition { file_id: library_file, offset };
+    let refs = analysis.find_all_refs(position).unwrap().unwrap();
+    assert_eq!(refs.len(), 3);
+    assert!(refs.references().iter().all(|it| !refs.is_editable(it)));
+    assert_eq!(refs.library_references().count(), 2);
+    assert!(analysis.rename(position, "z").unwrap().is_none());
+}
+
+#[test]
+fn test_library_indexing_can_be_canceled() {
+    let files = (0..100u32)
+        .map(|i| {
+            let path = RelativePathBuf::from(format!("f{}.rs", i));
+            (FileId(i + 1), path, Arc::new(format!("fn f{}() {{}}", i)))
+        })
+        .collect::<Vec<_>>();
+    let indexed = AtomicUsize::new(0);
+    let res = LibraryData::prepare_with_poll(SourceRootId(1), files.clone(), &|| {
+        if indexed.fetch_add(1, Ordering::SeqCst) == 10 {
+            Canceled::throw();
+        }
+    });
+    assert!(res.is_err());
+
+    let data = LibraryData::prepare_with_poll(SourceRootId(1), files, &|| ()).unwrap();
+    let mut host = AnalysisHost::default();
+    let mut change = AnalysisChange::new();
+    change.add_library(data);
+    host.apply_change(change);
+    let mut query = Query::new("f".to_string());
+    query.libs();
+    query.limit(1000);
+    assert_eq!(host.analysis().symbol_search(query).unwrap().len(), 100);
+}
+
 #[test]
 fn test_world_symbols_with_no_container() {
     let code = r#"
@@ -110,6 +241,21 @@ fn test_world_symbols_with_no_container() {
     assert!(s.container_name().is_none());
 }
 
+#[test]
+fn test_world_symbols_have_full_and_focus_ranges() {
+    let code = r#"
+fn foo() {
+    let x = 92;
+}
+"#;
+
+    let s = get_symbols_matching(code, "foo").pop().unwrap();
+
+    assert_eq!(&code[s.full_range()], "fn foo() {\n    let x = 92;\n}");
+    assert_eq!(&code[s.focus_range().unwrap()], "foo");
+    assert_eq!(s.range(), s.focus_range().unwrap());
+}
+
 #[test]
 fn test_world_symbols_include_container_name() {
     let code = r#"
@@ -139,6 +285,28 @@ mod foo {
     assert_eq!(s.container_name(), Some(&SmolStr::new("foo")));
 }
 
+#[test]
+fn test_world_symbols_include_container_path() {
+    let code = r#"
+mod m {
+    struct A;
+    impl A { fn new() -> A { A } }
+}
+struct B<T>(T);
+impl<T> B<T> { fn new() {} }
+    "#;
+
+    let symbols = get_symbols_matching(code, "new");
+    let mut paths = symbols
+        .iter()
+        .filter(|it| it.name() == "new")
+        .map(|it| it.container_path().unwrap().to_string())
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    assert_eq!(paths, vec!["B", "m::A"]);
+}
+
 #[test]
 fn test_syntax_tree_without_range() {
     // Basic syntax
@@ -390,3 +558,14 @@ SOURCE_FILE@[0; 25)
         .trim()
     );
 }
+
+#[test]
+fn test_syntax_diagnostics() {
+    let (analysis, file_id) = single_file("mod foo;\nfn main() {");
+    let diagnostics = analysis.syntax_diagnostics(file_id).unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.severity, Severity::Error);
+    assert_eq!(diagnostic.range, TextRange::offset_len(20.into(), 1.into()));
+    assert!(diagnostic.message.starts_with("Syntax Error"));
+}
@@ -1,15 +1,30 @@
 use itertools::Itertools;
 
 use ra_syntax::{
-    Location, SourceFile, SyntaxKind, TextRange, SyntaxNode,
-    ast::{self, AstNode},
-
+    Location, SourceFile, SyntaxKind, TextRange, TextUnit, SyntaxNode, SmolStr, SyntaxError,
+    SyntaxErrorKind,
+    algo::find_covering_node,
+    ast::{self, AstNode, NameOwner},
 };
 use ra_text_edit::{TextEdit, TextEditBuilder};
 
 use crate::{Diagnostic, LocalEdit, Severity};
 
 pub fn diagnostics(file: &SourceFile) -> Vec<Diagnostic> {
+    let mut errors = syntax_diagnostics(file);
+
+    for node in file.syntax().descendants() {
+        check_unnecessary_braces_in_use_statement(&mut errors, node);
+        check_struct_shorthand_initialization(&mut errors, node);
+        check_accidental_shadowing(&mut errors, node);
+    }
+
+    errors
+}
+
+/// Only the errors of the parser, which are known as soon as the file is
+/// parsed.
+pub fn syntax_diagnostics(file: &SourceFile) -> Vec<Diagnostic> {
     fn location_to_range(location: Location) -> TextRange {
         match location {
             Location::Offset(offset) => TextRange::offset_len(offset, 1.into()),
@@ -17,23 +32,60 @@ pub fn diagnostics(file: &SourceFile) -> Vec<Diagnostic> {
         }
     }
 
-    let mut errors: Vec<Diagnostic> = file
-        .errors()
+    file.errors()
         .into_iter()
-        .map(|err| Diagnostic {
-            range: location_to_range(err.location()),
-            msg: format!("Syntax Error: {}", err),
-            severity: Severity::Error,
-            fix: None,
+        .map(|err| match missing_semicolon_fix(file, &err) {
+            Some(fix) => Diagnostic {
+                range: location_to_range(err.location()),
+                msg: "expected `;`".to_string(),
+                severity: Severity::Error,
+                fix: Some(fix),
+            },
+            None => Diagnostic {
+                range: location_to_range(err.location()),
+                msg: format!("Syntax Error: {}", err),
+                severity: Severity::Error,
+                fix: None,
+            },
         })
-        .collect();
+        .collect()
+}
 
-    for node in file.syntax().descendants() {
-        check_unnecessary_braces_in_use_statement(&mut errors, node);
-        check_struct_shorthand_initialization(&mut errors, node);
+/// The parser recovers from a statement which isn't terminated before the
+/// next line. If inserting the `;` makes the enclosing block parse, the error
+/// gets a fix.
+fn missing_semicolon_fix(file: &SourceFile, err: &SyntaxError) -> Option<LocalEdit> {
+    match err.kind() {
+        SyntaxErrorKind::ParseError(ref err) if err.0 == "expected SEMI" => (),
+        _ => return None,
+    }
+    let offset = err.offset();
+    let text = file.syntax().text().to_string();
+    let rest = &text[offset.to_usize()..];
+    let gap = rest.find(|c: char| !c.is_whitespace()).map(|it| &rest[..it])?;
+    if !gap.contains('\n') {
+        return None;
     }
+    let block = find_covering_node(file.syntax(), TextRange::offset_len(offset, 0.into()))
+        .ancestors()
+        .find_map(ast::Block::cast)?;
 
-    errors
+    let mut edit = TextEditBuilder::default();
+    edit.insert(offset, ";".to_string());
+    let edit = edit.finish();
+    let fixed = SourceFile::parse(&edit.apply(&text));
+    let block_range = TextRange::from_to(
+        block.syntax().range().start(),
+        block.syntax().range().end() + TextUnit::of_char(';'),
+    );
+    if fixed.errors().iter().any(|it| block_range.contains_inclusive(it.offset())) {
+        return None;
+    }
+    Some(LocalEdit {
+        label: "insert `;`".to_string(),
+        edit,
+        cursor_position: Some(offset + TextUnit::of_char(';')),
+    })
 }
 
 fn check_unnecessary_braces_in_use_statement(
@@ -115,6 +167,66 @@ fn check_struct_shorthand_initialization(
     Some(())
 }
 
+/// Flags `let x = ..; let x = ..;`, where the first `x` is shadowed before it
+/// is ever used, which is usually a typo.
+fn check_accidental_shadowing(acc: &mut Vec<Diagnostic>, node: &SyntaxNode) -> Option<()> {
+    let block = ast::Block::cast(node)?;
+    // Bindings of this block which haven't been read so far.
+    let mut unread: Vec<SmolStr> = Vec::new();
+    for stmt in block.statements() {
+        unread.retain(|name| !mentions_name(stmt.syntax(), name));
+        let pat = match stmt.kind() {
+            ast::StmtKind::LetStmt(it) => match it.pat() {
+                Some(it) => it,
+                None => continue,
+            },
+            ast::StmtKind::ExprStmt(_) => continue,
+        };
+        let bind_pat = match pat.kind() {
+            ast::PatKind::BindPat(it) if it.pat().is_none() => it,
+            _ => {
+                // We don't track bindings of complex patterns, but they still
+                // end the lifetime of the bindings they shadow.
+                for bind_pat in pat.syntax().descendants().filter_map(ast::BindPat::cast) {
+                    if let Some(name) = bind_pat.name() {
+                        unread.retain(|it| *it != *name.text());
+                    }
+                }
+                continue;
+            }
+        };
+        let name = match bind_pat.name() {
+            Some(it) => it,
+            None => continue,
+        };
+        let text = name.text().clone();
+        if text.starts_with('_') {
+            continue;
+        }
+        if unread.contains(&text) {
+            acc.push(Diagnostic {
+                range: name.syntax().range(),
+                msg: format!("`{}` shadows a binding which is never used", text),
+                severity: Severity::WeakWarning,
+                fix: None,
+            });
+        } else {
+            unread.push(text);
+        }
+    }
+    Some(())
+}
+
+/// Checks for any reference to `name`, including inside macro calls, whose
+/// arguments are not parsed.
+fn mentions_name(node: &SyntaxNode, name: &SmolStr) -> bool {
+    node.descendants().any(|it| {
+        it.kind() == SyntaxKind::IDENT
+            && it.parent().map(SyntaxNode::kind) != Some(SyntaxKind::NAME)
+            && it.leaf_text() == Some(name)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test_utils::assert_eq_text;
@@ -243,4 +355,103 @@ fn main() {
             check_struct_shorthand_initialization,
         );
     }
+
+    fn check_diagnostic_ranges(code: &str, func: DiagnosticChecker, expected: &[&str]) {
+        let file = SourceFile::parse(code);
+        let mut diagnostics = Vec::new();
+        for node in file.syntax().descendants() {
+            func(&mut diagnostics, node);
+        }
+        let actual = diagnostics
+            .iter()
+            .map(|it| {
+                assert!(it.fix.is_none());
+                &code[it.range]
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_check_accidental_shadowing() {
+        check_diagnostic_ranges(
+            r#"
+fn main() {
+    let x = 1;
+    let x = 2;
+    let yyy = 1;
+    let mut yyy = yyy + 1;
+    let z = 1;
+    println!("{}", z);
+    let z = 2;
+}
+        "#,
+            check_accidental_shadowing,
+            &["x"],
+        );
+    }
+
+    #[test]
+    fn test_check_accidental_shadowing_after_binding_without_name() {
+        check_diagnostic_ranges(
+            r#"
+fn main() {
+    let x = 1;
+    let mut = 2;
+    let x = 3;
+}
+        "#,
+            check_accidental_shadowing,
+            &["x"],
+        );
+    }
+
+    #[test]
+    fn test_check_accidental_shadowing_not_applicable() {
+        check_not_applicable(
+            r#"
+fn main() {
+    let x = 1;
+    let x = x + 1;
+    let y = 1;
+    foo(|| y);
+    let y = 2;
+    let z = 1;
+    let (z, w) = (2, 3);
+    let z = 4;
+    let _a = 1;
+    let _a = 2;
+    let b = 1;
+    {
+        let b = 2;
+    }
+}
+        "#,
+            check_accidental_shadowing,
+        );
+    }
+
+    #[test]
+    fn test_missing_semicolon_fix() {
+        let before = "
+fn main() {
+    let x = 92
+    foo(x);
+}
+";
+        let file = SourceFile::parse(before);
+        let diags = diagnostics(&file);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].msg, "expected `;`");
+        let fix = diags[0].fix.as_ref().unwrap();
+        assert_eq_text!(&before.replace("92", "92;"), &fix.edit.apply(before));
+
+        // On one line, or with another error in the block, it's a plain syntax error.
+        for code in
+            ["fn main() { let x = 92 foo(x); }", "fn main() {\n let x = 92\n let y = ;\n}"].iter()
+        {
+            let file = SourceFile::parse(code);
+            assert!(diagnostics(&file).iter().all(|it| it.fix.is_none()), "{}", code);
+        }
+    }
 }
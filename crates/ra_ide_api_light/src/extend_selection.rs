@@ -236,6 +236,33 @@ const FOO: [usize; 2] = [
         );
     }
 
+    #[test]
+    fn test_extend_selection_statements_and_arms() {
+        do_check(
+            r#"
+fn foo() {
+    match x {
+        Some(a) => bar(<|>a, 1),
+        None => (),
+    }
+}"#,
+            &[
+                "a",
+                "a, ",
+                "(a, 1)",
+                "bar(a, 1)",
+                "Some(a) => bar(a, 1)",
+                "Some(a) => bar(a, 1),",
+                "{\n        Some(a) => bar(a, 1),\n        None => (),\n    }",
+                "match x {\n        Some(a) => bar(a, 1),\n        None => (),\n    }",
+            ],
+        );
+        do_check(
+            r#"fn foo() { let x = 1; bar(<|>x); }"#,
+            &["x", "(x)", "bar(x)", "bar(x);", "{ let x = 1; bar(x); }"],
+        );
+    }
+
     #[test]
     fn test_extend_selection_start_of_the_line() {
         do_check(
@@ -27,7 +27,7 @@ pub use self::{
     line_index::{LineCol, LineIndex},
     line_index_utils::translate_offset_with_edit,
     structure::{file_structure, StructureNode},
-    diagnostics::diagnostics,
+    diagnostics::{diagnostics, syntax_diagnostics},
     join_lines::join_lines,
     typing::{on_enter, on_dot_typed, on_eq_typed},
 
@@ -47,7 +47,7 @@ pub struct HighlightedRange {
     pub tag: &'static str,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Severity {
     Error,
     WeakWarning,
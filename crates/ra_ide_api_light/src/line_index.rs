@@ -24,6 +24,21 @@ impl Utf16Char {
     fn len(&self) -> TextUnit {
         self.end - self.start
     }
+
+    fn len_utf16(&self) -> TextUnit {
+        utf16_len(self.len())
+    }
+}
+
+/// The number of UTF-16 code units of a character which is `utf8_len` bytes
+/// long. Only the characters outside of the Basic Multilingual Plane, like
+/// most emoji, take four bytes, and they are encoded as surrogate pairs.
+pub(crate) fn utf16_len(utf8_len: TextUnit) -> TextUnit {
+    if utf8_len == TextUnit::from(4) {
+        TextUnit::from(2)
+    } else {
+        TextUnit::from(1)
+    }
 }
 
 impl LineIndex {
@@ -73,21 +88,22 @@ impl LineIndex {
         let line_start_offset = self.newlines[line];
         let col = offset - line_start_offset;
 
-        LineCol { line: line as u32, col_utf16: self.utf8_to_utf16_col(line as u32, col) as u32 }
+        LineCol { line: line as u32, col_utf16: self.to_utf16(line as u32, col) }
     }
 
     pub fn offset(&self, line_col: LineCol) -> TextUnit {
         //TODO: return Result
-        let col = self.utf16_to_utf8_col(line_col.line, line_col.col_utf16);
+        let col = self.from_utf16(line_col.line, line_col.col_utf16);
         self.newlines[line_col.line as usize] + col
     }
 
-    fn utf8_to_utf16_col(&self, line: u32, mut col: TextUnit) -> usize {
+    /// Converts the UTF-8 column `col` of `line` to a UTF-16 one.
+    pub fn to_utf16(&self, line: u32, mut col: TextUnit) -> u32 {
         if let Some(utf16_chars) = self.utf16_lines.get(&line) {
             let mut correction = TextUnit::from_usize(0);
             for c in utf16_chars {
                 if col >= c.end {
-                    correction += c.len() - TextUnit::from_usize(1);
+                    correction += c.len() - c.len_utf16();
                 } else {
                     // From here on, all utf16 characters come *after* the character we are mapping,
                     // so we don't need to take them into account
@@ -98,15 +114,16 @@ impl LineIndex {
             col -= correction;
         }
 
-        col.to_usize()
+        col.to_usize() as u32
     }
 
-    fn utf16_to_utf8_col(&self, line: u32, col: u32) -> TextUnit {
+    /// Converts the UTF-16 column `col` of `line` to a UTF-8 one.
+    pub fn from_utf16(&self, line: u32, col: u32) -> TextUnit {
         let mut col: TextUnit = col.into();
         if let Some(utf16_chars) = self.utf16_lines.get(&line) {
             for c in utf16_chars {
-                if col >= c.start {
-                    col += c.len() - TextUnit::from_usize(1);
+                if col > c.start {
+                    col += c.len() - c.len_utf16();
                 } else {
                     // From here on, all utf16 characters come *after* the character we are mapping,
                     // so we don't need to take them into account
@@ -133,7 +150,7 @@ pub fn to_line_col(text: &str, offset: TextUnit) -> LineCol {
             res.line += 1;
             res.col_utf16 = 0;
         } else {
-            res.col_utf16 += 1;
+            res.col_utf16 += c.len_utf16() as u32;
         }
     }
     res
@@ -184,7 +201,7 @@ mod test_line_index {
                 res.line += 1;
                 res.col_utf16 = 0;
             } else {
-                res.col_utf16 += 1;
+                res.col_utf16 += c.len_utf16() as u32;
             }
         }
         res
@@ -235,16 +252,16 @@ const C: char = 'メ';
         assert_eq!(col_index.utf16_lines[&1][0], Utf16Char { start: 17.into(), end: 20.into() });
 
         // UTF-8 to UTF-16, no changes
-        assert_eq!(col_index.utf8_to_utf16_col(1, 15.into()), 15);
+        assert_eq!(col_index.to_utf16(1, 15.into()), 15);
 
         // UTF-8 to UTF-16
-        assert_eq!(col_index.utf8_to_utf16_col(1, 22.into()), 20);
+        assert_eq!(col_index.to_utf16(1, 22.into()), 20);
 
         // UTF-16 to UTF-8, no changes
-        assert_eq!(col_index.utf16_to_utf8_col(1, 15), TextUnit::from(15));
+        assert_eq!(col_index.from_utf16(1, 15), TextUnit::from(15));
 
         // UTF-16 to UTF-8
-        assert_eq!(col_index.utf16_to_utf8_col(1, 19), TextUnit::from(21));
+        assert_eq!(col_index.from_utf16(1, 19), TextUnit::from(21));
     }
 
     #[test]
@@ -261,20 +278,44 @@ const C: char = \"メ メ\";
         assert_eq!(col_index.utf16_lines[&1][1], Utf16Char { start: 21.into(), end: 24.into() });
 
         // UTF-8 to UTF-16
-        assert_eq!(col_index.utf8_to_utf16_col(1, 15.into()), 15);
+        assert_eq!(col_index.to_utf16(1, 15.into()), 15);
 
-        assert_eq!(col_index.utf8_to_utf16_col(1, 21.into()), 19);
-        assert_eq!(col_index.utf8_to_utf16_col(1, 25.into()), 21);
+        assert_eq!(col_index.to_utf16(1, 21.into()), 19);
+        assert_eq!(col_index.to_utf16(1, 25.into()), 21);
 
-        assert!(col_index.utf8_to_utf16_col(2, 15.into()) == 15);
+        assert!(col_index.to_utf16(2, 15.into()) == 15);
 
         // UTF-16 to UTF-8
-        assert_eq!(col_index.utf16_to_utf8_col(1, 15), TextUnit::from_usize(15));
+        assert_eq!(col_index.from_utf16(1, 15), TextUnit::from_usize(15));
 
-        assert_eq!(col_index.utf16_to_utf8_col(1, 18), TextUnit::from_usize(20));
-        assert_eq!(col_index.utf16_to_utf8_col(1, 19), TextUnit::from_usize(23));
+        assert_eq!(col_index.from_utf16(1, 18), TextUnit::from_usize(20));
+        assert_eq!(col_index.from_utf16(1, 19), TextUnit::from_usize(21));
 
-        assert_eq!(col_index.utf16_to_utf8_col(2, 15), TextUnit::from_usize(15));
+        assert_eq!(col_index.from_utf16(2, 15), TextUnit::from_usize(15));
+    }
+
+    #[test]
+    fn test_astral_plane_chars() {
+        // `é` is two bytes, one UTF-16 code unit; `🦀` is four bytes, two code units.
+        let text = "\nlet c = \"é🦀x\";";
+        let col_index = LineIndex::new(text);
+
+        assert_eq!(col_index.utf16_lines[&1].len(), 2);
+        assert_eq!(col_index.utf16_lines[&1][1], Utf16Char { start: 11.into(), end: 15.into() });
+
+        // The byte, char and UTF-16 columns of `x` are 15, 11 and 12.
+        let x = TextUnit::from_usize(text.find('x').unwrap());
+        assert_eq!(x, TextUnit::from(16));
+        assert_eq!(col_index.to_utf16(1, 15.into()), 12);
+        assert_eq!(col_index.from_utf16(1, 12), TextUnit::from(15));
+        assert_eq!(col_index.line_col(x), LineCol { line: 1, col_utf16: 12 });
+        assert_eq!(col_index.offset(LineCol { line: 1, col_utf16: 12 }), x);
+
+        // Before and after the crab.
+        assert_eq!(col_index.to_utf16(1, 11.into()), 10);
+        assert_eq!(col_index.from_utf16(1, 10), TextUnit::from(11));
+        assert_eq!(col_index.from_utf16(1, 9), TextUnit::from(9));
+        assert_eq!(col_index.to_utf16(1, 9.into()), 9);
     }
 
 }
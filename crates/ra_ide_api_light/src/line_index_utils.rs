@@ -1,6 +1,6 @@
 use ra_text_edit::{AtomTextEdit, TextEdit};
 use ra_syntax::{TextUnit, TextRange};
-use crate::{LineIndex, LineCol, line_index::Utf16Char};
+use crate::{LineIndex, LineCol, line_index::{Utf16Char, utf16_len}};
 
 #[derive(Debug, Clone)]
 enum Step {
@@ -215,7 +215,7 @@ impl RunningLineCol {
     }
 
     fn adjust_col(&mut self, range: &TextRange) {
-        self.col_adjust += range.len() - TextUnit::from(1);
+        self.col_adjust += range.len() - utf16_len(range.len());
     }
 }
 
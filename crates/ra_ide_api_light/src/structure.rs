@@ -6,7 +6,7 @@ use ra_syntax::{
     AstNode, SourceFile, SyntaxKind, SyntaxNode, WalkEvent,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StructureNode {
     pub parent: Option<usize>,
     pub label: String,
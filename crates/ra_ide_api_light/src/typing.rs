@@ -95,8 +95,12 @@ pub fn on_dot_typed(file: &SourceFile, dot_offset: TextUnit) -> Option<LocalEdit
 
     // Make sure dot is a part of call chain
     let field_expr = whitespace.syntax().parent().and_then(ast::FieldExpr::cast)?;
-    let prev_indent = leading_indent(field_expr.syntax())?;
-    let target_indent = format!("    {}", prev_indent);
+    let target_indent = match field_expr.expr().and_then(chain_dot_indent) {
+        Some(it) => it.to_string(),
+        // The first continuation of the chain is indented relative to the
+        // line the receiver starts on.
+        None => format!("    {}", leading_indent(field_expr.syntax())?),
+    };
     let target_indent_len = TextUnit::of_str(&target_indent);
     if current_indent_len == target_indent_len {
         return None;
@@ -116,6 +120,31 @@ pub fn on_dot_typed(file: &SourceFile, dot_offset: TextUnit) -> Option<LocalEdit
     Some(res)
 }
 
+/// Finds the indent of the closest dot in the method chain `receiver` which
+/// starts a line, so that the next one can be aligned with it.
+fn chain_dot_indent(receiver: &ast::Expr) -> Option<&str> {
+    let mut expr = receiver;
+    loop {
+        let (dot, inner) = match expr.kind() {
+            ast::ExprKind::MethodCallExpr(it) => (it.syntax(), it.expr()?),
+            ast::ExprKind::FieldExpr(it) => (it.syntax(), it.expr()?),
+            ast::ExprKind::TryExpr(it) => {
+                expr = it.expr()?;
+                continue;
+            }
+            _ => return None,
+        };
+        let dot = dot.children().find(|it| it.kind() == DOT)?;
+        if let Some(whitespace) = dot.prev_sibling().and_then(ast::Whitespace::cast) {
+            let text = whitespace.text();
+            if let Some(newline) = text.rfind('\n') {
+                return Some(&text[newline + 1..]);
+            }
+        }
+        expr = inner;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test_utils::{add_cursor, assert_eq_text, extract_offset};
@@ -286,6 +315,44 @@ fn foo() {
         );
     }
 
+    #[test]
+    fn aligns_with_previous_chain_call() {
+        type_dot(
+            r"
+            fn build() -> Config {
+                Config::builder()
+                    .name(name)
+                    .size(92)?.verbose()
+                <|>
+            }
+            ",
+            r"
+            fn build() -> Config {
+                Config::builder()
+                    .name(name)
+                    .size(92)?.verbose()
+                    .
+            }
+            ",
+        );
+        type_dot(
+            r"
+            fn build() -> Config {
+                let config = Config::builder()
+                               .name(name)
+                    <|>
+            }
+            ",
+            r"
+            fn build() -> Config {
+                let config = Config::builder()
+                               .name(name)
+                               .
+            }
+            ",
+        );
+    }
+
     #[test]
     fn indents_middle_of_chain_call() {
         type_dot(
@@ -4,7 +4,7 @@ use crate::{
     Result
 };
 
-use ra_ide_api::{FileId, RunnableKind};
+use ra_ide_api::{FileId, RunnableKind, BinTarget};
 
 pub(crate) fn runnable_args(
     world: &ServerWorld,
@@ -29,7 +29,10 @@ pub(crate) fn runnable_args(
                 spec.push_to(&mut res);
             }
             res.push("--".to_string());
-            res.push(path.to_string());
+            // An empty path stands for all the tests of the crate.
+            if !path.is_empty() {
+                res.push(path.to_string());
+            }
             res.push("--nocapture".to_string());
         }
         RunnableKind::Bench { name } => {
@@ -41,10 +44,23 @@ pub(crate) fn runnable_args(
             res.push(name.to_string());
             res.push("--nocapture".to_string());
         }
-        RunnableKind::Bin => {
+        RunnableKind::Bin { target } => {
             res.push("run".to_string());
-            if let Some(spec) = spec {
-                spec.push_to(&mut res);
+            match (spec, target) {
+                (Some(spec), None) => spec.push_to(&mut res),
+                (spec, Some(target)) => {
+                    if let Some(spec) = spec {
+                        res.push("--package".to_string());
+                        res.push(spec.package);
+                    }
+                    let (flag, name) = match target {
+                        BinTarget::Bin { name } => ("--bin", name),
+                        BinTarget::Example { name } => ("--example", name),
+                    };
+                    res.push(flag.to_string());
+                    res.push(name.to_string());
+                }
+                (None, None) => (),
             }
         }
     }
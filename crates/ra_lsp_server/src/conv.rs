@@ -1,12 +1,12 @@
 use lsp_types::{
-    self, CreateFile, Documentation, DocumentChangeOperation, DocumentChanges, Location, LocationLink,
+    self, CreateFile, DeleteFile, Documentation, DocumentChangeOperation, DocumentChanges, Location, LocationLink,
     MarkupContent, MarkupKind, Position, Range, RenameFile, ResourceOp, SymbolKind, TextDocumentEdit, TextDocumentIdentifier,
     TextDocumentItem, TextDocumentPositionParams, Url, VersionedTextDocumentIdentifier,
     WorkspaceEdit,
 };
 use ra_ide_api::{
     CompletionItem, CompletionItemKind, FileId, FilePosition, FileRange, FileSystemEdit,
-    NavigationTarget, SourceChange, SourceFileEdit, RangeInfo,
+    NavigationTarget, NavigationTargetKind, SourceChange, SourceFileEdit, RangeInfo,
     LineCol, LineIndex, translate_offset_with_edit, InsertTextFormat
 };
 use ra_syntax::{SyntaxKind, TextRange, TextUnit};
@@ -52,6 +52,28 @@ impl Conv for SyntaxKind {
     }
 }
 
+impl Conv for NavigationTargetKind {
+    type Output = SymbolKind;
+
+    fn conv(self) -> <Self as Conv>::Output {
+        match self {
+            NavigationTargetKind::Module => SymbolKind::Module,
+            NavigationTargetKind::Function => SymbolKind::Function,
+            NavigationTargetKind::Method => SymbolKind::Method,
+            NavigationTargetKind::Struct => SymbolKind::Struct,
+            NavigationTargetKind::Enum => SymbolKind::Enum,
+            NavigationTargetKind::EnumVariant => SymbolKind::EnumMember,
+            NavigationTargetKind::Trait => SymbolKind::Interface,
+            NavigationTargetKind::TypeAlias => SymbolKind::TypeParameter,
+            NavigationTargetKind::Const | NavigationTargetKind::Static => SymbolKind::Constant,
+            NavigationTargetKind::Field => SymbolKind::Field,
+            NavigationTargetKind::Impl => SymbolKind::Object,
+            NavigationTargetKind::Macro => SymbolKind::Function,
+            NavigationTargetKind::Local => SymbolKind::Variable,
+        }
+    }
+}
+
 impl Conv for CompletionItemKind {
     type Output = ::lsp_types::CompletionItemKind;
 
@@ -73,6 +95,7 @@ impl Conv for CompletionItemKind {
             CompletionItemKind::Static => Value,
             CompletionItemKind::Method => Method,
             CompletionItemKind::TypeParam => TypeParameter,
+            CompletionItemKind::Attribute => Property,
         }
     }
 }
@@ -117,6 +140,12 @@ impl ConvWith for CompletionItem {
             documentation: self.documentation().map(|it| it.conv()),
             ..Default::default()
         };
+        // Editors sort by `sort_text`, falling back to the label.
+        if let Some(score) = self.score() {
+            res.sort_text = Some(format!("0{}{}", score as u8, self.label()));
+        } else if let Some(sort_text) = self.sort_text() {
+            res.sort_text = Some(sort_text.to_string());
+        }
         res.insert_text_format = Some(match self.insert_text_format() {
             InsertTextFormat::Snippet => lsp_types::InsertTextFormat::Snippet,
             InsertTextFormat::PlainText => lsp_types::InsertTextFormat::PlainText,
@@ -303,8 +332,25 @@ impl TryConvWith for SourceChange {
             }
         };
         let mut document_changes: Vec<DocumentChangeOperation> = Vec::new();
-        for resource_op in self.file_system_edits.try_conv_with(world)? {
-            document_changes.push(DocumentChangeOperation::Op(resource_op));
+        for file_system_edit in self.file_system_edits {
+            // The contents of a new file are inserted right after creating it.
+            let contents = match &file_system_edit {
+                FileSystemEdit::CreateFile { source_root, path, contents }
+                    if !contents.is_empty() =>
+                {
+                    Some((world.path_to_uri(*source_root, path)?, contents.clone()))
+                }
+                _ => None,
+            };
+            document_changes
+                .push(DocumentChangeOperation::Op(file_system_edit.try_conv_with(world)?));
+            if let Some((uri, contents)) = contents {
+                let text_document = VersionedTextDocumentIdentifier { uri, version: None };
+                let start = Position::new(0, 0);
+                let edits = vec![lsp_types::TextEdit::new(Range::new(start, start), contents)];
+                document_changes
+                    .push(DocumentChangeOperation::Edit(TextDocumentEdit { text_document, edits }));
+            }
         }
         for text_document_edit in self.source_file_edits.try_conv_with(world)? {
             document_changes.push(DocumentChangeOperation::Edit(text_document_edit));
@@ -336,7 +382,7 @@ impl TryConvWith for FileSystemEdit {
     type Output = ResourceOp;
     fn try_conv_with(self, world: &ServerWorld) -> Result<ResourceOp> {
         let res = match self {
-            FileSystemEdit::CreateFile { source_root, path } => {
+            FileSystemEdit::CreateFile { source_root, path, .. } => {
                 let uri = world.path_to_uri(source_root, &path)?;
                 ResourceOp::Create(CreateFile { uri, options: None })
             }
@@ -345,6 +391,10 @@ impl TryConvWith for FileSystemEdit {
                 let new_uri = world.path_to_uri(dst_source_root, &dst_path)?;
                 ResourceOp::Rename(RenameFile { old_uri, new_uri, options: None })
             }
+            FileSystemEdit::DeleteFile { file_id } => {
+                let uri = world.file_id_to_uri(file_id)?;
+                ResourceOp::Delete(DeleteFile { uri, options: None })
+            }
         };
         Ok(res)
     }
@@ -1,7 +1,14 @@
 mod handlers;
 mod subscriptions;
 
-use std::{fmt, path::PathBuf, sync::Arc};
+use std::{
+    fmt,
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
 
 use crossbeam_channel::{select, unbounded, Receiver, RecvError, Sender};
 use failure::{bail, format_err};
@@ -80,9 +87,11 @@ pub fn main_loop(
 
     let mut pending_requests = FxHashSet::default();
     let mut subs = Subscriptions::new();
+    let libraries_canceled = Arc::new(AtomicBool::new(false));
     let main_res = main_loop_inner(
         options,
         &pool,
+        &libraries_canceled,
         msg_sender,
         msg_receiver,
         task_sender,
@@ -92,6 +101,9 @@ pub fn main_loop(
         &mut subs,
     );
 
+    // Nobody is going to use the libraries which are still being indexed.
+    libraries_canceled.store(true, Ordering::SeqCst);
+
     log::info!("waiting for tasks to finish...");
     task_receiver.into_iter().for_each(|task| on_task(task, msg_sender, &mut pending_requests));
     log::info!("...tasks have finished");
@@ -150,6 +162,7 @@ impl fmt::Debug for Event {
 fn main_loop_inner(
     options: InitializationOptions,
     pool: &ThreadPool,
+    libraries_canceled: &Arc<AtomicBool>,
     msg_sender: &Sender<RawMessage>,
     msg_receiver: &Receiver<RawMessage>,
     task_sender: Sender<Task>,
@@ -234,12 +247,22 @@ fn main_loop_inner(
             let (root, files) = pending_libraries.pop().unwrap();
             in_flight_libraries += 1;
             let sender = libdata_sender.clone();
+            let canceled = Arc::clone(libraries_canceled);
             pool.execute(move || {
                 let start = ::std::time::Instant::now();
                 log::info!("indexing {:?} ... ", root);
-                let data = LibraryData::prepare(root, files);
-                log::info!("indexed {:?} {:?}", start.elapsed(), root);
-                sender.send(data).unwrap();
+                let poll = || {
+                    if canceled.load(Ordering::SeqCst) {
+                        Canceled::throw();
+                    }
+                };
+                match LibraryData::prepare_with_poll(root, files, &poll) {
+                    Ok(data) => {
+                        log::info!("indexed {:?} {:?}", start.elapsed(), root);
+                        sender.send(data).unwrap();
+                    }
+                    Err(_) => log::info!("canceled indexing {:?}", root),
+                }
             });
         }
 
@@ -9,7 +9,7 @@ use lsp_types::{
 };
 use ra_ide_api::{
     FileId, FilePosition, FileRange, FoldKind, Query, RangeInfo, RunnableKind, Severity, Cancelable,
-    AssistId,
+    AssistId, BinTarget,
 };
 use ra_syntax::{AstNode, SyntaxKind, TextUnit};
 use rustc_hash::FxHashMap;
@@ -191,9 +191,9 @@ pub fn handle_workspace_symbol(
         for nav in world.analysis().symbol_search(query)? {
             let info = SymbolInformation {
                 name: nav.name().to_string(),
-                kind: nav.kind().conv(),
+                kind: nav.target_kind().conv(),
                 location: nav.try_conv_with(world)?,
-                container_name: nav.container_name().map(|v| v.to_string()),
+                container_name: nav.container_path().map(|v| v.to_string()),
                 deprecated: None,
             };
             res.push(info);
@@ -276,9 +276,16 @@ pub fn handle_runnables(
             range: runnable.range.conv_with(&line_index),
             label: match &runnable.kind {
                 RunnableKind::Test { name } => format!("test {}", name),
+                RunnableKind::TestMod { path } if path.is_empty() => "test crate".to_string(),
                 RunnableKind::TestMod { path } => format!("test-mod {}", path),
                 RunnableKind::Bench { name } => format!("bench {}", name),
-                RunnableKind::Bin => "run binary".to_string(),
+                RunnableKind::Bin { target: None } => "run binary".to_string(),
+                RunnableKind::Bin { target: Some(BinTarget::Bin { name }) } => {
+                    format!("run binary {}", name)
+                }
+                RunnableKind::Bin { target: Some(BinTarget::Example { name }) } => {
+                    format!("run example {}", name)
+                }
             },
             bin: "cargo".to_string(),
             args,
@@ -115,6 +115,16 @@ fn test_eggs() {}
             position: None,
         },
         json!([
+          {
+            "args": [ "test", "--package", "foo", "--test", "spam", "--", "--nocapture" ],
+            "bin": "cargo",
+            "env": { "RUST_BACKTRACE": "short" },
+            "label": "test crate",
+            "range": {
+              "end": { "character": 0, "line": 2 },
+              "start": { "character": 0, "line": 0 }
+            }
+          },
           {
             "args": [ "test", "--package", "foo", "--test", "spam", "--", "test_eggs", "--nocapture" ],
             "bin": "cargo",
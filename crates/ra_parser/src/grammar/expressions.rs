@@ -357,6 +357,8 @@ fn method_call_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
 // fn foo() {
 //     x.foo;
 //     x.0.bar;
+//     x.0.1;
+//     x.0u8;
 // }
 fn field_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
     assert!(p.at(DOT));
@@ -367,6 +369,10 @@ fn field_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
     } else if p.at(INT_NUMBER) {
         p.bump()
     } else {
+        // test_err field_expr_float_index
+        // fn foo() {
+        //     x.1e3;
+        // }
         p.error("expected field name or number")
     }
     m.complete(p, FIELD_EXPR)
@@ -37,6 +37,7 @@ struct PackageData {
     is_member: bool,
     dependencies: Vec<PackageDependency>,
     edition: Edition,
+    features: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -89,6 +90,9 @@ impl Package {
     pub fn edition(self, ws: &CargoWorkspace) -> Edition {
         ws.packages[self].edition
     }
+    pub fn features(self, ws: &CargoWorkspace) -> &[String] {
+        ws.packages[self].features.as_slice()
+    }
     pub fn targets<'a>(self, ws: &'a CargoWorkspace) -> impl Iterator<Item = Target> + 'a {
         ws.packages[self].targets.iter().cloned()
     }
@@ -135,6 +139,8 @@ impl CargoWorkspace {
 
         for meta_pkg in meta.packages {
             let is_member = ws_members.contains(&meta_pkg.id);
+            let mut features = meta_pkg.features.keys().cloned().collect::<Vec<_>>();
+            features.sort();
             let pkg = packages.alloc(PackageData {
                 name: meta_pkg.name.into(),
                 manifest: meta_pkg.manifest_path.clone(),
@@ -142,6 +148,7 @@ impl CargoWorkspace {
                 is_member,
                 edition: Edition::from_string(&meta_pkg.edition),
                 dependencies: Vec::new(),
+                features,
             });
             let pkg_data = &mut packages[pkg];
             pkg_by_id.insert(meta_pkg.id.clone(), pkg);
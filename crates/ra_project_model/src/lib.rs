@@ -149,6 +149,8 @@ impl ProjectWorkspace {
                         if let Some(file_id) = load(root) {
                             let edition = pkg.edition(&cargo);
                             let crate_id = crate_graph.add_crate_root(file_id, edition);
+                            let features = pkg.features(&cargo).iter().map(|it| it.into());
+                            crate_graph.set_features(crate_id, features.collect());
                             if tgt.kind(&cargo) == TargetKind::Lib {
                                 lib_tgt = Some(crate_id);
                                 pkg_to_lib_crate.insert(pkg, crate_id);
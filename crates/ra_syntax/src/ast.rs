@@ -513,6 +513,14 @@ impl RefExpr {
     }
 }
 
+impl FieldExpr {
+    /// The index of the field in a tuple field access, like `x.0`.
+    pub fn index(&self) -> Option<usize> {
+        let index = self.syntax().children().find(|n| n.kind() == INT_NUMBER)?;
+        index.leaf_text()?.parse().ok()
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum PrefixOp {
     /// The `*` operator for dereferencing
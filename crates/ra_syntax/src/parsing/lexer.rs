@@ -31,9 +31,13 @@ pub struct Token {
 /// Break a string up into its component tokens
 pub fn tokenize(text: &str) -> Vec<Token> {
     let mut text = text;
-    let mut acc = Vec::new();
+    let mut acc: Vec<Token> = Vec::new();
     while !text.is_empty() {
-        let token = next_token(text);
+        let after_dot = acc.last().map(|it| it.kind) == Some(DOT);
+        let token = match tuple_field_index(text) {
+            Some(index) if after_dot => index,
+            _ => next_token(text),
+        };
         acc.push(token);
         let len: u32 = token.len.into();
         text = &text[len as usize..];
@@ -41,6 +45,17 @@ pub fn tokenize(text: &str) -> Vec<Token> {
     acc
 }
 
+/// Lexes the index of a tuple field, so that `x.0.1` is not lexed as `x`, `.`
+/// and the float `0.1`. Digits followed by a suffix or an exponent, as in
+/// `x.0u8` or `x.1e3`, are left to the number lexer.
+fn tuple_field_index(text: &str) -> Option<Token> {
+    let len = text.bytes().take_while(u8::is_ascii_digit).count();
+    if len == 0 || text[len..].starts_with(is_ident_continue) {
+        return None;
+    }
+    Some(Token { kind: INT_NUMBER, len: TextUnit::from_usize(len) })
+}
+
 /// Get the next token from a string
 pub fn next_token(text: &str) -> Token {
     assert!(!text.is_empty());
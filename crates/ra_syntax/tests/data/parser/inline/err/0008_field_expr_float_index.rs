@@ -0,0 +1,3 @@
+fn foo() {
+    x.1e3;
+}
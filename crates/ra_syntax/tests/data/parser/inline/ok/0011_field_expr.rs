@@ -1,4 +1,6 @@
 fn foo() {
     x.foo;
     x.0.bar;
+    x.0.1;
+    x.0u8;
 }
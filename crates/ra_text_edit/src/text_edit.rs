@@ -1,7 +1,7 @@
 use crate::AtomTextEdit;
 use text_unit::{TextRange, TextUnit};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TextEdit {
     atoms: Vec<AtomTextEdit>,
 }